@@ -0,0 +1,9 @@
+use fluvio_smartstream_wasm::{smartstream, Record};
+
+/// append first byte of each value to accumulator
+#[smartstream(aggregate)]
+fn first_bytes(accumulator: &[u8], record: &Record) -> Vec<u8> {
+    let mut next = accumulator.to_vec();
+    next.extend(record.value().as_ref().first());
+    next
+}
@@ -0,0 +1,2 @@
+#[cfg(not(target_os = "macos"))]
+mod aggregate;
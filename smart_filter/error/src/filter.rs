@@ -0,0 +1,11 @@
+use fluvio_smartstream_wasm::{smartstream, Record};
+
+/// keep records whose value contains `a`, fails on records starting with `z`
+#[smartstream(filter)]
+fn contains_a(record: &Record) -> Result<bool, String> {
+    let value = String::from_utf8_lossy(record.value().as_ref());
+    if value.starts_with('z') {
+        return Err("record starts with z".to_owned());
+    }
+    Ok(value.contains('a'))
+}
@@ -0,0 +1,8 @@
+use fluvio_smartstream_wasm::{smartstream, Record};
+
+/// uppercase value of each record, key is kept
+#[smartstream(map)]
+fn uppercase(record: &Record) -> (Option<Vec<u8>>, Vec<u8>) {
+    let key = record.key().map(|key| key.as_ref().to_vec());
+    (key, record.value().as_ref().to_ascii_uppercase())
+}
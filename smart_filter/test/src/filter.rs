@@ -1,45 +1,8 @@
-use fluvio_smartstream_wasm as smart_stream;
+use fluvio_smartstream_wasm::{smartstream, Record};
 
-use smart_stream::dataplane::record::DefaultRecord;
-use smart_stream::dataplane::core::{Decoder, Encoder};
-
-extern "C" {
-    fn copy_records(putr: i32, len: i32);
-}
-
-#[no_mangle]
-#[allow(clippy::missing_safety_doc)]
-pub unsafe fn filter(ptr: *mut u8, len: usize) -> i32 {
-    use std::io::Cursor;
-
-    let input_data = Vec::from_raw_parts(ptr, len, len);
-    let mut records: Vec<DefaultRecord> = vec![];
-
-    if let Err(_err) = records.decode(&mut Cursor::new(input_data), 0) {
-        return -1;
-    };
-
-    // filter only
-    let filter_records: Vec<DefaultRecord> = records
-        .into_iter()
-        .filter(|r| {
-            let value = String::from_utf8_lossy(r.value().as_ref());
-            value.contains('a')
-        })
-        .collect();
-
-    // encode back
-    let mut out = vec![];
-    if let Err(_err) = filter_records.encode(&mut out, 0) {
-        return -1;
-    }
-
-    let out_len = out.len();
-
-    let ptr = out.as_mut_ptr();
-    std::mem::forget(out);
-
-    copy_records(ptr as i32, out_len as i32);
-
-    filter_records.len() as i32
+/// keep records whose value contains `a`
+#[smartstream(filter)]
+fn contains_a(record: &Record) -> bool {
+    let value = String::from_utf8_lossy(record.value().as_ref());
+    value.contains('a')
 }
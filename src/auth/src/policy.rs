@@ -16,6 +16,7 @@ pub enum TypeAction {
 }
 
 pub enum InstanceAction {
+    Update,
     Delete,
 }
 
@@ -7,14 +7,17 @@
 use std::{io::Error as IoError, path::PathBuf};
 use std::io::ErrorKind;
 use std::convert::TryFrom;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tracing::{debug, trace, instrument};
 use structopt::StructOpt;
 use structopt::clap::arg_enum;
-use fluvio_future::io::StreamExt;
+use fluvio_future::io::{Stream, StreamExt};
 
 mod record_format;
 
-use fluvio::{Fluvio, PartitionConsumer, Offset, ConsumerConfig, FluvioError};
+use fluvio::{
+    Fluvio, PartitionConsumer, MultiplePartitionConsumer, Offset, ConsumerConfig, FluvioError,
+};
 use fluvio_sc_schema::ApiError;
 use fluvio::consumer::Record;
 
@@ -39,6 +42,14 @@ pub struct ConsumeOpt {
     #[structopt(short = "p", long, default_value = "0", value_name = "integer")]
     pub partition: i32,
 
+    /// Consume records from all partitions of the topic
+    #[structopt(
+        short = "A",
+        long = "all-partitions",
+        conflicts_with = "disable-continuous"
+    )]
+    pub all_partitions: bool,
+
     /// Start reading from beginning
     #[structopt(short = "B", long = "from-beginning")]
     pub from_beginning: bool,
@@ -55,6 +66,15 @@ pub struct ConsumeOpt {
     #[structopt(short, long, value_name = "integer")]
     pub offset: Option<i64>,
 
+    /// Start reading from records produced within this duration, e.g. 30s, 10m, 2h or 1d
+    #[structopt(
+        long,
+        value_name = "duration",
+        parse(try_from_str = parse_duration),
+        conflicts_with_all = &["from-beginning", "offset"]
+    )]
+    pub since: Option<Duration>,
+
     /// Maximum number of bytes to be retrieved
     #[structopt(short = "b", long = "maxbytes", value_name = "integer")]
     pub max_bytes: Option<i32>,
@@ -77,6 +97,32 @@ pub struct ConsumeOpt {
     /// Path to a WASM binary file
     #[structopt(short, long)]
     pub filter: Option<PathBuf>,
+
+    /// Path to a WASM binary file which maps records
+    #[structopt(long, conflicts_with = "filter")]
+    pub map: Option<PathBuf>,
+
+    /// Path to a WASM binary file which aggregates records
+    #[structopt(long, conflicts_with_all = &["filter", "map"])]
+    pub aggregate: Option<PathBuf>,
+
+    /// Name of a SmartStream registered with `fluvio smartstream create`
+    #[structopt(long, value_name = "name", conflicts_with_all = &["filter", "map", "aggregate"])]
+    pub smart_stream: Option<String>,
+
+    /// How the registered SmartStream is applied to records
+    #[structopt(
+        long,
+        value_name = "kind",
+        possible_values = &SmartStreamKindOpt::variants(),
+        case_insensitive = true,
+        default_value
+    )]
+    pub smart_stream_kind: SmartStreamKindOpt,
+
+    /// Initial value of accumulator used by aggregate
+    #[structopt(long)]
+    pub initial: Option<String>,
 }
 
 impl ConsumeOpt {
@@ -85,10 +131,15 @@ impl ConsumeOpt {
         fields(topic = %self.topic, partition = self.partition),
     )]
     pub async fn process(self, fluvio: &Fluvio) -> Result<(), ConsumerError> {
-        let consumer = fluvio
-            .partition_consumer(&self.topic, self.partition)
-            .await?;
-        self.consume_records(consumer).await?;
+        if self.all_partitions {
+            let consumer = fluvio.consumer(&self.topic).await?;
+            self.consume_topic_records(consumer).await?;
+        } else {
+            let consumer = fluvio
+                .partition_consumer(&self.topic, self.partition)
+                .await?;
+            self.consume_records(consumer).await?;
+        }
         Ok(())
     }
 
@@ -105,7 +156,35 @@ impl ConsumeOpt {
         trace!(config = ?self, "Starting consumer:");
         self.init_ctrlc()?;
         let offset = self.calculate_offset()?;
+        let consume_config = self.consume_config()?;
+
+        if self.disable_continuous {
+            self.consume_records_batch(&consumer, offset, consume_config)
+                .await?;
+        } else {
+            let stream = consumer.stream_with_config(offset, consume_config).await?;
+            self.consume_records_stream(stream).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Consume records of all partitions as a single stream
+    pub async fn consume_topic_records(
+        &self,
+        consumer: MultiplePartitionConsumer,
+    ) -> Result<(), ConsumerError> {
+        trace!(config = ?self, "Starting topic consumer:");
+        self.init_ctrlc()?;
+        let offset = self.calculate_offset()?;
+        let consume_config = self.consume_config()?;
 
+        let stream = consumer.stream_with_config(offset, consume_config).await?;
+        self.consume_records_stream(stream).await?;
+        Ok(())
+    }
+
+    fn consume_config(&self) -> Result<ConsumerConfig, ConsumerError> {
         let mut consume_config = {
             let mut config = ConsumerConfig::default();
             if let Some(max_bytes) = self.max_bytes {
@@ -120,15 +199,32 @@ impl ConsumeOpt {
             consume_config = consume_config.with_wasm_filter(buffer);
         }
 
-        if self.disable_continuous {
-            self.consume_records_batch(&consumer, offset, consume_config)
-                .await?;
-        } else {
-            self.consume_records_stream(&consumer, offset, consume_config)
-                .await?;
+        if let Some(map_path) = &self.map {
+            let buffer = std::fs::read(map_path)?;
+            debug!(len = buffer.len(), "read map bytes");
+            consume_config = consume_config.with_wasm_map(buffer);
         }
 
-        Ok(())
+        if let Some(aggregate_path) = &self.aggregate {
+            let buffer = std::fs::read(aggregate_path)?;
+            debug!(len = buffer.len(), "read aggregate bytes");
+            let accumulator = self.initial.clone().unwrap_or_default().into_bytes();
+            consume_config = consume_config.with_wasm_aggregate(buffer, accumulator);
+        }
+
+        if let Some(name) = &self.smart_stream {
+            debug!(%name, kind = ?self.smart_stream_kind, "using registered smartstream");
+            consume_config = match self.smart_stream_kind {
+                SmartStreamKindOpt::filter => consume_config.with_smart_stream_filter(name),
+                SmartStreamKindOpt::map => consume_config.with_smart_stream_map(name),
+                SmartStreamKindOpt::aggregate => {
+                    let accumulator = self.initial.clone().unwrap_or_default().into_bytes();
+                    consume_config.with_smart_stream_aggregate(name, accumulator)
+                }
+            };
+        }
+
+        Ok(consume_config)
     }
 
     /// Consume records in a single batch, then exit
@@ -158,11 +254,9 @@ impl ConsumeOpt {
     /// Consume records as a stream, waiting for new records to arrive
     async fn consume_records_stream(
         &self,
-        consumer: &PartitionConsumer,
-        offset: Offset,
-        config: ConsumerConfig,
+        stream: impl Stream<Item = Result<Record, FluvioError>>,
     ) -> Result<(), ConsumerError> {
-        let mut stream = consumer.stream_with_config(offset, config).await?;
+        let mut stream = Box::pin(stream);
 
         while let Some(result) = stream.next().await {
             let result: std::result::Result<Record, _> = result;
@@ -232,6 +326,11 @@ impl ConsumeOpt {
 
     /// Calculate the Offset to use with the consumer based on the provided offset number
     fn calculate_offset(&self) -> Result<Offset, ConsumerError> {
+        if let Some(since) = self.since {
+            let start_time = SystemTime::now().checked_sub(since).unwrap_or(UNIX_EPOCH);
+            return Ok(Offset::from_timestamp(start_time));
+        }
+
         let maybe_initial_offset = if self.from_beginning {
             let big_offset = self.offset.unwrap_or(0);
             // Try to convert to u32
@@ -254,6 +353,33 @@ impl ConsumeOpt {
     }
 }
 
+/// parse duration with unit suffix: s, m, h or d. Number without unit is in seconds
+fn parse_duration(value: &str) -> Result<Duration, String> {
+    let unit_start = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(unit_start);
+    let number: u64 = number
+        .parse()
+        .map_err(|_| format!("invalid duration: {}", value))?;
+    let unit_seconds = match unit {
+        "" | "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        _ => {
+            return Err(format!(
+                "invalid duration unit: {}, expected s, m, h or d",
+                unit
+            ))
+        }
+    };
+    number
+        .checked_mul(unit_seconds)
+        .map(Duration::from_secs)
+        .ok_or_else(|| format!("duration is too long: {}", value))
+}
+
 // Uses clap::arg_enum to choose possible variables
 arg_enum! {
     #[derive(Debug, Clone, PartialEq)]
@@ -267,6 +393,23 @@ arg_enum! {
     }
 }
 
+arg_enum! {
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    #[allow(non_camel_case_types)]
+    pub enum SmartStreamKindOpt {
+        filter,
+        map,
+        aggregate,
+    }
+}
+
+/// Registered SmartStream is used as filter unless specified
+impl ::std::default::Default for SmartStreamKindOpt {
+    fn default() -> Self {
+        SmartStreamKindOpt::filter
+    }
+}
+
 /// Consume output type defaults to text formatting
 impl ::std::default::Default for ConsumeOutputType {
     fn default() -> Self {
@@ -6,11 +6,13 @@ mod topic;
 mod consume;
 mod produce;
 mod partition;
+mod smartstream;
 
 pub use topic::TopicCmd;
 pub use consume::ConsumeOpt;
 pub use produce::ProduceOpt;
 pub use partition::PartitionCmd;
+pub use smartstream::SmartStreamCmd;
 
 use crate::Result;
 use fluvio::Fluvio;
@@ -48,6 +50,14 @@ pub enum FluvioCmd {
     /// total throughput of the Topic.
     #[structopt(name = "partition")]
     Partition(PartitionCmd),
+
+    /// Manage and view SmartStreams
+    ///
+    /// SmartStreams are WASM modules registered in the cluster under a name,
+    /// consumers refer to them by name instead of uploading the module with
+    /// every stream. SPUs compile each module once and reuse it.
+    #[structopt(name = "smartstream")]
+    SmartStream(SmartStreamCmd),
 }
 
 impl FluvioCmd {
@@ -69,6 +79,9 @@ impl FluvioCmd {
             Self::Partition(partition) => {
                 partition.process(out, &fluvio).await?;
             }
+            Self::SmartStream(smart_stream) => {
+                smart_stream.process(out, &fluvio).await?;
+            }
         }
 
         Ok(())
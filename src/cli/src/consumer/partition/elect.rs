@@ -0,0 +1,30 @@
+//!
+//! # Elect Partition Leaders
+//!
+//! CLI tree to move leadership of Partitions back to preferred replicas
+//!
+
+use tracing::debug;
+use structopt::StructOpt;
+
+use fluvio::Fluvio;
+
+use crate::Result;
+
+#[derive(Debug, StructOpt)]
+pub struct ElectLeadersOpt {
+    /// Topics whose partitions are elected, all topics if not set
+    #[structopt(short = "t", long = "topic", value_name = "topic")]
+    topics: Vec<String>,
+}
+
+impl ElectLeadersOpt {
+    pub async fn process(self, fluvio: &Fluvio) -> Result<()> {
+        debug!("electing preferred leaders of topics: {:?}", self.topics);
+        let mut admin = fluvio.admin().await;
+        admin.elect_leaders(self.topics).await?;
+        println!("preferred leaders which are in sync are elected");
+
+        Ok(())
+    }
+}
@@ -6,8 +6,12 @@ use crate::Result;
 use crate::common::output::Terminal;
 use crate::common::FluvioExtensionMetadata;
 use crate::consumer::partition::list::ListPartitionOpt;
+use crate::consumer::partition::reassign::ReassignPartitionOpt;
+use crate::consumer::partition::elect::ElectLeadersOpt;
 
 mod list;
+mod reassign;
+mod elect;
 
 #[derive(Debug, StructOpt)]
 #[structopt(name = "partition", about = "Partition operations")]
@@ -18,6 +22,20 @@ pub enum PartitionCmd {
         template = crate::common::COMMAND_TEMPLATE,
     )]
     List(ListPartitionOpt),
+
+    /// Move replicas of a Partition to other SPUs
+    #[structopt(
+        name = "reassign",
+        template = crate::common::COMMAND_TEMPLATE,
+    )]
+    Reassign(ReassignPartitionOpt),
+
+    /// Move leadership of Partitions back to preferred replicas
+    #[structopt(
+        name = "elect-leaders",
+        template = crate::common::COMMAND_TEMPLATE,
+    )]
+    ElectLeaders(ElectLeadersOpt),
 }
 
 impl PartitionCmd {
@@ -26,6 +44,12 @@ impl PartitionCmd {
             Self::List(list) => {
                 list.process(out, fluvio).await?;
             }
+            Self::Reassign(reassign) => {
+                reassign.process(fluvio).await?;
+            }
+            Self::ElectLeaders(elect) => {
+                elect.process(fluvio).await?;
+            }
         }
 
         Ok(())
@@ -0,0 +1,62 @@
+//!
+//! # Reassign Partition
+//!
+//! CLI tree to move replicas of a Partition to other SPUs
+//!
+
+use tracing::debug;
+use structopt::StructOpt;
+
+use fluvio::Fluvio;
+use fluvio::metadata::partition::{PartitionUpdate, ReplicaKey};
+
+use crate::Result;
+
+#[derive(Debug, StructOpt)]
+pub struct ReassignPartitionOpt {
+    /// Topic name
+    #[structopt(value_name = "topic")]
+    topic: String,
+
+    /// Partition id
+    #[structopt(short = "p", long, default_value = "0", value_name = "integer")]
+    partition: i32,
+
+    /// SPU ids of new replicas, the first one is preferred leader
+    ///
+    /// New replicas are added first. Replicas which are moved out are removed,
+    /// with their storage, once new replicas are in sync with the leader.
+    #[structopt(
+        short = "r",
+        long,
+        value_name = "spu ids",
+        required = true,
+        use_delimiter = true
+    )]
+    replicas: Vec<i32>,
+
+    /// Validates replicas, does not move them
+    #[structopt(short = "d", long)]
+    dry_run: bool,
+}
+
+impl ReassignPartitionOpt {
+    pub async fn process(self, fluvio: &Fluvio) -> Result<()> {
+        let key = ReplicaKey::new(self.topic, self.partition);
+        let update = PartitionUpdate {
+            replicas: self.replicas,
+        };
+
+        debug!("reassigning partition: {} update: {:#?}", key, update);
+        let mut admin = fluvio.admin().await;
+        admin
+            .update(key.to_string(), self.dry_run, update.clone())
+            .await?;
+        println!(
+            "partition \"{}\" is being reassigned to {:?}",
+            key, update.replicas
+        );
+
+        Ok(())
+    }
+}
@@ -4,7 +4,7 @@ use std::path::PathBuf;
 use structopt::StructOpt;
 use tracing::debug;
 
-use fluvio::{Fluvio, TopicProducer};
+use fluvio::{Fluvio, TopicProducer, ProducerConfig, Compression};
 use fluvio_types::print_cli_ok;
 use crate::common::FluvioExtensionMetadata;
 use crate::consumer::error::ConsumerError;
@@ -39,6 +39,14 @@ pub struct ProduceOpt {
     /// Path to a file to produce to the topic. If absent, producer will read stdin.
     #[structopt(short, long)]
     pub file: Option<PathBuf>,
+
+    /// Compression codec for records sent to the topic
+    #[structopt(
+        long,
+        default_value = "none",
+        possible_values = &["none", "gzip", "snappy", "lz4", "zstd"]
+    )]
+    pub compression: Compression,
 }
 
 fn validate_key_separator(separator: String) -> std::result::Result<(), String> {
@@ -52,7 +60,10 @@ fn validate_key_separator(separator: String) -> std::result::Result<(), String>
 
 impl ProduceOpt {
     pub async fn process(self, fluvio: &Fluvio) -> Result<(), ConsumerError> {
-        let mut producer = fluvio.topic_producer(&self.topic).await?;
+        let config = ProducerConfig::default().with_compression(self.compression);
+        let mut producer = fluvio
+            .topic_producer_with_config(&self.topic, config)
+            .await?;
 
         match &self.file {
             Some(path) => {
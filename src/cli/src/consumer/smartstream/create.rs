@@ -0,0 +1,50 @@
+//!
+//! # Create SmartStream
+//!
+//! CLI tree to register SmartStream module
+//!
+
+use std::path::PathBuf;
+
+use tracing::debug;
+use structopt::StructOpt;
+
+use fluvio::Fluvio;
+use fluvio::metadata::smartstream::SmartStreamSpec;
+use crate::Result;
+
+#[derive(Debug, StructOpt)]
+pub struct CreateSmartStreamOpt {
+    /// The name of the SmartStream to create
+    #[structopt(value_name = "name")]
+    name: String,
+
+    /// Path to the WASM binary file
+    #[structopt(long, value_name = "path", parse(from_os_str))]
+    wasm: PathBuf,
+
+    /// Version of module, must be greater than version already registered to replace it
+    #[structopt(long, value_name = "integer", default_value = "1")]
+    version: u32,
+
+    /// Validates configuration, does not register
+    #[structopt(short = "d", long)]
+    dry_run: bool,
+}
+
+impl CreateSmartStreamOpt {
+    pub async fn process(self, fluvio: &Fluvio) -> Result<()> {
+        let wasm = std::fs::read(&self.wasm)?;
+        debug!(
+            name = %self.name,
+            version = self.version,
+            len = wasm.len(),
+            "creating smartstream"
+        );
+        let spec = SmartStreamSpec::new(self.version, wasm);
+        let mut admin = fluvio.admin().await;
+        admin.create(self.name.clone(), self.dry_run, spec).await?;
+        println!("smartstream \"{}\" created", &self.name);
+        Ok(())
+    }
+}
@@ -0,0 +1,29 @@
+//!
+//! # Delete SmartStream
+//!
+//! CLI tree to delete SmartStream
+//!
+
+use tracing::debug;
+use structopt::StructOpt;
+
+use fluvio::Fluvio;
+use fluvio::metadata::smartstream::SmartStreamSpec;
+use crate::Result;
+
+#[derive(Debug, StructOpt)]
+pub struct DeleteSmartStreamOpt {
+    /// The name of the SmartStream to delete
+    #[structopt(value_name = "name")]
+    name: String,
+}
+
+impl DeleteSmartStreamOpt {
+    pub async fn process(self, fluvio: &Fluvio) -> Result<()> {
+        debug!("deleting smartstream: {}", &self.name);
+        let mut admin = fluvio.admin().await;
+        admin.delete::<SmartStreamSpec, _>(&self.name).await?;
+        println!("smartstream \"{}\" deleted", &self.name);
+        Ok(())
+    }
+}
@@ -0,0 +1,98 @@
+//!
+//! # List SmartStreams CLI
+//!
+//! CLI tree and processing to list SmartStreams
+//!
+
+use std::sync::Arc;
+use structopt::StructOpt;
+use tracing::debug;
+
+use fluvio::Fluvio;
+use fluvio::metadata::smartstream::SmartStreamSpec;
+
+use crate::common::output::Terminal;
+use crate::common::OutputFormat;
+use crate::Result;
+
+#[derive(Debug, StructOpt)]
+pub struct ListSmartStreamsOpt {
+    /// Output
+    #[structopt(flatten)]
+    output: OutputFormat,
+}
+
+impl ListSmartStreamsOpt {
+    pub async fn process<O: Terminal>(self, out: Arc<O>, fluvio: &Fluvio) -> Result<()> {
+        let output_type = self.output.format;
+        debug!("list smartstreams {:#?} ", output_type);
+        let mut admin = fluvio.admin().await;
+
+        let smart_streams = admin.list::<SmartStreamSpec, _>(vec![]).await?;
+        display::format_response_output(out, smart_streams, output_type)?;
+        Ok(())
+    }
+}
+
+mod display {
+
+    use prettytable::*;
+    use serde::Serialize;
+
+    use fluvio::metadata::objects::Metadata;
+    use fluvio::metadata::smartstream::SmartStreamSpec;
+
+    use crate::common::output::{OutputType, TableOutputHandler, Terminal, OutputError};
+    use crate::common::t_println;
+
+    #[derive(Serialize)]
+    struct ListSmartStreams(Vec<Metadata<SmartStreamSpec>>);
+
+    /// Process server based on output type
+    pub fn format_response_output<O>(
+        out: std::sync::Arc<O>,
+        list_smart_streams: Vec<Metadata<SmartStreamSpec>>,
+        output_type: OutputType,
+    ) -> Result<(), OutputError>
+    where
+        O: Terminal,
+    {
+        if !list_smart_streams.is_empty() {
+            let table_list = ListSmartStreams(list_smart_streams);
+            out.render_list(&table_list, output_type)
+        } else {
+            t_println!(out, "No smartstreams found");
+            Ok(())
+        }
+    }
+
+    // -----------------------------------
+    // Output Handlers
+    // -----------------------------------
+    impl TableOutputHandler for ListSmartStreams {
+        /// table header implementation
+        fn header(&self) -> Row {
+            row!["NAME", "VERSION", "SIZE"]
+        }
+
+        /// return errors in string format
+        fn errors(&self) -> Vec<String> {
+            vec![]
+        }
+
+        /// table content implementation
+        fn content(&self) -> Vec<Row> {
+            self.0
+                .iter()
+                .map(|metadata| {
+                    let smart_stream = &metadata.spec;
+                    row![
+                        l -> metadata.name,
+                        r -> smart_stream.version.to_string(),
+                        r -> smart_stream.wasm.len().to_string()
+                    ]
+                })
+                .collect()
+        }
+    }
+}
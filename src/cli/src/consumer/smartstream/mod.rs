@@ -0,0 +1,69 @@
+use std::sync::Arc;
+use structopt::StructOpt;
+
+mod create;
+mod delete;
+mod list;
+
+use create::CreateSmartStreamOpt;
+use delete::DeleteSmartStreamOpt;
+use list::ListSmartStreamsOpt;
+
+use fluvio::Fluvio;
+
+use crate::Result;
+use crate::common::COMMAND_TEMPLATE;
+use crate::common::output::Terminal;
+use crate::common::FluvioExtensionMetadata;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "smartstream", about = "SmartStream operations")]
+pub enum SmartStreamCmd {
+    /// Register a WASM module as SmartStream with the given name
+    #[structopt(
+        name = "create",
+        template = COMMAND_TEMPLATE,
+    )]
+    Create(CreateSmartStreamOpt),
+
+    /// Delete a SmartStream with the given name
+    #[structopt(
+        name = "delete",
+        template = COMMAND_TEMPLATE,
+    )]
+    Delete(DeleteSmartStreamOpt),
+
+    /// List all of the SmartStreams in the cluster
+    #[structopt(
+        name = "list",
+        template = COMMAND_TEMPLATE,
+    )]
+    List(ListSmartStreamsOpt),
+}
+
+impl SmartStreamCmd {
+    pub async fn process<O: Terminal>(self, out: Arc<O>, fluvio: &Fluvio) -> Result<()> {
+        match self {
+            Self::Create(create) => {
+                create.process(fluvio).await?;
+            }
+            Self::Delete(delete) => {
+                delete.process(fluvio).await?;
+            }
+            Self::List(list) => {
+                list.process(out, fluvio).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn metadata() -> FluvioExtensionMetadata {
+        FluvioExtensionMetadata {
+            title: "smartstream".into(),
+            package: Some("fluvio/fluvio".parse().unwrap()),
+            description: "SmartStream Operations".into(),
+            version: semver::Version::parse(env!("CARGO_PKG_VERSION")).unwrap(),
+        }
+    }
+}
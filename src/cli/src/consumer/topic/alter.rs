@@ -0,0 +1,47 @@
+//!
+//! # Alter Topics
+//!
+//! CLI tree to change existing Topics
+//!
+
+use tracing::debug;
+use structopt::StructOpt;
+
+use fluvio::Fluvio;
+use fluvio::metadata::topic::TopicUpdate;
+use crate::consumer::error::ConsumerError;
+
+#[derive(Debug, StructOpt)]
+pub struct AlterTopicOpt {
+    /// The name of the Topic to alter
+    #[structopt(value_name = "name")]
+    topic: String,
+
+    /// The new number of Partitions of the Topic
+    ///
+    /// Partitions can only be added. Existing partitions keep their
+    /// replicas, new partitions are assigned to SPUs like on creation.
+    #[structopt(short = "p", long = "partitions", value_name = "partitions")]
+    partitions: i32,
+
+    /// Validates configuration, does not alter
+    #[structopt(short = "d", long)]
+    dry_run: bool,
+}
+
+impl AlterTopicOpt {
+    pub async fn process(self, fluvio: &Fluvio) -> Result<(), ConsumerError> {
+        let update = TopicUpdate {
+            partitions: self.partitions,
+        };
+
+        debug!("altering topic: {} update: {:#?}", self.topic, update);
+        let mut admin = fluvio.admin().await;
+        admin
+            .update(self.topic.clone(), self.dry_run, update)
+            .await?;
+        println!("topic \"{}\" altered", self.topic);
+
+        Ok(())
+    }
+}
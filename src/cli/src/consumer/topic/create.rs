@@ -10,6 +10,7 @@ use std::path::PathBuf;
 
 use tracing::debug;
 use structopt::StructOpt;
+use structopt::clap::arg_enum;
 
 use fluvio::Fluvio;
 use fluvio::metadata::topic::TopicSpec;
@@ -80,6 +81,67 @@ pub struct CreateTopicOpt {
     /// Validates configuration, does not provision
     #[structopt(short = "d", long)]
     dry_run: bool,
+
+    /// Retention time in seconds, overrides SPU default
+    #[structopt(long, value_name = "integer")]
+    retention_seconds: Option<u32>,
+
+    /// Maximum size of each partition in bytes, overrides SPU default
+    #[structopt(long, value_name = "bytes")]
+    max_partition_size: Option<u64>,
+
+    /// Maximum size of each log segment in bytes, overrides SPU default
+    #[structopt(long, value_name = "bytes")]
+    segment_size: Option<u32>,
+
+    /// Number of writes before flushing to disk, overrides SPU default
+    #[structopt(long, value_name = "integer")]
+    flush_write_count: Option<u32>,
+
+    /// Idle time in milliseconds before flushing to disk, overrides SPU default
+    #[structopt(long, value_name = "integer")]
+    flush_idle_msec: Option<u32>,
+
+    /// Name of a registered SmartStream applied to records before they are written
+    #[structopt(long, value_name = "name")]
+    smart_stream: Option<String>,
+
+    /// How the SmartStream is applied to produced records
+    #[structopt(
+        long,
+        value_name = "kind",
+        possible_values = &TopicSmartStreamKindOpt::variants(),
+        case_insensitive = true,
+        default_value = "filter"
+    )]
+    smart_stream_kind: TopicSmartStreamKindOpt,
+
+    /// Keep only latest record of each key instead of removing old segments
+    #[structopt(
+        long,
+        value_name = "policy",
+        possible_values = &CleanupPolicyOpt::variants(),
+        case_insensitive = true
+    )]
+    cleanup_policy: Option<CleanupPolicyOpt>,
+}
+
+arg_enum! {
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    #[allow(non_camel_case_types)]
+    pub enum TopicSmartStreamKindOpt {
+        filter,
+        map,
+    }
+}
+
+arg_enum! {
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    #[allow(non_camel_case_types)]
+    pub enum CleanupPolicyOpt {
+        delete,
+        compact,
+    }
 }
 
 impl CreateTopicOpt {
@@ -99,9 +161,12 @@ impl CreateTopicOpt {
     fn validate(self) -> Result<(String, TopicSpec), ConsumerError> {
         use fluvio::metadata::topic::PartitionMaps;
         use fluvio::metadata::topic::TopicReplicaParam;
+        use fluvio::metadata::topic::TopicStorageConfig;
+        use fluvio::metadata::topic::{TopicSmartStream, TopicSmartStreamKind};
+        use fluvio::metadata::topic::CleanupPolicy;
         use load::PartitionLoad;
 
-        let topic = if let Some(replica_assign_file) = &self.replica_assignment {
+        let mut topic = if let Some(replica_assign_file) = &self.replica_assignment {
             TopicSpec::Assigned(
                 PartitionMaps::file_decode(replica_assign_file).map_err(|err| {
                     IoError::new(
@@ -114,12 +179,41 @@ impl CreateTopicOpt {
                 })?,
             )
         } else {
-            TopicSpec::Computed(TopicReplicaParam {
-                partitions: self.partitions,
-                replication_factor: self.replication as i32,
-                ignore_rack_assignment: self.ignore_rack_assigment,
-            })
+            TopicSpec::Computed(TopicReplicaParam::new(
+                self.partitions,
+                self.replication as i32,
+                self.ignore_rack_assigment,
+            ))
+        };
+
+        let storage = TopicStorageConfig {
+            retention_seconds: self.retention_seconds,
+            max_partition_size: self.max_partition_size,
+            segment_size: self.segment_size,
+            flush_write_count: self.flush_write_count,
+            flush_idle_msec: self.flush_idle_msec,
         };
+        if !storage.is_empty() {
+            storage
+                .validate()
+                .map_err(|err| ConsumerError::InvalidArg(err.to_string()))?;
+            topic.set_storage(storage);
+        }
+
+        if let Some(name) = self.smart_stream {
+            let kind = match self.smart_stream_kind {
+                TopicSmartStreamKindOpt::filter => TopicSmartStreamKind::Filter,
+                TopicSmartStreamKindOpt::map => TopicSmartStreamKind::Map,
+            };
+            topic.set_smart_stream(TopicSmartStream::new(name, kind));
+        }
+
+        if let Some(cleanup_policy) = self.cleanup_policy {
+            topic.set_cleanup_policy(match cleanup_policy {
+                CleanupPolicyOpt::delete => CleanupPolicy::Delete,
+                CleanupPolicyOpt::compact => CleanupPolicy::Compact,
+            });
+        }
 
         let is_valid = hostname_validator::is_valid(&self.topic);
         if !is_valid {
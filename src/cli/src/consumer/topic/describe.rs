@@ -144,6 +144,21 @@ mod display {
                 }
             }
 
+            if let Some(storage) = spec.storage() {
+                key_values.push(("Storage".to_owned(), Some(storage.to_string())));
+            }
+
+            if let Some(smart_stream) = spec.smart_stream() {
+                key_values.push(("SmartStream".to_owned(), Some(smart_stream.to_string())));
+            }
+
+            if let Some(cleanup_policy) = spec.cleanup_policy() {
+                key_values.push((
+                    "Cleanup Policy".to_owned(),
+                    Some(cleanup_policy.to_string()),
+                ));
+            }
+
             key_values.push((
                 "Status".to_owned(),
                 Some(status.resolution.resolution_label().to_string()),
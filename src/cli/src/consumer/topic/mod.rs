@@ -1,11 +1,13 @@
 use std::sync::Arc;
 use structopt::StructOpt;
 
+mod alter;
 mod create;
 mod delete;
 mod describe;
 mod list;
 
+use alter::AlterTopicOpt;
 use create::CreateTopicOpt;
 use delete::DeleteTopicOpt;
 use describe::DescribeTopicsOpt;
@@ -35,6 +37,13 @@ pub enum TopicCmd {
     )]
     Delete(DeleteTopicOpt),
 
+    /// Change a Topic with the given name
+    #[structopt(
+        name = "alter",
+        template = COMMAND_TEMPLATE,
+    )]
+    Alter(AlterTopicOpt),
+
     /// Print detailed information about a Topic
     #[structopt(
         name = "describe",
@@ -59,6 +68,9 @@ impl TopicCmd {
             Self::Delete(delete) => {
                 delete.process(fluvio).await?;
             }
+            Self::Alter(alter) => {
+                alter.process(fluvio).await?;
+            }
             Self::Describe(describe) => {
                 describe.process(out, fluvio).await?;
             }
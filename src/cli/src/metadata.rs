@@ -5,6 +5,7 @@ use structopt::StructOpt;
 use crate::Result;
 use crate::consumer::TopicCmd;
 use crate::consumer::PartitionCmd;
+use crate::consumer::SmartStreamCmd;
 use crate::consumer::ConsumeOpt;
 use crate::consumer::ProduceOpt;
 use fluvio_command::CommandExt;
@@ -26,6 +27,7 @@ impl MetadataOpt {
         let mut metadata = vec![
             TopicCmd::metadata(),
             PartitionCmd::metadata(),
+            SmartStreamCmd::metadata(),
             ProduceOpt::metadata(),
             ConsumeOpt::metadata(),
         ];
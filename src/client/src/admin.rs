@@ -4,15 +4,17 @@ use std::fmt::Display;
 use tracing::debug;
 use dataplane::core::Encoder;
 use dataplane::core::Decoder;
-use fluvio_sc_schema::objects::{Metadata, AllCreatableSpec};
-use fluvio_sc_schema::AdminRequest;
+use dataplane::ErrorCode;
+use fluvio_sc_schema::objects::{Metadata, AllCreatableSpec, AllUpdatableSpec};
+use fluvio_sc_schema::{AdminRequest, Status};
+use fluvio_sc_schema::election::ElectLeadersRequest;
 use fluvio_socket::FlvSocketError;
 use fluvio_socket::AllMultiplexerSocket;
 use fluvio_future::native_tls::AllDomainConnector;
 
 use crate::sockets::{ClientConfig, VersionedSerialSocket, SerialFrame};
 use crate::{FluvioError, FluvioConfig};
-use crate::metadata::objects::{ListResponse, ListSpec, DeleteSpec, CreateRequest};
+use crate::metadata::objects::{ListResponse, ListSpec, DeleteSpec, CreateRequest, UpdateRequest};
 use crate::config::ConfigFile;
 
 /// An interface for managing a Fluvio cluster
@@ -120,6 +122,36 @@ impl FluvioAdmin {
         self.0.send_receive(request).await
     }
 
+    /// send request which changes metadata.  only leader SC accepts it,
+    /// so other configured SCs are tried when connected SC is not leader
+    async fn send_to_leader<R>(&mut self, request: R) -> Result<(), FluvioError>
+    where
+        R: AdminRequest<Response = Status> + Clone + Send + Sync,
+    {
+        let mut remaining = self.0.config().endpoints().len();
+        loop {
+            let status = self.send_receive(request.clone()).await?;
+            remaining = remaining.saturating_sub(1);
+            if status.error_code == ErrorCode::ScNotLeader && remaining > 0 {
+                debug!("connected SC is not leader, trying next SC");
+                self.connect_next_sc().await?;
+                continue;
+            }
+            status.as_result()?;
+            return Ok(());
+        }
+    }
+
+    async fn connect_next_sc(&mut self) -> Result<(), FluvioError> {
+        let inner_client = self.0.config().with_next_endpoint().connect().await?;
+        debug!("connected to cluster at: {}", inner_client.config().addr());
+
+        let (socket, config, versions) = inner_client.split();
+        let socket = AllMultiplexerSocket::shared(socket);
+        self.0 = VersionedSerialSocket::new(socket, config, versions);
+        Ok(())
+    }
+
     /// create new object
     pub async fn create<S>(
         &mut self,
@@ -136,9 +168,32 @@ impl FluvioAdmin {
             spec: spec.into(),
         };
 
-        self.send_receive(create_request).await?.as_result()?;
+        self.send_to_leader(create_request).await
+    }
 
-        Ok(())
+    /// update existing object
+    pub async fn update<S>(
+        &mut self,
+        name: String,
+        dry_run: bool,
+        spec: S,
+    ) -> Result<(), FluvioError>
+    where
+        S: Into<AllUpdatableSpec>,
+    {
+        let update_request = UpdateRequest {
+            name,
+            dry_run,
+            spec: spec.into(),
+        };
+
+        self.send_to_leader(update_request).await
+    }
+
+    /// move leadership of partitions back to preferred replicas which are in sync,
+    /// partitions of all topics if topics are empty
+    pub async fn elect_leaders(&mut self, topics: Vec<String>) -> Result<(), FluvioError> {
+        self.send_to_leader(ElectLeadersRequest { topics }).await
     }
 
     /// delete object by key
@@ -149,8 +204,7 @@ impl FluvioAdmin {
         K: Into<S::DeleteKey>,
     {
         let delete_request = S::into_request(key);
-        self.send_receive(delete_request).await?.as_result()?;
-        Ok(())
+        self.send_to_leader(delete_request).await
     }
 
     pub async fn list<S, F>(&mut self, filters: F) -> Result<Vec<Metadata<S>>, FluvioError>
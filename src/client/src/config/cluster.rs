@@ -11,7 +11,8 @@ use crate::config::TlsPolicy;
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[non_exhaustive]
 pub struct FluvioConfig {
-    /// The address to connect to the Fluvio cluster
+    /// The address to connect to the Fluvio cluster.
+    /// Addresses of SC replicas are separated by comma, client fails over between them
     // TODO use a validated address type.
     // We don't want to have a "" address.
     #[serde(alias = "addr")]
@@ -4,9 +4,11 @@ use futures_util::stream::Stream;
 use tracing::{debug, error, trace};
 use once_cell::sync::Lazy;
 use futures_util::future::{Either, err};
-use futures_util::stream::{StreamExt, once, iter};
+use futures_util::stream::{StreamExt, once, iter, select_all};
 
-use fluvio_spu_schema::server::stream_fetch::{DefaultStreamFetchRequest, DefaultStreamFetchResponse};
+use fluvio_spu_schema::server::stream_fetch::{
+    DefaultStreamFetchRequest, DefaultStreamFetchResponse, SmartStreamKind,
+};
 use dataplane::Isolation;
 use dataplane::ReplicaKey;
 use dataplane::ErrorCode;
@@ -17,6 +19,7 @@ use dataplane::fetch::FetchablePartitionResponse;
 use dataplane::record::RecordSet;
 use dataplane::record::DefaultRecord;
 use dataplane::batch::DefaultBatch;
+use dataplane::transaction::committed_batches;
 use fluvio_types::event::offsets::OffsetPublisher;
 
 use crate::FluvioError;
@@ -244,7 +247,7 @@ impl PartitionConsumer {
     pub async fn stream(
         &self,
         offset: Offset,
-    ) -> Result<impl Stream<Item = Result<Record, FluvioError>>, FluvioError> {
+    ) -> Result<impl Stream<Item = Result<Record, FluvioError>> + 'static, FluvioError> {
         let stream = self
             .stream_with_config(offset, ConsumerConfig::default())
             .await?;
@@ -293,16 +296,18 @@ impl PartitionConsumer {
         &self,
         offset: Offset,
         config: ConsumerConfig,
-    ) -> Result<impl Stream<Item = Result<Record, FluvioError>>, FluvioError> {
+    ) -> Result<impl Stream<Item = Result<Record, FluvioError>> + 'static, FluvioError> {
+        let partition = self.partition;
         let stream = self.stream_batches_with_config(offset, config).await?;
         let flattened =
-            stream.flat_map(|result: Result<DefaultBatch, _>| match result {
+            stream.flat_map(move |result: Result<DefaultBatch, _>| match result {
                 Err(e) => Either::Right(once(err(e))),
                 Ok(batch) => {
                     let base_offset = batch.base_offset;
                     let records = batch.own_records().into_iter().enumerate().map(
                         move |(relative, record)| {
                             Ok(Record {
+                                partition,
                                 offset: base_offset + relative as i64,
                                 record,
                             })
@@ -343,12 +348,21 @@ impl PartitionConsumer {
         &self,
         offset: Offset,
         config: ConsumerConfig,
-    ) -> Result<impl Stream<Item = Result<DefaultBatch, FluvioError>>, FluvioError> {
+    ) -> Result<impl Stream<Item = Result<DefaultBatch, FluvioError>> + 'static, FluvioError> {
         let stream = self.request_stream(offset, config).await?;
         let flattened = stream.flat_map(|batch_result: Result<DefaultStreamFetchResponse, _>| {
             let response: DefaultStreamFetchResponse = match batch_result {
                 // If error code is None, continue
                 Ok(response) if response.partition.error_code == ErrorCode::None => response,
+                // SmartStream failed on record, report where it failed
+                Ok(DefaultStreamFetchResponse {
+                    smart_stream_error: Some(smart_stream_error),
+                    ..
+                }) => {
+                    return Either::Right(once(err(FluvioError::SmartStreamRuntimeError(
+                        smart_stream_error,
+                    ))));
+                }
                 // If error code is anything else, wrap it in an error
                 Ok(response) => {
                     let code = response.partition.error_code;
@@ -359,7 +373,12 @@ impl PartitionConsumer {
                 Err(e) => return Either::Right(once(err(e))),
             };
 
-            let batches = response.partition.records.batches.into_iter().map(Ok);
+            // aborted transactions are only returned with read committed isolation,
+            // otherwise only transaction markers are removed
+            let aborted = response.partition.aborted.unwrap_or_default();
+            let batches = committed_batches(response.partition.records.batches, &aborted)
+                .into_iter()
+                .map(Ok);
             Either::Left(iter(batches))
         });
 
@@ -373,11 +392,16 @@ impl PartitionConsumer {
         &self,
         offset: Offset,
         config: ConsumerConfig,
-    ) -> Result<impl Stream<Item = Result<DefaultStreamFetchResponse, FluvioError>>, FluvioError>
-    {
+    ) -> Result<
+        impl Stream<Item = Result<DefaultStreamFetchResponse, FluvioError>> + 'static,
+        FluvioError,
+    > {
         use fluvio_future::task::spawn;
         use futures_util::stream::empty;
-        use fluvio_spu_schema::server::stream_fetch::WASM_MODULE_API;
+        use fluvio_spu_schema::server::stream_fetch::{
+            WASM_MODULE_API, SMART_STREAM_KIND_API, SMART_STREAM_AGGREGATE_API,
+            SMART_STREAM_NAME_API,
+        };
         use fluvio_protocol::api::Request;
 
         let replica = ReplicaKey::new(&self.topic, self.partition);
@@ -408,12 +432,35 @@ impl PartitionConsumer {
             .lookup_version(DefaultStreamFetchRequest::API_KEY)
             .unwrap_or((WASM_MODULE_API - 1) as i16);
 
-        if !config.wasm_module.is_empty() {
-            if stream_fetch_version >= WASM_MODULE_API as i16 {
-                stream_request.wasm_module = config.wasm_module;
-            } else {
+        if !config.wasm_module.is_empty() || !config.smart_stream_name.is_empty() {
+            if stream_fetch_version < WASM_MODULE_API as i16 {
                 return Err(FluvioError::Other("SPU does not support WASM".to_owned()));
             }
+            if config.smart_stream_kind != SmartStreamKind::Filter
+                && stream_fetch_version < SMART_STREAM_KIND_API
+            {
+                return Err(FluvioError::Other(format!(
+                    "SPU does not support SmartStream {:?}",
+                    config.smart_stream_kind
+                )));
+            }
+            if config.smart_stream_kind == SmartStreamKind::Aggregate
+                && stream_fetch_version < SMART_STREAM_AGGREGATE_API
+            {
+                return Err(FluvioError::Other(
+                    "SPU does not support SmartStream Aggregate".to_owned(),
+                ));
+            }
+            if !config.smart_stream_name.is_empty() && stream_fetch_version < SMART_STREAM_NAME_API
+            {
+                return Err(FluvioError::Other(
+                    "SPU does not support registered SmartStream".to_owned(),
+                ));
+            }
+            stream_request.wasm_module = config.wasm_module;
+            stream_request.smart_stream_name = config.smart_stream_name;
+            stream_request.smart_stream_kind = config.smart_stream_kind;
+            stream_request.aggregate_accumulator = config.aggregate_accumulator;
         }
 
         let mut stream = self
@@ -493,6 +540,328 @@ impl PartitionConsumer {
     }
 }
 
+/// An interface for consuming events from all or some partitions of a topic
+///
+/// Records of all selected partitions are merged into a single stream,
+/// each [`Record`] reports the partition it was read from. When leader of a partition
+/// changes, streaming of the partition is resumed from the new leader.
+/// Streaming of a partition stops after an error which reconnecting can't resolve,
+/// such as deleted topic or denied permission, is returned.
+///
+/// # Example
+///
+/// ```no_run
+/// # use fluvio::{Fluvio, Offset, FluvioError};
+/// # mod futures {
+/// #     pub use futures_util::stream::StreamExt;
+/// # }
+/// # async fn example(fluvio: &Fluvio) -> Result<(), FluvioError> {
+/// use futures::StreamExt;
+/// let consumer = fluvio.consumer("my-topic").await?;
+/// let mut stream = consumer.stream(Offset::beginning()).await?;
+/// while let Some(Ok(record)) = stream.next().await {
+///     let value = String::from_utf8_lossy(record.value());
+///     println!("Got record: partition={}, value={}", record.partition(), value);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`Record`]: struct.Record.html
+pub struct MultiplePartitionConsumer {
+    topic: String,
+    partitions: Option<Vec<i32>>,
+    pool: Arc<SpuPool>,
+}
+
+impl MultiplePartitionConsumer {
+    pub(crate) fn new(topic: String, pool: Arc<SpuPool>) -> Self {
+        Self {
+            topic,
+            partitions: None,
+            pool,
+        }
+    }
+
+    /// Consume only given partitions instead of all partitions of the topic
+    pub fn with_partitions(mut self, partitions: Vec<i32>) -> Self {
+        self.partitions = Some(partitions);
+        self
+    }
+
+    /// Returns the name of the Topic that this consumer reads from
+    pub fn topic(&self) -> &str {
+        &self.topic
+    }
+
+    /// Continuously streams events from a particular offset in each selected partition
+    pub async fn stream(
+        &self,
+        offset: Offset,
+    ) -> Result<impl Stream<Item = Result<Record, FluvioError>> + 'static, FluvioError> {
+        self.stream_with_config(offset, ConsumerConfig::default())
+            .await
+    }
+
+    /// Continuously streams events from a particular offset in each selected partition,
+    /// using custom [`ConsumerConfig`] for every partition
+    ///
+    /// [`ConsumerConfig`]: struct.ConsumerConfig.html
+    pub async fn stream_with_config(
+        &self,
+        offset: Offset,
+        config: ConsumerConfig,
+    ) -> Result<impl Stream<Item = Result<Record, FluvioError>> + 'static, FluvioError> {
+        let partitions = match &self.partitions {
+            Some(partitions) => partitions.clone(),
+            None => {
+                let topic = self
+                    .pool
+                    .metadata
+                    .topics()
+                    .lookup_by_key(&self.topic)
+                    .await?
+                    .ok_or_else(|| FluvioError::TopicNotFound(self.topic.to_string()))?;
+                (0..topic.spec.partitions()).collect()
+            }
+        };
+        debug!(topic = %self.topic, ?partitions, "streaming partitions");
+
+        let streams = partitions.into_iter().map(|partition| {
+            let consumer = PartitionConsumer::new(self.topic.clone(), partition, self.pool.clone());
+            follow_leader::stream(consumer, offset.clone(), config.clone())
+        });
+
+        Ok(select_all(streams))
+    }
+}
+
+/// stream of partition which reconnects when partition leader changes or connection to it is lost
+pub(crate) mod follow_leader {
+
+    use std::time::Duration;
+
+    use tracing::debug;
+    use tokio::select;
+    use futures_util::stream::{BoxStream, StreamExt, unfold};
+
+    use fluvio_future::timer::sleep;
+    use fluvio_types::SpuId;
+    use fluvio_sc_schema::ApiError;
+    use dataplane::{ErrorCode, ReplicaKey};
+
+    use crate::FluvioError;
+    use crate::offset::Offset;
+    use crate::metadata::partition::PartitionSpec;
+    use crate::metadata::store::ChangeListener;
+    use crate::sync::AlwaysNewContext;
+
+    use super::{PartitionConsumer, ConsumerConfig, Record};
+
+    const RECONNECT_BACKOFF: Duration = Duration::from_millis(500);
+
+    struct FollowState {
+        consumer: PartitionConsumer,
+        config: ConsumerConfig,
+        /// initial offset until first record is received, then offset of next record
+        offset: Offset,
+        current: Option<(SpuId, BoxStream<'static, Result<Record, FluvioError>>)>,
+        partitions: ChangeListener<PartitionSpec, AlwaysNewContext>,
+        reconnecting: bool,
+        /// stream has ended with error which is not resolved by reconnecting
+        done: bool,
+    }
+
+    impl FollowState {
+        async fn leader(&self) -> Option<SpuId> {
+            let replica = ReplicaKey::new(&self.consumer.topic, self.consumer.partition);
+            self.consumer
+                .pool
+                .metadata
+                .partitions()
+                .store()
+                .value(&replica)
+                .await
+                .map(|partition| partition.spec.leader)
+        }
+
+        async fn connect(
+            &mut self,
+        ) -> Result<(SpuId, BoxStream<'static, Result<Record, FluvioError>>), FluvioError> {
+            if self.reconnecting {
+                sleep(RECONNECT_BACKOFF).await;
+            }
+            self.reconnecting = true;
+
+            let leader = self.leader().await.ok_or_else(|| {
+                FluvioError::PartitionNotFound(self.consumer.topic.clone(), self.consumer.partition)
+            })?;
+            debug!(
+                topic = %self.consumer.topic,
+                partition = self.consumer.partition,
+                leader,
+                offset = ?self.offset,
+                "streaming from partition leader"
+            );
+            let stream = self
+                .consumer
+                .stream_with_config(self.offset.clone(), self.config.clone())
+                .await?;
+            Ok((leader, stream.boxed()))
+        }
+    }
+
+    /// partition was moved away from SPU, stream is restarted from current leader
+    fn is_leader_error(err: &FluvioError) -> bool {
+        match err {
+            FluvioError::ApiError(ApiError::Code(code, _)) => matches!(
+                code,
+                ErrorCode::NotLeaderForPartition | ErrorCode::PartitionNotLeader
+            ),
+            _ => false,
+        }
+    }
+
+    /// error which may go away when stream is reconnected, such as lost connection
+    /// or partition which is being moved. other errors, such as deleted topic
+    /// or denied permission, end the stream
+    fn is_retriable_error(err: &FluvioError) -> bool {
+        match err {
+            FluvioError::IoError(_) | FluvioError::FlvSocketError(_) => true,
+            FluvioError::SPUNotFound(_) => true,
+            FluvioError::ApiError(ApiError::Code(code, _)) => matches!(
+                code,
+                ErrorCode::NotLeaderForPartition
+                    | ErrorCode::PartitionNotLeader
+                    | ErrorCode::RequestTimedOut
+                    | ErrorCode::StorageError
+                    | ErrorCode::SpuOffline
+                    | ErrorCode::SpuNotFound
+                    | ErrorCode::TopicPendingInitialization
+                    | ErrorCode::PartitionPendingInitialization
+                    | ErrorCode::ScNotLeader
+            ),
+            _ => false,
+        }
+    }
+
+    fn error_item(
+        mut state: FollowState,
+        err: FluvioError,
+    ) -> Option<(Result<Record, FluvioError>, FollowState)> {
+        if !is_retriable_error(&err) {
+            debug!(%err, "stream of partition stopped");
+            state.done = true;
+        }
+        Some((Err(err), state))
+    }
+
+    async fn next_record(
+        mut state: FollowState,
+    ) -> Option<(Result<Record, FluvioError>, FollowState)> {
+        if state.done {
+            return None;
+        }
+
+        loop {
+            let (leader, mut stream) = match state.current.take() {
+                Some(current) => current,
+                None => match state.connect().await {
+                    Ok(current) => current,
+                    Err(err) => return error_item(state, err),
+                },
+            };
+
+            select! {
+                item = stream.next() => match item {
+                    Some(Ok(record)) => {
+                        state.reconnecting = false;
+                        state.offset = Offset::absolute(record.offset() + 1)
+                            .expect("offset of record is not negative");
+                        state.current = Some((leader, stream));
+                        return Some((Ok(record), state));
+                    }
+                    Some(Err(err)) if is_leader_error(&err) => {
+                        debug!(leader, "replica is no longer leader, reconnecting");
+                    }
+                    Some(Err(err)) => return error_item(state, err),
+                    None => {
+                        debug!(leader, "stream from leader ended, reconnecting");
+                        state.consumer.pool.remove_spu_socket(leader).await;
+                    }
+                },
+                // listener is notified of changes of any partition
+                _ = state.partitions.listen() => {
+                    state.partitions.load_last();
+                    match state.leader().await {
+                        Some(current_leader) if current_leader == leader => {
+                            state.current = Some((leader, stream));
+                        }
+                        Some(current_leader) => {
+                            debug!(leader, current_leader, "partition leader changed, reconnecting");
+                            state.reconnecting = false;
+                        }
+                        None => {
+                            let err = FluvioError::PartitionNotFound(
+                                state.consumer.topic.clone(),
+                                state.consumer.partition,
+                            );
+                            return error_item(state, err);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// stream records of partition starting from offset, following changes of partition leader
+    pub(crate) fn stream(
+        consumer: PartitionConsumer,
+        offset: Offset,
+        config: ConsumerConfig,
+    ) -> BoxStream<'static, Result<Record, FluvioError>> {
+        let mut partitions = consumer
+            .pool
+            .metadata
+            .partitions()
+            .store()
+            .change_listener();
+        partitions.load_last();
+        let state = FollowState {
+            consumer,
+            config,
+            offset,
+            current: None,
+            partitions,
+            reconnecting: false,
+            done: false,
+        };
+        unfold(state, next_record).boxed()
+    }
+
+    #[cfg(test)]
+    mod tests {
+
+        use super::*;
+
+        #[test]
+        fn test_retriable_errors() {
+            let code = |code| FluvioError::ApiError(ApiError::Code(code, None));
+            assert!(is_retriable_error(&code(ErrorCode::NotLeaderForPartition)));
+            assert!(is_retriable_error(&code(ErrorCode::SpuOffline)));
+            assert!(!is_retriable_error(&code(ErrorCode::TopicNotFound)));
+            assert!(!is_retriable_error(&code(ErrorCode::PermissionDenied)));
+            assert!(!is_retriable_error(&FluvioError::TopicNotFound(
+                "test".to_owned()
+            )));
+            assert!(!is_retriable_error(&FluvioError::PartitionNotFound(
+                "test".to_owned(),
+                0
+            )));
+        }
+    }
+}
+
 mod publish_stream {
 
     use std::pin::Pin;
@@ -563,11 +932,14 @@ static MAX_FETCH_BYTES: Lazy<i32> = Lazy::new(|| {
 });
 
 /// Configures the behavior of consumer fetching and streaming
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ConsumerConfig {
     pub(crate) max_bytes: i32,
     pub(crate) isolation: Isolation,
     wasm_module: Vec<u8>,
+    smart_stream_name: String,
+    smart_stream_kind: SmartStreamKind,
+    aggregate_accumulator: Vec<u8>,
 }
 
 impl Default for ConsumerConfig {
@@ -576,6 +948,9 @@ impl Default for ConsumerConfig {
             max_bytes: *MAX_FETCH_BYTES,
             isolation: Isolation::default(),
             wasm_module: vec![],
+            smart_stream_name: String::new(),
+            smart_stream_kind: SmartStreamKind::default(),
+            aggregate_accumulator: vec![],
         }
     }
 }
@@ -587,15 +962,65 @@ impl ConsumerConfig {
         self
     }
 
+    /// Isolation of records read, with read committed only records of committed transactions are returned
+    pub fn with_isolation(mut self, isolation: Isolation) -> Self {
+        self.isolation = isolation;
+        self
+    }
+
     /// set wasm filter
     pub fn with_wasm_filter(mut self, bytes: Vec<u8>) -> Self {
         self.wasm_module = bytes;
+        self.smart_stream_kind = SmartStreamKind::Filter;
+        self
+    }
+
+    /// set wasm map, which rewrites key and value of records inside SPU
+    pub fn with_wasm_map(mut self, bytes: Vec<u8>) -> Self {
+        self.wasm_module = bytes;
+        self.smart_stream_kind = SmartStreamKind::Map;
+        self
+    }
+
+    /// set wasm aggregate, which combines each record with running accumulator starting from `accumulator`
+    pub fn with_wasm_aggregate(mut self, bytes: Vec<u8>, accumulator: Vec<u8>) -> Self {
+        self.wasm_module = bytes;
+        self.smart_stream_kind = SmartStreamKind::Aggregate;
+        self.aggregate_accumulator = accumulator;
+        self
+    }
+
+    /// use filter registered in SC with `fluvio smartstream create`
+    pub fn with_smart_stream_filter(mut self, name: impl Into<String>) -> Self {
+        self.smart_stream_name = name.into();
+        self.smart_stream_kind = SmartStreamKind::Filter;
+        self
+    }
+
+    /// use map registered in SC with `fluvio smartstream create`
+    pub fn with_smart_stream_map(mut self, name: impl Into<String>) -> Self {
+        self.smart_stream_name = name.into();
+        self.smart_stream_kind = SmartStreamKind::Map;
+        self
+    }
+
+    /// use aggregate registered in SC with `fluvio smartstream create`
+    pub fn with_smart_stream_aggregate(
+        mut self,
+        name: impl Into<String>,
+        accumulator: Vec<u8>,
+    ) -> Self {
+        self.smart_stream_name = name.into();
+        self.smart_stream_kind = SmartStreamKind::Aggregate;
+        self.aggregate_accumulator = accumulator;
         self
     }
 }
 
 /// The individual record for a given stream.
 pub struct Record {
+    /// The partition this Record was read from
+    partition: i32,
     /// The offset of this Record into its partition
     offset: i64,
     /// The Record contents
@@ -603,6 +1028,11 @@ pub struct Record {
 }
 
 impl Record {
+    /// The partition this Record was read from
+    pub fn partition(&self) -> i32 {
+        self.partition
+    }
+
     /// The offset from the initial offset for a given stream.
     pub fn offset(&self) -> i64 {
         self.offset
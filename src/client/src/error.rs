@@ -3,6 +3,8 @@ use thiserror::Error;
 
 use fluvio_socket::FlvSocketError;
 use fluvio_sc_schema::ApiError;
+use dataplane::ErrorCode;
+use dataplane::smartstream::SmartStreamRuntimeError;
 use crate::config::ConfigError;
 use semver::Version;
 
@@ -30,6 +32,13 @@ pub enum FluvioError {
         cluster_version: Version,
         client_minimum_version: Version,
     },
+    #[error("Failed to produce records: {}", .0.to_sentence())]
+    ProduceError(ErrorCode),
+    #[error("Transaction error: {0}")]
+    TransactionError(String),
+    /// SmartStream module failed processing record, consumer may resume after its offset
+    #[error(transparent)]
+    SmartStreamRuntimeError(#[from] SmartStreamRuntimeError),
     #[error("Unknown error: {0}")]
     Other(String),
 }
@@ -11,8 +11,10 @@ use semver::Version;
 
 use crate::config::ConfigFile;
 use crate::admin::FluvioAdmin;
-use crate::TopicProducer;
-use crate::PartitionConsumer;
+use crate::{TopicProducer, ProducerConfig};
+use crate::producer::{IdempotentProducer, TransactionManager};
+use crate::{PartitionConsumer, MultiplePartitionConsumer};
+use crate::{GroupConsumer, GroupConfig};
 use crate::FluvioError;
 use crate::FluvioConfig;
 use crate::spu::SpuPool;
@@ -110,19 +112,66 @@ impl Fluvio {
     pub async fn topic_producer<S: Into<String>>(
         &self,
         topic: S,
+    ) -> Result<TopicProducer, FluvioError> {
+        self.topic_producer_with_config(topic, ProducerConfig::default())
+            .await
+    }
+
+    /// Creates a new `TopicProducer` for the given topic name with custom configuration
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use fluvio::{Fluvio, FluvioError, ProducerConfig, Compression};
+    /// # async fn do_produce_to_topic(fluvio: &Fluvio) -> Result<(), FluvioError> {
+    /// let config = ProducerConfig::default().with_compression(Compression::Gzip);
+    /// let producer = fluvio.topic_producer_with_config("my-topic", config).await?;
+    /// producer.send("Key", "Hello, Fluvio!").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn topic_producer_with_config<S: Into<String>>(
+        &self,
+        topic: S,
+        config: ProducerConfig,
     ) -> Result<TopicProducer, FluvioError> {
         let topic = topic.into();
         debug!(topic = &*topic, "Creating producer");
-        Ok(TopicProducer::new(topic, self.spu_pool()?))
+        let idempotent = if config.idempotent {
+            Some(Arc::new(
+                IdempotentProducer::init(
+                    config.transactional_id.clone(),
+                    config.transaction_timeout,
+                    self.create_serial_client().await,
+                )
+                .await?,
+            ))
+        } else {
+            None
+        };
+        let transaction = match (&config.transactional_id, &idempotent) {
+            (Some(transactional_id), Some(idempotent)) => Some(TransactionManager::new(
+                transactional_id.clone(),
+                idempotent.clone(),
+                self.create_serial_client().await,
+            )),
+            _ => None,
+        };
+        Ok(TopicProducer::new(
+            topic,
+            self.spu_pool()?,
+            config,
+            idempotent,
+            transaction,
+        ))
     }
 
     /// Creates a new `PartitionConsumer` for the given topic and partition
     ///
-    /// Currently, consumers are scoped to both a specific Fluvio topic
-    /// _and_ to a particular partition within that topic. That means that
-    /// if you have a topic with multiple partitions, then in order to receive
-    /// all of the events in all of the partitions, you will need to create
-    /// one consumer per partition.
+    /// Partition consumers are scoped to both a specific Fluvio topic
+    /// _and_ to a particular partition within that topic. To receive
+    /// all of the events in all of the partitions of a topic with a single
+    /// stream, use [`consumer`] instead.
     ///
     /// # Example
     ///
@@ -137,6 +186,8 @@ impl Fluvio {
     /// # Ok(())
     /// # }
     /// ```
+    ///
+    /// [`consumer`]: struct.Fluvio.html#method.consumer
     pub async fn partition_consumer<S: Into<String>>(
         &self,
         topic: S,
@@ -147,6 +198,78 @@ impl Fluvio {
         Ok(PartitionConsumer::new(topic, partition, self.spu_pool()?))
     }
 
+    /// Creates a new `MultiplePartitionConsumer` for all partitions of the given topic
+    ///
+    /// Records of every partition are merged into a single stream.
+    /// Use [`with_partitions`] to consume only some of the partitions.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use fluvio::{Fluvio, Offset, FluvioError};
+    /// # async fn do_consume_topic(fluvio: &Fluvio) -> Result<(), FluvioError> {
+    /// let consumer = fluvio.consumer("my-topic").await?;
+    /// let stream = consumer.stream(Offset::beginning()).await?;
+    ///
+    /// let some_partitions = fluvio.consumer("my-topic").await?.with_partitions(vec![0, 2]);
+    /// let stream = some_partitions.stream(Offset::beginning()).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`with_partitions`]: struct.MultiplePartitionConsumer.html#method.with_partitions
+    pub async fn consumer<S: Into<String>>(
+        &self,
+        topic: S,
+    ) -> Result<MultiplePartitionConsumer, FluvioError> {
+        let topic = topic.into();
+        debug!(topic = &*topic, "Creating multiple partition consumer");
+        Ok(MultiplePartitionConsumer::new(topic, self.spu_pool()?))
+    }
+
+    /// Creates a new `GroupConsumer` for the given topic and group
+    ///
+    /// Partitions of the topic are shared among consumers of the same group,
+    /// each partition is consumed by only one member at a time.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use fluvio::{Fluvio, Offset, FluvioError};
+    /// # async fn do_consume_in_group(fluvio: &Fluvio) -> Result<(), FluvioError> {
+    /// let consumer = fluvio.group_consumer("my-topic", "my-group").await?;
+    /// let stream = consumer.stream(Offset::beginning()).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn group_consumer<S: Into<String>, G: Into<String>>(
+        &self,
+        topic: S,
+        group: G,
+    ) -> Result<GroupConsumer, FluvioError> {
+        self.group_consumer_with_config(topic, group, GroupConfig::default())
+            .await
+    }
+
+    /// Creates a new `GroupConsumer` with custom group configuration
+    pub async fn group_consumer_with_config<S: Into<String>, G: Into<String>>(
+        &self,
+        topic: S,
+        group: G,
+        config: GroupConfig,
+    ) -> Result<GroupConsumer, FluvioError> {
+        let topic = topic.into();
+        let group = group.into();
+        debug!(topic = &*topic, group = &*group, "Creating group consumer");
+        Ok(GroupConsumer::new(
+            group,
+            topic,
+            self.spu_pool()?,
+            self.create_serial_client().await,
+            config,
+        ))
+    }
+
     /// Provides an interface for managing a Fluvio cluster
     ///
     /// # Example
@@ -0,0 +1,379 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tracing::{debug, error};
+use async_mutex::Mutex;
+use async_channel::{bounded, Sender};
+use futures_util::future::ready;
+use futures_util::stream::{self, BoxStream, Stream, StreamExt};
+
+use fluvio_future::task::spawn;
+use fluvio_future::timer::sleep;
+use fluvio_types::PartitionId;
+use fluvio_sc_schema::ApiError;
+use fluvio_sc_schema::group::*;
+use dataplane::ErrorCode;
+
+use crate::FluvioError;
+use crate::consumer::{PartitionConsumer, ConsumerConfig, Record};
+use crate::consumer::follow_leader;
+use crate::offset::Offset;
+use crate::spu::SpuPool;
+use crate::sockets::{SerialFrame, VersionedSerialSocket};
+
+const DEFAULT_SESSION_TIMEOUT: Duration = Duration::from_secs(10);
+const DEFAULT_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(3);
+/// wait between joins while other members has not rejoined the group
+const REJOIN_BACKOFF: Duration = Duration::from_millis(200);
+
+/// Configures membership of group consumer
+#[derive(Debug, Clone)]
+pub struct GroupConfig {
+    pub(crate) session_timeout: Duration,
+    pub(crate) heartbeat_interval: Duration,
+    pub(crate) consumer: ConsumerConfig,
+}
+
+impl Default for GroupConfig {
+    fn default() -> Self {
+        Self {
+            session_timeout: DEFAULT_SESSION_TIMEOUT,
+            heartbeat_interval: DEFAULT_HEARTBEAT_INTERVAL,
+            consumer: ConsumerConfig::default(),
+        }
+    }
+}
+
+impl GroupConfig {
+    /// Time after which member is removed from group if SC has not received heartbeat from it
+    pub fn with_session_timeout(mut self, session_timeout: Duration) -> Self {
+        self.session_timeout = session_timeout;
+        self
+    }
+
+    /// Interval between heartbeats, should be well below session timeout
+    pub fn with_heartbeat_interval(mut self, heartbeat_interval: Duration) -> Self {
+        self.heartbeat_interval = heartbeat_interval;
+        self
+    }
+
+    /// Configuration used to stream each assigned partition
+    pub fn with_consumer_config(mut self, consumer: ConsumerConfig) -> Self {
+        self.consumer = consumer;
+        self
+    }
+}
+
+/// membership in current generation of group
+#[derive(Debug, Default)]
+struct Membership {
+    member_id: String,
+    generation: i32,
+    partitions: Vec<PartitionId>,
+}
+
+struct GroupInner {
+    group: String,
+    topic: String,
+    pool: Arc<SpuPool>,
+    client: Mutex<VersionedSerialSocket>,
+    config: GroupConfig,
+    membership: Mutex<Membership>,
+}
+
+/// Consumer which shares partitions of a topic with other members of a named group
+///
+/// SC assigns partitions of the topic among members of the group. When members
+/// join or leave, partitions are reassigned and stream of each member is restarted
+/// with its new partitions. Each partition is resumed from offset committed by the group,
+/// or from the offset given to [`stream`] if group has not committed offset for it yet.
+///
+/// # Example
+///
+/// ```no_run
+/// # use fluvio::{Fluvio, Offset, FluvioError};
+/// # mod futures {
+/// #     pub use futures_util::stream::StreamExt;
+/// # }
+/// # async fn example(fluvio: &Fluvio) -> Result<(), FluvioError> {
+/// use futures::StreamExt;
+/// let consumer = fluvio.group_consumer("my-topic", "my-group").await?;
+/// let mut stream = consumer.stream(Offset::beginning()).await?;
+/// while let Some(Ok(record)) = stream.next().await {
+///     println!("partition: {}, value: {}", record.partition(), String::from_utf8_lossy(record.value()));
+///     consumer.commit_record(&record).await?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`stream`]: struct.GroupConsumer.html#method.stream
+#[derive(Clone)]
+pub struct GroupConsumer {
+    inner: Arc<GroupInner>,
+}
+
+impl GroupConsumer {
+    pub(crate) fn new(
+        group: String,
+        topic: String,
+        pool: Arc<SpuPool>,
+        client: VersionedSerialSocket,
+        config: GroupConfig,
+    ) -> Self {
+        Self {
+            inner: Arc::new(GroupInner {
+                group,
+                topic,
+                pool,
+                client: Mutex::new(client),
+                config,
+                membership: Mutex::new(Membership::default()),
+            }),
+        }
+    }
+
+    /// Returns the name of the group
+    pub fn group(&self) -> &str {
+        &self.inner.group
+    }
+
+    /// Returns the name of the Topic that this consumer reads from
+    pub fn topic(&self) -> &str {
+        &self.inner.topic
+    }
+
+    /// Returns partitions assigned to this member in current generation
+    pub async fn partitions(&self) -> Vec<PartitionId> {
+        self.inner.membership.lock().await.partitions.clone()
+    }
+
+    /// Joins the group and continuously streams records from assigned partitions
+    ///
+    /// Partitions without committed offset are read from `offset`.
+    /// Stream is restarted with new assignment whenever group is rebalanced.
+    pub async fn stream(
+        &self,
+        offset: Offset,
+    ) -> Result<impl Stream<Item = Result<Record, FluvioError>>, FluvioError> {
+        let first = self.inner.clone().generation_stream(offset.clone()).await?;
+
+        // after generation ends, rejoin group and continue with new assignment
+        let inner = self.inner.clone();
+        let next_generations = stream::unfold(Some(inner), move |inner| {
+            let offset = offset.clone();
+            async move {
+                let inner = inner?;
+                match inner.clone().generation_stream(offset).await {
+                    Ok(stream) => Some((stream, Some(inner))),
+                    Err(err) => Some((stream::once(ready(Err(err))).boxed(), None)),
+                }
+            }
+        });
+
+        Ok(stream::once(ready(first)).chain(next_generations).flatten())
+    }
+
+    /// Commits offset of next record to be consumed from partition
+    pub async fn commit(&self, partition: PartitionId, offset: i64) -> Result<(), FluvioError> {
+        self.commit_offsets(vec![(partition, offset)]).await
+    }
+
+    /// Commits record as consumed, group resumes after it
+    pub async fn commit_record(&self, record: &Record) -> Result<(), FluvioError> {
+        self.commit(record.partition(), record.offset() + 1).await
+    }
+
+    /// Commits offsets of next records to be consumed from partitions
+    ///
+    /// Commit fails if partition is no longer assigned to this member.
+    pub async fn commit_offsets(
+        &self,
+        offsets: Vec<(PartitionId, i64)>,
+    ) -> Result<(), FluvioError> {
+        let (member_id, generation) = self.inner.member().await;
+        let request = OffsetCommitRequest {
+            group: self.inner.group.clone(),
+            member_id,
+            generation,
+            topic: self.inner.topic.clone(),
+            offsets: offsets
+                .into_iter()
+                .map(|(partition, offset)| PartitionOffset { partition, offset })
+                .collect(),
+        };
+        let response = self.inner.client.lock().await.send_receive(request).await?;
+        if response.error_code.is_error() {
+            return Err(ApiError::Code(response.error_code, None).into());
+        }
+        Ok(())
+    }
+
+    /// Leaves the group, partitions of this member are reassigned to other members
+    pub async fn leave(&self) -> Result<(), FluvioError> {
+        let member_id = std::mem::take(&mut *self.inner.membership.lock().await).member_id;
+        if member_id.is_empty() {
+            return Ok(());
+        }
+
+        let request = LeaveGroupRequest {
+            group: self.inner.group.clone(),
+            member_id,
+        };
+        let response = self.inner.client.lock().await.send_receive(request).await?;
+        match response.error_code {
+            ErrorCode::None | ErrorCode::UnknownMemberId => Ok(()),
+            error_code => Err(ApiError::Code(error_code, None).into()),
+        }
+    }
+}
+
+impl GroupInner {
+    async fn member(&self) -> (String, i32) {
+        let membership = self.membership.lock().await;
+        (membership.member_id.clone(), membership.generation)
+    }
+
+    /// join group, known member id keeps membership.
+    /// partitions are assigned once all members has rejoined, so join is retried until then
+    async fn join(&self) -> Result<(), FluvioError> {
+        loop {
+            let (member_id, _) = self.member().await;
+            let request = JoinGroupRequest {
+                group: self.group.clone(),
+                member_id,
+                topic: self.topic.clone(),
+                session_timeout_ms: self.config.session_timeout.as_millis() as i32,
+            };
+            let response = self.client.lock().await.send_receive(request).await?;
+            match response.error_code {
+                ErrorCode::None => {}
+                ErrorCode::RebalanceInProgress => {
+                    debug!(
+                        group = %self.group,
+                        member_id = %response.member_id,
+                        generation = response.generation,
+                        "waiting for other members to rejoin"
+                    );
+                    *self.membership.lock().await = Membership {
+                        member_id: response.member_id,
+                        generation: response.generation,
+                        partitions: vec![],
+                    };
+                    sleep(REJOIN_BACKOFF).await;
+                    continue;
+                }
+                error_code => return Err(ApiError::Code(error_code, None).into()),
+            }
+
+            debug!(
+                group = %self.group,
+                member_id = %response.member_id,
+                generation = response.generation,
+                partitions = ?response.partitions,
+                "joined group"
+            );
+            *self.membership.lock().await = Membership {
+                member_id: response.member_id,
+                generation: response.generation,
+                partitions: response.partitions,
+            };
+            return Ok(());
+        }
+    }
+
+    async fn committed_offsets(
+        &self,
+        partitions: Vec<PartitionId>,
+    ) -> Result<HashMap<PartitionId, i64>, FluvioError> {
+        let request = OffsetFetchRequest {
+            group: self.group.clone(),
+            topic: self.topic.clone(),
+            partitions,
+        };
+        let response = self.client.lock().await.send_receive(request).await?;
+        if response.error_code.is_error() {
+            return Err(ApiError::Code(response.error_code, None).into());
+        }
+        Ok(response
+            .offsets
+            .into_iter()
+            .map(|partition_offset| (partition_offset.partition, partition_offset.offset))
+            .collect())
+    }
+
+    /// join group and stream assigned partitions until group is rebalanced
+    async fn generation_stream(
+        self: Arc<Self>,
+        offset: Offset,
+    ) -> Result<BoxStream<'static, Result<Record, FluvioError>>, FluvioError> {
+        self.join().await?;
+        let (partitions, generation) = {
+            let membership = self.membership.lock().await;
+            (membership.partitions.clone(), membership.generation)
+        };
+        let committed = self.committed_offsets(partitions.clone()).await?;
+
+        let mut streams = vec![];
+        for partition in partitions {
+            let start = match committed.get(&partition) {
+                Some(committed) => Offset::absolute(*committed)?,
+                None => offset.clone(),
+            };
+            debug!(partition, ?start, "streaming assigned partition");
+            let consumer = PartitionConsumer::new(self.topic.clone(), partition, self.pool.clone());
+            streams.push(follow_leader::stream(
+                consumer,
+                start,
+                self.config.consumer.clone(),
+            ));
+        }
+        // member without partitions waits for next rebalance
+        let records = if streams.is_empty() {
+            stream::pending().boxed()
+        } else {
+            stream::select_all(streams).boxed()
+        };
+
+        let (rebalance, rebalance_listener) = bounded(1);
+        spawn(heartbeat_loop(self, generation, rebalance));
+
+        Ok(records
+            .take_until(async move {
+                let _ = rebalance_listener.recv().await;
+            })
+            .boxed())
+    }
+}
+
+/// send heartbeats until group is rebalanced or stream of generation is dropped
+async fn heartbeat_loop(inner: Arc<GroupInner>, generation: i32, rebalance: Sender<()>) {
+    loop {
+        sleep(inner.config.heartbeat_interval).await;
+        if rebalance.is_closed() {
+            debug!(group = %inner.group, generation, "stream dropped, stopping heartbeat");
+            break;
+        }
+
+        let (member_id, _) = inner.member().await;
+        let request = GroupHeartbeatRequest {
+            group: inner.group.clone(),
+            member_id,
+            generation,
+        };
+        let error_code = match inner.client.lock().await.send_receive(request).await {
+            Ok(response) => response.error_code,
+            Err(err) => {
+                error!("error sending group heartbeat: {:?}", err);
+                continue;
+            }
+        };
+
+        if error_code.is_error() {
+            debug!(group = %inner.group, generation, ?error_code, "group rebalanced");
+            let _ = rebalance.send(()).await;
+            break;
+        }
+    }
+}
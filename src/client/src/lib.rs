@@ -86,6 +86,7 @@ mod sockets;
 mod admin;
 mod fluvio;
 pub mod consumer;
+mod group;
 mod producer;
 mod offset;
 mod sync;
@@ -95,9 +96,13 @@ pub mod config;
 
 pub use error::FluvioError;
 pub use config::FluvioConfig;
-pub use producer::TopicProducer;
-pub use consumer::{PartitionConsumer, ConsumerConfig};
+pub use producer::{TopicProducer, ProducerConfig, ProducerAcks, DeliveryHandle, RecordMetadata};
+pub use consumer::{PartitionConsumer, MultiplePartitionConsumer, ConsumerConfig};
+pub use group::{GroupConsumer, GroupConfig};
 pub use offset::Offset;
+pub use dataplane::compression::Compression;
+pub use dataplane::Isolation;
+pub use dataplane::smartstream::SmartStreamRuntimeError;
 
 pub use crate::admin::FluvioAdmin;
 pub use crate::fluvio::Fluvio;
@@ -208,6 +213,10 @@ pub mod metadata {
         pub use fluvio_sc_schema::partition::*;
     }
 
+    pub mod smartstream {
+        pub use fluvio_sc_schema::smartstream::*;
+    }
+
     pub mod objects {
         pub use fluvio_sc_schema::objects::*;
     }
@@ -1,5 +1,6 @@
 use std::io::Error as IoError;
 use std::io::ErrorKind;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use tracing::{debug, trace};
 use dataplane::ReplicaKey;
@@ -14,6 +15,7 @@ pub(crate) enum OffsetInner {
     Absolute(i64),
     FromBeginning(i64),
     FromEnd(i64),
+    FromTimestamp(i64),
 }
 
 /// Describes the location of an event stored in a Fluvio partition
@@ -255,6 +257,35 @@ impl Offset {
         }
     }
 
+    /// Creates an offset pointing to the first event produced at or after given time
+    ///
+    /// Producer stamps events with the time they were sent. The offset is
+    /// resolved to the first batch of events whose timestamp is not older
+    /// than `time`. If there are no such events, it points to the end of the log,
+    /// so only events produced later are read.
+    ///
+    /// Events without timestamp, for example produced by older clients,
+    /// are never selected by time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::time::{Duration, SystemTime};
+    /// # use fluvio::Offset;
+    /// // Creates an offset pointing to events produced in the last 10 minutes
+    /// let ten_minutes_ago = SystemTime::now() - Duration::from_secs(600);
+    /// let offset: Offset = Offset::from_timestamp(ten_minutes_ago);
+    /// ```
+    pub fn from_timestamp(time: SystemTime) -> Offset {
+        let timestamp = time
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis() as i64)
+            .unwrap_or(0);
+        Self {
+            inner: OffsetInner::FromTimestamp(timestamp),
+        }
+    }
+
     /// Converts this offset into an absolute offset
     ///
     /// If this offset is relative from the beginning (i.e. it was created
@@ -267,6 +298,9 @@ impl Offset {
     /// the absolute offset by finding the last stably-committed event and subtracting
     /// the relative offset from it.
     ///
+    /// If this offset was created using [`from_timestamp`], then `to_absolute`
+    /// asks the partition leader for offset of first event at or after the timestamp.
+    ///
     /// Calling `to_absolute` on an offset that is already absolute just returns
     /// that same offset.
     ///
@@ -285,14 +319,24 @@ impl Offset {
             OffsetInner::Absolute(offset) => offset,
             OffsetInner::FromBeginning(offset) => {
                 let replica = ReplicaKey::new(topic, partition);
-                let offsets = fetch_offsets(client, &replica).await?;
+                let offsets = fetch_offsets(client, &replica, None).await?;
                 offsets.start_offset + offset
             }
             OffsetInner::FromEnd(offset) => {
                 let replica = ReplicaKey::new(topic, partition);
-                let offsets = fetch_offsets(client, &replica).await?;
+                let offsets = fetch_offsets(client, &replica, None).await?;
                 offsets.last_stable_offset - offset
             }
+            OffsetInner::FromTimestamp(timestamp) => {
+                let replica = ReplicaKey::new(topic, partition);
+                let offsets = fetch_offsets(client, &replica, Some(timestamp)).await?;
+                offsets.timestamp_offset.ok_or_else(|| {
+                    FluvioError::Other(format!(
+                        "SPU does not support offset lookup by timestamp for: {}",
+                        replica
+                    ))
+                })?
+            }
         };
 
         Ok(offset)
@@ -302,15 +346,19 @@ impl Offset {
 async fn fetch_offsets<F: SerialFrame>(
     client: &mut F,
     replica: &ReplicaKey,
+    timestamp: Option<i64>,
 ) -> Result<FetchOffsetPartitionResponse, FluvioError> {
     debug!("fetching offset for replica: {}", replica);
 
-    let response = client
-        .send_receive(FetchOffsetsRequest::new(
+    let request = match timestamp {
+        Some(timestamp) => FetchOffsetsRequest::with_timestamp(
             replica.topic.to_owned(),
             replica.partition,
-        ))
-        .await?;
+            timestamp,
+        ),
+        None => FetchOffsetsRequest::new(replica.topic.to_owned(), replica.partition),
+    };
+    let response = client.send_receive(request).await?;
 
     trace!(
         "receive fetch response replica: {}, {:#?}",
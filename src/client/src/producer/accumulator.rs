@@ -0,0 +1,301 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use async_channel::{bounded, Sender, Receiver};
+use async_mutex::Mutex;
+use event_listener::{Event, EventListener};
+use tracing::trace;
+
+use dataplane::core::Encoder;
+use dataplane::record::DefaultRecord;
+use dataplane::Offset;
+use fluvio_types::PartitionId;
+
+use crate::FluvioError;
+
+/// Metadata assigned to a record once it has been produced
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordMetadata {
+    partition: PartitionId,
+    offset: Offset,
+    timestamp: i64,
+}
+
+impl RecordMetadata {
+    /// Partition the record was sent to
+    pub fn partition(&self) -> PartitionId {
+        self.partition
+    }
+
+    /// Offset of the record in the partition, -1 if producer doesn't wait for acknowledgement
+    pub fn offset(&self) -> Offset {
+        self.offset
+    }
+
+    /// Time in milliseconds when the SPU appended the record to the log, -1 if unknown
+    pub fn timestamp(&self) -> i64 {
+        self.timestamp
+    }
+}
+
+/// Handle to a record sent by the producer
+///
+/// Resolves to the metadata of the record once its batch has been acknowledged.
+pub struct DeliveryHandle {
+    receiver: Receiver<Result<RecordMetadata, FluvioError>>,
+}
+
+impl DeliveryHandle {
+    /// Wait until the record has been acknowledged and return its metadata
+    pub async fn wait(self) -> Result<RecordMetadata, FluvioError> {
+        self.receiver.recv().await.map_err(|_| {
+            FluvioError::Other("producer was dropped before record was sent".to_owned())
+        })?
+    }
+}
+
+/// records accumulated for a partition, together with their delivery senders
+pub(crate) struct ProducerBatch {
+    partition: PartitionId,
+    records: Vec<DefaultRecord>,
+    senders: Vec<Sender<Result<RecordMetadata, FluvioError>>>,
+    size: usize,
+    created: Instant,
+    /// time in milliseconds when batch was created, records are stamped relative to it
+    first_timestamp: i64,
+}
+
+impl ProducerBatch {
+    fn new(partition: PartitionId) -> Self {
+        Self {
+            partition,
+            records: vec![],
+            senders: vec![],
+            size: 0,
+            created: Instant::now(),
+            first_timestamp: current_timestamp(),
+        }
+    }
+
+    /// create batch with records, return batch and handles for each record
+    pub fn with_records(
+        partition: PartitionId,
+        records: Vec<DefaultRecord>,
+    ) -> (Self, Vec<DeliveryHandle>) {
+        let mut batch = Self::new(partition);
+        let handles = records
+            .into_iter()
+            .map(|record| batch.push(record))
+            .collect();
+        (batch, handles)
+    }
+
+    fn push(&mut self, mut record: DefaultRecord) -> DeliveryHandle {
+        let (sender, receiver) = bounded(1);
+        record
+            .preamble
+            .set_timestamp_delta(current_timestamp() - self.first_timestamp);
+        self.size += record.write_size(0);
+        self.records.push(record);
+        self.senders.push(sender);
+        DeliveryHandle { receiver }
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn first_timestamp(&self) -> i64 {
+        self.first_timestamp
+    }
+
+    pub fn take_records(&mut self) -> Vec<DefaultRecord> {
+        std::mem::take(&mut self.records)
+    }
+
+    /// notify records with offsets starting from base offset
+    pub fn complete(self, base_offset: Offset, timestamp: i64) {
+        let partition = self.partition;
+        for (index, sender) in self.senders.into_iter().enumerate() {
+            let offset = if base_offset < 0 {
+                base_offset
+            } else {
+                base_offset + index as Offset
+            };
+            let _ = sender.try_send(Ok(RecordMetadata {
+                partition,
+                offset,
+                timestamp,
+            }));
+        }
+    }
+
+    /// notify records with error
+    pub fn fail<F>(self, error: F)
+    where
+        F: Fn() -> FluvioError,
+    {
+        for sender in self.senders.into_iter() {
+            let _ = sender.try_send(Err(error()));
+        }
+    }
+}
+
+/// milliseconds since unix epoch
+fn current_timestamp() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+/// Accumulate records into batches for each partition
+pub(crate) struct RecordAccumulator {
+    batches: Mutex<HashMap<PartitionId, ProducerBatch>>,
+    batch_size: usize,
+    batch_ready: Event,
+}
+
+impl RecordAccumulator {
+    pub fn new(batch_size: usize) -> Self {
+        Self {
+            batches: Mutex::new(HashMap::new()),
+            batch_size,
+            batch_ready: Event::new(),
+        }
+    }
+
+    /// add record to partition batch, notify listener if batch is full
+    pub async fn push(&self, partition: PartitionId, record: DefaultRecord) -> DeliveryHandle {
+        let mut batches = self.batches.lock().await;
+        let batch = batches
+            .entry(partition)
+            .or_insert_with(|| ProducerBatch::new(partition));
+        let handle = batch.push(record);
+        if batch.size() >= self.batch_size {
+            trace!(partition, size = batch.size(), "batch is full");
+            self.batch_ready.notify(1);
+        }
+        handle
+    }
+
+    /// listener notified when there is full batch
+    pub fn batch_ready(&self) -> EventListener {
+        self.batch_ready.listen()
+    }
+
+    /// take batches that are full or have lingered long enough
+    /// also return how long until next batch will be ready
+    pub async fn take_ready(
+        &self,
+        linger: Duration,
+    ) -> (Vec<(PartitionId, ProducerBatch)>, Option<Duration>) {
+        let mut batches = self.batches.lock().await;
+        let now = Instant::now();
+        let ready_partitions: Vec<PartitionId> = batches
+            .iter()
+            .filter(|(_, batch)| {
+                batch.size() >= self.batch_size || now.duration_since(batch.created) >= linger
+            })
+            .map(|(partition, _)| *partition)
+            .collect();
+
+        let ready = ready_partitions
+            .into_iter()
+            .filter_map(|partition| batches.remove(&partition).map(|batch| (partition, batch)))
+            .collect();
+
+        let next_ready = batches
+            .values()
+            .map(|batch| {
+                linger
+                    .checked_sub(now.duration_since(batch.created))
+                    .unwrap_or_default()
+            })
+            .min();
+
+        (ready, next_ready)
+    }
+
+    /// take all pending batches
+    pub async fn take_all(&self) -> Vec<(PartitionId, ProducerBatch)> {
+        let mut batches = self.batches.lock().await;
+        batches.drain().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::time::Duration;
+
+    use fluvio_future::test_async;
+    use fluvio_future::timer::sleep;
+
+    use super::*;
+
+    #[test_async]
+    async fn test_accumulator_batch_size() -> Result<(), ()> {
+        let record_size = DefaultRecord::new("0123456789").write_size(0);
+        let accumulator = RecordAccumulator::new(record_size * 3);
+
+        for _ in 0..3 {
+            accumulator.push(0, DefaultRecord::new("0123456789")).await;
+        }
+        accumulator.push(1, DefaultRecord::new("0123456789")).await;
+
+        let (ready, next_ready) = accumulator.take_ready(Duration::from_secs(60)).await;
+        assert_eq!(ready.len(), 1);
+        assert_eq!(ready[0].0, 0);
+        assert_eq!(ready[0].1.records.len(), 3);
+        assert!(next_ready.is_some());
+
+        assert_eq!(accumulator.take_all().await.len(), 1);
+        Ok(())
+    }
+
+    #[test_async]
+    async fn test_accumulator_linger() -> Result<(), ()> {
+        let accumulator = RecordAccumulator::new(16384);
+        accumulator.push(0, DefaultRecord::new("a")).await;
+
+        let (ready, _) = accumulator.take_ready(Duration::from_millis(50)).await;
+        assert!(ready.is_empty());
+
+        sleep(Duration::from_millis(60)).await;
+        let (ready, next_ready) = accumulator.take_ready(Duration::from_millis(50)).await;
+        assert_eq!(ready.len(), 1);
+        assert!(next_ready.is_none());
+        Ok(())
+    }
+
+    #[test_async]
+    async fn test_record_timestamps() -> Result<(), ()> {
+        let accumulator = RecordAccumulator::new(16384);
+        accumulator.push(0, DefaultRecord::new("a")).await;
+        sleep(Duration::from_millis(20)).await;
+        accumulator.push(0, DefaultRecord::new("b")).await;
+
+        let (_, batch) = accumulator.take_all().await.pop().expect("batch");
+        assert!(batch.first_timestamp() > 0);
+        assert!(batch.records[1].get_timestamp_delta() >= 20);
+        Ok(())
+    }
+
+    #[test_async]
+    async fn test_delivery_handle() -> Result<(), ()> {
+        let (batch, handles) =
+            ProducerBatch::with_records(2, vec![DefaultRecord::new("a"), DefaultRecord::new("b")]);
+        batch.complete(10, 1000);
+
+        let mut offsets = vec![];
+        for handle in handles {
+            let metadata = handle.wait().await.expect("metadata");
+            assert_eq!(metadata.partition(), 2);
+            assert_eq!(metadata.timestamp(), 1000);
+            offsets.push(metadata.offset());
+        }
+        assert_eq!(offsets, vec![10, 11]);
+        Ok(())
+    }
+}
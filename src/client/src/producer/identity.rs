@@ -0,0 +1,275 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use tracing::debug;
+use async_mutex::{Mutex, MutexGuard};
+
+use dataplane::ErrorCode;
+use dataplane::produce::{DefaultProduceRequest, ProduceResponse};
+use fluvio_types::PartitionId;
+use fluvio_sc_schema::ApiError;
+use fluvio_sc_schema::producer::InitProducerIdRequest;
+
+use crate::FluvioError;
+use crate::sockets::{SerialFrame, VersionedSerialSocket};
+
+/// Identity of idempotent producer, allocated by SC
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct ProducerIdentity {
+    pub id: i64,
+    pub epoch: i16,
+}
+
+/// Identity and sequence numbers of idempotent producer
+///
+/// When outcome of request is unknown, its batches may have been written by SPU,
+/// so their sequences can't be used by other batches. Identity is then renewed by SC,
+/// and sequences start over: idempotent producer gets new producer id,
+/// transactional producer gets bumped epoch, which aborts its transaction.
+pub(crate) struct IdempotentProducer {
+    transactional_id: Option<String>,
+    transaction_timeout: Duration,
+    client: Mutex<VersionedSerialSocket>,
+    state: Mutex<SequenceState>,
+}
+
+impl IdempotentProducer {
+    /// allocate id for idempotent producer
+    /// transactional producer gets id registered for its transactional id
+    pub async fn init(
+        transactional_id: Option<String>,
+        transaction_timeout: Duration,
+        client: VersionedSerialSocket,
+    ) -> Result<Self, FluvioError> {
+        let producer = Self {
+            transactional_id,
+            transaction_timeout,
+            client: Mutex::new(client),
+            state: Mutex::new(SequenceState::default()),
+        };
+        let identity = producer.init_producer_id().await?;
+        producer.state.lock().await.reset(identity);
+        Ok(producer)
+    }
+
+    pub async fn identity(&self) -> ProducerIdentity {
+        self.state.lock().await.identity
+    }
+
+    pub async fn state(&self) -> MutexGuard<'_, SequenceState> {
+        self.state.lock().await
+    }
+
+    /// get new identity from SC, sequences start over
+    pub async fn renew(&self, state: &mut SequenceState) -> Result<(), FluvioError> {
+        let identity = self.init_producer_id().await?;
+        debug!(
+            old_producer_id = state.identity.id,
+            old_epoch = state.identity.epoch,
+            producer_id = identity.id,
+            epoch = identity.epoch,
+            "renewed producer identity"
+        );
+        state.reset(identity);
+        Ok(())
+    }
+
+    async fn init_producer_id(&self) -> Result<ProducerIdentity, FluvioError> {
+        let request = InitProducerIdRequest {
+            transactional_id: self.transactional_id.clone(),
+            transaction_timeout_ms: self.transaction_timeout.as_millis() as i32,
+        };
+        let response = self.client.lock().await.send_receive(request).await?;
+        if response.error_code.is_error() {
+            return Err(ApiError::Code(response.error_code, None).into());
+        }
+        debug!(
+            producer_id = response.producer_id,
+            epoch = response.producer_epoch,
+            "allocated producer id"
+        );
+        Ok(ProducerIdentity {
+            id: response.producer_id,
+            epoch: response.producer_epoch,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct SequenceState {
+    pub identity: ProducerIdentity,
+    // next sequence number of each partition,
+    // advanced only when SPU has acknowledged batches of partition
+    sequences: HashMap<PartitionId, i32>,
+    /// batches of failed request may have been written, identity must be renewed before next batch
+    pub unknown_outcome: bool,
+}
+
+impl Default for SequenceState {
+    fn default() -> Self {
+        Self {
+            identity: ProducerIdentity { id: -1, epoch: -1 },
+            sequences: HashMap::new(),
+            unknown_outcome: false,
+        }
+    }
+}
+
+impl SequenceState {
+    fn reset(&mut self, identity: ProducerIdentity) {
+        self.identity = identity;
+        self.sequences.clear();
+        self.unknown_outcome = false;
+    }
+
+    /// stamp producer id and sequence numbers on batches, batches of transactional producer
+    /// are also marked as transactional. sequences are not advanced until request is acknowledged,
+    /// return number of records of each partition
+    pub fn assign(
+        &self,
+        request: &mut DefaultProduceRequest,
+        transactional: bool,
+    ) -> HashMap<PartitionId, i32> {
+        let mut record_counts = HashMap::new();
+        for partition_request in request
+            .topics
+            .iter_mut()
+            .flat_map(|topic| topic.partitions.iter_mut())
+        {
+            let first_sequence = self
+                .sequences
+                .get(&partition_request.partition_index)
+                .copied()
+                .unwrap_or(0);
+            let mut sequence = first_sequence;
+            for batch in partition_request.records.batches.iter_mut() {
+                let count = batch.records().len() as i32;
+                let header = batch.get_mut_header();
+                header.set_producer(self.identity.id, self.identity.epoch, sequence);
+                header.set_transactional(transactional);
+                sequence = sequence.wrapping_add(count);
+            }
+            record_counts.insert(
+                partition_request.partition_index,
+                sequence.wrapping_sub(first_sequence),
+            );
+        }
+        record_counts
+    }
+
+    /// advance sequences of partitions whose batches has been written by SPU.
+    /// batches which timed out waiting for replicas are already written by leader,
+    /// so their sequences are used up too.
+    /// if request failed, it is unknown whether batches has been written
+    pub fn commit(
+        &mut self,
+        record_counts: &HashMap<PartitionId, i32>,
+        result: &Result<Option<ProduceResponse>, FluvioError>,
+    ) {
+        let written: Vec<PartitionId> = match result {
+            Ok(Some(response)) => response
+                .responses
+                .iter()
+                .flat_map(|topic| topic.partitions.iter())
+                .filter(|partition| {
+                    partition.error_code.is_ok()
+                        || partition.error_code == ErrorCode::RequestTimedOut
+                })
+                .map(|partition| partition.partition_index)
+                .collect(),
+            // no acknowledgement is expected when acks is none
+            Ok(None) => record_counts.keys().copied().collect(),
+            Err(_) => {
+                if !record_counts.is_empty() {
+                    self.unknown_outcome = true;
+                }
+                return;
+            }
+        };
+
+        for partition in written {
+            if let Some(count) = record_counts.get(&partition) {
+                let sequence = self.sequences.entry(partition).or_insert(0);
+                *sequence = sequence.wrapping_add(*count);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use dataplane::batch::DefaultBatch;
+    use dataplane::produce::{
+        DefaultPartitionRequest, DefaultTopicRequest, PartitionProduceResponse,
+        TopicProduceResponse,
+    };
+    use dataplane::record::DefaultRecord;
+
+    use super::*;
+
+    fn request(records: usize) -> DefaultProduceRequest {
+        let mut partition = DefaultPartitionRequest {
+            partition_index: 0,
+            ..Default::default()
+        };
+        partition.records.batches.push(DefaultBatch::new(
+            (0..records).map(|_| DefaultRecord::new("value")).collect(),
+        ));
+        let mut topic = DefaultTopicRequest {
+            name: "test".to_owned(),
+            ..Default::default()
+        };
+        topic.partitions.push(partition);
+        let mut request = DefaultProduceRequest::default();
+        request.topics.push(topic);
+        request
+    }
+
+    fn ok_response() -> Result<Option<ProduceResponse>, FluvioError> {
+        Ok(Some(ProduceResponse {
+            responses: vec![TopicProduceResponse {
+                name: "test".to_owned(),
+                partitions: vec![PartitionProduceResponse {
+                    partition_index: 0,
+                    ..Default::default()
+                }],
+            }],
+            ..Default::default()
+        }))
+    }
+
+    fn first_sequence(request: &DefaultProduceRequest) -> (i64, i16, i32) {
+        let header = request.topics[0].partitions[0].records.batches[0].get_header();
+        (
+            header.producer_id,
+            header.producer_epoch,
+            header.first_sequence,
+        )
+    }
+
+    #[test]
+    fn test_sequences_after_unknown_outcome() {
+        let mut state = SequenceState::default();
+        state.reset(ProducerIdentity { id: 1, epoch: 0 });
+
+        let mut first = request(3);
+        let counts = state.assign(&mut first, false);
+        state.commit(&counts, &ok_response());
+        assert!(!state.unknown_outcome);
+
+        // batch may or may not have been written, its sequences are not reused by next batch
+        let mut second = request(2);
+        let counts = state.assign(&mut second, false);
+        assert_eq!(first_sequence(&second), (1, 0, 3));
+        state.commit(&counts, &Err(FluvioError::Other("connection".to_owned())));
+        assert!(state.unknown_outcome);
+
+        // next batch is sent with renewed identity
+        state.reset(ProducerIdentity { id: 2, epoch: 0 });
+        assert!(!state.unknown_outcome);
+        let mut third = request(2);
+        state.assign(&mut third, false);
+        assert_eq!(first_sequence(&third), (2, 0, 0));
+    }
+}
@@ -0,0 +1,1184 @@
+use std::sync::Arc;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use tracing::{debug, instrument, trace};
+use siphasher::sip::SipHasher;
+use async_mutex::Mutex;
+use async_channel::{bounded, Receiver, Sender};
+use tokio::select;
+
+use fluvio_future::task::spawn;
+use fluvio_future::timer::sleep;
+use dataplane::{ErrorCode, ReplicaKey};
+use dataplane::produce::DefaultProduceRequest;
+use dataplane::produce::DefaultPartitionRequest;
+use dataplane::produce::DefaultTopicRequest;
+use dataplane::produce::ProduceResponse;
+use dataplane::batch::DefaultBatch;
+use dataplane::compression::Compression;
+use dataplane::record::DefaultRecord;
+use dataplane::record::DefaultAsyncBuffer;
+
+use crate::FluvioError;
+use crate::spu::SpuPool;
+use crate::sockets::SerialFrame;
+use fluvio_types::{SpuId, PartitionId};
+use crate::sync::StoreContext;
+use crate::metadata::partition::PartitionSpec;
+
+mod accumulator;
+mod identity;
+mod transaction;
+
+pub use accumulator::{DeliveryHandle, RecordMetadata};
+use accumulator::{ProducerBatch, RecordAccumulator};
+pub(crate) use identity::{IdempotentProducer, ProducerIdentity};
+pub(crate) use transaction::TransactionManager;
+
+/// An interface for producing events to a particular topic
+///
+/// A `TopicProducer` allows you to send events to the specific
+/// topic it was initialized for. Once you have a `TopicProducer`,
+/// you can send events to the topic, choosing which partition
+/// each event should be delivered to.
+///
+/// If the producer is configured with a linger time, records are
+/// buffered per partition and sent in batches by a background task.
+///
+/// A producer configured with a transactional id must send records within a
+/// transaction, see [`begin_transaction`](TopicProducer::begin_transaction).
+pub struct TopicProducer {
+    topic: String,
+    pool: Arc<SpuPool>,
+    partitioner: Arc<Mutex<dyn Partitioner + Send + Sync>>,
+    sender: BatchSender,
+    buffer: Option<ProducerBuffer>,
+}
+
+/// records waiting to be flushed by background task
+struct ProducerBuffer {
+    accumulator: Arc<RecordAccumulator>,
+    // background task is terminated when this is dropped
+    _shutdown: Sender<()>,
+}
+
+/// delay before produce request is retried
+const RETRY_BACKOFF: Duration = Duration::from_millis(100);
+
+impl TopicProducer {
+    pub(crate) fn new(
+        topic: String,
+        pool: Arc<SpuPool>,
+        config: ProducerConfig,
+        idempotent: Option<Arc<IdempotentProducer>>,
+        transaction: Option<TransactionManager>,
+    ) -> Self {
+        let partitioner_config = PartitionerConfig { partition_count: 1 };
+        let partitioner = Arc::new(Mutex::new(SiphashRoundRobinPartitioner::new(
+            partitioner_config,
+        )));
+        let linger = config.linger;
+        let batch_size = config.batch_size;
+        let sender = BatchSender::new(
+            topic.clone(),
+            pool.clone(),
+            config,
+            idempotent,
+            transaction.map(Arc::new),
+        );
+
+        let buffer = linger.map(|linger| {
+            let accumulator = Arc::new(RecordAccumulator::new(batch_size));
+            let (shutdown, shutdown_listener) = bounded(1);
+            spawn(flush_loop(
+                accumulator.clone(),
+                sender.clone(),
+                linger,
+                shutdown_listener,
+            ));
+            ProducerBuffer {
+                accumulator,
+                _shutdown: shutdown,
+            }
+        });
+
+        Self {
+            topic,
+            pool,
+            partitioner,
+            sender,
+            buffer,
+        }
+    }
+
+    /// Sends a key/value record to this producer's Topic.
+    ///
+    /// The partition that the record will be sent to is derived from the Key.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fluvio::{TopicProducer, FluvioError};
+    /// # async fn example(producer: &TopicProducer) -> Result<(), FluvioError> {
+    /// let metadata = producer.send("Key", "Value").await?;
+    /// println!("partition: {}, offset: {}", metadata.partition(), metadata.offset());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrument(
+        skip(self, key, value),
+        fields(topic = %self.topic),
+    )]
+    pub async fn send<K, V>(&self, key: K, value: V) -> Result<RecordMetadata, FluvioError>
+    where
+        K: Into<Vec<u8>>,
+        V: Into<Vec<u8>>,
+    {
+        let mut metadata = self.send_all(Some((Some(key.into()), value))).await?;
+        metadata
+            .pop()
+            .ok_or_else(|| FluvioError::Other("no metadata for record".to_owned()))
+    }
+
+    /// Sends key/value records to this producer's Topic
+    ///
+    /// Returns the metadata of each record, in the same order as the records were given.
+    #[instrument(
+        skip(self, records),
+        fields(topic = %self.topic),
+    )]
+    pub async fn send_all<K, V, I>(&self, records: I) -> Result<Vec<RecordMetadata>, FluvioError>
+    where
+        K: Into<Vec<u8>>,
+        V: Into<Vec<u8>>,
+        I: IntoIterator<Item = (Option<K>, V)>,
+    {
+        let handles = self.dispatch(records).await?;
+        let mut metadata = Vec::with_capacity(handles.len());
+        for handle in handles {
+            metadata.push(handle.wait().await?);
+        }
+
+        Ok(metadata)
+    }
+
+    /// Sends a key/value record without waiting for it to be acknowledged
+    ///
+    /// The returned [`DeliveryHandle`] resolves to the metadata of the record
+    /// once it has been sent. With a linger time configured, the record is
+    /// buffered until its batch is full or the linger time expires.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fluvio::{TopicProducer, FluvioError};
+    /// # async fn example(producer: &TopicProducer) -> Result<(), FluvioError> {
+    /// let handle = producer.send_async("Key", "Value").await?;
+    /// let metadata = handle.wait().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrument(
+        skip(self, key, value),
+        fields(topic = %self.topic),
+    )]
+    pub async fn send_async<K, V>(&self, key: K, value: V) -> Result<DeliveryHandle, FluvioError>
+    where
+        K: Into<Vec<u8>>,
+        V: Into<Vec<u8>>,
+    {
+        let mut handles = self.dispatch(Some((Some(key.into()), value))).await?;
+        handles
+            .pop()
+            .ok_or_else(|| FluvioError::Other("no delivery handle for record".to_owned()))
+    }
+
+    /// Sends all buffered records and waits until in-flight requests are completed
+    #[instrument(
+        skip(self),
+        fields(topic = %self.topic),
+    )]
+    pub async fn flush(&self) -> Result<(), FluvioError> {
+        if let Some(buffer) = &self.buffer {
+            let batches = buffer.accumulator.take_all().await;
+            self.sender.send_batches(batches).await;
+        }
+        self.sender.wait_in_flight().await;
+        Ok(())
+    }
+
+    /// Starts a transaction, records sent until the transaction is committed or aborted are part of it
+    ///
+    /// Consumers with read committed isolation receive records of a transaction
+    /// only after it has been committed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use fluvio::{TopicProducer, FluvioError};
+    /// # async fn example(producer: &TopicProducer) -> Result<(), FluvioError> {
+    /// producer.begin_transaction().await?;
+    /// producer.send("Key", "Value").await?;
+    /// producer.commit_transaction().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn begin_transaction(&self) -> Result<(), FluvioError> {
+        self.transaction()?.begin().await
+    }
+
+    /// Sends buffered records, then commits the current transaction
+    #[instrument(
+        skip(self),
+        fields(topic = %self.topic),
+    )]
+    pub async fn commit_transaction(&self) -> Result<(), FluvioError> {
+        let transaction = self.transaction()?;
+        self.flush().await?;
+        transaction.end(true).await
+    }
+
+    /// Sends buffered records, then aborts the current transaction
+    #[instrument(
+        skip(self),
+        fields(topic = %self.topic),
+    )]
+    pub async fn abort_transaction(&self) -> Result<(), FluvioError> {
+        let transaction = self.transaction()?;
+        self.flush().await?;
+        transaction.end(false).await
+    }
+
+    fn transaction(&self) -> Result<&TransactionManager, FluvioError> {
+        self.sender.transaction.as_deref().ok_or_else(|| {
+            FluvioError::TransactionError(
+                "producer is not configured with transactional id".to_owned(),
+            )
+        })
+    }
+
+    /// assign records to partitions, then either buffer them or send them right away
+    async fn dispatch<K, V, I>(&self, records: I) -> Result<Vec<DeliveryHandle>, FluvioError>
+    where
+        K: Into<Vec<u8>>,
+        V: Into<Vec<u8>>,
+        I: IntoIterator<Item = (Option<K>, V)>,
+    {
+        let topics = self.pool.metadata.topics();
+        let topic_spec = topics
+            .lookup_by_key(&self.topic)
+            .await?
+            .ok_or_else(|| FluvioError::TopicNotFound(self.topic.to_string()))?
+            .spec;
+        let partition_count = topic_spec.partitions();
+        let partition_config = PartitionerConfig { partition_count };
+
+        let entries = records
+            .into_iter()
+            .map::<(Option<Vec<u8>>, Vec<u8>), _>(|(k, v)| (k.map(|it| it.into()), v.into()))
+            .map(|(key, value)| {
+                let key = key.map(|it| DefaultAsyncBuffer::new(it));
+                let value = DefaultAsyncBuffer::new(value);
+                DefaultRecord::from((key, value))
+            });
+
+        // Calculate the partition for each entry
+        // Use a block scope to ensure we drop the partitioner lock
+        let records_by_partition = {
+            let mut iter = Vec::new();
+            let mut partitioner = self.partitioner.lock().await;
+            partitioner.update_config(partition_config);
+
+            for record in entries {
+                let key = record.key.as_ref().map(|k| k.as_ref());
+                let value = record.value.as_ref();
+                let partition = partitioner.partition(key, value);
+                iter.push((partition, record));
+            }
+            iter
+        };
+
+        if let Some(buffer) = &self.buffer {
+            let mut handles = Vec::with_capacity(records_by_partition.len());
+            for (partition, record) in records_by_partition {
+                handles.push(buffer.accumulator.push(partition, record).await);
+            }
+            return Ok(handles);
+        }
+
+        // Without buffering, send one batch per partition right away
+        let mut records_map: HashMap<PartitionId, Vec<DefaultRecord>> = HashMap::new();
+        let mut order = Vec::with_capacity(records_by_partition.len());
+        for (partition, record) in records_by_partition {
+            let partition_records = records_map.entry(partition).or_insert_with(Vec::new);
+            order.push((partition, partition_records.len()));
+            partition_records.push(record);
+        }
+
+        let mut handles_map = HashMap::new();
+        let mut batches = Vec::with_capacity(records_map.len());
+        for (partition, records) in records_map {
+            let (batch, handles) = ProducerBatch::with_records(partition, records);
+            handles_map.insert(partition, handles.into_iter().map(Some).collect::<Vec<_>>());
+            batches.push((partition, batch));
+        }
+        self.sender.send_batches(batches).await;
+
+        // Return handles in the same order as the records were given
+        let handles = order
+            .into_iter()
+            .filter_map(|(partition, index)| {
+                handles_map
+                    .get_mut(&partition)
+                    .and_then(|handles| handles[index].take())
+            })
+            .collect();
+        Ok(handles)
+    }
+
+    /// Sends an event to a specific partition within this producer's topic
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use fluvio::{TopicProducer, FluvioError};
+    /// # async fn do_send_record(producer: &TopicProducer) -> Result<(), FluvioError> {
+    /// let partition = 0;
+    /// producer.send_record("Hello, Fluvio!", partition).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrument(
+        skip(self, buffer),
+        fields(topic = %self.topic),
+    )]
+    #[deprecated(since = "0.6.2")]
+    pub async fn send_record<B: AsRef<[u8]>>(
+        &self,
+        buffer: B,
+        _partition: i32,
+    ) -> Result<(), FluvioError> {
+        let buffer: Vec<u8> = Vec::from(buffer.as_ref());
+        self.send_all(Some((None::<Vec<u8>>, buffer))).await?;
+        Ok(())
+    }
+}
+
+/// send batches that are ready until producer is dropped, then send remaining batches
+async fn flush_loop(
+    accumulator: Arc<RecordAccumulator>,
+    sender: BatchSender,
+    linger: Duration,
+    shutdown: Receiver<()>,
+) {
+    debug!(topic = %sender.topic, ?linger, "starting producer flush loop");
+    loop {
+        let batch_ready = accumulator.batch_ready();
+        let (ready, next_ready) = accumulator.take_ready(linger).await;
+        if !ready.is_empty() {
+            trace!(batches = ready.len(), "sending ready batches");
+            sender.send_batches(ready).await;
+        }
+
+        select! {
+            _ = sleep(next_ready.unwrap_or(linger)) => {},
+            _ = batch_ready => {},
+            _ = shutdown.recv() => {
+                debug!(topic = %sender.topic, "producer dropped, sending remaining batches");
+                sender.send_batches(accumulator.take_all().await).await;
+                break;
+            }
+        }
+    }
+}
+
+/// Sends batches to partition leaders, limiting number of in-flight requests
+#[derive(Clone)]
+struct BatchSender {
+    topic: String,
+    pool: Arc<SpuPool>,
+    config: ProducerConfig,
+    in_flight: (Sender<()>, Receiver<()>),
+    idempotent: Option<Arc<IdempotentProducer>>,
+    transaction: Option<Arc<TransactionManager>>,
+}
+
+impl BatchSender {
+    fn new(
+        topic: String,
+        pool: Arc<SpuPool>,
+        config: ProducerConfig,
+        idempotent: Option<Arc<IdempotentProducer>>,
+        transaction: Option<Arc<TransactionManager>>,
+    ) -> Self {
+        // idempotent producer sends one request at time, so batches arrive in sequence order
+        let max_in_flight = if idempotent.is_some() {
+            1
+        } else {
+            config.max_in_flight.max(1)
+        };
+        Self {
+            topic,
+            pool,
+            config,
+            in_flight: bounded(max_in_flight),
+            idempotent,
+            transaction,
+        }
+    }
+
+    /// send one request per partition leader, delivery handles are notified when each request completes
+    async fn send_batches(&self, batches: Vec<(PartitionId, ProducerBatch)>) {
+        if batches.is_empty() {
+            return;
+        }
+
+        let mut pending: HashMap<PartitionId, ProducerBatch> = HashMap::new();
+        let mut records_by_partition = vec![];
+        for (partition, mut batch) in batches {
+            records_by_partition.extend(
+                batch
+                    .take_records()
+                    .into_iter()
+                    .map(|record| (partition, record)),
+            );
+            pending.insert(partition, batch);
+        }
+
+        // partitions must be part of transaction before records are written to them
+        if let Some(transaction) = &self.transaction {
+            if let Err(err) = transaction
+                .add_partitions(&self.topic, pending.keys().copied())
+                .await
+            {
+                let message = err.to_string();
+                for (_, batch) in pending {
+                    batch.fail(|| FluvioError::Other(message.clone()));
+                }
+                return;
+            }
+        }
+
+        let requests = match group_by_spu(
+            &self.topic,
+            self.pool.metadata.partitions(),
+            records_by_partition,
+        )
+        .await
+        .and_then(|partitions_by_spu| {
+            assemble_requests(&self.topic, partitions_by_spu, &self.config)
+        }) {
+            Ok(requests) => requests,
+            Err(err) => {
+                let message = err.to_string();
+                for (_, batch) in pending {
+                    batch.fail(|| FluvioError::Other(message.clone()));
+                }
+                return;
+            }
+        };
+
+        for (leader, mut request) in requests {
+            let request_batches: HashMap<PartitionId, ProducerBatch> = request
+                .topics
+                .iter()
+                .flat_map(|topic| topic.partitions.iter())
+                .filter_map(|partition| {
+                    pending
+                        .remove(&partition.partition_index)
+                        .map(|batch| (partition.partition_index, batch))
+                })
+                .collect();
+
+            assign_timestamps(&mut request, &request_batches);
+
+            // wait for in-flight slot, it is released once response is received
+            let _ = self.in_flight.0.send(()).await;
+            let record_counts = match self.assign_sequences(&mut request).await {
+                Ok(record_counts) => record_counts,
+                Err(err) => {
+                    let _ = self.in_flight.1.recv().await;
+                    complete_batches(request_batches, Err(err));
+                    continue;
+                }
+            };
+            let sender = self.clone();
+            spawn(async move {
+                sender
+                    .send_request_with_retries(leader, request, request_batches, record_counts)
+                    .await;
+                let _ = sender.in_flight.1.recv().await;
+            });
+        }
+    }
+
+    /// stamp producer id and sequence numbers on batches of idempotent producer,
+    /// return number of records of each partition.
+    /// if outcome of previous request is unknown, idempotent producer is renewed first,
+    /// transaction of transactional producer must be aborted instead
+    async fn assign_sequences(
+        &self,
+        request: &mut DefaultProduceRequest,
+    ) -> Result<HashMap<PartitionId, i32>, FluvioError> {
+        let idempotent = match &self.idempotent {
+            Some(idempotent) => idempotent,
+            None => return Ok(HashMap::new()),
+        };
+
+        let mut state = idempotent.state().await;
+        if state.unknown_outcome {
+            if self.transaction.is_some() {
+                return Err(unknown_outcome_error());
+            }
+            idempotent.renew(&mut state).await?;
+        }
+        Ok(state.assign(request, self.transaction.is_some()))
+    }
+
+    /// advance sequences of partitions whose batches has been written by SPU
+    async fn commit_sequences(
+        &self,
+        record_counts: &HashMap<PartitionId, i32>,
+        result: &Result<Option<ProduceResponse>, FluvioError>,
+    ) {
+        if let Some(idempotent) = &self.idempotent {
+            idempotent.state().await.commit(record_counts, result);
+        }
+    }
+
+    /// send request and notify delivery handles of batches.
+    /// timed out batches have been written by leader, so they and requests without response
+    /// are retried only by idempotent producer, since SPU deduplicates its batches.
+    /// only partitions which timed out are retried, other partitions are completed
+    async fn send_request_with_retries(
+        &self,
+        leader: SpuId,
+        mut request: DefaultProduceRequest,
+        mut batches: HashMap<PartitionId, ProducerBatch>,
+        mut record_counts: HashMap<PartitionId, i32>,
+    ) {
+        let mut attempt = 0;
+        loop {
+            let can_retry = attempt < self.config.retries;
+            let result = match self.send_request(leader, request.clone()).await {
+                Ok(Some(response)) if can_retry && self.idempotent.is_some() => {
+                    let retriable = retriable_partitions(&response);
+                    if retriable.is_empty() {
+                        Ok(Some(response))
+                    } else {
+                        let (retry_batches, completed_batches): (HashMap<_, _>, HashMap<_, _>) =
+                            batches
+                                .into_iter()
+                                .partition(|(partition, _)| retriable.contains(partition));
+                        let response = Ok(Some(response));
+                        let completed_counts = record_counts
+                            .iter()
+                            .filter(|(partition, _)| !retriable.contains(partition))
+                            .map(|(partition, count)| (*partition, *count))
+                            .collect();
+                        self.commit_sequences(&completed_counts, &response).await;
+                        record_counts.retain(|partition, _| retriable.contains(partition));
+                        complete_batches(completed_batches, response);
+                        batches = retry_batches;
+                        for topic in request.topics.iter_mut() {
+                            topic
+                                .partitions
+                                .retain(|partition| retriable.contains(&partition.partition_index));
+                        }
+
+                        attempt += 1;
+                        debug!(leader, attempt, partitions = ?retriable, "retrying timed out partitions");
+                        sleep(RETRY_BACKOFF).await;
+                        continue;
+                    }
+                }
+                Err(err) if can_retry && self.idempotent.is_some() && is_retriable_error(&err) => {
+                    attempt += 1;
+                    debug!(leader, attempt, %err, "retrying produce request");
+                    sleep(RETRY_BACKOFF).await;
+                    continue;
+                }
+                result => result,
+            };
+
+            self.commit_sequences(&record_counts, &result).await;
+            complete_batches(batches, result);
+            return;
+        }
+    }
+
+    async fn send_request(
+        &self,
+        leader: SpuId,
+        request: DefaultProduceRequest,
+    ) -> Result<Option<ProduceResponse>, FluvioError> {
+        let mut spu_client = self.pool.create_serial_socket_from_leader(leader).await?;
+        if self.config.acks == ProducerAcks::None {
+            spu_client.send_only(request).await?;
+            Ok(None)
+        } else {
+            Ok(Some(spu_client.send_receive(request).await?))
+        }
+    }
+
+    /// wait until all in-flight requests are completed
+    async fn wait_in_flight(&self) {
+        let slots = self.in_flight.0.capacity().unwrap_or(1);
+        for _ in 0..slots {
+            let _ = self.in_flight.0.send(()).await;
+        }
+        for _ in 0..slots {
+            let _ = self.in_flight.1.recv().await;
+        }
+    }
+}
+
+/// partitions which timed out waiting for replicas
+fn retriable_partitions(response: &ProduceResponse) -> HashSet<PartitionId> {
+    response
+        .responses
+        .iter()
+        .flat_map(|topic| topic.partitions.iter())
+        .filter(|partition| partition.error_code == ErrorCode::RequestTimedOut)
+        .map(|partition| partition.partition_index)
+        .collect()
+}
+
+/// batches of transaction may have been written with sequences which can't be used again
+pub(crate) fn unknown_outcome_error() -> FluvioError {
+    FluvioError::TransactionError(
+        "outcome of produce request is unknown, transaction must be aborted".to_owned(),
+    )
+}
+
+/// request failed because of connection, records may or may not have been written
+fn is_retriable_error(err: &FluvioError) -> bool {
+    matches!(
+        err,
+        FluvioError::FlvSocketError(_) | FluvioError::IoError(_)
+    )
+}
+
+/// notify delivery handles of batches with the produce response
+fn complete_batches(
+    mut batches: HashMap<PartitionId, ProducerBatch>,
+    result: Result<Option<ProduceResponse>, FluvioError>,
+) {
+    match result {
+        Ok(Some(response)) => {
+            for partition in response
+                .responses
+                .into_iter()
+                .flat_map(|topic| topic.partitions.into_iter())
+            {
+                if let Some(batch) = batches.remove(&partition.partition_index) {
+                    if partition.error_code.is_ok() {
+                        batch.complete(partition.base_offset, partition.log_append_time_ms);
+                    } else {
+                        let error_code = partition.error_code;
+                        batch.fail(|| FluvioError::ProduceError(error_code));
+                    }
+                }
+            }
+        }
+        Ok(None) => {
+            for (_, batch) in batches.drain() {
+                batch.complete(-1, -1);
+            }
+        }
+        Err(err) => {
+            let message = err.to_string();
+            for (_, batch) in batches.drain() {
+                batch.fail(|| FluvioError::Other(message.clone()));
+            }
+        }
+    }
+
+    for (partition, batch) in batches {
+        batch.fail(|| {
+            FluvioError::Other(format!(
+                "partition {} missing in produce response",
+                partition
+            ))
+        });
+    }
+}
+
+/// stamp batches with time when their records started accumulating
+fn assign_timestamps(
+    request: &mut DefaultProduceRequest,
+    batches: &HashMap<PartitionId, ProducerBatch>,
+) {
+    for partition_request in request
+        .topics
+        .iter_mut()
+        .flat_map(|topic| topic.partitions.iter_mut())
+    {
+        if let Some(batch) = batches.get(&partition_request.partition_index) {
+            for records in partition_request.records.batches.iter_mut() {
+                records.set_first_timestamp(batch.first_timestamp());
+            }
+        }
+    }
+}
+
+async fn group_by_spu(
+    topic: &str,
+    partitions: &StoreContext<PartitionSpec>,
+    records_by_partition: Vec<(PartitionId, DefaultRecord)>,
+) -> Result<HashMap<SpuId, HashMap<PartitionId, Vec<DefaultRecord>>>, FluvioError> {
+    let mut map: HashMap<SpuId, HashMap<i32, Vec<DefaultRecord>>> = HashMap::new();
+    for (partition, record) in records_by_partition {
+        let replica_key = ReplicaKey::new(topic, partition);
+        let partition_spec = partitions
+            .lookup_by_key(&replica_key)
+            .await?
+            .ok_or_else(|| FluvioError::PartitionNotFound(topic.to_string(), partition))?
+            .spec;
+        let leader = partition_spec.leader;
+        map.entry(leader)
+            .or_insert_with(HashMap::new)
+            .entry(partition)
+            .or_insert_with(Vec::new)
+            .push(record);
+    }
+
+    Ok(map)
+}
+
+fn assemble_requests(
+    topic: &str,
+    partitions_by_spu: HashMap<SpuId, HashMap<PartitionId, Vec<DefaultRecord>>>,
+    config: &ProducerConfig,
+) -> Result<Vec<(SpuId, DefaultProduceRequest)>, FluvioError> {
+    let mut requests: Vec<(SpuId, DefaultProduceRequest)> =
+        Vec::with_capacity(partitions_by_spu.len());
+
+    for (leader, partitions) in partitions_by_spu {
+        let mut request = DefaultProduceRequest::default();
+
+        let mut topic_request = DefaultTopicRequest::default();
+        topic_request.name = topic.to_string();
+
+        for (partition, records) in partitions {
+            let mut partition_request = DefaultPartitionRequest::default();
+            partition_request.partition_index = partition;
+            let mut batch = DefaultBatch::new(records);
+            batch.compress(config.compression)?;
+            partition_request.records.batches.push(batch);
+            topic_request.partitions.push(partition_request);
+        }
+
+        request.acks = config.acks.into();
+        request.timeout_ms = config.timeout.as_millis() as i32;
+        request.topics.push(topic_request);
+        requests.push((leader, request));
+    }
+
+    Ok(requests)
+}
+
+/// Acknowledgement the producer requires from the SPU before a send completes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProducerAcks {
+    /// Don't wait for any acknowledgement
+    None,
+    /// Wait until the leader has written the records
+    Leader,
+    /// Wait until the records have been committed by the in-sync replicas
+    All,
+}
+
+impl From<ProducerAcks> for i16 {
+    fn from(acks: ProducerAcks) -> Self {
+        match acks {
+            ProducerAcks::None => 0,
+            ProducerAcks::Leader => 1,
+            ProducerAcks::All => -1,
+        }
+    }
+}
+
+/// Configures the behavior of topic producer
+#[derive(Debug, Clone)]
+pub struct ProducerConfig {
+    pub(crate) compression: Compression,
+    pub(crate) acks: ProducerAcks,
+    pub(crate) timeout: Duration,
+    pub(crate) linger: Option<Duration>,
+    pub(crate) batch_size: usize,
+    pub(crate) max_in_flight: usize,
+    pub(crate) retries: usize,
+    pub(crate) idempotent: bool,
+    pub(crate) transactional_id: Option<String>,
+    pub(crate) transaction_timeout: Duration,
+}
+
+impl Default for ProducerConfig {
+    fn default() -> Self {
+        Self {
+            compression: Compression::default(),
+            acks: ProducerAcks::Leader,
+            timeout: Duration::from_millis(1500),
+            linger: None,
+            batch_size: 16384,
+            max_in_flight: 5,
+            retries: 0,
+            idempotent: false,
+            transactional_id: None,
+            transaction_timeout: Duration::from_secs(60),
+        }
+    }
+}
+
+impl ProducerConfig {
+    /// Acknowledgement required for each send, by default the leader must write the records
+    pub fn with_acks(mut self, acks: ProducerAcks) -> Self {
+        self.acks = acks;
+        self
+    }
+
+    /// Maximum time SPU waits for replicas to acknowledge the records
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Compression codec used for record batches, by default records are not compressed
+    pub fn with_compression(mut self, compression: Compression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Buffer records for up to linger time before sending them in a batch,
+    /// by default records are sent as soon as they are produced
+    pub fn with_linger(mut self, linger: Duration) -> Self {
+        self.linger = Some(linger);
+        self
+    }
+
+    /// Size in bytes at which a buffered batch is sent without waiting for linger time
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// Maximum number of produce requests waiting for a response
+    pub fn with_max_in_flight(mut self, max_in_flight: usize) -> Self {
+        self.max_in_flight = max_in_flight;
+        self
+    }
+
+    /// Number of times a request is retried after timeout or connection failure, by default requests are not retried
+    pub fn with_retries(mut self, retries: usize) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Stamp producer id and sequence numbers on each batch, so SPU doesn't write retried batches twice.
+    /// Idempotent producer has a single request in flight.
+    pub fn with_idempotence(mut self, idempotent: bool) -> Self {
+        self.idempotent = idempotent;
+        self
+    }
+
+    /// Identifies producer across restarts so records are sent in transactions, implies idempotence.
+    /// Transaction left open by previous producer with the same id is aborted.
+    pub fn with_transactional_id<S: Into<String>>(mut self, transactional_id: S) -> Self {
+        self.transactional_id = Some(transactional_id.into());
+        self.idempotent = true;
+        self
+    }
+
+    /// Time after which SC aborts a transaction which has not been committed
+    pub fn with_transaction_timeout(mut self, timeout: Duration) -> Self {
+        self.transaction_timeout = timeout;
+        self
+    }
+}
+
+/// A trait for defining a partitioning strategy for key/value records.
+///
+/// A Partitioner is given a slice of potential keys, and the number of
+/// partitions in the current Topic. It must map each key from the input
+/// slice into a partition stored at the same index in the output Vec.
+///
+/// It is up to the implementor to decide how the keys get mapped to
+/// partitions. This includes deciding what partition to assign to records
+/// with no keys (represented by `None` values in the keys slice).
+///
+/// See [`SiphashRoundRobinPartitioner`] for a reference implementation.
+trait Partitioner {
+    fn partition(&mut self, key: Option<&[u8]>, value: &[u8]) -> PartitionId;
+    fn update_config(&mut self, config: PartitionerConfig);
+}
+
+struct PartitionerConfig {
+    partition_count: i32,
+}
+
+/// A [`Partitioner`] which combines hashing and round-robin partition assignment
+///
+/// - Records with keys get their keys hashed with siphash
+/// - Records without keys get assigned to partitions using round-robin
+struct SiphashRoundRobinPartitioner {
+    index: PartitionId,
+    config: PartitionerConfig,
+}
+
+impl SiphashRoundRobinPartitioner {
+    pub fn new(config: PartitionerConfig) -> Self {
+        Self { index: 0, config }
+    }
+}
+
+impl Partitioner for SiphashRoundRobinPartitioner {
+    fn partition(&mut self, maybe_key: Option<&[u8]>, _value: &[u8]) -> i32 {
+        match maybe_key {
+            Some(key) => partition_siphash(key, self.config.partition_count),
+            None => {
+                let partition = self.index;
+                self.index = (self.index + 1) % self.config.partition_count;
+                partition
+            }
+        }
+    }
+
+    fn update_config(&mut self, config: PartitionerConfig) {
+        self.config = config;
+    }
+}
+
+fn partition_siphash(key: &[u8], partition_count: i32) -> i32 {
+    use std::hash::{Hash, Hasher};
+    use std::convert::TryFrom;
+
+    assert!(partition_count >= 0, "Partition must not be less than zero");
+    let mut hasher = SipHasher::new();
+    key.hash(&mut hasher);
+    let hashed = hasher.finish();
+
+    i32::try_from(hashed % partition_count as u64).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata::store::MetadataStoreObject;
+
+    /// Ensure that feeding keyless records one-at-a-time does not assign the same partition
+    #[test]
+    fn test_round_robin_individual() {
+        let config = PartitionerConfig { partition_count: 3 };
+        let mut partitioner = SiphashRoundRobinPartitioner::new(config);
+
+        let key1_partition = partitioner.partition(None, &[]);
+        assert_eq!(key1_partition, 0);
+        let key2_partition = partitioner.partition(None, &[]);
+        assert_eq!(key2_partition, 1);
+        let key3_partition = partitioner.partition(None, &[]);
+        assert_eq!(key3_partition, 2);
+        let key4_partition = partitioner.partition(None, &[]);
+        assert_eq!(key4_partition, 0);
+        let key5_partition = partitioner.partition(None, &[]);
+        assert_eq!(key5_partition, 1);
+        let key6_partition = partitioner.partition(None, &[]);
+        assert_eq!(key6_partition, 2);
+    }
+
+    #[fluvio_future::test_async]
+    async fn test_group_by_spu() -> Result<(), ()> {
+        let partitions = StoreContext::new();
+        let partition_specs: Vec<_> = vec![
+            (ReplicaKey::new("TOPIC", 0), PartitionSpec::new(0, vec![])), // Partition 0
+            (ReplicaKey::new("TOPIC", 1), PartitionSpec::new(0, vec![])), // Partition 1
+            (ReplicaKey::new("TOPIC", 2), PartitionSpec::new(1, vec![])), // Partition 2
+        ]
+        .into_iter()
+        .map(|(key, spec)| MetadataStoreObject::with_spec(key, spec))
+        .collect();
+        partitions.store().sync_all(partition_specs).await;
+        let records_by_partition = vec![
+            (0, DefaultRecord::new("A")),
+            (1, DefaultRecord::new("B")),
+            (2, DefaultRecord::new("C")),
+            (0, DefaultRecord::new("D")),
+            (1, DefaultRecord::new("E")),
+            (2, DefaultRecord::new("F")),
+        ];
+
+        let grouped = group_by_spu("TOPIC", &partitions, records_by_partition)
+            .await
+            .unwrap();
+
+        // SPU 0 should have partitions 0 and 1, but not 2
+        let spu_0 = grouped.get(&0).unwrap();
+        let partition_0 = spu_0.get(&0).unwrap();
+
+        assert!(
+            partition_0
+                .iter()
+                .any(|record| record.value.as_ref() == b"A"),
+            "SPU 0/Partition 0 should contain record A",
+        );
+        assert!(
+            partition_0
+                .iter()
+                .any(|record| record.value.as_ref() == b"D"),
+            "SPU 0/Partition 0 should contain record D",
+        );
+
+        let partition_1 = spu_0.get(&1).unwrap();
+        assert!(
+            partition_1
+                .iter()
+                .any(|record| record.value.as_ref() == b"B"),
+            "SPU 0/Partition 1 should contain record B",
+        );
+        assert!(
+            partition_1
+                .iter()
+                .any(|record| record.value.as_ref() == b"E"),
+            "SPU 0/Partition 1 should contain record E",
+        );
+
+        assert!(
+            spu_0.get(&2).is_none(),
+            "SPU 0 should not contain Partition 2",
+        );
+
+        let spu_1 = grouped.get(&1).unwrap();
+        let partition_2 = spu_1.get(&2).unwrap();
+
+        assert!(
+            spu_1.get(&0).is_none(),
+            "SPU 1 should not contain Partition 0",
+        );
+
+        assert!(
+            spu_1.get(&1).is_none(),
+            "SPU 1 should not contain Partition 1",
+        );
+
+        assert!(
+            partition_2
+                .iter()
+                .any(|record| record.value.as_ref() == b"C"),
+            "SPU 1/Partition 2 should contain record C",
+        );
+
+        assert!(
+            partition_2
+                .iter()
+                .any(|record| record.value.as_ref() == b"F"),
+            "SPU 1/Partition 2 should contain record F",
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_assemble_requests() {
+        let partitions_by_spu = {
+            let mut pbs = HashMap::new();
+
+            let spu_0 = {
+                let mut s0_partitions = HashMap::new();
+                let partition_0_records = vec![DefaultRecord::new("A"), DefaultRecord::new("B")];
+                let partition_1_records = vec![DefaultRecord::new("C"), DefaultRecord::new("D")];
+                s0_partitions.insert(0, partition_0_records);
+                s0_partitions.insert(1, partition_1_records);
+                s0_partitions
+            };
+
+            let spu_1 = {
+                let mut s1_partitions = HashMap::new();
+                let partition_2_records = vec![DefaultRecord::new("E"), DefaultRecord::new("F")];
+                let partition_3_records = vec![DefaultRecord::new("G"), DefaultRecord::new("H")];
+                s1_partitions.insert(2, partition_2_records);
+                s1_partitions.insert(3, partition_3_records);
+                s1_partitions
+            };
+            pbs.insert(0, spu_0);
+            pbs.insert(1, spu_1);
+            pbs
+        };
+
+        let requests = assemble_requests("TOPIC", partitions_by_spu, &ProducerConfig::default())
+            .expect("requests");
+        assert_eq!(requests.len(), 2);
+
+        // SPU 0
+        {
+            let (spu0, request) = requests.iter().find(|(spu, _)| *spu == 0).unwrap();
+            assert_eq!(*spu0, 0);
+            assert_eq!(request.topics.len(), 1);
+            let topic_request = request.topics.get(0).unwrap();
+            assert_eq!(topic_request.name, "TOPIC");
+            assert_eq!(topic_request.partitions.len(), 2);
+
+            let partition_0_request = topic_request
+                .partitions
+                .iter()
+                .find(|p| p.partition_index == 0)
+                .unwrap();
+            assert_eq!(partition_0_request.records.batches.len(), 1);
+            let batch = partition_0_request.records.batches.get(0).unwrap();
+            assert_eq!(batch.records().len(), 2);
+            let record_0_0 = batch.records().get(0).unwrap();
+            assert_eq!(record_0_0.value.as_ref(), b"A");
+            let record_0_1 = batch.records().get(1).unwrap();
+            assert_eq!(record_0_1.value.as_ref(), b"B");
+
+            let partition_1_request = topic_request
+                .partitions
+                .iter()
+                .find(|p| p.partition_index == 1)
+                .unwrap();
+            assert_eq!(partition_1_request.records.batches.len(), 1);
+            let batch = partition_1_request.records.batches.get(0).unwrap();
+            assert_eq!(batch.records().len(), 2);
+            let record_1_0 = batch.records().get(0).unwrap();
+            assert_eq!(record_1_0.value.as_ref(), b"C");
+            let record_1_1 = batch.records().get(1).unwrap();
+            assert_eq!(record_1_1.value.as_ref(), b"D");
+        }
+
+        // SPU 1
+        {
+            let (spu1, request) = requests.iter().find(|(spu, _)| *spu == 1).unwrap();
+            assert_eq!(*spu1, 1);
+            assert_eq!(request.topics.len(), 1);
+            let topic_request = request.topics.get(0).unwrap();
+            assert_eq!(topic_request.name, "TOPIC");
+            assert_eq!(topic_request.partitions.len(), 2);
+
+            let partition_0_request = topic_request
+                .partitions
+                .iter()
+                .find(|p| p.partition_index == 2)
+                .unwrap();
+            assert_eq!(partition_0_request.records.batches.len(), 1);
+            let batch = partition_0_request.records.batches.get(0).unwrap();
+            assert_eq!(batch.records().len(), 2);
+            let record_0_0 = batch.records().get(0).unwrap();
+            assert_eq!(record_0_0.value.as_ref(), b"E");
+            let record_0_1 = batch.records().get(1).unwrap();
+            assert_eq!(record_0_1.value.as_ref(), b"F");
+
+            let partition_1_request = topic_request
+                .partitions
+                .iter()
+                .find(|p| p.partition_index == 3)
+                .unwrap();
+            assert_eq!(partition_1_request.records.batches.len(), 1);
+            let batch = partition_1_request.records.batches.get(0).unwrap();
+            assert_eq!(batch.records().len(), 2);
+            let record_1_0 = batch.records().get(0).unwrap();
+            assert_eq!(record_1_0.value.as_ref(), b"G");
+            let record_1_1 = batch.records().get(1).unwrap();
+            assert_eq!(record_1_1.value.as_ref(), b"H");
+        }
+    }
+}
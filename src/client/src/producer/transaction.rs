@@ -0,0 +1,157 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use tracing::debug;
+use async_mutex::Mutex;
+
+use fluvio_future::timer::sleep;
+use dataplane::{ErrorCode, ReplicaKey};
+use fluvio_types::PartitionId;
+use fluvio_sc_schema::ApiError;
+use fluvio_sc_schema::transaction::{AddPartitionsToTxnRequest, EndTxnRequest};
+
+use crate::FluvioError;
+use crate::sockets::{SerialFrame, VersionedSerialSocket};
+
+use super::{IdempotentProducer, RETRY_BACKOFF, unknown_outcome_error};
+
+/// Transaction of transactional producer, coordinated by SC
+///
+/// Each partition is registered with SC before records are sent to it,
+/// so SC can write commit or abort marker to every partition in the transaction.
+/// If outcome of some produce request is unknown, transaction can only be aborted.
+pub(crate) struct TransactionManager {
+    transactional_id: String,
+    producer: Arc<IdempotentProducer>,
+    client: Mutex<VersionedSerialSocket>,
+    state: Mutex<TransactionState>,
+}
+
+#[derive(Default)]
+struct TransactionState {
+    active: bool,
+    partitions: HashSet<ReplicaKey>,
+}
+
+impl TransactionManager {
+    pub fn new(
+        transactional_id: String,
+        producer: Arc<IdempotentProducer>,
+        client: VersionedSerialSocket,
+    ) -> Self {
+        Self {
+            transactional_id,
+            producer,
+            client: Mutex::new(client),
+            state: Mutex::new(TransactionState::default()),
+        }
+    }
+
+    pub async fn begin(&self) -> Result<(), FluvioError> {
+        let mut state = self.state.lock().await;
+        if state.active {
+            return Err(FluvioError::TransactionError(
+                "transaction is already in progress".to_owned(),
+            ));
+        }
+        state.active = true;
+        Ok(())
+    }
+
+    /// register partitions which are not yet part of current transaction
+    pub async fn add_partitions(
+        &self,
+        topic: &str,
+        partitions: impl Iterator<Item = PartitionId>,
+    ) -> Result<(), FluvioError> {
+        let mut state = self.state.lock().await;
+        if !state.active {
+            return Err(FluvioError::TransactionError(
+                "transactional producer must begin transaction before sending records".to_owned(),
+            ));
+        }
+
+        let new_partitions: Vec<ReplicaKey> = partitions
+            .map(|partition| ReplicaKey::new(topic, partition))
+            .filter(|replica| !state.partitions.contains(replica))
+            .collect();
+        if new_partitions.is_empty() {
+            return Ok(());
+        }
+
+        let sequences = self.producer.state().await;
+        if sequences.unknown_outcome {
+            return Err(unknown_outcome_error());
+        }
+        let identity = sequences.identity;
+        drop(sequences);
+
+        debug!(partitions = ?new_partitions, "adding partitions to transaction");
+        let request = AddPartitionsToTxnRequest {
+            transactional_id: self.transactional_id.clone(),
+            producer_id: identity.id,
+            producer_epoch: identity.epoch,
+            partitions: new_partitions.clone(),
+        };
+        loop {
+            let response = self
+                .client
+                .lock()
+                .await
+                .send_receive(request.clone())
+                .await?;
+            match response.error_code {
+                ErrorCode::None => break,
+                // markers of previous transaction are not written yet
+                ErrorCode::ConcurrentTransactions => sleep(RETRY_BACKOFF).await,
+                error_code => return Err(ApiError::Code(error_code, None).into()),
+            }
+        }
+
+        state.partitions.extend(new_partitions);
+        Ok(())
+    }
+
+    /// commit or abort current transaction
+    pub async fn end(&self, commit: bool) -> Result<(), FluvioError> {
+        let mut state = self.state.lock().await;
+        if !state.active {
+            return Err(FluvioError::TransactionError(
+                "no transaction in progress".to_owned(),
+            ));
+        }
+
+        let mut sequences = self.producer.state().await;
+        if sequences.unknown_outcome {
+            if commit {
+                return Err(unknown_outcome_error());
+            }
+            // SC aborts transaction when its producer epoch is bumped
+            debug!("aborting transaction by renewing producer epoch");
+            self.producer.renew(&mut sequences).await?;
+            *state = TransactionState::default();
+            return Ok(());
+        }
+        let identity = sequences.identity;
+        drop(sequences);
+
+        debug!(
+            commit,
+            partitions = state.partitions.len(),
+            "ending transaction"
+        );
+        let request = EndTxnRequest {
+            transactional_id: self.transactional_id.clone(),
+            producer_id: identity.id,
+            producer_epoch: identity.epoch,
+            commit,
+        };
+        let response = self.client.lock().await.send_receive(request).await?;
+        if response.error_code.is_error() {
+            return Err(ApiError::Code(response.error_code, None).into());
+        }
+
+        *state = TransactionState::default();
+        Ok(())
+    }
+}
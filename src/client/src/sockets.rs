@@ -174,9 +174,62 @@ impl ClientConfig {
         self.addr = domain
     }
 
+    /// addresses in config, addresses of SC replicas are separated by comma
+    pub fn endpoints(&self) -> Vec<String> {
+        self.addr
+            .split(',')
+            .map(|endpoint| endpoint.trim())
+            .filter(|endpoint| !endpoint.is_empty())
+            .map(|endpoint| endpoint.to_owned())
+            .collect()
+    }
+
+    /// connect to first endpoint which is reachable
     pub(crate) async fn connect(self) -> Result<VersionedSocket, FluvioError> {
-        let socket = AllFlvSocket::connect_with_connector(&self.addr, &self.connector).await?;
-        VersionedSocket::connect(socket, self).await
+        let mut last_error = None;
+        for endpoint in self.endpoints() {
+            match AllFlvSocket::connect_with_connector(&endpoint, &self.connector).await {
+                Ok(socket) => {
+                    let config = self.with_endpoints_from(&endpoint);
+                    return VersionedSocket::connect(socket, config).await;
+                }
+                Err(err) => {
+                    debug!(%endpoint, "can't connect: {}", err);
+                    last_error = Some(err);
+                }
+            }
+        }
+
+        match last_error {
+            Some(err) => Err(err.into()),
+            None => Err(FluvioError::Other(format!("no endpoint in: {}", self.addr))),
+        }
+    }
+
+    /// config with endpoints rotated, so connect starts from endpoint after current one
+    pub(crate) fn with_next_endpoint(&self) -> Self {
+        let mut endpoints = self.endpoints();
+        if !endpoints.is_empty() {
+            endpoints.rotate_left(1);
+        }
+        self.with_endpoints(endpoints)
+    }
+
+    /// config with endpoints rotated, so connect starts from given endpoint
+    fn with_endpoints_from(&self, endpoint: &str) -> Self {
+        let mut endpoints = self.endpoints();
+        if let Some(pos) = endpoints.iter().position(|e| e == endpoint) {
+            endpoints.rotate_left(pos);
+        }
+        self.with_endpoints(endpoints)
+    }
+
+    fn with_endpoints(&self, endpoints: Vec<String>) -> Self {
+        Self {
+            addr: endpoints.join(","),
+            client_id: self.client_id.clone(),
+            connector: self.connector.clone(),
+        }
     }
 
     /// create new config with prefix add to domain, this is useful for SNI
@@ -259,6 +312,15 @@ impl VersionedSerialSocket {
     pub fn versions(&self) -> &Versions {
         &self.versions
     }
+
+    /// send request without waiting for reply
+    pub async fn send_only<R>(&mut self, request: R) -> Result<(), FlvSocketError>
+    where
+        R: Request + Send + Sync,
+    {
+        let req_msg = self.new_request(request, self.versions.lookup_version(R::API_KEY));
+        self.socket.send_request_only(req_msg).await
+    }
 }
 
 #[async_trait]
@@ -278,3 +340,29 @@ impl SerialFrame for VersionedSerialSocket {
         self.socket.send_and_receive(req_msg).await
     }
 }
+
+#[cfg(test)]
+mod test {
+
+    use super::ClientConfig;
+
+    #[test]
+    fn test_endpoint_rotation() {
+        let config = ClientConfig::with_addr("sc-0:9003, sc-1:9003,sc-2:9003".to_owned());
+        assert_eq!(
+            config.endpoints(),
+            vec!["sc-0:9003", "sc-1:9003", "sc-2:9003"]
+        );
+        assert_eq!(
+            config.with_next_endpoint().addr(),
+            "sc-1:9003,sc-2:9003,sc-0:9003"
+        );
+        assert_eq!(
+            config.with_endpoints_from("sc-2:9003").addr(),
+            "sc-2:9003,sc-0:9003,sc-1:9003"
+        );
+
+        let single = ClientConfig::with_addr("localhost:9003".to_owned());
+        assert_eq!(single.with_next_endpoint().addr(), "localhost:9003");
+    }
+}
@@ -173,6 +173,13 @@ impl SpuPool {
         Ok(stream)
     }
 
+    /// drop connection to spu, next request to it creates new connection
+    pub async fn remove_spu_socket(&self, spu: SpuId) {
+        if self.spu_clients.lock().await.remove(&spu).is_some() {
+            debug!(spu, "removed spu connection");
+        }
+    }
+
     pub fn shutdown(&mut self) {
         self.metadata.shutdown();
     }
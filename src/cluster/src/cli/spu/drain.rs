@@ -0,0 +1,164 @@
+//!
+//! # Drain SPU
+//!
+//! CLI tree to move all partition replicas out of an SPU
+//!
+use std::collections::BTreeMap;
+use std::io::Error as IoError;
+use std::io::ErrorKind;
+
+use tracing::debug;
+use structopt::StructOpt;
+
+use fluvio::Fluvio;
+use fluvio::metadata::spu::SpuSpec;
+use fluvio::metadata::partition::{PartitionSpec, PartitionUpdate};
+
+use crate::cli::ClusterCliError;
+
+#[derive(Debug, StructOpt)]
+pub struct DrainSpuOpt {
+    /// SPU id
+    #[structopt(short = "i", long = "id")]
+    id: i32,
+
+    /// Print reassignments, do not move replicas
+    #[structopt(short = "d", long)]
+    dry_run: bool,
+}
+
+impl DrainSpuOpt {
+    pub async fn process(self, fluvio: &Fluvio) -> Result<(), ClusterCliError> {
+        let mut admin = fluvio.admin().await;
+
+        let spus: Vec<i32> = admin
+            .list::<SpuSpec, _>(vec![])
+            .await?
+            .into_iter()
+            .filter(|spu| spu.status.is_online())
+            .map(|spu| spu.spec.id)
+            .collect();
+
+        // partition being reassigned is planned from replicas it is moving to,
+        // replicas which are being removed are not moved again
+        let partitions: Vec<(String, Vec<i32>)> = admin
+            .list::<PartitionSpec, _>(vec![])
+            .await?
+            .into_iter()
+            .map(|partition| (partition.name, partition.spec.target_replicas()))
+            .collect();
+
+        let moves = plan_drain(self.id, &spus, partitions)?;
+        if moves.is_empty() {
+            println!("spu {} has no replicas", self.id);
+            return Ok(());
+        }
+
+        for (name, replicas) in moves {
+            debug!("moving partition: {} to replicas: {:?}", name, replicas);
+            admin
+                .update(
+                    name.clone(),
+                    self.dry_run,
+                    PartitionUpdate {
+                        replicas: replicas.clone(),
+                    },
+                )
+                .await?;
+            if self.dry_run {
+                println!(
+                    "partition \"{}\" would be reassigned to {:?}",
+                    name, replicas
+                );
+            } else {
+                println!(
+                    "partition \"{}\" is being reassigned to {:?}",
+                    name, replicas
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// compute new replicas for every partition hosted by `drained` spu.
+/// drained replica is replaced in place by the least loaded online spu
+/// which is not already a replica of the partition
+fn plan_drain(
+    drained: i32,
+    online_spus: &[i32],
+    partitions: Vec<(String, Vec<i32>)>,
+) -> Result<Vec<(String, Vec<i32>)>, ClusterCliError> {
+    let mut load: BTreeMap<i32, usize> = online_spus
+        .iter()
+        .filter(|id| **id != drained)
+        .map(|id| (*id, 0))
+        .collect();
+
+    for (_, replicas) in &partitions {
+        for replica in replicas {
+            if let Some(count) = load.get_mut(replica) {
+                *count += 1;
+            }
+        }
+    }
+
+    let mut moves = vec![];
+    for (name, mut replicas) in partitions {
+        let position = match replicas.iter().position(|id| *id == drained) {
+            Some(position) => position,
+            None => continue,
+        };
+
+        let candidate = load
+            .iter()
+            .filter(|(id, _)| !replicas.contains(id))
+            .min_by_key(|(_, count)| **count)
+            .map(|(id, _)| *id)
+            .ok_or_else(|| {
+                IoError::new(
+                    ErrorKind::Other,
+                    format!("no spu available to move replica of partition {}", name),
+                )
+            })?;
+
+        if let Some(count) = load.get_mut(&candidate) {
+            *count += 1;
+        }
+        replicas[position] = candidate;
+        moves.push((name, replicas));
+    }
+
+    Ok(moves)
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::plan_drain;
+
+    #[test]
+    fn test_plan_drain() {
+        let partitions = vec![
+            ("test-0".to_owned(), vec![5001, 5002]),
+            ("test-1".to_owned(), vec![5002, 5003]),
+            ("test-2".to_owned(), vec![5003, 5001]),
+        ];
+
+        let moves = plan_drain(5001, &[5001, 5002, 5003, 5004], partitions).expect("plan");
+        assert_eq!(
+            moves,
+            vec![
+                ("test-0".to_owned(), vec![5004, 5002]),
+                ("test-2".to_owned(), vec![5003, 5004]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_plan_drain_without_candidate() {
+        let partitions = vec![("test-0".to_owned(), vec![5001, 5002])];
+        assert!(plan_drain(5001, &[5001, 5002], partitions).is_err());
+    }
+}
@@ -5,6 +5,7 @@ mod list;
 mod display;
 mod register;
 mod unregister;
+mod drain;
 
 use fluvio::Fluvio;
 // pub use display::*;
@@ -15,6 +16,7 @@ use super::common::output::Terminal;
 use list::ListSpusOpt;
 use register::RegisterCustomSpuOpt;
 use unregister::UnregisterCustomSpuOpt;
+use drain::DrainSpuOpt;
 
 #[derive(Debug, StructOpt)]
 pub enum SpuCmd {
@@ -38,6 +40,13 @@ pub enum SpuCmd {
         template = COMMAND_TEMPLATE,
     )]
     List(ListSpusOpt),
+
+    /// Move all partition replicas out of an SPU
+    #[structopt(
+        name = "drain",
+        template = COMMAND_TEMPLATE,
+    )]
+    Drain(DrainSpuOpt),
 }
 
 impl SpuCmd {
@@ -56,6 +65,9 @@ impl SpuCmd {
             Self::List(list) => {
                 list.process(out, fluvio).await?;
             }
+            Self::Drain(drain) => {
+                drain.process(fluvio).await?;
+            }
         }
         Ok(())
     }
@@ -222,6 +222,8 @@ impl ClusterUninstaller {
         let _ = self.remove_custom_objects("spugroups", ns, None);
         let _ = self.remove_custom_objects("spus", ns, None);
         let _ = self.remove_custom_objects("topics", ns, None);
+        let _ = self.remove_custom_objects("consumergroups", ns, None);
+        let _ = self.remove_custom_objects("smartstreams", ns, None);
         let _ = self.remove_custom_objects("persistentvolumeclaims", ns, Some("app=spu"));
 
         // delete secrets
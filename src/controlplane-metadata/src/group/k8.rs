@@ -0,0 +1,25 @@
+use crate::k8_types::{Crd, GROUP, V1, CrdNames, Spec, Status, DefaultHeader};
+
+use super::ConsumerGroupStatus;
+use super::ConsumerGroupSpec;
+
+const CONSUMER_GROUP_API: Crd = Crd {
+    group: GROUP,
+    version: V1,
+    names: CrdNames {
+        kind: "ConsumerGroup",
+        plural: "consumergroups",
+        singular: "consumergroup",
+    },
+};
+
+impl Spec for ConsumerGroupSpec {
+    type Status = ConsumerGroupStatus;
+    type Header = DefaultHeader;
+
+    fn metadata() -> &'static Crd {
+        &CONSUMER_GROUP_API
+    }
+}
+
+impl Status for ConsumerGroupStatus {}
@@ -0,0 +1,82 @@
+#![allow(clippy::assign_op_pattern)]
+
+//!
+//! # Consumer Group Spec
+//!
+//! Consumer group is created by SC when the group first commits offsets.
+//! Members and partition assignment are not persisted, only committed offsets are kept.
+//!
+use dataplane::derive::{Decode, Encode};
+use dataplane::Offset;
+use fluvio_types::PartitionId;
+
+#[derive(Decode, Encode, Default, Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "use_serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct ConsumerGroupSpec {
+    #[cfg_attr(feature = "use_serde", serde(default))]
+    pub offsets: Vec<CommittedOffset>,
+}
+
+impl ConsumerGroupSpec {
+    /// offset committed for partition
+    pub fn offset(&self, topic: &str, partition: PartitionId) -> Option<Offset> {
+        self.offsets
+            .iter()
+            .find(|committed| committed.topic == topic && committed.partition == partition)
+            .map(|committed| committed.offset)
+    }
+
+    /// set committed offset of partition
+    pub fn commit(&mut self, topic: &str, partition: PartitionId, offset: Offset) {
+        match self
+            .offsets
+            .iter_mut()
+            .find(|committed| committed.topic == topic && committed.partition == partition)
+        {
+            Some(committed) => committed.offset = offset,
+            None => self.offsets.push(CommittedOffset {
+                topic: topic.to_owned(),
+                partition,
+                offset,
+            }),
+        }
+    }
+}
+
+/// next offset to be consumed from partition
+#[derive(Decode, Encode, Default, Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "use_serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct CommittedOffset {
+    pub topic: String,
+    pub partition: PartitionId,
+    pub offset: Offset,
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::ConsumerGroupSpec;
+
+    #[test]
+    fn test_commit_offsets() {
+        let mut spec = ConsumerGroupSpec::default();
+        assert_eq!(spec.offset("test", 0), None);
+
+        spec.commit("test", 0, 10);
+        spec.commit("test", 1, 5);
+        spec.commit("test", 0, 20);
+
+        assert_eq!(spec.offset("test", 0), Some(20));
+        assert_eq!(spec.offset("test", 1), Some(5));
+        assert_eq!(spec.offset("other", 0), None);
+        assert_eq!(spec.offsets.len(), 2);
+    }
+}
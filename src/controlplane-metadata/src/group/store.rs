@@ -0,0 +1,11 @@
+//!
+//! Consumer Group
+//!
+
+use crate::store::*;
+
+use super::*;
+
+pub type ConsumerGroupMetadata<C> = MetadataStoreObject<ConsumerGroupSpec, C>;
+
+pub type ConsumerGroupLocalStore<C> = LocalStore<ConsumerGroupSpec, C>;
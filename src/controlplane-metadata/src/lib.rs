@@ -2,6 +2,8 @@ pub mod spu;
 pub mod topic;
 pub mod partition;
 pub mod spg;
+pub mod group;
+pub mod smartstream;
 pub mod message;
 
 pub use fluvio_stream_model::core;
@@ -25,6 +27,8 @@ pub mod extended {
         SpuGroup,
         Topic,
         Partition,
+        ConsumerGroup,
+        SmartStream,
     }
 
     pub trait SpecExt: Spec {
@@ -9,3 +9,6 @@ pub use self::replica_msg::ReplicaMsgs;
 
 use crate::spu::SpuSpec;
 pub type SpuMsg = Message<SpuSpec>;
+
+use crate::smartstream::SmartStream;
+pub type SmartStreamMsg = Message<SmartStream>;
@@ -8,6 +8,7 @@ use crate::partition::ReplicaKey;
 use crate::core::{MetadataItem};
 use crate::store::MetadataStoreObject;
 use crate::partition::PartitionSpec;
+use crate::topic::{TopicStorageConfig, TopicSmartStream, CleanupPolicy};
 use super::store::*;
 
 #[derive(Decode, Encode, Debug, PartialEq, Clone, Default)]
@@ -16,6 +17,12 @@ pub struct Replica {
     pub leader: SpuId,
     pub replicas: Vec<SpuId>,
     pub is_being_deleted: bool,
+    #[fluvio(min_version = 1)]
+    pub storage: Option<TopicStorageConfig>,
+    #[fluvio(min_version = 2)]
+    pub smart_stream: Option<TopicSmartStream>,
+    #[fluvio(min_version = 3)]
+    pub cleanup_policy: Option<CleanupPolicy>,
 }
 
 impl Replica {
@@ -34,8 +41,31 @@ impl Replica {
             leader,
             replicas,
             is_being_deleted,
+            storage: None,
+            smart_stream: None,
+            cleanup_policy: None,
         }
     }
+
+    /// spu is leader or follower of this replica
+    pub fn has_spu(&self, spu: &SpuId) -> bool {
+        self.leader == *spu || self.replicas.contains(spu)
+    }
+
+    pub fn with_storage(mut self, storage: Option<TopicStorageConfig>) -> Self {
+        self.storage = storage;
+        self
+    }
+
+    pub fn with_smart_stream(mut self, smart_stream: Option<TopicSmartStream>) -> Self {
+        self.smart_stream = smart_stream;
+        self
+    }
+
+    pub fn with_cleanup_policy(mut self, cleanup_policy: Option<CleanupPolicy>) -> Self {
+        self.cleanup_policy = cleanup_policy;
+        self
+    }
 }
 
 impl<C> From<PartitionMetadata<C>> for Replica
@@ -52,6 +82,9 @@ where
             leader: inner.spec.leader,
             replicas: inner.spec.replicas,
             is_being_deleted,
+            storage: inner.spec.storage,
+            smart_stream: inner.spec.smart_stream,
+            cleanup_policy: inner.spec.cleanup_policy,
         }
     }
 }
@@ -7,6 +7,8 @@
 use fluvio_types::SpuId;
 use dataplane::derive::{Decode, Encode};
 
+use crate::topic::{TopicStorageConfig, TopicSmartStream, CleanupPolicy};
+
 /// Spec for Partition
 /// Each partition has replicas spread among SPU
 /// one of replica is leader which is duplicated in the leader field
@@ -19,6 +21,31 @@ use dataplane::derive::{Decode, Encode};
 pub struct PartitionSpec {
     pub leader: SpuId,
     pub replicas: Vec<SpuId>,
+    #[cfg_attr(
+        feature = "use_serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    #[fluvio(min_version = 1)]
+    pub storage: Option<TopicStorageConfig>,
+    #[cfg_attr(
+        feature = "use_serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    #[fluvio(min_version = 2)]
+    pub smart_stream: Option<TopicSmartStream>,
+    #[cfg_attr(
+        feature = "use_serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    #[fluvio(min_version = 3)]
+    pub cleanup_policy: Option<CleanupPolicy>,
+    /// replicas being moved out, removed once other replicas are in sync with leader
+    #[cfg_attr(
+        feature = "use_serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    #[fluvio(min_version = 4)]
+    pub removing_replicas: Vec<SpuId>,
 }
 
 impl std::default::Default for PartitionSpec {
@@ -26,19 +53,87 @@ impl std::default::Default for PartitionSpec {
         PartitionSpec {
             leader: 0,
             replicas: Vec::default(),
+            storage: None,
+            smart_stream: None,
+            cleanup_policy: None,
+            removing_replicas: vec![],
         }
     }
 }
 
 impl PartitionSpec {
     pub fn new(leader: SpuId, replicas: Vec<SpuId>) -> Self {
-        Self { leader, replicas }
+        Self {
+            leader,
+            replicas,
+            storage: None,
+            smart_stream: None,
+            cleanup_policy: None,
+            removing_replicas: vec![],
+        }
+    }
+
+    pub fn with_storage(mut self, storage: Option<TopicStorageConfig>) -> Self {
+        self.storage = storage;
+        self
+    }
+
+    pub fn with_smart_stream(mut self, smart_stream: Option<TopicSmartStream>) -> Self {
+        self.smart_stream = smart_stream;
+        self
+    }
+
+    pub fn with_cleanup_policy(mut self, cleanup_policy: Option<CleanupPolicy>) -> Self {
+        self.cleanup_policy = cleanup_policy;
+        self
     }
 
     pub fn has_spu(&self, spu: &SpuId) -> bool {
         self.replicas.contains(spu)
     }
 
+    /// replicas are being moved to other spus
+    pub fn is_reassigning(&self) -> bool {
+        !self.removing_replicas.is_empty()
+    }
+
+    /// replicas which stay after reassignment is completed
+    pub fn target_replicas(&self) -> Vec<SpuId> {
+        self.replicas
+            .iter()
+            .filter(|r| !self.removing_replicas.contains(r))
+            .cloned()
+            .collect()
+    }
+
+    /// start moving replicas to target spus.
+    /// target replicas are added while existing replicas not in target are kept
+    /// until target replicas are in sync with leader
+    pub fn reassign(&mut self, target: Vec<SpuId>) {
+        let removing: Vec<SpuId> = self
+            .replicas
+            .iter()
+            .filter(|r| !target.contains(r))
+            .cloned()
+            .collect();
+        let mut replicas = target;
+        replicas.extend(removing.iter());
+        self.replicas = replicas;
+        self.removing_replicas = removing;
+    }
+
+    /// drop replicas which have been moved out,
+    /// leader moves to first target replica if it has been moved out
+    pub fn complete_reassignment(&mut self) {
+        self.replicas = self.target_replicas();
+        self.removing_replicas.clear();
+        if !self.replicas.contains(&self.leader) {
+            if let Some(leader) = self.replicas.first() {
+                self.leader = *leader;
+            }
+        }
+    }
+
     /// follower replicas
     pub fn followers(&self) -> Vec<SpuId> {
         self.replicas
@@ -57,3 +152,39 @@ impl From<Vec<i32>> for PartitionSpec {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+
+    use super::PartitionSpec;
+
+    #[test]
+    fn test_reassign_replicas() {
+        let mut spec: PartitionSpec = vec![1, 2, 3].into();
+
+        spec.reassign(vec![3, 4, 5]);
+        assert!(spec.is_reassigning());
+        assert_eq!(spec.leader, 1);
+        assert_eq!(spec.replicas, vec![3, 4, 5, 1, 2]);
+        assert_eq!(spec.removing_replicas, vec![1, 2]);
+        assert_eq!(spec.target_replicas(), vec![3, 4, 5]);
+
+        spec.complete_reassignment();
+        assert!(!spec.is_reassigning());
+        assert_eq!(spec.leader, 3);
+        assert_eq!(spec.replicas, vec![3, 4, 5]);
+    }
+
+    #[test]
+    fn test_reassign_keeps_leader() {
+        let mut spec: PartitionSpec = vec![1, 2].into();
+
+        spec.reassign(vec![1, 3]);
+        assert_eq!(spec.replicas, vec![1, 3, 2]);
+        assert_eq!(spec.removing_replicas, vec![2]);
+
+        spec.complete_reassignment();
+        assert_eq!(spec.leader, 1);
+        assert_eq!(spec.replicas, vec![1, 3]);
+    }
+}
@@ -32,6 +32,13 @@ pub struct PartitionStatus {
     pub lsr: u32,
     pub replicas: Vec<ReplicaStatus>,
     pub is_being_deleted: bool,
+    /// transaction markers not yet written by leader, removed once leader confirms them
+    #[cfg_attr(
+        feature = "use_serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    #[fluvio(min_version = 5)]
+    pub pending_txn_markers: Vec<PendingTxnMarker>,
 }
 
 impl fmt::Display for PartitionStatus {
@@ -170,6 +177,13 @@ impl PartitionStatus {
         self.update_lrs();
     }
 
+    /// drop status of replicas which are no longer assigned to partition
+    pub fn retain_replicas(&mut self, replicas: &[SpuId]) {
+        self.replicas
+            .retain(|status| replicas.contains(&status.spu));
+        self.update_lrs();
+    }
+
     /// recalculate lrs which is count of follower whose leo is same as leader
     fn update_lrs(&mut self) {
         let leader_leo = self.leader.leo;
@@ -186,6 +200,19 @@ fn find_status(status: &mut Vec<ReplicaStatus>, spu: SpuId) -> Option<&'_ mut Re
     status.iter_mut().find(|status| status.spu == spu)
 }
 
+/// marker completing transaction of producer in this partition
+#[derive(Decode, Encode, Default, Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "use_serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct PendingTxnMarker {
+    pub producer_id: i64,
+    pub producer_epoch: i16,
+    pub commit: bool,
+}
+
 #[derive(Decode, Encode, Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "use_serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PartitionResolution {
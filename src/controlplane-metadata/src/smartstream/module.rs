@@ -0,0 +1,42 @@
+#![allow(clippy::assign_op_pattern)]
+
+use std::fmt;
+
+use dataplane::derive::{Decode, Encode};
+use crate::core::MetadataItem;
+
+use super::SmartStreamSpec;
+use super::store::*;
+
+/// SmartStream module as sent from SC to SPU
+#[derive(Decode, Encode, Debug, PartialEq, Clone, Default)]
+pub struct SmartStream {
+    pub name: String,
+    pub version: u32,
+    pub wasm: Vec<u8>,
+}
+
+impl SmartStream {
+    pub fn new(name: impl Into<String>, spec: SmartStreamSpec) -> Self {
+        Self {
+            name: name.into(),
+            version: spec.version,
+            wasm: spec.wasm,
+        }
+    }
+}
+
+impl<C> From<SmartStreamMetadata<C>> for SmartStream
+where
+    C: MetadataItem,
+{
+    fn from(item: SmartStreamMetadata<C>) -> Self {
+        Self::new(item.key, item.spec)
+    }
+}
+
+impl fmt::Display for SmartStream {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} version: {}", self.name, self.version)
+    }
+}
@@ -0,0 +1,51 @@
+#![allow(clippy::assign_op_pattern)]
+
+//!
+//! # SmartStream Spec
+//!
+//! Named WASM module registered with SC. Consumers reference the module by name,
+//! SPUs cache compiled module by name and version.
+//!
+use dataplane::derive::{Decode, Encode};
+
+#[derive(Decode, Encode, Default, Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "use_serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct SmartStreamSpec {
+    /// version of module, must increase when module is replaced
+    pub version: u32,
+    /// raw WASM module
+    #[cfg_attr(feature = "use_serde", serde(with = "base64_bytes"))]
+    pub wasm: Vec<u8>,
+}
+
+impl SmartStreamSpec {
+    pub fn new(version: u32, wasm: Vec<u8>) -> Self {
+        Self { version, wasm }
+    }
+}
+
+#[cfg(feature = "use_serde")]
+mod base64_bytes {
+
+    use serde::{Deserialize, Deserializer, Serializer};
+    use serde::de::Error;
+
+    pub fn serialize<S>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&base64::encode(bytes))
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let encoded = String::deserialize(deserializer)?;
+        base64::decode(encoded).map_err(D::Error::custom)
+    }
+}
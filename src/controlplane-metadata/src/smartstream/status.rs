@@ -0,0 +1,12 @@
+#![allow(clippy::assign_op_pattern)]
+
+use dataplane::derive::*;
+
+/// SmartStream has no status, module is compiled by SPU when first used
+#[derive(Encode, Decode, Default, Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "use_serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct SmartStreamStatus {}
@@ -0,0 +1,11 @@
+//!
+//! SmartStream
+//!
+
+use crate::store::*;
+
+use super::*;
+
+pub type SmartStreamMetadata<C> = MetadataStoreObject<SmartStreamSpec, C>;
+
+pub type SmartStreamLocalStore<C> = LocalStore<SmartStreamSpec, C>;
@@ -92,7 +92,7 @@ mod custom_metadata {
     impl Creatable for CustomSpuSpec {}
 
     // This can be auto generated by enum derive later
-    #[derive(Debug)]
+    #[derive(Debug, Clone)]
     pub enum CustomSpuKey {
         Name(String),
         Id(i32),
@@ -126,6 +126,51 @@ impl TopicSpec {
         }
     }
 
+    /// storage settings which override SPU defaults
+    pub fn storage(&self) -> Option<&TopicStorageConfig> {
+        match self {
+            Self::Computed(param) => param.storage.as_ref(),
+            Self::Assigned(partition_map) => partition_map.storage.as_ref(),
+        }
+    }
+
+    pub fn set_storage(&mut self, storage: TopicStorageConfig) {
+        match self {
+            Self::Computed(param) => param.storage = Some(storage),
+            Self::Assigned(partition_map) => partition_map.storage = Some(storage),
+        }
+    }
+
+    /// SmartStream applied to records before they are written
+    pub fn smart_stream(&self) -> Option<&TopicSmartStream> {
+        match self {
+            Self::Computed(param) => param.smart_stream.as_ref(),
+            Self::Assigned(partition_map) => partition_map.smart_stream.as_ref(),
+        }
+    }
+
+    pub fn set_smart_stream(&mut self, smart_stream: TopicSmartStream) {
+        match self {
+            Self::Computed(param) => param.smart_stream = Some(smart_stream),
+            Self::Assigned(partition_map) => partition_map.smart_stream = Some(smart_stream),
+        }
+    }
+
+    /// how records are removed from partition logs
+    pub fn cleanup_policy(&self) -> Option<&CleanupPolicy> {
+        match self {
+            Self::Computed(param) => param.cleanup_policy.as_ref(),
+            Self::Assigned(partition_map) => partition_map.cleanup_policy.as_ref(),
+        }
+    }
+
+    pub fn set_cleanup_policy(&mut self, cleanup_policy: CleanupPolicy) {
+        match self {
+            Self::Computed(param) => param.cleanup_policy = Some(cleanup_policy),
+            Self::Assigned(partition_map) => partition_map.cleanup_policy = Some(cleanup_policy),
+        }
+    }
+
     pub fn partition_map_str(&self) -> Option<String> {
         match self {
             Self::Computed(_) => None,
@@ -274,6 +319,24 @@ pub struct TopicReplicaParam {
     pub replication_factor: ReplicationFactor,
     #[cfg_attr(feature = "use_serde", serde(skip_serializing_if = "bool::clone"))]
     pub ignore_rack_assignment: IgnoreRackAssignment,
+    #[cfg_attr(
+        feature = "use_serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    #[fluvio(min_version = 1)]
+    pub storage: Option<TopicStorageConfig>,
+    #[cfg_attr(
+        feature = "use_serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    #[fluvio(min_version = 2)]
+    pub smart_stream: Option<TopicSmartStream>,
+    #[cfg_attr(
+        feature = "use_serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    #[fluvio(min_version = 3)]
+    pub cleanup_policy: Option<CleanupPolicy>,
 }
 
 #[allow(dead_code)]
@@ -291,6 +354,9 @@ impl TopicReplicaParam {
             partitions,
             replication_factor,
             ignore_rack_assignment,
+            storage: None,
+            smart_stream: None,
+            cleanup_policy: None,
         }
     }
 }
@@ -317,11 +383,42 @@ impl std::fmt::Display for TopicReplicaParam {
 #[cfg_attr(feature = "use_serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PartitionMaps {
     maps: Vec<PartitionMap>,
+    #[cfg_attr(
+        feature = "use_serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    #[fluvio(min_version = 1)]
+    storage: Option<TopicStorageConfig>,
+    #[cfg_attr(
+        feature = "use_serde",
+        serde(
+            default,
+            rename = "smartStream",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    #[fluvio(min_version = 2)]
+    smart_stream: Option<TopicSmartStream>,
+    #[cfg_attr(
+        feature = "use_serde",
+        serde(
+            default,
+            rename = "cleanupPolicy",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    #[fluvio(min_version = 3)]
+    cleanup_policy: Option<CleanupPolicy>,
 }
 
 impl From<Vec<PartitionMap>> for PartitionMaps {
     fn from(maps: Vec<PartitionMap>) -> Self {
-        Self { maps }
+        Self {
+            maps,
+            storage: None,
+            smart_stream: None,
+            cleanup_policy: None,
+        }
     }
 }
 
@@ -525,6 +622,178 @@ pub struct PartitionMap {
     pub replicas: Vec<SpuId>,
 }
 
+/// Storage settings for topic.
+/// Settings which are not set fall back to SPU's storage configuration
+#[derive(Decode, Encode, Default, Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "use_serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct TopicStorageConfig {
+    /// records older than this are removed
+    #[cfg_attr(
+        feature = "use_serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub retention_seconds: Option<u32>,
+    /// max bytes kept per partition, oldest records are removed first
+    #[cfg_attr(
+        feature = "use_serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub max_partition_size: Option<u64>,
+    #[cfg_attr(
+        feature = "use_serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub segment_size: Option<u32>,
+    /// flush after number of writes
+    #[cfg_attr(
+        feature = "use_serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub flush_write_count: Option<u32>,
+    /// flush after idle time
+    #[cfg_attr(
+        feature = "use_serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub flush_idle_msec: Option<u32>,
+}
+
+impl TopicStorageConfig {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Validate storage settings
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.retention_seconds == Some(0) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "retention time must be greater than 0",
+            ));
+        }
+
+        if self.segment_size == Some(0) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "segment size must be greater than 0",
+            ));
+        }
+
+        if let (Some(segment_size), Some(max_partition_size)) =
+            (self.segment_size, self.max_partition_size)
+        {
+            if (segment_size as u64) > max_partition_size {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "max partition size must not be smaller than segment size",
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for TopicStorageConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let mut settings = vec![];
+        if let Some(retention_seconds) = self.retention_seconds {
+            settings.push(format!("retention:{}s", retention_seconds));
+        }
+        if let Some(max_partition_size) = self.max_partition_size {
+            settings.push(format!("max-partition-size:{}", max_partition_size));
+        }
+        if let Some(segment_size) = self.segment_size {
+            settings.push(format!("segment-size:{}", segment_size));
+        }
+        if let Some(flush_write_count) = self.flush_write_count {
+            settings.push(format!("flush-write-count:{}", flush_write_count));
+        }
+        if let Some(flush_idle_msec) = self.flush_idle_msec {
+            settings.push(format!("flush-idle:{}ms", flush_idle_msec));
+        }
+        write!(f, "{}", settings.join(", "))
+    }
+}
+
+/// SmartStream which SPU applies to produced records before they are written.
+/// Batch is rejected when module fails on any of its records
+#[derive(Decode, Encode, Default, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "use_serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TopicSmartStream {
+    /// name of SmartStream registered in SC
+    pub name: String,
+    pub kind: TopicSmartStreamKind,
+}
+
+impl TopicSmartStream {
+    pub fn new(name: impl Into<String>, kind: TopicSmartStreamKind) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+        }
+    }
+}
+
+impl std::fmt::Display for TopicSmartStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{} ({})", self.name, self.kind)
+    }
+}
+
+#[derive(Decode, Encode, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "use_serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TopicSmartStreamKind {
+    /// drop records which don't pass filter
+    Filter,
+    /// replace records with output of map
+    Map,
+}
+
+impl Default for TopicSmartStreamKind {
+    fn default() -> Self {
+        TopicSmartStreamKind::Filter
+    }
+}
+
+impl std::fmt::Display for TopicSmartStreamKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Filter => write!(f, "filter"),
+            Self::Map => write!(f, "map"),
+        }
+    }
+}
+
+/// How records are removed from partition logs
+#[derive(Decode, Encode, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "use_serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CleanupPolicy {
+    /// segments are removed when they are out of retention
+    Delete,
+    /// only latest record of each key is kept
+    Compact,
+}
+
+impl Default for CleanupPolicy {
+    fn default() -> Self {
+        CleanupPolicy::Delete
+    }
+}
+
+impl std::fmt::Display for CleanupPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Delete => write!(f, "delete"),
+            Self::Compact => write!(f, "compact"),
+        }
+    }
+}
+
 // -----------------------------------
 // Unit Tests
 // -----------------------------------
@@ -686,6 +955,95 @@ pub mod test {
         );
     }
 
+    #[test]
+    fn test_storage_config_validation() {
+        assert!(TopicStorageConfig::default().validate().is_ok());
+
+        let zero_retention = TopicStorageConfig {
+            retention_seconds: Some(0),
+            ..Default::default()
+        };
+        assert_eq!(
+            format!("{}", zero_retention.validate().unwrap_err()),
+            "retention time must be greater than 0"
+        );
+
+        let small_partition = TopicStorageConfig {
+            segment_size: Some(1000),
+            max_partition_size: Some(100),
+            ..Default::default()
+        };
+        assert_eq!(
+            format!("{}", small_partition.validate().unwrap_err()),
+            "max partition size must not be smaller than segment size"
+        );
+    }
+
+    #[test]
+    fn test_storage_config_encoding() {
+        let mut topic_spec = TopicSpec::new_computed(2, 1, None);
+        topic_spec.set_storage(TopicStorageConfig {
+            retention_seconds: Some(3600),
+            max_partition_size: Some(10_000_000_000),
+            ..Default::default()
+        });
+
+        // storage config is available since version 1
+        let mut dest = vec![];
+        topic_spec.encode(&mut dest, 1).expect("encode");
+        let mut decoded = TopicSpec::default();
+        decoded.decode(&mut Cursor::new(&dest), 1).expect("decode");
+        assert_eq!(decoded, topic_spec);
+
+        let mut dest = vec![];
+        topic_spec.encode(&mut dest, 0).expect("encode");
+        let mut decoded = TopicSpec::default();
+        decoded.decode(&mut Cursor::new(&dest), 0).expect("decode");
+        assert_eq!(decoded.partitions(), 2);
+        assert!(decoded.storage().is_none());
+    }
+
+    #[test]
+    fn test_smart_stream_encoding() {
+        let mut topic_spec = TopicSpec::new_assigned(vec![(0, vec![5001])]);
+        topic_spec.set_smart_stream(TopicSmartStream::new("validate", TopicSmartStreamKind::Map));
+
+        // smartstream is available since version 2
+        let mut dest = vec![];
+        topic_spec.encode(&mut dest, 2).expect("encode");
+        let mut decoded = TopicSpec::default();
+        decoded.decode(&mut Cursor::new(&dest), 2).expect("decode");
+        assert_eq!(decoded, topic_spec);
+
+        let mut dest = vec![];
+        topic_spec.encode(&mut dest, 1).expect("encode");
+        let mut decoded = TopicSpec::default();
+        decoded.decode(&mut Cursor::new(&dest), 1).expect("decode");
+        assert_eq!(decoded.partitions(), 1);
+        assert!(decoded.smart_stream().is_none());
+    }
+
+    #[test]
+    fn test_cleanup_policy_encoding() {
+        let mut topic_spec = TopicSpec::new_computed(1, 1, None);
+        topic_spec.set_cleanup_policy(CleanupPolicy::Compact);
+
+        // cleanup policy is available since version 3
+        let mut dest = vec![];
+        topic_spec.encode(&mut dest, 3).expect("encode");
+        let mut decoded = TopicSpec::default();
+        decoded.decode(&mut Cursor::new(&dest), 3).expect("decode");
+        assert_eq!(decoded, topic_spec);
+        assert_eq!(decoded.cleanup_policy(), Some(&CleanupPolicy::Compact));
+
+        let mut dest = vec![];
+        topic_spec.encode(&mut dest, 2).expect("encode");
+        let mut decoded = TopicSpec::default();
+        decoded.decode(&mut Cursor::new(&dest), 2).expect("decode");
+        assert_eq!(decoded.partitions(), 1);
+        assert!(decoded.cleanup_policy().is_none());
+    }
+
     // Partitions repeatedly reference spu-ids. The purpose of
     // this API is to return a list of all unique SPUs
     #[test]
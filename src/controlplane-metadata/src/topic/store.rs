@@ -36,8 +36,12 @@ where
             let replica_key = ReplicaKey::new(self.key(), *idx);
             debug!("Topic: {} creating partition: {}", self.key(), replica_key);
             if !partition_store.contains_key(&replica_key).await {
+                let partition_spec = PartitionSpec::from(replicas.clone())
+                    .with_storage(self.spec.storage().cloned())
+                    .with_smart_stream(self.spec.smart_stream().cloned())
+                    .with_cleanup_policy(self.spec.cleanup_policy().cloned());
                 partitions.push(
-                    MetadataStoreObject::with_spec(replica_key, replicas.clone().into())
+                    MetadataStoreObject::with_spec(replica_key, partition_spec)
                         .with_context(self.ctx.create_child()),
                 )
             }
@@ -12,6 +12,8 @@ pub use self::requests::update_replica::*;
 pub use self::requests::register_spu::*;
 pub use self::requests::update_lrs::*;
 pub use self::requests::remove::*;
+pub use self::requests::update_txn_markers::*;
+pub use self::requests::update_smartstream::*;
 
 use dataplane::api::RequestMessage;
 
@@ -3,3 +3,5 @@ pub mod update_replica;
 pub mod register_spu;
 pub mod update_lrs;
 pub mod remove;
+pub mod update_txn_markers;
+pub mod update_smartstream;
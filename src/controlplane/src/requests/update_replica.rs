@@ -17,6 +17,7 @@ pub struct UpdateReplicaRequest {
 
 impl Request for UpdateReplicaRequest {
     const API_KEY: u16 = InternalSpuApi::UpdateReplica as u16;
+    const DEFAULT_API_VERSION: i16 = 3;
     type Response = UpdateReplicaResponse;
 }
 
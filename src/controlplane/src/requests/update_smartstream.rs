@@ -0,0 +1,43 @@
+#![allow(clippy::assign_op_pattern)]
+
+use dataplane::derive::Decode;
+use dataplane::derive::Encode;
+use dataplane::api::Request;
+use fluvio_controlplane_metadata::message::SmartStreamMsg;
+use fluvio_controlplane_metadata::smartstream::SmartStream;
+
+use crate::InternalSpuApi;
+
+/// Changes to registered SmartStream modules
+#[derive(Decode, Encode, Debug, Default)]
+pub struct UpdateSmartStreamRequest {
+    pub epoch: i64,
+    pub changes: Vec<SmartStreamMsg>,
+    pub all: Vec<SmartStream>,
+}
+
+impl Request for UpdateSmartStreamRequest {
+    const API_KEY: u16 = InternalSpuApi::UpdateSmartStream as u16;
+    type Response = UpdateSmartStreamResponse;
+}
+
+impl UpdateSmartStreamRequest {
+    pub fn with_changes(epoch: i64, changes: Vec<SmartStreamMsg>) -> Self {
+        Self {
+            epoch,
+            changes,
+            all: vec![],
+        }
+    }
+
+    pub fn with_all(epoch: i64, all: Vec<SmartStream>) -> Self {
+        Self {
+            epoch,
+            changes: vec![],
+            all,
+        }
+    }
+}
+
+#[derive(Decode, Encode, Default, Debug)]
+pub struct UpdateSmartStreamResponse {}
@@ -0,0 +1,57 @@
+#![allow(clippy::assign_op_pattern)]
+
+use dataplane::derive::Decode;
+use dataplane::derive::Encode;
+use dataplane::api::Request;
+use dataplane::ReplicaKey;
+use crate::InternalSpuApi;
+use crate::InternalScKey;
+
+/// Write transaction markers to leader replicas
+#[derive(Decode, Encode, Debug, Default, Clone)]
+pub struct UpdateTxnMarkersRequest {
+    pub markers: Vec<TxnMarker>,
+}
+
+impl Request for UpdateTxnMarkersRequest {
+    const API_KEY: u16 = InternalSpuApi::UpdateTxnMarkers as u16;
+    type Response = UpdateTxnMarkersResponse;
+}
+
+impl UpdateTxnMarkersRequest {
+    pub fn new(markers: Vec<TxnMarker>) -> Self {
+        Self { markers }
+    }
+}
+
+/// Completes transaction of producer in a replica
+#[derive(Decode, Encode, Debug, Default, Clone, PartialEq)]
+pub struct TxnMarker {
+    pub replica: ReplicaKey,
+    pub producer_id: i64,
+    pub producer_epoch: i16,
+    pub commit: bool,
+}
+
+#[derive(Decode, Encode, Default, Debug)]
+pub struct UpdateTxnMarkersResponse {}
+
+/// Markers which has been written by leader replicas, sent from SPU to SC
+#[derive(Decode, Encode, Debug, Default, Clone)]
+pub struct TxnMarkersWrittenRequest {
+    pub markers: Vec<TxnMarker>,
+}
+
+impl Request for TxnMarkersWrittenRequest {
+    const API_KEY: u16 = InternalScKey::TxnMarkersWritten as u16;
+    type Response = TxnMarkersWrittenResponse;
+}
+
+impl TxnMarkersWrittenRequest {
+    pub fn new(markers: Vec<TxnMarker>) -> Self {
+        Self { markers }
+    }
+}
+
+#[derive(Decode, Encode, Default, Debug)]
+pub struct TxnMarkersWrittenResponse {}
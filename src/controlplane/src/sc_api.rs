@@ -13,6 +13,7 @@ use dataplane::derive::Decode;
 use super::RegisterSpuRequest;
 use super::UpdateLrsRequest;
 use super::ReplicaRemovedRequest;
+use super::TxnMarkersWrittenRequest;
 
 /// API call from Spu to SC
 
@@ -23,6 +24,7 @@ pub enum InternalScKey {
     RegisterSpu = 2000,
     UpdateLrs = 2001,
     ReplicaRemoved = 2002,
+    TxnMarkersWritten = 2003,
 }
 
 impl Default for InternalScKey {
@@ -37,6 +39,7 @@ pub enum InternalScRequest {
     RegisterSpuRequest(RequestMessage<RegisterSpuRequest>),
     UpdateLrsRequest(RequestMessage<UpdateLrsRequest>),
     ReplicaRemovedRequest(RequestMessage<ReplicaRemovedRequest>),
+    TxnMarkersWrittenRequest(RequestMessage<TxnMarkersWrittenRequest>),
 }
 
 impl Default for InternalScRequest {
@@ -64,6 +67,9 @@ impl ApiMessage for InternalScRequest {
             InternalScKey::ReplicaRemoved => {
                 api_decode!(InternalScRequest, ReplicaRemovedRequest, src, header)
             }
+            InternalScKey::TxnMarkersWritten => {
+                api_decode!(InternalScRequest, TxnMarkersWrittenRequest, src, header)
+            }
         }
     }
 }
@@ -11,6 +11,8 @@ use dataplane::derive::Decode;
 
 use super::UpdateSpuRequest;
 use super::UpdateReplicaRequest;
+use super::UpdateTxnMarkersRequest;
+use super::UpdateSmartStreamRequest;
 
 #[fluvio(encode_discriminant)]
 #[derive(PartialEq, Debug, Encode, Decode, Clone, Copy)]
@@ -18,6 +20,8 @@ use super::UpdateReplicaRequest;
 pub enum InternalSpuApi {
     UpdateSpu = 1001,
     UpdateReplica = 1002,
+    UpdateTxnMarkers = 1003,
+    UpdateSmartStream = 1004,
 }
 
 impl Default for InternalSpuApi {
@@ -30,6 +34,8 @@ impl Default for InternalSpuApi {
 pub enum InternalSpuRequest {
     UpdateSpuRequest(RequestMessage<UpdateSpuRequest>),
     UpdateReplicaRequest(RequestMessage<UpdateReplicaRequest>),
+    UpdateTxnMarkersRequest(RequestMessage<UpdateTxnMarkersRequest>),
+    UpdateSmartStreamRequest(RequestMessage<UpdateSmartStreamRequest>),
 }
 
 // Added to satisfy Encode/Decode traits
@@ -43,6 +49,14 @@ impl InternalSpuRequest {
     pub fn new_update_spu_req(msg: UpdateSpuRequest) -> Self {
         Self::UpdateSpuRequest(RequestMessage::new_request(msg))
     }
+
+    pub fn new_update_txn_markers_req(msg: UpdateTxnMarkersRequest) -> Self {
+        Self::UpdateTxnMarkersRequest(RequestMessage::new_request(msg))
+    }
+
+    pub fn new_update_smart_stream_req(msg: UpdateSmartStreamRequest) -> Self {
+        Self::UpdateSmartStreamRequest(RequestMessage::new_request(msg))
+    }
 }
 
 impl ApiMessage for InternalSpuRequest {
@@ -57,6 +71,12 @@ impl ApiMessage for InternalSpuRequest {
         match header.api_key().try_into()? {
             InternalSpuApi::UpdateSpu => api_decode!(Self, UpdateSpuRequest, src, header),
             InternalSpuApi::UpdateReplica => api_decode!(Self, UpdateReplicaRequest, src, header),
+            InternalSpuApi::UpdateTxnMarkers => {
+                api_decode!(Self, UpdateTxnMarkersRequest, src, header)
+            }
+            InternalSpuApi::UpdateSmartStream => {
+                api_decode!(Self, UpdateSmartStreamRequest, src, header)
+            }
         }
     }
 }
@@ -1,14 +1,20 @@
+use std::borrow::Cow;
+use std::io::Cursor;
 use std::io::Error;
+use std::io::ErrorKind;
 use std::mem::size_of;
 use std::fmt::Debug;
 
 use log::trace;
+use once_cell::sync::OnceCell;
 
 use crate::core::bytes::Buf;
 use crate::core::bytes::BufMut;
 
 use crate::core::Decoder;
+use crate::core::DecoderVarInt;
 use crate::core::Encoder;
+use crate::core::EncoderVarInt;
 use crate::core::Version;
 use crate::derive::Decode;
 use crate::derive::Encode;
@@ -16,9 +22,12 @@ use crate::derive::Encode;
 use crate::Offset;
 use crate::Size;
 use crate::record::DefaultRecord;
+use crate::compression::Compression;
+use crate::transaction::{CONTROL_FLAG_MASK, TRANSACTIONAL_FLAG_MASK};
 
 pub type DefaultBatchRecords = Vec<DefaultRecord>;
 pub type DefaultBatch = Batch<DefaultBatchRecords>;
+pub type RawBatch = Batch<RawBatchRecords>;
 
 pub trait BatchRecords: Default + Debug + Encoder + Decoder {
     /// how many bytes does record wants to process
@@ -29,13 +38,86 @@ pub trait BatchRecords: Default + Debug + Encoder + Decoder {
 
 impl BatchRecords for DefaultBatchRecords {}
 
+/// Records of batch which are kept encoded, so they can be passed on without decoding.
+/// Encoded same as `DefaultBatchRecords`
+#[derive(Default, Debug, Clone)]
+pub struct RawBatchRecords {
+    count: i32,
+    records: Vec<u8>,
+}
+
+impl RawBatchRecords {
+    /// add record which is already encoded
+    pub fn push(&mut self, record: &[u8]) {
+        self.count += 1;
+        self.records.extend_from_slice(record);
+    }
+
+    pub fn push_record(&mut self, record: &DefaultRecord) -> Result<(), Error> {
+        record.encode(&mut self.records, 0)?;
+        self.count += 1;
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.count as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+}
+
+impl BatchRecords for RawBatchRecords {}
+
+impl Encoder for RawBatchRecords {
+    fn write_size(&self, version: Version) -> usize {
+        self.count.write_size(version) + self.records.len()
+    }
+
+    fn encode<T>(&self, dest: &mut T, version: Version) -> Result<(), Error>
+    where
+        T: BufMut,
+    {
+        self.count.encode(dest, version)?;
+        dest.put_slice(&self.records);
+        Ok(())
+    }
+}
+
+impl Decoder for RawBatchRecords {
+    fn decode<T>(&mut self, src: &mut T, version: Version) -> Result<(), Error>
+    where
+        T: Buf,
+    {
+        let mut count: i32 = 0;
+        count.decode(src, version)?;
+        for _ in 0..count {
+            let mut len: i64 = 0;
+            len.decode_varint(src)?;
+            if len < 0 || src.remaining() < len as usize {
+                return Err(Error::new(
+                    ErrorKind::UnexpectedEof,
+                    "not enough bytes for record",
+                ));
+            }
+            len.encode_varint(&mut self.records)?;
+            let start = self.records.len();
+            self.records.resize(start + len as usize, 0);
+            src.copy_to_slice(&mut self.records[start..]);
+            self.count += 1;
+        }
+        Ok(())
+    }
+}
+
 /// size of the offset and length
 pub const BATCH_PREAMBLE_SIZE: usize = size_of::<Offset>()     // Offset
         + size_of::<i32>(); // i32
 
 pub const BATCH_FILE_HEADER_SIZE: usize = BATCH_PREAMBLE_SIZE + BATCH_HEADER_SIZE;
 
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone)]
 pub struct Batch<R>
 where
     R: BatchRecords,
@@ -44,6 +126,8 @@ where
     pub batch_len: i32, // only for decoding
     pub header: BatchHeader,
     records: R,
+    /// records as compressed on the wire, compressed once and reused for size and encoding
+    compressed_records: OnceCell<Vec<u8>>,
 }
 
 impl<R> Batch<R>
@@ -70,9 +154,22 @@ where
 
     #[inline(always)]
     pub fn mut_records(&mut self) -> &mut R {
+        self.compressed_records = OnceCell::new();
         &mut self.records
     }
 
+    pub fn get_compression(&self) -> Result<Compression, Error> {
+        self.header.get_compression()
+    }
+
+    /// compress records using codec, compressed records are kept for encoding
+    pub fn compress(&mut self, compression: Compression) -> Result<(), Error> {
+        self.header.set_compression(compression);
+        self.compressed_records = OnceCell::new();
+        self.encoded_records(0)?;
+        Ok(())
+    }
+
     pub fn get_base_offset(&self) -> Offset {
         self.base_offset
     }
@@ -115,6 +212,74 @@ where
         self.header.decode(src, version)?;
         Ok(())
     }
+
+    /// decode records after header, uncompress them if batch is compressed
+    pub fn decode_records<T>(&mut self, src: &mut T, version: Version) -> Result<(), Error>
+    where
+        T: Buf,
+    {
+        let compression = self.get_compression()?;
+        if compression == Compression::None {
+            return self.records.decode(src, version);
+        }
+
+        let len = (self.batch_len as usize).saturating_sub(BATCH_HEADER_SIZE);
+        if src.remaining() < len {
+            return Err(Error::new(
+                ErrorKind::UnexpectedEof,
+                format!(
+                    "not enough bytes for compressed records, expected: {}, remaining: {}",
+                    len,
+                    src.remaining()
+                ),
+            ));
+        }
+        let mut compressed = vec![0u8; len];
+        src.copy_to_slice(&mut compressed);
+        let uncompressed = compression.uncompress(&compressed)?;
+        trace!(
+            "uncompressed {} records from {} to {} bytes",
+            compression,
+            len,
+            uncompressed.len()
+        );
+        self.records
+            .decode(&mut Cursor::new(uncompressed), version)?;
+        self.compressed_records = OnceCell::from(compressed);
+        Ok(())
+    }
+
+    /// records as they are encoded in the batch, compressed records are cached
+    fn encoded_records(&self, version: Version) -> Result<Cow<'_, [u8]>, Error> {
+        if let Some(compressed) = self.compressed_records.get() {
+            return Ok(Cow::Borrowed(compressed));
+        }
+        match self.get_compression()? {
+            Compression::None => {
+                let mut out: Vec<u8> = Vec::new();
+                self.records.encode(&mut out, version)?;
+                Ok(Cow::Owned(out))
+            }
+            compression => {
+                let compressed = self.compressed_records.get_or_try_init(|| {
+                    let mut out: Vec<u8> = Vec::new();
+                    self.records.encode(&mut out, version)?;
+                    compression.compress(&out)
+                })?;
+                Ok(Cow::Borrowed(compressed))
+            }
+        }
+    }
+
+    fn records_write_size(&self, version: Version) -> usize {
+        match self.get_compression() {
+            Ok(Compression::None) | Err(_) => self.records.write_size(version),
+            Ok(_) => self
+                .encoded_records(version)
+                .map(|records| records.len())
+                .unwrap_or_else(|_| self.records.write_size(version)),
+        }
+    }
 }
 
 impl DefaultBatch {
@@ -146,9 +311,23 @@ impl DefaultBatch {
         };
         record.preamble.set_offset_delta(last_offset_delta);
         self.header.last_offset_delta = last_offset_delta as i32;
+        self.compressed_records = OnceCell::new();
         self.records.push(record)
     }
 
+    /// set timestamp of batch in milliseconds, timestamps of records are relative to it.
+    /// max timestamp is computed from timestamp deltas of records
+    pub fn set_first_timestamp(&mut self, timestamp: i64) {
+        let max_delta = self
+            .records
+            .iter()
+            .map(|record| record.get_timestamp_delta())
+            .max()
+            .unwrap_or(0);
+        self.header.first_timestamp = timestamp;
+        self.header.max_time_stamp = timestamp + max_delta;
+    }
+
     /// computed last offset which is base offset + number of records
     pub fn computed_last_offset(&self) -> Offset {
         self.get_base_offset() + self.records.len() as Offset
@@ -165,7 +344,7 @@ where
     {
         trace!("decoding batch");
         self.decode_from_file_buf(src, version)?;
-        self.records.decode(src, version)?;
+        self.decode_records(src, version)?;
         Ok(())
     }
 }
@@ -176,7 +355,7 @@ where
     R: BatchRecords,
 {
     fn write_size(&self, version: Version) -> usize {
-        BATCH_PREAMBLE_SIZE + BATCH_HEADER_SIZE + self.records.write_size(version)
+        BATCH_PREAMBLE_SIZE + BATCH_HEADER_SIZE + self.records_write_size(version)
     }
 
     fn encode<T>(&self, dest: &mut T, version: Version) -> Result<(), Error>
@@ -185,7 +364,8 @@ where
     {
         trace!("Encoding Batch");
         self.base_offset.encode(dest, version)?;
-        let batch_len: i32 = (BATCH_HEADER_SIZE + self.records.write_size(version)) as i32;
+        let records = self.encoded_records(version)?;
+        let batch_len: i32 = (BATCH_HEADER_SIZE + records.len()) as i32;
         batch_len.encode(dest, version)?;
 
         // encode parts of header
@@ -201,7 +381,7 @@ where
         self.header.producer_id.encode(buf, version)?;
         self.header.producer_epoch.encode(buf, version)?;
         self.header.first_sequence.encode(buf, version)?;
-        self.records.encode(buf, version)?;
+        buf.extend_from_slice(&records);
 
         let crc = crc32c::crc32c(&out);
         crc.encode(dest, version)?;
@@ -210,7 +390,7 @@ where
     }
 }
 
-#[derive(Debug, Decode, Encode)]
+#[derive(Debug, Decode, Encode, Clone)]
 pub struct BatchHeader {
     pub partition_leader_epoch: i32,
     pub magic: i8,
@@ -224,6 +404,59 @@ pub struct BatchHeader {
     pub first_sequence: i32,
 }
 
+impl BatchHeader {
+    pub fn get_compression(&self) -> Result<Compression, Error> {
+        Compression::from_attributes(self.attributes)
+    }
+
+    pub fn set_compression(&mut self, compression: Compression) {
+        self.attributes = compression.to_attributes(self.attributes);
+    }
+
+    /// batch was produced by idempotent producer
+    pub fn is_idempotent(&self) -> bool {
+        self.producer_id >= 0
+    }
+
+    /// set producer identity and sequence number of first record
+    pub fn set_producer(&mut self, producer_id: i64, producer_epoch: i16, first_sequence: i32) {
+        self.producer_id = producer_id;
+        self.producer_epoch = producer_epoch;
+        self.first_sequence = first_sequence;
+    }
+
+    /// sequence number of last record in the batch
+    pub fn last_sequence(&self) -> i32 {
+        self.first_sequence.wrapping_add(self.last_offset_delta)
+    }
+
+    /// batch is part of transaction
+    pub fn is_transactional(&self) -> bool {
+        self.attributes & TRANSACTIONAL_FLAG_MASK != 0
+    }
+
+    pub fn set_transactional(&mut self, transactional: bool) {
+        if transactional {
+            self.attributes |= TRANSACTIONAL_FLAG_MASK;
+        } else {
+            self.attributes &= !TRANSACTIONAL_FLAG_MASK;
+        }
+    }
+
+    /// batch contains control record which completes transaction
+    pub fn is_control(&self) -> bool {
+        self.attributes & CONTROL_FLAG_MASK != 0
+    }
+
+    pub fn set_control(&mut self, control: bool) {
+        if control {
+            self.attributes |= CONTROL_FLAG_MASK;
+        } else {
+            self.attributes &= !CONTROL_FLAG_MASK;
+        }
+    }
+}
+
 impl Default for BatchHeader {
     fn default() -> Self {
         BatchHeader {
@@ -262,6 +495,7 @@ mod test {
     use crate::core::Encoder;
     use crate::record::{DefaultRecord, DefaultAsyncBuffer};
     use crate::batch::DefaultBatch;
+    use crate::compression::Compression;
     use super::BatchHeader;
     use super::BATCH_HEADER_SIZE;
 
@@ -298,6 +532,53 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_encode_and_decode_compressed_batch() -> Result<(), IoError> {
+        let codecs = [
+            Compression::Gzip,
+            Compression::Snappy,
+            Compression::Lz4,
+            Compression::Zstd,
+        ];
+        let value = br#"{"name":"fluvio","kind":"stream"}"#.repeat(10);
+        let plain = DefaultBatch::new(vec![
+            DefaultRecord::new(value.clone()),
+            DefaultRecord::new(value.clone()),
+        ]);
+        let plain_size = plain.write_size(0);
+
+        for codec in codecs.iter() {
+            let mut batch = DefaultBatch::new(vec![
+                DefaultRecord::new(value.clone()),
+                DefaultRecord::new(value.clone()),
+            ]);
+            batch.compress(*codec)?;
+            assert!(batch.write_size(0) < plain_size);
+
+            let bytes = batch.as_bytes(0)?;
+            assert_eq!(bytes.len(), batch.write_size(0));
+
+            let mut decoded = DefaultBatch::decode_from(&mut Cursor::new(bytes.clone()), 0)?;
+            assert_eq!(decoded.get_compression()?, *codec);
+            assert_eq!(decoded.records().len(), 2);
+            assert_eq!(decoded.records()[1].value.as_ref(), value.as_slice());
+
+            // re-encoding keeps compressed records as received
+            decoded.set_base_offset(100);
+            let re_encoded = decoded.as_bytes(0)?;
+            assert_eq!(re_encoded.len(), bytes.len());
+            assert_eq!(&re_encoded[8..], &bytes[8..]);
+
+            // records changed after compression are compressed again, once for size and encoding
+            batch.mut_records().push(DefaultRecord::new(value.clone()));
+            let size = batch.write_size(0);
+            assert!(batch.compressed_records.get().is_some());
+            assert_eq!(batch.as_bytes(0)?.len(), size);
+        }
+
+        Ok(())
+    }
+
     /*  raw batch encoded
 
     0000   02 00 00 00 45 00 00 c7 00 00 40 00 40 06 00 00
@@ -387,4 +668,20 @@ mod test {
 
         assert_eq!(batch_created.get_last_offset_delta(), 2);
     }
+
+    #[test]
+    fn test_batch_timestamps() -> Result<(), IoError> {
+        let mut records = vec![DefaultRecord::new("a"), DefaultRecord::new("b")];
+        records[1].preamble.set_timestamp_delta(20);
+        let mut batch = DefaultBatch::new(records);
+        batch.set_first_timestamp(1000);
+        assert_eq!(batch.get_header().first_timestamp, 1000);
+        assert_eq!(batch.get_header().max_time_stamp, 1020);
+
+        let bytes = batch.as_bytes(0)?;
+        let decoded = DefaultBatch::decode_from(&mut Cursor::new(bytes), 0)?;
+        assert_eq!(decoded.get_header().max_time_stamp, 1020);
+        assert_eq!(decoded.records()[1].get_timestamp_delta(), 20);
+        Ok(())
+    }
 }
@@ -0,0 +1,218 @@
+//!
+//! # Record batch compression
+//!
+//! Codec is stored in the lower 3 bits of batch attributes, using same values as Kafka.
+//! Lz4 uses standard LZ4 frame format, same as Kafka.
+//!
+
+use std::fmt;
+use std::io::Error;
+use std::io::ErrorKind;
+use std::str::FromStr;
+
+/// mask for compression codec in batch attributes
+pub const COMPRESSION_CODEC_MASK: i16 = 0x07;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(i16)]
+pub enum Compression {
+    None = 0,
+    Gzip = 1,
+    Snappy = 2,
+    Lz4 = 3,
+    Zstd = 4,
+}
+
+impl Default for Compression {
+    fn default() -> Self {
+        Compression::None
+    }
+}
+
+impl Compression {
+    /// extract codec from batch attributes
+    pub fn from_attributes(attributes: i16) -> Result<Self, Error> {
+        match attributes & COMPRESSION_CODEC_MASK {
+            0 => Ok(Compression::None),
+            1 => Ok(Compression::Gzip),
+            2 => Ok(Compression::Snappy),
+            3 => Ok(Compression::Lz4),
+            4 => Ok(Compression::Zstd),
+            codec => Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("unknown compression codec: {}", codec),
+            )),
+        }
+    }
+
+    /// set codec bits into batch attributes
+    pub fn to_attributes(self, attributes: i16) -> i16 {
+        (attributes & !COMPRESSION_CODEC_MASK) | self as i16
+    }
+
+    pub fn compress(&self, src: &[u8]) -> Result<Vec<u8>, Error> {
+        match self {
+            Compression::None => Ok(src.to_vec()),
+            #[cfg(feature = "compress")]
+            Compression::Gzip => codec::gzip_compress(src),
+            #[cfg(feature = "compress")]
+            Compression::Snappy => codec::snappy_compress(src),
+            #[cfg(feature = "compress")]
+            Compression::Lz4 => codec::lz4_compress(src),
+            #[cfg(feature = "compress")]
+            Compression::Zstd => zstd::stream::encode_all(src, 0),
+            #[cfg(not(feature = "compress"))]
+            _ => Err(self.unsupported()),
+        }
+    }
+
+    pub fn uncompress(&self, src: &[u8]) -> Result<Vec<u8>, Error> {
+        match self {
+            Compression::None => Ok(src.to_vec()),
+            #[cfg(feature = "compress")]
+            Compression::Gzip => codec::gzip_uncompress(src),
+            #[cfg(feature = "compress")]
+            Compression::Snappy => codec::snappy_uncompress(src),
+            #[cfg(feature = "compress")]
+            Compression::Lz4 => codec::lz4_uncompress(src),
+            #[cfg(feature = "compress")]
+            Compression::Zstd => zstd::stream::decode_all(src),
+            #[cfg(not(feature = "compress"))]
+            _ => Err(self.unsupported()),
+        }
+    }
+
+    #[cfg(not(feature = "compress"))]
+    fn unsupported(&self) -> Error {
+        Error::new(
+            ErrorKind::Other,
+            format!("compression {} is not supported", self),
+        )
+    }
+}
+
+impl fmt::Display for Compression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Compression::None => "none",
+            Compression::Gzip => "gzip",
+            Compression::Snappy => "snappy",
+            Compression::Lz4 => "lz4",
+            Compression::Zstd => "zstd",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Compression {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "none" => Ok(Compression::None),
+            "gzip" => Ok(Compression::Gzip),
+            "snappy" => Ok(Compression::Snappy),
+            "lz4" => Ok(Compression::Lz4),
+            "zstd" => Ok(Compression::Zstd),
+            _ => Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("unknown compression: {}", s),
+            )),
+        }
+    }
+}
+
+#[cfg(feature = "compress")]
+mod codec {
+
+    use std::io::Error;
+    use std::io::ErrorKind;
+    use std::io::Read;
+    use std::io::Write;
+
+    use flate2::Compression as GzipLevel;
+    use flate2::read::GzDecoder;
+    use flate2::write::GzEncoder;
+    use lz4_flex::frame::{FrameDecoder, FrameEncoder};
+
+    pub fn gzip_compress(src: &[u8]) -> Result<Vec<u8>, Error> {
+        let mut encoder = GzEncoder::new(Vec::new(), GzipLevel::default());
+        encoder.write_all(src)?;
+        encoder.finish()
+    }
+
+    pub fn gzip_uncompress(src: &[u8]) -> Result<Vec<u8>, Error> {
+        let mut out = Vec::new();
+        GzDecoder::new(src).read_to_end(&mut out)?;
+        Ok(out)
+    }
+
+    pub fn snappy_compress(src: &[u8]) -> Result<Vec<u8>, Error> {
+        snap::raw::Encoder::new()
+            .compress_vec(src)
+            .map_err(|err| Error::new(ErrorKind::InvalidData, err.to_string()))
+    }
+
+    pub fn snappy_uncompress(src: &[u8]) -> Result<Vec<u8>, Error> {
+        snap::raw::Decoder::new()
+            .decompress_vec(src)
+            .map_err(|err| Error::new(ErrorKind::InvalidData, err.to_string()))
+    }
+
+    pub fn lz4_compress(src: &[u8]) -> Result<Vec<u8>, Error> {
+        let mut encoder = FrameEncoder::new(Vec::new());
+        encoder.write_all(src)?;
+        Ok(encoder.finish()?)
+    }
+
+    pub fn lz4_uncompress(src: &[u8]) -> Result<Vec<u8>, Error> {
+        let mut out = Vec::new();
+        FrameDecoder::new(src).read_to_end(&mut out)?;
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::Compression;
+
+    const CODECS: [Compression; 5] = [
+        Compression::None,
+        Compression::Gzip,
+        Compression::Snappy,
+        Compression::Lz4,
+        Compression::Zstd,
+    ];
+
+    #[test]
+    fn test_compression_attributes() {
+        for codec in CODECS.iter() {
+            let attributes = codec.to_attributes(0x10);
+            assert_eq!(attributes & 0x10, 0x10);
+            assert_eq!(Compression::from_attributes(attributes).unwrap(), *codec);
+        }
+        assert!(Compression::from_attributes(0x05).is_err());
+    }
+
+    #[cfg(feature = "compress")]
+    #[test]
+    fn test_compression_round_trip() {
+        let data = br#"{"name":"fluvio","kind":"stream"}"#.repeat(20);
+        for codec in CODECS.iter() {
+            let compressed = codec.compress(&data).expect("compress");
+            if *codec != Compression::None {
+                assert!(compressed.len() < data.len());
+            }
+            let uncompressed = codec.uncompress(&compressed).expect("uncompress");
+            assert_eq!(uncompressed, data);
+        }
+    }
+
+    #[test]
+    fn test_compression_parse() {
+        assert_eq!("gzip".parse::<Compression>().unwrap(), Compression::Gzip);
+        assert_eq!("ZSTD".parse::<Compression>().unwrap(), Compression::Zstd);
+        assert!("brotli".parse::<Compression>().is_err());
+    }
+}
@@ -23,8 +23,19 @@ pub enum ErrorCode {
 
     OffsetOutOfRange = 1,
     NotLeaderForPartition = 6,
+    RequestTimedOut = 7,
     MessageTooLarge = 10,
     PermissionDenied = 13,
+    InvalidRequiredAcks = 21,
+    IllegalGeneration = 22,
+    UnknownMemberId = 25,
+    RebalanceInProgress = 27,
+    OutOfOrderSequenceNumber = 45,
+    DuplicateSequenceNumber = 46,
+    InvalidProducerEpoch = 47,
+    InvalidTxnState = 48,
+    InvalidProducerIdMapping = 49,
+    ConcurrentTransactions = 51,
     StorageError = 56,
 
     // Spu errors
@@ -48,6 +59,22 @@ pub enum ErrorCode {
 
     // Stream Fetch error
     FetchSessionNotFoud = 3002,
+    PartitionNotFound = 3003,
+    PartitionInvalidReplicas = 3004,
+    PartitionReassignmentInProgress = 3005,
+
+    // SmartStream errors
+    SmartStreamError = 4000,
+    SmartStreamFuelExhausted = 4001,
+    SmartStreamMemoryLimitExceeded = 4002,
+    SmartStreamRuntimeError = 4003,
+    SmartStreamNotFound = 4004,
+    SmartStreamAlreadyExists = 4005,
+    SmartStreamRecordRejected = 4006,
+
+    // Sc errors
+    ScError = 5000,
+    ScNotLeader = 5001,
 }
 
 impl Default for ErrorCode {
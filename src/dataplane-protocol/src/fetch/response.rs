@@ -110,7 +110,7 @@ impl FetchablePartitionResponse<RecordSet> {
     }
 }
 
-#[derive(Encode, Decode, FluvioDefault, Debug)]
+#[derive(Encode, Decode, FluvioDefault, Debug, Clone, PartialEq)]
 pub struct AbortedTransaction {
     pub producer_id: i64,
     pub first_offset: i64,
@@ -5,10 +5,13 @@ mod common;
 mod error_code;
 
 pub mod batch;
+pub mod compression;
 pub mod record;
 pub mod fetch;
 pub mod produce;
 pub mod versions;
+pub mod transaction;
+pub mod smartstream;
 
 #[cfg(feature = "fixture")]
 pub mod fixture;
@@ -17,7 +17,7 @@ pub type DefaultProduceRequest = ProduceRequest<RecordSet>;
 pub type DefaultPartitionRequest = PartitionProduceData<RecordSet>;
 pub type DefaultTopicRequest = TopicProduceData<RecordSet>;
 
-#[derive(Encode, Decode, FluvioDefault, Debug)]
+#[derive(Encode, Decode, FluvioDefault, Debug, Clone)]
 pub struct ProduceRequest<R>
 where
     R: Encoder + Decoder + Default + Debug,
@@ -52,7 +52,7 @@ where
     type Response = ProduceResponse;
 }
 
-#[derive(Encode, Decode, FluvioDefault, Debug)]
+#[derive(Encode, Decode, FluvioDefault, Debug, Clone)]
 pub struct TopicProduceData<R>
 where
     R: Encoder + Decoder + Default + Debug,
@@ -65,7 +65,7 @@ where
     pub data: PhantomData<R>,
 }
 
-#[derive(Encode, Decode, FluvioDefault, Debug)]
+#[derive(Encode, Decode, FluvioDefault, Debug, Clone)]
 pub struct PartitionProduceData<R>
 where
     R: Encoder + Decoder + Default + Debug,
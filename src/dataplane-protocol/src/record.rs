@@ -20,7 +20,7 @@ use crate::core::Version;
 use crate::derive::Decode;
 use crate::derive::Encode;
 
-use crate::batch::DefaultBatch;
+use crate::batch::{Batch, BatchRecords, DefaultBatch, RawBatch};
 use crate::Offset;
 
 pub type DefaultRecord = Record<DefaultAsyncBuffer>;
@@ -38,7 +38,7 @@ pub trait AsyncBuffer {
 
 pub trait Records {}
 
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct DefaultAsyncBuffer(Bytes);
 
 impl DefaultAsyncBuffer {
@@ -178,7 +178,7 @@ impl Decoder for DefaultAsyncBuffer {
 
 /// Represents sets of batches in storage
 //  It is written consequently with len as prefix
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone)]
 pub struct RecordSet {
     pub batches: Vec<DefaultBatch>,
 }
@@ -224,82 +224,155 @@ impl Decoder for RecordSet {
     where
         T: Buf,
     {
-        trace!("raw buffer len: {}", src.remaining());
-        let mut len: i32 = 0;
-        len.decode(src, version)?;
-        trace!("Record sets decoded content len: {}", len);
+        decode_batches(&mut self.batches, src, version)
+    }
+}
 
-        if src.remaining() < len as usize {
-            return Err(Error::new(
-                ErrorKind::UnexpectedEof,
-                format!(
-                    "expected message len: {} but founded {}",
-                    len,
-                    src.remaining()
-                ),
-            ));
-        }
+impl Encoder for RecordSet {
+    fn write_size(&self, version: Version) -> usize {
+        batches_write_size(&self.batches, version)
+    }
 
-        let mut buf = src.take(len as usize);
+    fn encode<T>(&self, dest: &mut T, version: Version) -> Result<(), Error>
+    where
+        T: BufMut,
+    {
+        encode_batches(&self.batches, dest, version)
+    }
+}
 
-        let mut count = 0;
-        while buf.remaining() > 0 {
-            trace!(
-                "decoding batches: {}, remaining bytes: {}",
-                count,
-                buf.remaining()
-            );
-            let mut batch = DefaultBatch::default();
-            match batch.decode(&mut buf, version) {
-                Ok(_) => self.batches.push(batch),
-                Err(err) => match err.kind() {
-                    ErrorKind::UnexpectedEof => {
-                        warn!("not enough bytes for batch: {}", buf.remaining());
-                        return Ok(());
-                    }
-                    _ => {
-                        warn!("problem decoding batch: {}", err);
-                        return Ok(());
-                    }
-                },
-            }
-            count += 1;
-        }
+/// Set of batches whose records are kept encoded.
+/// Encoded same as `RecordSet`, so it can be decoded as `RecordSet`
+#[derive(Default, Debug, Clone)]
+pub struct RawRecordSet {
+    pub batches: Vec<RawBatch>,
+}
 
-        Ok(())
+impl RawRecordSet {
+    pub fn add(mut self, batch: RawBatch) -> Self {
+        self.batches.push(batch);
+        self
+    }
+
+    /// last offset covered by batches
+    pub fn last_offset(&self) -> Option<Offset> {
+        self.batches.last().map(|batch| batch.get_last_offset())
+    }
+
+    /// total records
+    pub fn total_records(&self) -> usize {
+        self.batches.iter().map(|batch| batch.records().len()).sum()
     }
 }
 
-impl Encoder for RecordSet {
+impl Decoder for RawRecordSet {
+    fn decode<T>(&mut self, src: &mut T, version: Version) -> Result<(), Error>
+    where
+        T: Buf,
+    {
+        decode_batches(&mut self.batches, src, version)
+    }
+}
+
+impl Encoder for RawRecordSet {
     fn write_size(&self, version: Version) -> usize {
-        self.batches
-            .iter()
-            .fold(4, |sum, val| sum + val.write_size(version))
+        batches_write_size(&self.batches, version)
     }
 
     fn encode<T>(&self, dest: &mut T, version: Version) -> Result<(), Error>
     where
         T: BufMut,
     {
-        trace!("Record set encoding");
-
-        let mut out: Vec<u8> = Vec::new();
+        encode_batches(&self.batches, dest, version)
+    }
+}
 
-        for batch in &self.batches {
-            trace!("encoding batch..");
-            batch.encode(&mut out, version)?;
+fn decode_batches<R, T>(
+    batches: &mut Vec<Batch<R>>,
+    src: &mut T,
+    version: Version,
+) -> Result<(), Error>
+where
+    R: BatchRecords,
+    T: Buf,
+{
+    trace!("raw buffer len: {}", src.remaining());
+    let mut len: i32 = 0;
+    len.decode(src, version)?;
+    trace!("Record sets decoded content len: {}", len);
+
+    if src.remaining() < len as usize {
+        return Err(Error::new(
+            ErrorKind::UnexpectedEof,
+            format!(
+                "expected message len: {} but founded {}",
+                len,
+                src.remaining()
+            ),
+        ));
+    }
+
+    let mut buf = src.take(len as usize);
+
+    let mut count = 0;
+    while buf.remaining() > 0 {
+        trace!(
+            "decoding batches: {}, remaining bytes: {}",
+            count,
+            buf.remaining()
+        );
+        let mut batch = Batch::<R>::default();
+        match batch.decode(&mut buf, version) {
+            Ok(_) => batches.push(batch),
+            Err(err) => match err.kind() {
+                ErrorKind::UnexpectedEof => {
+                    warn!("not enough bytes for batch: {}", buf.remaining());
+                    return Ok(());
+                }
+                _ => {
+                    warn!("problem decoding batch: {}", err);
+                    return Ok(());
+                }
+            },
         }
+        count += 1;
+    }
 
-        let length: i32 = out.len() as i32;
-        trace!("Record Set encode len: {}", length);
-        length.encode(dest, version)?;
+    Ok(())
+}
 
-        dest.put_slice(&out);
-        Ok(())
+fn batches_write_size<R>(batches: &[Batch<R>], version: Version) -> usize
+where
+    R: BatchRecords,
+{
+    batches
+        .iter()
+        .fold(4, |sum, val| sum + val.write_size(version))
+}
+
+fn encode_batches<R, T>(batches: &[Batch<R>], dest: &mut T, version: Version) -> Result<(), Error>
+where
+    R: BatchRecords,
+    T: BufMut,
+{
+    trace!("Record set encoding");
+
+    let mut out: Vec<u8> = Vec::new();
+
+    for batch in batches {
+        trace!("encoding batch..");
+        batch.encode(&mut out, version)?;
     }
+
+    let length: i32 = out.len() as i32;
+    trace!("Record Set encode len: {}", length);
+    length.encode(dest, version)?;
+
+    dest.put_slice(&out);
+    Ok(())
 }
 
-#[derive(Decode, Encode, Default, Debug)]
+#[derive(Decode, Encode, Default, Debug, Clone)]
 pub struct RecordHeader {
     attributes: i8,
     #[varint]
@@ -316,9 +389,18 @@ impl RecordHeader {
     pub fn offset_delta(&self) -> Offset {
         self.offset_delta
     }
+
+    /// set timestamp in milliseconds relative to first timestamp of batch
+    pub fn set_timestamp_delta(&mut self, delta: i64) {
+        self.timestamp_delta = delta;
+    }
+
+    pub fn timestamp_delta(&self) -> i64 {
+        self.timestamp_delta
+    }
 }
 
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct Record<B>
 where
     B: Default,
@@ -337,6 +419,10 @@ where
         self.preamble.offset_delta
     }
 
+    pub fn get_timestamp_delta(&self) -> i64 {
+        self.preamble.timestamp_delta
+    }
+
     /// add offset delta with new relative base offset
     pub fn add_base_offset(&mut self, relative_base_offset: Offset) {
         self.preamble.offset_delta += relative_base_offset;
@@ -581,6 +667,31 @@ mod test {
         assert_eq!(decoded_batches.batches.len(), 2);
     }
 
+    /// raw records are passed on as they are encoded
+    #[test]
+    fn test_raw_record_set_encoding() {
+        use super::{RecordSet, RawRecordSet};
+        use crate::batch::DefaultBatch;
+
+        let records = RecordSet::default()
+            .add(DefaultBatch::new(vec![
+                DefaultRecord::new_key_value("k1", "v1"),
+                DefaultRecord::new("v2"),
+            ]))
+            .add(DefaultBatch::new(vec![DefaultRecord::new("v3")]));
+        let bytes = records.as_bytes(0).expect("bytes");
+
+        let raw = RawRecordSet::decode_from(&mut Cursor::new(&bytes), 0).expect("decode");
+        assert_eq!(raw.batches.len(), 2);
+        assert_eq!(raw.total_records(), 3);
+        assert_eq!(raw.as_bytes(0).expect("bytes"), bytes);
+
+        let decoded = RecordSet::decode_from(&mut Cursor::new(raw.as_bytes(0).expect("bytes")), 0)
+            .expect("decode");
+        assert_eq!(decoded.total_records(), 3);
+        assert_eq!(decoded.batches[1].records()[0].value().as_ref(), b"v3");
+    }
+
     #[test]
     fn test_key_value_encoding() {
         let key = "KKKKKKKKKK".to_string();
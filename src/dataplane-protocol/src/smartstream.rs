@@ -0,0 +1,41 @@
+//!
+//! # SmartStream types
+//!
+//! Error raised by SmartStream module while processing a record.
+//! Module passes it to SPU, which sends it back to consumer.
+//! Positions of records kept by filter of file slice.
+//!
+
+use std::fmt;
+
+use crate::Offset;
+use crate::derive::{Encode, Decode};
+
+/// Error returned by SmartStream function for a record
+#[derive(Debug, Clone, Default, PartialEq, Encode, Decode)]
+pub struct SmartStreamRuntimeError {
+    /// offset of failed record, module reports offset delta within batch and SPU
+    /// replaces it with absolute offset. Filter of file slice reports absolute offset
+    pub offset: Offset,
+    pub message: String,
+}
+
+impl fmt::Display for SmartStreamRuntimeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "SmartStream failed at offset {}: {}",
+            self.offset, self.message
+        )
+    }
+}
+
+impl std::error::Error for SmartStreamRuntimeError {}
+
+/// Record kept by filter of file slice, as position and length of encoded record
+/// within slice passed to module. SPU copies kept records from slice as they are stored
+#[derive(Debug, Clone, Copy, Default, PartialEq, Encode, Decode)]
+pub struct SmartStreamRecordPosition {
+    pub position: i32,
+    pub len: i32,
+}
@@ -0,0 +1,174 @@
+//!
+//! # Transaction control records
+//!
+//! Transaction is completed by control batch written to each partition of the transaction.
+//! Control batch contains a single record whose key encodes the control type, same as Kafka.
+//!
+
+use std::collections::HashSet;
+
+use crate::batch::DefaultBatch;
+use crate::fetch::AbortedTransaction;
+use crate::record::DefaultRecord;
+
+/// batch attribute set when batch is part of transaction
+pub const TRANSACTIONAL_FLAG_MASK: i16 = 0x10;
+/// batch attribute set for control batch
+pub const CONTROL_FLAG_MASK: i16 = 0x20;
+
+const CONTROL_RECORD_VERSION: i16 = 0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(i16)]
+pub enum ControlRecordType {
+    Abort = 0,
+    Commit = 1,
+}
+
+impl ControlRecordType {
+    /// control record key is version followed by type
+    fn encode_key(self) -> Vec<u8> {
+        let mut key = CONTROL_RECORD_VERSION.to_be_bytes().to_vec();
+        key.extend_from_slice(&(self as i16).to_be_bytes());
+        key
+    }
+
+    fn decode_key(key: &[u8]) -> Option<Self> {
+        if key.len() < 4 {
+            return None;
+        }
+        match i16::from_be_bytes([key[2], key[3]]) {
+            0 => Some(Self::Abort),
+            1 => Some(Self::Commit),
+            _ => None,
+        }
+    }
+}
+
+impl DefaultBatch {
+    /// create control batch which completes transaction of producer
+    pub fn control(producer_id: i64, producer_epoch: i16, control: ControlRecordType) -> Self {
+        let record = DefaultRecord::new_key_value(control.encode_key(), Vec::<u8>::new());
+        let mut batch = DefaultBatch::new(vec![record]);
+        let header = batch.get_mut_header();
+        header.set_producer(producer_id, producer_epoch, -1);
+        header.set_transactional(true);
+        header.set_control(true);
+        batch
+    }
+
+    /// type of control batch, none if this is not control batch
+    pub fn control_type(&self) -> Option<ControlRecordType> {
+        if !self.get_header().is_control() {
+            return None;
+        }
+        self.records()
+            .first()
+            .and_then(|record| record.key())
+            .and_then(|key| ControlRecordType::decode_key(key.as_ref()))
+    }
+}
+
+/// remove control batches and batches of aborted transactions
+///
+/// Aborted transactions must include every transaction aborted in range of batches.
+/// Batches must be ordered by offset.
+pub fn committed_batches(
+    batches: Vec<DefaultBatch>,
+    aborted: &[AbortedTransaction],
+) -> Vec<DefaultBatch> {
+    let mut aborted: Vec<&AbortedTransaction> = aborted.iter().collect();
+    aborted.sort_by_key(|txn| txn.first_offset);
+    let mut aborted = aborted.into_iter().peekable();
+
+    // producers whose current transaction has been aborted
+    let mut aborted_producers = HashSet::new();
+    let mut committed = Vec::with_capacity(batches.len());
+
+    for batch in batches {
+        while let Some(txn) = aborted.next_if(|txn| txn.first_offset <= batch.get_last_offset()) {
+            aborted_producers.insert(txn.producer_id);
+        }
+
+        let header = batch.get_header();
+        if header.is_control() {
+            if batch.control_type() == Some(ControlRecordType::Abort) {
+                aborted_producers.remove(&header.producer_id);
+            }
+            continue;
+        }
+
+        if header.is_transactional() && aborted_producers.contains(&header.producer_id) {
+            continue;
+        }
+
+        committed.push(batch);
+    }
+
+    committed
+}
+
+#[cfg(test)]
+mod test {
+
+    use crate::batch::DefaultBatch;
+    use crate::fetch::AbortedTransaction;
+    use crate::record::DefaultRecord;
+
+    use super::*;
+
+    fn txn_batch(producer_id: i64, base_offset: i64) -> DefaultBatch {
+        let mut batch = DefaultBatch::new(vec![DefaultRecord::new("value")]);
+        batch
+            .get_mut_header()
+            .set_producer(producer_id, 0, base_offset as i32);
+        batch.get_mut_header().set_transactional(true);
+        batch.set_base_offset(base_offset);
+        batch
+    }
+
+    fn control_batch(
+        producer_id: i64,
+        control: ControlRecordType,
+        base_offset: i64,
+    ) -> DefaultBatch {
+        let mut batch = DefaultBatch::control(producer_id, 0, control);
+        batch.set_base_offset(base_offset);
+        batch
+    }
+
+    #[test]
+    fn test_control_batch() {
+        let batch = DefaultBatch::control(5, 1, ControlRecordType::Commit);
+        assert!(batch.get_header().is_control());
+        assert!(batch.get_header().is_transactional());
+        assert_eq!(batch.control_type(), Some(ControlRecordType::Commit));
+        assert_eq!(
+            DefaultBatch::new(vec![DefaultRecord::new("a")]).control_type(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_committed_batches() {
+        let batches = vec![
+            txn_batch(1, 0),
+            txn_batch(2, 1),
+            control_batch(1, ControlRecordType::Abort, 2),
+            DefaultBatch::new(vec![DefaultRecord::new("plain")]).base_offset(3),
+            control_batch(2, ControlRecordType::Commit, 4),
+            // producer 1 starts new transaction after abort
+            txn_batch(1, 5),
+        ];
+        let aborted = vec![AbortedTransaction {
+            producer_id: 1,
+            first_offset: 0,
+        }];
+
+        let offsets: Vec<i64> = committed_batches(batches, &aborted)
+            .iter()
+            .map(|batch| batch.get_base_offset())
+            .collect();
+        assert_eq!(offsets, vec![1, 3, 5]);
+    }
+}
@@ -265,6 +265,21 @@ impl Decoder for i64 {
     }
 }
 
+impl Decoder for u64 {
+    fn decode<T>(&mut self, src: &mut T, _version: Version) -> Result<(), Error>
+    where
+        T: Buf,
+    {
+        if src.remaining() < 8 {
+            return Err(Error::new(ErrorKind::UnexpectedEof, "can't read u64"));
+        }
+        let value = src.get_u64();
+        trace!("u64: {:#x} => {}", &value, &value);
+        *self = value;
+        Ok(())
+    }
+}
+
 impl DecoderVarInt for i64 {
     fn decode_varint<T>(&mut self, src: &mut T) -> Result<(), Error>
     where
@@ -537,6 +552,16 @@ mod test {
         assert_eq!(value, 32);
     }
 
+    #[test]
+    fn test_decode_u64() {
+        let data = [0xff, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x20];
+
+        let mut value: u64 = 0;
+        let result = value.decode(&mut Cursor::new(&data), 0);
+        assert!(result.is_ok());
+        assert_eq!(value, 0xff00_0000_0000_0020);
+    }
+
     #[test]
     fn test_decode_invalid_string_not_len() {
         let data = [0x11]; // doesn't have right bytes
@@ -314,6 +314,26 @@ impl Encoder for i64 {
     }
 }
 
+impl Encoder for u64 {
+    fn write_size(&self, _version: Version) -> usize {
+        8
+    }
+
+    fn encode<T>(&self, dest: &mut T, _version: Version) -> Result<(), Error>
+    where
+        T: BufMut,
+    {
+        if dest.remaining_mut() < 8 {
+            return Err(Error::new(
+                ErrorKind::UnexpectedEof,
+                "not enough capacity for u64",
+            ));
+        }
+        dest.put_u64(*self);
+        Ok(())
+    }
+}
+
 impl EncoderVarInt for i64 {
     fn var_write_size(&self) -> usize {
         variant_size(*self)
@@ -466,6 +486,18 @@ mod test {
         assert_eq!(value.write_size(0), 8);
     }
 
+    #[test]
+    fn test_encode_u64() {
+        let mut dest = vec![];
+        let value: u64 = u64::MAX;
+        let result = value.encode(&mut dest, 0);
+        assert!(result.is_ok());
+        assert_eq!(dest.len(), 8);
+        assert_eq!(dest[0], 0xff);
+        assert_eq!(dest[7], 0xff);
+        assert_eq!(value.write_size(0), 8);
+    }
+
     #[test]
     fn test_encode_string_option_none() {
         let mut dest = vec![];
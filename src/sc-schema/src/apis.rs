@@ -22,6 +22,16 @@ pub enum AdminPublicApiKey {
     Delete = 1002,
     List = 1003,
     Watch = 1004,
+    InitProducerId = 1005,
+    AddPartitionsToTxn = 1006,
+    EndTxn = 1007,
+    JoinGroup = 1008,
+    GroupHeartbeat = 1009,
+    LeaveGroup = 1010,
+    OffsetCommit = 1011,
+    OffsetFetch = 1012,
+    Update = 1013,
+    ElectLeaders = 1014,
 }
 
 impl Default for AdminPublicApiKey {
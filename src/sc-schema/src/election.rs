@@ -0,0 +1,30 @@
+#![allow(clippy::assign_op_pattern)]
+
+//!
+//! # Preferred Leader Election
+//!
+//! Moves leadership of partitions back to their preferred replica, first replica of partition,
+//! when it is online and in sync with current leader.
+//!
+
+use dataplane::api::Request;
+use dataplane::derive::Decode;
+use dataplane::derive::Encode;
+
+use crate::Status;
+use crate::AdminPublicApiKey;
+use crate::AdminRequest;
+
+/// elect preferred leaders of partitions of topics, all topics if empty
+#[derive(Encode, Decode, Default, Debug, Clone)]
+pub struct ElectLeadersRequest {
+    pub topics: Vec<String>,
+}
+
+impl AdminRequest for ElectLeadersRequest {}
+
+impl Request for ElectLeadersRequest {
+    const API_KEY: u16 = AdminPublicApiKey::ElectLeaders as u16;
+    const DEFAULT_API_VERSION: i16 = 0;
+    type Response = Status;
+}
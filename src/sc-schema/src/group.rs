@@ -0,0 +1,140 @@
+#![allow(clippy::assign_op_pattern)]
+
+//!
+//! # Consumer Groups
+//!
+//! Members join a group through SC, which assigns partitions of the topic among members.
+//! When members join or leave, generation of the group is bumped and members must rejoin
+//! to get new assignment. Offsets committed by the group are stored as group metadata.
+//!
+
+use dataplane::api::Request;
+use dataplane::derive::Decode;
+use dataplane::derive::Encode;
+use dataplane::{ErrorCode, Offset};
+use fluvio_types::PartitionId;
+
+use crate::AdminPublicApiKey;
+use crate::AdminRequest;
+
+/// join group, new member passes empty member id
+#[derive(Encode, Decode, Default, Debug)]
+pub struct JoinGroupRequest {
+    pub group: String,
+    pub member_id: String,
+    pub topic: String,
+    pub session_timeout_ms: i32,
+}
+
+impl AdminRequest for JoinGroupRequest {}
+
+impl Request for JoinGroupRequest {
+    const API_KEY: u16 = AdminPublicApiKey::JoinGroup as u16;
+    const DEFAULT_API_VERSION: i16 = 0;
+    type Response = JoinGroupResponse;
+}
+
+/// `RebalanceInProgress` error tells member to join again, partitions are assigned
+/// once all members has rejoined
+#[derive(Encode, Decode, Default, Debug)]
+pub struct JoinGroupResponse {
+    pub error_code: ErrorCode,
+    pub member_id: String,
+    pub generation: i32,
+    /// partitions assigned to member
+    pub partitions: Vec<PartitionId>,
+}
+
+/// member must send heartbeat within session timeout to stay in group
+#[derive(Encode, Decode, Default, Debug)]
+pub struct GroupHeartbeatRequest {
+    pub group: String,
+    pub member_id: String,
+    pub generation: i32,
+}
+
+impl AdminRequest for GroupHeartbeatRequest {}
+
+impl Request for GroupHeartbeatRequest {
+    const API_KEY: u16 = AdminPublicApiKey::GroupHeartbeat as u16;
+    const DEFAULT_API_VERSION: i16 = 0;
+    type Response = GroupHeartbeatResponse;
+}
+
+/// `RebalanceInProgress` error tells member to rejoin the group
+#[derive(Encode, Decode, Default, Debug)]
+pub struct GroupHeartbeatResponse {
+    pub error_code: ErrorCode,
+}
+
+#[derive(Encode, Decode, Default, Debug)]
+pub struct LeaveGroupRequest {
+    pub group: String,
+    pub member_id: String,
+}
+
+impl AdminRequest for LeaveGroupRequest {}
+
+impl Request for LeaveGroupRequest {
+    const API_KEY: u16 = AdminPublicApiKey::LeaveGroup as u16;
+    const DEFAULT_API_VERSION: i16 = 0;
+    type Response = LeaveGroupResponse;
+}
+
+#[derive(Encode, Decode, Default, Debug)]
+pub struct LeaveGroupResponse {
+    pub error_code: ErrorCode,
+}
+
+/// offset of partition, next offset to be consumed
+#[derive(Encode, Decode, Default, Debug, Clone, PartialEq)]
+pub struct PartitionOffset {
+    pub partition: PartitionId,
+    pub offset: Offset,
+}
+
+/// commit offsets of partitions assigned to member
+#[derive(Encode, Decode, Default, Debug)]
+pub struct OffsetCommitRequest {
+    pub group: String,
+    pub member_id: String,
+    pub generation: i32,
+    pub topic: String,
+    pub offsets: Vec<PartitionOffset>,
+}
+
+impl AdminRequest for OffsetCommitRequest {}
+
+impl Request for OffsetCommitRequest {
+    const API_KEY: u16 = AdminPublicApiKey::OffsetCommit as u16;
+    const DEFAULT_API_VERSION: i16 = 0;
+    type Response = OffsetCommitResponse;
+}
+
+#[derive(Encode, Decode, Default, Debug)]
+pub struct OffsetCommitResponse {
+    pub error_code: ErrorCode,
+}
+
+/// fetch committed offsets of group
+#[derive(Encode, Decode, Default, Debug)]
+pub struct OffsetFetchRequest {
+    pub group: String,
+    pub topic: String,
+    pub partitions: Vec<PartitionId>,
+}
+
+impl AdminRequest for OffsetFetchRequest {}
+
+impl Request for OffsetFetchRequest {
+    const API_KEY: u16 = AdminPublicApiKey::OffsetFetch as u16;
+    const DEFAULT_API_VERSION: i16 = 0;
+    type Response = OffsetFetchResponse;
+}
+
+/// only partitions with committed offset are returned
+#[derive(Encode, Decode, Default, Debug)]
+pub struct OffsetFetchResponse {
+    pub error_code: ErrorCode,
+    pub offsets: Vec<PartitionOffset>,
+}
@@ -4,6 +4,11 @@ pub mod spg;
 pub mod partition;
 pub mod versions;
 pub mod objects;
+pub mod producer;
+pub mod transaction;
+pub mod election;
+pub mod group;
+pub mod smartstream;
 mod apis;
 mod request;
 mod response;
@@ -13,7 +13,7 @@ use crate::AdminRequest;
 
 pub use create::AllCreatableSpec;
 
-#[derive(Encode, Decode, Default, Debug)]
+#[derive(Encode, Decode, Default, Debug, Clone)]
 pub struct CreateRequest {
     pub name: String,
     pub dry_run: bool,
@@ -22,7 +22,7 @@ pub struct CreateRequest {
 
 impl Request for CreateRequest {
     const API_KEY: u16 = AdminPublicApiKey::Create as u16;
-    const DEFAULT_API_VERSION: i16 = 0;
+    const DEFAULT_API_VERSION: i16 = 3;
     type Response = Status;
 }
 
@@ -41,18 +41,21 @@ mod create {
     use fluvio_controlplane_metadata::topic::TopicSpec;
     use fluvio_controlplane_metadata::spu::CustomSpuSpec;
     use fluvio_controlplane_metadata::spg::SpuGroupSpec;
+    use fluvio_controlplane_metadata::smartstream::SmartStreamSpec;
     use super::*;
 
     const TOPIC: u8 = 0;
     const CUSTOM_SPU: u8 = 1;
     const SPG: u8 = 2;
+    const SMART_STREAM: u8 = 3;
 
-    #[derive(Debug)]
+    #[derive(Debug, Clone)]
     /// enum of spec that can be created
     pub enum AllCreatableSpec {
         Topic(TopicSpec),
         CustomSpu(CustomSpuSpec),
         SpuGroup(SpuGroupSpec),
+        SmartStream(SmartStreamSpec),
     }
 
     impl Default for AllCreatableSpec {
@@ -70,6 +73,7 @@ mod create {
                     Self::Topic(s) => s.write_size(version),
                     Self::CustomSpu(s) => s.write_size(version),
                     Self::SpuGroup(s) => s.write_size(version),
+                    Self::SmartStream(s) => s.write_size(version),
                 }
         }
 
@@ -96,6 +100,12 @@ mod create {
                     typ.encode(dest, version)?;
                     s.encode(dest, version)?;
                 }
+
+                Self::SmartStream(s) => {
+                    let typ: u8 = SMART_STREAM;
+                    typ.encode(dest, version)?;
+                    s.encode(dest, version)?;
+                }
             }
 
             Ok(())
@@ -133,6 +143,13 @@ mod create {
                     Ok(())
                 }
 
+                SMART_STREAM => {
+                    let mut response = SmartStreamSpec::default();
+                    response.decode(src, version)?;
+                    *self = Self::SmartStream(response);
+                    Ok(())
+                }
+
                 // Unexpected type
                 _ => Err(Error::new(
                     ErrorKind::InvalidData,
@@ -17,6 +17,7 @@ use fluvio_controlplane_metadata::topic::TopicSpec;
 use fluvio_controlplane_metadata::spu::CustomSpuSpec;
 use fluvio_controlplane_metadata::spu::CustomSpuKey;
 use fluvio_controlplane_metadata::spg::SpuGroupSpec;
+use fluvio_controlplane_metadata::smartstream::SmartStreamSpec;
 use fluvio_controlplane_metadata::core::Spec;
 use fluvio_controlplane_metadata::core::Removable;
 
@@ -33,11 +34,12 @@ pub trait DeleteSpec: Removable {
 }
 
 // This can be auto generated by enum derive later
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum DeleteRequest {
     Topic(String),
     CustomSpu(CustomSpuKey),
     SpuGroup(String),
+    SmartStream(String),
 }
 
 impl Default for DeleteRequest {
@@ -53,6 +55,7 @@ impl DeleteRequest {
             Self::Topic(_) => TopicSpec::LABEL,
             Self::CustomSpu(_) => CustomSpuSpec::LABEL,
             Self::SpuGroup(_) => SpuGroupSpec::LABEL,
+            Self::SmartStream(_) => SmartStreamSpec::LABEL,
         }
     }
 }
@@ -74,6 +77,7 @@ impl Encoder for DeleteRequest {
                 Self::Topic(s) => s.write_size(version),
                 Self::CustomSpu(s) => s.write_size(version),
                 Self::SpuGroup(s) => s.write_size(version),
+                Self::SmartStream(s) => s.write_size(version),
             }
     }
 
@@ -88,6 +92,7 @@ impl Encoder for DeleteRequest {
             Self::Topic(s) => s.encode(dest, version)?,
             Self::CustomSpu(s) => s.encode(dest, version)?,
             Self::SpuGroup(s) => s.encode(dest, version)?,
+            Self::SmartStream(s) => s.encode(dest, version)?,
         }
 
         Ok(())
@@ -125,6 +130,13 @@ impl Decoder for DeleteRequest {
                 Ok(())
             }
 
+            SmartStreamSpec::LABEL => {
+                let mut response = String::default();
+                response.decode(src, version)?;
+                *self = Self::SmartStream(response);
+                Ok(())
+            }
+
             // Unexpected type
             _ => Err(Error::new(
                 ErrorKind::InvalidData,
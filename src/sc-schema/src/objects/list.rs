@@ -16,6 +16,7 @@ use fluvio_controlplane_metadata::core::*;
 use fluvio_controlplane_metadata::topic::TopicSpec;
 use fluvio_controlplane_metadata::spu::*;
 use fluvio_controlplane_metadata::spg::SpuGroupSpec;
+use fluvio_controlplane_metadata::smartstream::SmartStreamSpec;
 use fluvio_controlplane_metadata::store::*;
 use fluvio_controlplane_metadata::partition::PartitionSpec;
 use crate::AdminPublicApiKey;
@@ -45,6 +46,7 @@ pub enum ListRequest {
     SpuGroup(Vec<NameFilter>),
     CustomSpu(Vec<NameFilter>),
     Partition(Vec<NameFilter>),
+    SmartStream(Vec<NameFilter>),
 }
 
 impl Default for ListRequest {
@@ -55,7 +57,7 @@ impl Default for ListRequest {
 
 impl Request for ListRequest {
     const API_KEY: u16 = AdminPublicApiKey::List as u16;
-    const DEFAULT_API_VERSION: i16 = 0;
+    const DEFAULT_API_VERSION: i16 = 4;
     type Response = ListResponse;
 }
 
@@ -68,6 +70,7 @@ pub enum ListResponse {
     CustomSpu(Vec<Metadata<CustomSpuSpec>>),
     SpuGroup(Vec<Metadata<SpuGroupSpec>>),
     Partition(Vec<Metadata<PartitionSpec>>),
+    SmartStream(Vec<Metadata<SmartStreamSpec>>),
 }
 
 impl Default for ListResponse {
@@ -157,6 +160,7 @@ mod encoding {
                 Self::SpuGroup(_) => SpuGroupSpec::LABEL,
                 Self::CustomSpu(_) => CustomSpuSpec::LABEL,
                 Self::Partition(_) => PartitionSpec::LABEL,
+                Self::SmartStream(_) => SmartStreamSpec::LABEL,
             }
         }
     }
@@ -172,6 +176,7 @@ mod encoding {
                     Self::SpuGroup(s) => s.write_size(version),
                     Self::Spu(s) => s.write_size(version),
                     Self::Partition(s) => s.write_size(version),
+                    Self::SmartStream(s) => s.write_size(version),
                 }
         }
 
@@ -188,6 +193,7 @@ mod encoding {
                 Self::SpuGroup(s) => s.encode(dest, version)?,
                 Self::Spu(s) => s.encode(dest, version)?,
                 Self::Partition(s) => s.encode(dest, version)?,
+                Self::SmartStream(s) => s.encode(dest, version)?,
             }
 
             Ok(())
@@ -239,6 +245,13 @@ mod encoding {
                     Ok(())
                 }
 
+                SmartStreamSpec::LABEL => {
+                    let mut response: Vec<NameFilter> = vec![];
+                    response.decode(src, version)?;
+                    *self = Self::SmartStream(response);
+                    Ok(())
+                }
+
                 // Unexpected type
                 _ => Err(Error::new(
                     ErrorKind::InvalidData,
@@ -257,6 +270,7 @@ mod encoding {
                 Self::SpuGroup(_) => SpuGroupSpec::LABEL,
                 Self::CustomSpu(_) => CustomSpuSpec::LABEL,
                 Self::Partition(_) => PartitionSpec::LABEL,
+                Self::SmartStream(_) => SmartStreamSpec::LABEL,
             }
         }
     }
@@ -272,6 +286,7 @@ mod encoding {
                     Self::SpuGroup(s) => s.write_size(version),
                     Self::Spu(s) => s.write_size(version),
                     Self::Partition(s) => s.write_size(version),
+                    Self::SmartStream(s) => s.write_size(version),
                 }
         }
 
@@ -288,6 +303,7 @@ mod encoding {
                 Self::SpuGroup(s) => s.encode(dest, version)?,
                 Self::Spu(s) => s.encode(dest, version)?,
                 Self::Partition(s) => s.encode(dest, version)?,
+                Self::SmartStream(s) => s.encode(dest, version)?,
             }
 
             Ok(())
@@ -339,6 +355,13 @@ mod encoding {
                     Ok(())
                 }
 
+                SmartStreamSpec::LABEL => {
+                    let mut response: Vec<Metadata<SmartStreamSpec>> = vec![];
+                    response.decode(src, version)?;
+                    *self = Self::SmartStream(response);
+                    Ok(())
+                }
+
                 // Unexpected type
                 _ => Err(Error::new(
                     ErrorKind::InvalidData,
@@ -1,9 +1,11 @@
 mod create;
 mod delete;
 mod list;
+mod update;
 mod watch;
 
 pub use create::*;
 pub use delete::*;
 pub use list::*;
+pub use update::*;
 pub use watch::*;
@@ -0,0 +1,128 @@
+#![allow(clippy::assign_op_pattern)]
+
+use std::fmt::Debug;
+
+use dataplane::derive::{Decode, Encode};
+use dataplane::core::Encoder;
+use dataplane::core::Decoder;
+use dataplane::api::Request;
+
+use crate::Status;
+use crate::AdminPublicApiKey;
+use crate::AdminRequest;
+
+pub use update::AllUpdatableSpec;
+
+/// change spec of existing object
+#[derive(Encode, Decode, Default, Debug, Clone)]
+pub struct UpdateRequest {
+    pub name: String,
+    pub dry_run: bool,
+    pub spec: AllUpdatableSpec,
+}
+
+impl Request for UpdateRequest {
+    const API_KEY: u16 = AdminPublicApiKey::Update as u16;
+    const DEFAULT_API_VERSION: i16 = 0;
+    type Response = Status;
+}
+
+impl AdminRequest for UpdateRequest {}
+
+#[allow(clippy::module_inception)]
+mod update {
+
+    use std::io::Error;
+    use std::io::ErrorKind;
+
+    use tracing::trace;
+
+    use dataplane::core::Version;
+    use dataplane::bytes::{Buf, BufMut};
+
+    use crate::topic::TopicUpdate;
+    use crate::partition::PartitionUpdate;
+    use super::*;
+
+    const TOPIC: u8 = 0;
+    const PARTITION: u8 = 1;
+
+    #[derive(Debug, Clone)]
+    /// enum of changes that can be applied to existing objects
+    pub enum AllUpdatableSpec {
+        Topic(TopicUpdate),
+        Partition(PartitionUpdate),
+    }
+
+    impl Default for AllUpdatableSpec {
+        fn default() -> Self {
+            Self::Topic(TopicUpdate::default())
+        }
+    }
+
+    impl Encoder for AllUpdatableSpec {
+        fn write_size(&self, version: Version) -> usize {
+            let type_size = (0u8).write_size(version);
+
+            type_size
+                + match self {
+                    Self::Topic(s) => s.write_size(version),
+                    Self::Partition(s) => s.write_size(version),
+                }
+        }
+
+        fn encode<T>(&self, dest: &mut T, version: Version) -> Result<(), Error>
+        where
+            T: BufMut,
+        {
+            match self {
+                Self::Topic(s) => {
+                    let typ: u8 = TOPIC;
+                    typ.encode(dest, version)?;
+                    s.encode(dest, version)?;
+                }
+
+                Self::Partition(s) => {
+                    let typ: u8 = PARTITION;
+                    typ.encode(dest, version)?;
+                    s.encode(dest, version)?;
+                }
+            }
+
+            Ok(())
+        }
+    }
+
+    impl Decoder for AllUpdatableSpec {
+        fn decode<T>(&mut self, src: &mut T, version: Version) -> Result<(), Error>
+        where
+            T: Buf,
+        {
+            let mut typ: u8 = 0;
+            typ.decode(src, version)?;
+            trace!("decoded type: {}", typ);
+
+            match typ {
+                TOPIC => {
+                    let mut response = TopicUpdate::default();
+                    response.decode(src, version)?;
+                    *self = Self::Topic(response);
+                    Ok(())
+                }
+
+                PARTITION => {
+                    let mut response = PartitionUpdate::default();
+                    response.decode(src, version)?;
+                    *self = Self::Partition(response);
+                    Ok(())
+                }
+
+                // Unexpected type
+                _ => Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!("invalid update spec type {}", typ),
+                )),
+            }
+        }
+    }
+}
@@ -11,6 +11,7 @@ use fluvio_controlplane_metadata::core::*;
 use fluvio_controlplane_metadata::topic::TopicSpec;
 use fluvio_controlplane_metadata::spu::*;
 use fluvio_controlplane_metadata::spg::SpuGroupSpec;
+use fluvio_controlplane_metadata::smartstream::SmartStreamSpec;
 use fluvio_controlplane_metadata::partition::PartitionSpec;
 use fluvio_controlplane_metadata::store::Epoch;
 use fluvio_controlplane_metadata::message::Message;
@@ -35,6 +36,7 @@ pub enum WatchRequest {
     Spu(Epoch),
     SpuGroup(Epoch),
     Partition(Epoch),
+    SmartStream(Epoch),
 }
 
 impl Default for WatchRequest {
@@ -45,7 +47,7 @@ impl Default for WatchRequest {
 
 impl Request for WatchRequest {
     const API_KEY: u16 = AdminPublicApiKey::Watch as u16;
-    const DEFAULT_API_VERSION: i16 = 0;
+    const DEFAULT_API_VERSION: i16 = 4;
     type Response = WatchResponse;
 }
 
@@ -57,6 +59,7 @@ pub enum WatchResponse {
     Spu(MetadataUpdate<SpuSpec>),
     SpuGroup(MetadataUpdate<SpuGroupSpec>),
     Partition(MetadataUpdate<PartitionSpec>),
+    SmartStream(MetadataUpdate<SmartStreamSpec>),
 }
 
 impl Default for WatchResponse {
@@ -122,6 +125,7 @@ mod encoding {
                 Self::Spu(_) => SpuSpec::LABEL,
                 Self::SpuGroup(_) => SpuGroupSpec::LABEL,
                 Self::Partition(_) => PartitionSpec::LABEL,
+                Self::SmartStream(_) => SmartStreamSpec::LABEL,
             }
         }
     }
@@ -136,6 +140,7 @@ mod encoding {
                     Self::SpuGroup(s) => s.write_size(version),
                     Self::Spu(s) => s.write_size(version),
                     Self::Partition(s) => s.write_size(version),
+                    Self::SmartStream(s) => s.write_size(version),
                 }
         }
 
@@ -151,6 +156,7 @@ mod encoding {
                 Self::SpuGroup(s) => s.encode(dest, version)?,
                 Self::Spu(s) => s.encode(dest, version)?,
                 Self::Partition(s) => s.encode(dest, version)?,
+                Self::SmartStream(s) => s.encode(dest, version)?,
             }
 
             Ok(())
@@ -195,6 +201,13 @@ mod encoding {
                     Ok(())
                 }
 
+                SmartStreamSpec::LABEL => {
+                    let mut response: Epoch = Epoch::default();
+                    response.decode(src, version)?;
+                    *self = Self::SmartStream(response);
+                    Ok(())
+                }
+
                 // Unexpected type
                 _ => Err(Error::new(
                     ErrorKind::InvalidData,
@@ -212,6 +225,7 @@ mod encoding {
                 Self::Spu(_) => SpuSpec::LABEL,
                 Self::SpuGroup(_) => SpuGroupSpec::LABEL,
                 Self::Partition(_) => PartitionSpec::LABEL,
+                Self::SmartStream(_) => SmartStreamSpec::LABEL,
             }
         }
     }
@@ -226,6 +240,7 @@ mod encoding {
                     Self::SpuGroup(s) => s.write_size(version),
                     Self::Spu(s) => s.write_size(version),
                     Self::Partition(s) => s.write_size(version),
+                    Self::SmartStream(s) => s.write_size(version),
                 }
         }
 
@@ -241,6 +256,7 @@ mod encoding {
                 Self::SpuGroup(s) => s.encode(dest, version)?,
                 Self::Spu(s) => s.encode(dest, version)?,
                 Self::Partition(s) => s.encode(dest, version)?,
+                Self::SmartStream(s) => s.encode(dest, version)?,
             }
 
             Ok(())
@@ -285,6 +301,13 @@ mod encoding {
                     Ok(())
                 }
 
+                SmartStreamSpec::LABEL => {
+                    let mut response = MetadataUpdate::default();
+                    response.decode(src, version)?;
+                    *self = Self::SmartStream(response);
+                    Ok(())
+                }
+
                 // Unexpected type
                 _ => Err(Error::new(
                     ErrorKind::InvalidData,
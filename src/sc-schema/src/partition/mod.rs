@@ -1,5 +1,20 @@
 pub use fluvio_controlplane_metadata::partition::*;
 
+pub use update::PartitionUpdate;
+
+mod update {
+
+    use dataplane::derive::{Decode, Encode};
+    use dataplane::core::{Encoder, Decoder};
+    use fluvio_types::SpuId;
+
+    /// move replicas of existing partition, first replica is preferred leader
+    #[derive(Encode, Decode, Default, Debug, Clone, PartialEq)]
+    pub struct PartitionUpdate {
+        pub replicas: Vec<SpuId>,
+    }
+}
+
 mod convert {
 
     use std::io::Error;
@@ -9,6 +24,12 @@ mod convert {
     use crate::objects::*;
     use super::*;
 
+    impl From<PartitionUpdate> for AllUpdatableSpec {
+        fn from(update: PartitionUpdate) -> Self {
+            Self::Partition(update)
+        }
+    }
+
     impl ListSpec for PartitionSpec {
         type Filter = NameFilter;
 
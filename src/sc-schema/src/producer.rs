@@ -0,0 +1,42 @@
+#![allow(clippy::assign_op_pattern)]
+
+//!
+//! # Producer Id
+//!
+//! Allocates id for idempotent producer. Producer stamps id and sequence numbers on each batch,
+//! so SPU can detect batches which are retried.
+//!
+//! Transactional producer passes its transactional id, so same producer id is returned
+//! across producer restarts with epoch bumped to fence off previous instance.
+//!
+
+use dataplane::api::Request;
+use dataplane::derive::Decode;
+use dataplane::derive::Encode;
+use dataplane::ErrorCode;
+
+use crate::AdminPublicApiKey;
+use crate::AdminRequest;
+
+#[derive(Encode, Decode, Default, Debug)]
+pub struct InitProducerIdRequest {
+    #[fluvio(min_version = 1)]
+    pub transactional_id: Option<String>,
+    #[fluvio(min_version = 1)]
+    pub transaction_timeout_ms: i32,
+}
+
+impl AdminRequest for InitProducerIdRequest {}
+
+impl Request for InitProducerIdRequest {
+    const API_KEY: u16 = AdminPublicApiKey::InitProducerId as u16;
+    const DEFAULT_API_VERSION: i16 = 1;
+    type Response = InitProducerIdResponse;
+}
+
+#[derive(Encode, Decode, Default, Debug)]
+pub struct InitProducerIdResponse {
+    pub error_code: ErrorCode,
+    pub producer_id: i64,
+    pub producer_epoch: i16,
+}
@@ -19,6 +19,13 @@ use dataplane::derive::Encode;
 use dataplane::versions::ApiVersionsRequest;
 
 use super::objects::*;
+use super::producer::InitProducerIdRequest;
+use super::transaction::{AddPartitionsToTxnRequest, EndTxnRequest};
+use super::election::ElectLeadersRequest;
+use super::group::{
+    JoinGroupRequest, GroupHeartbeatRequest, LeaveGroupRequest, OffsetCommitRequest,
+    OffsetFetchRequest,
+};
 use super::AdminPublicApiKey;
 
 #[derive(Debug, Encode)]
@@ -27,8 +34,18 @@ pub enum AdminPublicRequest {
     ApiVersionsRequest(RequestMessage<ApiVersionsRequest>),
     CreateRequest(RequestMessage<CreateRequest>),
     DeleteRequest(RequestMessage<DeleteRequest>),
+    UpdateRequest(RequestMessage<UpdateRequest>),
     ListRequest(RequestMessage<ListRequest>),
     WatchRequest(RequestMessage<WatchRequest>),
+    InitProducerIdRequest(RequestMessage<InitProducerIdRequest>),
+    AddPartitionsToTxnRequest(RequestMessage<AddPartitionsToTxnRequest>),
+    EndTxnRequest(RequestMessage<EndTxnRequest>),
+    JoinGroupRequest(RequestMessage<JoinGroupRequest>),
+    GroupHeartbeatRequest(RequestMessage<GroupHeartbeatRequest>),
+    LeaveGroupRequest(RequestMessage<LeaveGroupRequest>),
+    OffsetCommitRequest(RequestMessage<OffsetCommitRequest>),
+    OffsetFetchRequest(RequestMessage<OffsetFetchRequest>),
+    ElectLeadersRequest(RequestMessage<ElectLeadersRequest>),
 }
 
 impl Default for AdminPublicRequest {
@@ -57,8 +74,28 @@ impl ApiMessage for AdminPublicRequest {
 
             AdminPublicApiKey::Create => api_decode!(Self, CreateRequest, src, header),
             AdminPublicApiKey::Delete => api_decode!(Self, DeleteRequest, src, header),
+            AdminPublicApiKey::Update => api_decode!(Self, UpdateRequest, src, header),
             AdminPublicApiKey::List => api_decode!(Self, ListRequest, src, header),
             AdminPublicApiKey::Watch => api_decode!(Self, WatchRequest, src, header),
+            AdminPublicApiKey::InitProducerId => {
+                api_decode!(Self, InitProducerIdRequest, src, header)
+            }
+            AdminPublicApiKey::AddPartitionsToTxn => {
+                api_decode!(Self, AddPartitionsToTxnRequest, src, header)
+            }
+            AdminPublicApiKey::EndTxn => api_decode!(Self, EndTxnRequest, src, header),
+            AdminPublicApiKey::JoinGroup => api_decode!(Self, JoinGroupRequest, src, header),
+            AdminPublicApiKey::GroupHeartbeat => {
+                api_decode!(Self, GroupHeartbeatRequest, src, header)
+            }
+            AdminPublicApiKey::LeaveGroup => api_decode!(Self, LeaveGroupRequest, src, header),
+            AdminPublicApiKey::OffsetCommit => {
+                api_decode!(Self, OffsetCommitRequest, src, header)
+            }
+            AdminPublicApiKey::OffsetFetch => api_decode!(Self, OffsetFetchRequest, src, header),
+            AdminPublicApiKey::ElectLeaders => {
+                api_decode!(Self, ElectLeadersRequest, src, header)
+            }
         }
     }
 }
@@ -1,5 +1,20 @@
 pub use fluvio_controlplane_metadata::topic::*;
 
+pub use update::TopicUpdate;
+
+mod update {
+
+    use dataplane::derive::{Decode, Encode};
+    use dataplane::core::{Encoder, Decoder};
+    use fluvio_types::PartitionCount;
+
+    /// change to existing topic, partitions can only be added
+    #[derive(Encode, Decode, Default, Debug, Clone, PartialEq)]
+    pub struct TopicUpdate {
+        pub partitions: PartitionCount,
+    }
+}
+
 mod convert {
 
     use std::convert::TryInto;
@@ -15,6 +30,12 @@ mod convert {
         }
     }
 
+    impl From<TopicUpdate> for AllUpdatableSpec {
+        fn from(update: TopicUpdate) -> Self {
+            Self::Topic(update)
+        }
+    }
+
     impl DeleteSpec for TopicSpec {
         fn into_request<K>(key: K) -> DeleteRequest
         where
@@ -0,0 +1,60 @@
+#![allow(clippy::assign_op_pattern)]
+
+//!
+//! # Transactions
+//!
+//! Transactional producer registers each partition with SC before writing to it.
+//! When transaction ends, SC writes commit or abort marker to every registered partition.
+//!
+
+use dataplane::api::Request;
+use dataplane::derive::Decode;
+use dataplane::derive::Encode;
+use dataplane::{ErrorCode, ReplicaKey};
+
+use crate::AdminPublicApiKey;
+use crate::AdminRequest;
+
+/// add partitions to ongoing transaction
+#[derive(Encode, Decode, Default, Debug, Clone)]
+pub struct AddPartitionsToTxnRequest {
+    pub transactional_id: String,
+    pub producer_id: i64,
+    pub producer_epoch: i16,
+    pub partitions: Vec<ReplicaKey>,
+}
+
+impl AdminRequest for AddPartitionsToTxnRequest {}
+
+impl Request for AddPartitionsToTxnRequest {
+    const API_KEY: u16 = AdminPublicApiKey::AddPartitionsToTxn as u16;
+    const DEFAULT_API_VERSION: i16 = 0;
+    type Response = AddPartitionsToTxnResponse;
+}
+
+#[derive(Encode, Decode, Default, Debug)]
+pub struct AddPartitionsToTxnResponse {
+    pub error_code: ErrorCode,
+}
+
+/// commit or abort transaction
+#[derive(Encode, Decode, Default, Debug)]
+pub struct EndTxnRequest {
+    pub transactional_id: String,
+    pub producer_id: i64,
+    pub producer_epoch: i16,
+    pub commit: bool,
+}
+
+impl AdminRequest for EndTxnRequest {}
+
+impl Request for EndTxnRequest {
+    const API_KEY: u16 = AdminPublicApiKey::EndTxn as u16;
+    const DEFAULT_API_VERSION: i16 = 0;
+    type Response = EndTxnResponse;
+}
+
+#[derive(Encode, Decode, Default, Debug)]
+pub struct EndTxnResponse {
+    pub error_code: ErrorCode,
+}
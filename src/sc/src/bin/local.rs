@@ -0,0 +1,11 @@
+use structopt::StructOpt;
+
+use fluvio_sc::cli::ScOpt;
+use fluvio_sc::local::main_local_loop as main_loop;
+
+fn main() {
+    fluvio_future::subscriber::init_tracer(None);
+
+    let opt = ScOpt::from_args();
+    main_loop(opt);
+}
@@ -60,6 +60,14 @@ pub struct ScOpt {
         env
     )]
     auth_policy: Option<PathBuf>,
+
+    /// Directory where metadata is persisted when running without Kubernetes
+    #[structopt(long = "metadata-dir", value_name = "metadata dir path", env)]
+    metadata_dir: Option<PathBuf>,
+
+    /// Id of this SC among replicas sharing metadata dir, enables leader election
+    #[structopt(long = "instance-id", value_name = "id", env)]
+    instance_id: Option<String>,
 }
 
 impl ScOpt {
@@ -81,6 +89,26 @@ impl ScOpt {
         Ok((sc_config, k8_config, tls_option))
     }
 
+    #[allow(clippy::type_complexity)]
+    fn get_sc_and_metadata_dir(
+        mut self,
+    ) -> Result<(Config, PathBuf, Option<(String, TlsConfig)>), ScError> {
+        let metadata_dir = self.metadata_dir.take().ok_or_else(|| {
+            IoError::new(
+                ErrorKind::NotFound,
+                "metadata dir must be specified to run without kubernetes",
+            )
+        })?;
+
+        if self.namespace.is_none() {
+            self.namespace = Some(ScConfig::default().namespace);
+        }
+
+        let (sc_config, tls_option) = self.as_sc_config()?;
+
+        Ok((sc_config, metadata_dir, tls_option))
+    }
+
     /// as sc configuration, 2nd part of tls configuration(proxy addr, tls config)
     #[allow(clippy::wrong_self_convention)]
     fn as_sc_config(self) -> Result<(Config, Option<(String, TlsConfig)>), IoError> {
@@ -97,6 +125,7 @@ impl ScOpt {
 
         config.namespace = self.namespace.unwrap();
         config.x509_auth_scopes = self.x509_auth_scopes;
+        config.instance_id = self.instance_id;
 
         // Set Configuration Authorzation Policy
         let policy = match self.auth_policy {
@@ -135,6 +164,17 @@ impl ScOpt {
             Ok(config) => config,
         }
     }
+
+    /// parse configuration for SC which keeps metadata in local directory instead of Kubernetes
+    pub fn parse_local_or_exit(self) -> (Config, PathBuf, Option<(String, TlsConfig)>) {
+        match self.get_sc_and_metadata_dir() {
+            Err(err) => {
+                print_cli_err!(err);
+                process::exit(-1);
+            }
+            Ok(config) => config,
+        }
+    }
 }
 
 #[derive(Debug, StructOpt, Clone, Default)]
@@ -25,6 +25,8 @@ pub struct ScConfig {
     pub run_k8_dispatchers: bool,
     pub namespace: String,
     pub x509_auth_scopes: Option<PathBuf>,
+    /// id of this SC among replicas competing for leadership, single SC if not set
+    pub instance_id: Option<String>,
 }
 
 impl ::std::default::Default for ScConfig {
@@ -35,6 +37,7 @@ impl ::std::default::Default for ScConfig {
             run_k8_dispatchers: true,
             namespace: "default".to_owned(),
             x509_auth_scopes: None,
+            instance_id: None,
         }
     }
 }
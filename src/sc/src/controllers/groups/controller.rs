@@ -0,0 +1,39 @@
+//!
+//! # Group Controller
+//!
+//! Removes group members which stopped sending heartbeats
+
+use std::time::Duration;
+
+use tracing::debug;
+use tracing::instrument;
+
+use fluvio_future::task::spawn;
+use fluvio_future::timer::sleep;
+
+use crate::core::SharedContext;
+
+const SESSION_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug)]
+pub struct GroupController {
+    ctx: SharedContext,
+}
+
+impl GroupController {
+    pub fn start(ctx: SharedContext) {
+        let controller = Self { ctx };
+
+        spawn(controller.dispatch_loop());
+    }
+
+    #[instrument(skip(self), name = "GroupLoop")]
+    async fn dispatch_loop(self) {
+        debug!("starting dispatch loop");
+
+        loop {
+            sleep(SESSION_CHECK_INTERVAL).await;
+            self.ctx.group_coordinator().expire_members().await;
+        }
+    }
+}
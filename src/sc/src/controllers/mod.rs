@@ -1,3 +1,5 @@
+pub mod groups;
 pub mod partitions;
 pub mod spus;
 pub mod topics;
+pub mod transactions;
@@ -2,7 +2,10 @@
 //! # Auth Controller
 //!
 
+use std::time::{Duration, Instant};
+
 use tracing::{debug, trace};
+use once_cell::sync::Lazy;
 
 use fluvio_future::task::spawn;
 use tracing::instrument;
@@ -15,6 +18,15 @@ use crate::stores::K8ChangeListener;
 
 use super::reducer::PartitionReducer;
 
+/// interval of moving leadership back to preferred replicas
+static PREFERRED_LEADER_INTERVAL_SEC: Lazy<u64> = Lazy::new(|| {
+    use std::env;
+
+    let var_value = env::var("FLV_PREFERRED_LEADER_INTERVAL").unwrap_or_default();
+    let interval: u64 = var_value.parse().unwrap_or(300);
+    interval
+});
+
 /// Handles Partition election
 #[derive(Debug)]
 pub struct PartitionController {
@@ -43,12 +55,16 @@ impl PartitionController {
     #[instrument(skip(self), name = "PartitionLoop")]
     async fn dispatch_loop(mut self) {
         use tokio::select;
+        use fluvio_future::timer::sleep;
 
         debug!("starting dispatch loop");
 
         let mut spu_status_listener = self.spus.change_listener();
         let mut partition_listener = self.partitions.change_listener();
 
+        let election_interval = Duration::from_secs(*PREFERRED_LEADER_INTERVAL_SEC);
+        let mut last_election = Instant::now();
+
         loop {
             self.sync_spu_changes(&mut spu_status_listener).await;
             self.sync_partition_changes(&mut partition_listener).await;
@@ -62,6 +78,10 @@ impl PartitionController {
                 },
                 _ = partition_listener.listen() => {
                     debug!("detected partition changes");
+                },
+                _ = sleep(election_interval.saturating_sub(last_election.elapsed())) => {
+                    self.elect_preferred_leaders().await;
+                    last_election = Instant::now();
                 }
 
             }
@@ -76,18 +96,16 @@ impl PartitionController {
             return;
         }
 
-        // we only care about delete timestamp changes which are in metadata only
-        let changes = listener.sync_meta_changes().await;
+        // delete timestamp changes are in metadata,
+        // replicas of reassigned partition catching up are in status
+        let changes = listener.sync_changes().await;
         if changes.is_empty() {
-            trace!("no partition metadata changes");
+            trace!("no partition changes");
             return;
         }
 
         let (updates, _) = changes.parts();
-        trace!(
-            meta_changes = &*format!("{:#?}", updates),
-            "metadata changes"
-        );
+        trace!(changes = &*format!("{:#?}", updates), "partition changes");
 
         let actions = self.reducer.process_partition_update(updates).await;
 
@@ -97,6 +115,16 @@ impl PartitionController {
         }
     }
 
+    /// move leadership of partitions back to preferred replicas
+    async fn elect_preferred_leaders(&self) {
+        let actions = self.reducer.elect_preferred_leaders().await;
+
+        debug!("preferred leader election actions: {}", actions.len());
+        for action in actions.into_iter() {
+            self.partitions.send_action(action).await;
+        }
+    }
+
     /// sync spu states to partition
     /// check to make sure
     async fn sync_spu_changes(&mut self, listener: &mut K8ChangeListener<SpuSpec>) {
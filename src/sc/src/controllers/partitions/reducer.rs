@@ -9,10 +9,11 @@ use tracing::debug;
 use tracing::warn;
 
 use fluvio_controlplane_metadata::core::MetadataItem;
+use fluvio_types::SpuId;
 
 use crate::stores::partition::{
     PartitionSpec, PartitionAdminStore, ReplicaStatus, PartitionAdminMd, PartitionResolution,
-    ElectionPolicy, ElectionScoring,
+    ElectionPolicy, ElectionScoring, PartitionStatus,
 };
 use crate::stores::spu::{SpuAdminStore, SpuAdminMd, SpuLocalStorePolicy};
 use crate::stores::actions::WSAction;
@@ -84,21 +85,29 @@ impl PartitionReducer {
         &self,
         updates: Vec<PartitionAdminMd>,
     ) -> Vec<PartitionWSAction> {
-        // reconcile delete timestamp in the metadata with delete status
-        updates
-            .into_iter()
-            .filter_map(|partition| {
-                if partition.ctx().item().is_being_deleted() && !partition.status.is_being_deleted {
+        let mut actions = vec![];
+        for partition in updates.into_iter() {
+            // reconcile delete timestamp in the metadata with delete status
+            if partition.ctx().item().is_being_deleted() {
+                if !partition.status.is_being_deleted {
                     debug!("set partition: {} to delete", partition.key());
-                    Some(PartitionWSAction::UpdateStatus((
+                    actions.push(PartitionWSAction::UpdateStatus((
                         partition.key,
                         partition.status.set_to_delete(),
-                    )))
-                } else {
-                    None
+                    )));
                 }
-            })
-            .collect()
+            } else if partition.spec.is_reassigning() && is_reassignment_in_sync(&partition) {
+                debug!(
+                    "partition: {} replicas are in sync, removing: {:?}",
+                    partition.key(),
+                    partition.spec.removing_replicas
+                );
+                let mut spec = partition.spec;
+                spec.complete_reassignment();
+                actions.push(PartitionWSAction::UpdateSpec((partition.key, spec)));
+            }
+        }
+        actions
     }
 
     ///
@@ -177,6 +186,51 @@ impl PartitionReducer {
         }
     }
 
+    /// move leadership back to preferred leader, first replica of partition,
+    /// when it is online and has replicated all records of current leader
+    pub async fn elect_preferred_leaders(&self) -> Vec<PartitionWSAction> {
+        let mut actions = vec![];
+        let spu_status = self.spu_store.online_status().await;
+
+        for partition_kv_epoch in self.partition_store.read().await.values() {
+            let partition_kv = partition_kv_epoch.inner();
+            // reassignment moves leader on its own
+            if partition_kv.status.is_being_deleted || partition_kv.spec.is_reassigning() {
+                continue;
+            }
+
+            let preferred = match partition_kv.spec.replicas.first() {
+                Some(preferred) => *preferred,
+                None => continue,
+            };
+            if preferred == partition_kv.spec.leader || !spu_status.contains(&preferred) {
+                continue;
+            }
+
+            // wait until current leader has reported its status
+            let status = &partition_kv.status;
+            if !status.is_online() || status.leader.spu != partition_kv.spec.leader {
+                continue;
+            }
+
+            if is_replica_caught_up(status, preferred) {
+                debug!(
+                    "moving leader of partition: {} from: {} to preferred: {}",
+                    partition_kv.key(),
+                    partition_kv.spec.leader,
+                    preferred
+                );
+                let mut part_kv_change = partition_kv.clone();
+                part_kv_change.spec.leader = preferred;
+                actions.push(PartitionWSAction::UpdateSpec((
+                    part_kv_change.key_owned(),
+                    part_kv_change.spec,
+                )));
+            }
+        }
+        actions
+    }
+
     /// perform election when spu become online
     async fn force_election_spu_on(
         &self,
@@ -222,6 +276,36 @@ impl PartitionReducer {
     }
 }
 
+/// all target replicas of reassignment have replicated committed records of leader
+fn is_reassignment_in_sync(partition: &PartitionAdminMd) -> bool {
+    let status = &partition.status;
+    if !status.is_online() || status.leader.spu != partition.spec.leader {
+        return false;
+    }
+
+    partition
+        .spec
+        .target_replicas()
+        .iter()
+        .filter(|spu| **spu != status.leader.spu)
+        .all(|spu| is_replica_in_sync(status, *spu))
+}
+
+/// follower replica has replicated all records of leader, including uncommitted ones,
+/// so records acknowledged by leader are not lost when leadership is moved voluntarily
+fn is_replica_caught_up(status: &PartitionStatus, spu: SpuId) -> bool {
+    status
+        .replica_iter()
+        .any(|replica| replica.spu == spu && replica.leo >= 0 && replica.leo >= status.leader.leo)
+}
+
+/// follower replica has replicated committed records of leader
+fn is_replica_in_sync(status: &PartitionStatus, spu: SpuId) -> bool {
+    status
+        .replica_iter()
+        .any(|replica| replica.spu == spu && replica.leo >= 0 && replica.leo >= status.leader.hw)
+}
+
 struct SimplePolicy {}
 
 impl SimplePolicy {
@@ -254,6 +338,97 @@ impl ElectionPolicy for SimplePolicy {
 #[cfg(test)]
 pub mod test {
 
+    use fluvio_future::test_async;
+
+    use super::*;
+
+    #[test_async]
+    async fn test_reassignment_completed_when_in_sync() -> Result<(), ()> {
+        let reducer = PartitionReducer::default();
+
+        let mut spec: PartitionSpec = vec![1, 2].into();
+        spec.reassign(vec![2, 3]);
+
+        // new replica 3 is behind leader
+        let mut partition = PartitionAdminMd::with_spec(("topic", 0), spec.clone());
+        partition.status = PartitionStatus::new2(
+            (1, 10, 10),
+            vec![(2, 10, 10).into(), (3, 4, 4).into()],
+            PartitionResolution::Online,
+        );
+        assert!(reducer
+            .process_partition_update(vec![partition.clone()])
+            .await
+            .is_empty());
+
+        // replica 3 caught up, leader moves to 2
+        partition.status.replicas[1] = (3, 10, 10).into();
+        let actions = reducer.process_partition_update(vec![partition]).await;
+        let mut expected = spec;
+        expected.complete_reassignment();
+        assert_eq!(expected.leader, 2);
+        assert_eq!(
+            actions,
+            vec![PartitionWSAction::UpdateSpec((
+                ("topic", 0).into(),
+                expected
+            ))]
+        );
+        Ok(())
+    }
+
+    #[test_async]
+    async fn test_elect_preferred_leader() -> Result<(), ()> {
+        // leadership moved to replica 2 while preferred replica 1 was offline
+        let mut spec: PartitionSpec = vec![1, 2].into();
+        spec.leader = 2;
+        let mut partition = PartitionAdminMd::with_spec(("topic", 0), spec.clone());
+        partition.status = PartitionStatus::new2(
+            (2, 10, 10),
+            vec![(1, 4, 4).into()],
+            PartitionResolution::Online,
+        );
+
+        // preferred replica is behind leader
+        let reducer = PartitionReducer::new(
+            PartitionAdminStore::bulk_new(vec![partition.clone()]),
+            SpuAdminStore::quick(vec![(1, true, None), (2, true, None)]),
+        );
+        assert!(reducer.elect_preferred_leaders().await.is_empty());
+
+        // preferred replica has committed records, but not all records of leader
+        partition.status.leader = (2, 10, 12).into();
+        partition.status.replicas[0] = (1, 10, 10).into();
+        let reducer = PartitionReducer::new(
+            PartitionAdminStore::bulk_new(vec![partition.clone()]),
+            SpuAdminStore::quick(vec![(1, true, None), (2, true, None)]),
+        );
+        assert!(reducer.elect_preferred_leaders().await.is_empty());
+
+        // preferred replica caught up but is offline
+        partition.status.replicas[0] = (1, 10, 12).into();
+        let reducer = PartitionReducer::new(
+            PartitionAdminStore::bulk_new(vec![partition.clone()]),
+            SpuAdminStore::quick(vec![(1, false, None), (2, true, None)]),
+        );
+        assert!(reducer.elect_preferred_leaders().await.is_empty());
+
+        let reducer = PartitionReducer::new(
+            PartitionAdminStore::bulk_new(vec![partition]),
+            SpuAdminStore::quick(vec![(1, true, None), (2, true, None)]),
+        );
+        let mut expected = spec;
+        expected.leader = 1;
+        assert_eq!(
+            reducer.elect_preferred_leaders().await,
+            vec![PartitionWSAction::UpdateSpec((
+                ("topic", 0).into(),
+                expected
+            ))]
+        );
+        Ok(())
+    }
+
     /*
     #[test_async]
     async fn test_process_partition_actions_without_partitions() -> Result<(), ()> {
@@ -69,6 +69,27 @@ pub async fn generate_replica_map(
     }
 }
 
+///
+/// Extend replica map of provisioned topic when partitions have been added
+///  * existing partitions keep their replicas
+///  * returns None if there are not enough spus for new partitions
+///
+pub async fn extend_replica_map(
+    spus: &SpuAdminStore,
+    param: &TopicReplicaParam,
+    replica_map: &ReplicaMap,
+) -> Option<ReplicaMap> {
+    if spus.count().await < param.replication_factor {
+        return None;
+    }
+
+    let mut extended_map = replica_map.clone();
+    for (idx, replicas) in generate_replica_map_for_topic(spus, param, None).await {
+        extended_map.entry(idx).or_insert(replicas);
+    }
+    Some(extended_map)
+}
+
 ///
 /// Compare assigned SPUs versus local SPUs. If all assigned SPUs are live,
 /// update topic status to ok. otherwise, mark as waiting for live SPUs
@@ -197,6 +218,20 @@ impl TopicNextState {
                     );
                     let mut next_state = TopicNextState::same_next_state(topic);
                     if next_state.resolution == TopicResolution::Provisioned {
+                        // partitions have been added, new partitions are created once map is updated
+                        if param.partitions as usize > topic.status.replica_map.len() {
+                            if let Some(replica_map) =
+                                extend_replica_map(spu_store, param, &topic.status.replica_map)
+                                    .await
+                            {
+                                debug!(
+                                    "topic: {} extending replica map to {} partitions",
+                                    topic.key(),
+                                    param.partitions
+                                );
+                                next_state.replica_map = replica_map;
+                            }
+                        }
                         next_state.partitions = topic.create_new_partitions(partition_store).await;
                         next_state
                     } else {
@@ -582,4 +617,31 @@ pub mod replica_map_test {
         assert_eq!(computed, expected);
         Ok(())
     }
+
+    #[test_async]
+    async fn extend_replica_map_keeps_existing_partitions() -> Result<(), ()> {
+        let spus = SpuAdminStore::quick(vec![(0, true, None), (1, true, None), (2, true, None)]);
+
+        let mut replica_map = BTreeMap::new();
+        replica_map.insert(0, vec![2, 0]);
+        replica_map.insert(1, vec![0, 1]);
+
+        // add 2 partitions
+        let param = (4, 2, false).into();
+        let extended = extend_replica_map(&spus, &param, &replica_map)
+            .await
+            .expect("map");
+        assert_eq!(extended.len(), 4);
+        assert_eq!(extended.get(&0), Some(&vec![2, 0]));
+        assert_eq!(extended.get(&1), Some(&vec![0, 1]));
+        assert_eq!(extended.get(&2).map(|r| r.len()), Some(2));
+        assert_eq!(extended.get(&3).map(|r| r.len()), Some(2));
+
+        // not enough spus for replication factor
+        let param = (4, 4, false).into();
+        assert!(extend_replica_map(&spus, &param, &replica_map)
+            .await
+            .is_none());
+        Ok(())
+    }
 }
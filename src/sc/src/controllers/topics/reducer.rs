@@ -155,6 +155,7 @@ impl TopicReducer {
         // apply changes to topics
         if updated_topic.status.resolution != topic.status.resolution
             || updated_topic.status.reason != topic.status.reason
+            || updated_topic.status.replica_map != topic.status.replica_map
         {
             debug!(
                 "{} status change to {} from: {}",
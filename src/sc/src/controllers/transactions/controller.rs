@@ -0,0 +1,47 @@
+//!
+//! # Transaction Controller
+//!
+//! Aborts transactions which are not completed within transaction timeout
+
+use std::time::Duration;
+
+use tracing::debug;
+use tracing::instrument;
+
+use fluvio_future::task::spawn;
+use fluvio_future::timer::sleep;
+
+use crate::core::SharedContext;
+
+const EXPIRATION_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+#[derive(Debug)]
+pub struct TransactionController {
+    ctx: SharedContext,
+}
+
+impl TransactionController {
+    pub fn start(ctx: SharedContext) {
+        let controller = Self { ctx };
+
+        spawn(controller.dispatch_loop());
+    }
+
+    #[instrument(skip(self), name = "TransactionLoop")]
+    async fn dispatch_loop(self) {
+        debug!("starting dispatch loop");
+
+        loop {
+            sleep(EXPIRATION_CHECK_INTERVAL).await;
+
+            let markers = self.ctx.transactions().expire_transactions().await;
+            if !markers.is_empty() {
+                debug!(markers = markers.len(), "aborting expired transactions");
+                self.ctx
+                    .transactions()
+                    .queue_markers(self.ctx.partitions(), markers)
+                    .await;
+            }
+        }
+    }
+}
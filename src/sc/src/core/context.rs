@@ -4,15 +4,21 @@
 //! Metadata stores a copy of the data from KV store in local memory.
 //!
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::config::ScConfig;
 use crate::stores::spu::*;
 use crate::stores::partition::*;
 use crate::stores::topic::*;
 use crate::stores::spg::*;
+use crate::stores::group::*;
+use crate::stores::smartstream::*;
 use crate::stores::*;
 use crate::controllers::spus::SpuStatusChannel;
 
+use super::{TransactionCoordinator, GroupCoordinator};
+
 pub type SharedContext = Arc<Context>;
 
 /// Global Context for SC
@@ -23,8 +29,14 @@ pub struct Context {
     partitions: StoreContext<PartitionSpec>,
     topics: StoreContext<TopicSpec>,
     spgs: StoreContext<SpuGroupSpec>,
+    groups: StoreContext<ConsumerGroupSpec>,
+    smartstreams: StoreContext<SmartStreamSpec>,
     health: SpuStatusChannel,
     config: ScConfig,
+    producer_ids: AtomicI64,
+    transactions: TransactionCoordinator,
+    group_coordinator: GroupCoordinator,
+    leader: AtomicBool,
 }
 
 // -----------------------------------
@@ -43,8 +55,14 @@ impl Context {
             partitions: StoreContext::new(),
             topics: StoreContext::new(),
             spgs: StoreContext::new(),
+            groups: StoreContext::new(),
+            smartstreams: StoreContext::new(),
             health: SpuStatusChannel::new(),
             config,
+            producer_ids: AtomicI64::new(initial_producer_id()),
+            transactions: TransactionCoordinator::default(),
+            group_coordinator: GroupCoordinator::default(),
+            leader: AtomicBool::new(true),
         }
     }
 
@@ -67,6 +85,16 @@ impl Context {
         &self.spgs
     }
 
+    /// reference to consumer groups
+    pub fn groups(&self) -> &StoreContext<ConsumerGroupSpec> {
+        &self.groups
+    }
+
+    /// reference to registered smartstream modules
+    pub fn smartstreams(&self) -> &StoreContext<SmartStreamSpec> {
+        &self.smartstreams
+    }
+
     /// spu health channel
     pub fn health(&self) -> &SpuStatusChannel {
         &self.health
@@ -80,4 +108,38 @@ impl Context {
     pub fn namespace(&self) -> &str {
         &self.config.namespace
     }
+
+    /// allocate id for idempotent producer
+    pub fn next_producer_id(&self) -> i64 {
+        self.producer_ids.fetch_add(1, Ordering::SeqCst)
+    }
+
+    /// coordinator of transactional producers
+    pub fn transactions(&self) -> &TransactionCoordinator {
+        &self.transactions
+    }
+
+    /// coordinator of consumer group members
+    pub fn group_coordinator(&self) -> &GroupCoordinator {
+        &self.group_coordinator
+    }
+
+    /// true if this SC is leader and can change metadata.
+    /// follower only serves list and watch requests
+    pub fn is_leader(&self) -> bool {
+        self.leader.load(Ordering::SeqCst)
+    }
+
+    pub fn set_leader(&self, leader: bool) {
+        self.leader.store(leader, Ordering::SeqCst);
+    }
+}
+
+/// producer ids are not persisted, start from current time in microseconds
+/// so ids are not reused after SC restarts
+fn initial_producer_id() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_micros() as i64)
+        .unwrap_or(0)
 }
@@ -0,0 +1,517 @@
+//!
+//! # Group Coordinator
+//!
+//! Keeps track of members of consumer groups and assigns partitions among them.
+//! Membership is kept in memory. When member joins, leaves or misses heartbeats,
+//! generation of the group is bumped and all members must rejoin.
+//! Member keeps partitions of previous generation, so it can commit their offsets,
+//! until it rejoins. Partitions are assigned only after every member has rejoined,
+//! so partition is never consumed by two members at same time.
+//!
+//! Committed offsets are stored in consumer group metadata.
+//!
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::io::{Error as IoError, ErrorKind};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use tracing::debug;
+use async_lock::Mutex;
+
+use dataplane::{ErrorCode, Offset};
+use fluvio_types::PartitionId;
+
+use crate::stores::StoreContext;
+use crate::stores::group::ConsumerGroupSpec;
+
+#[derive(Debug)]
+struct GroupMember {
+    topic: String,
+    session_timeout: Duration,
+    last_heartbeat: Instant,
+    /// generation in which partitions were assigned
+    generation: i32,
+    partitions: Vec<PartitionId>,
+}
+
+#[derive(Debug, Default)]
+struct GroupState {
+    generation: i32,
+    members: BTreeMap<String, GroupMember>,
+    /// members which has not rejoined since generation was bumped
+    rejoining: BTreeSet<String>,
+    /// partition count of topics consumed by members
+    topics: HashMap<String, i32>,
+}
+
+impl GroupState {
+    /// bump generation, every member except joining one must rejoin before partitions are assigned
+    fn rebalance(&mut self, joining: Option<&str>) {
+        self.generation += 1;
+        self.rejoining = self
+            .members
+            .keys()
+            .filter(|member_id| Some(member_id.as_str()) != joining)
+            .cloned()
+            .collect();
+
+        debug!(
+            generation = self.generation,
+            members = self.members.len(),
+            rejoining = self.rejoining.len(),
+            "rebalancing group"
+        );
+        self.assign();
+    }
+
+    /// member has stopped consuming partitions of previous generation
+    fn rejoined(&mut self, member_id: &str) {
+        if !self.rejoining.remove(member_id) {
+            return;
+        }
+        if let Some(member) = self.members.get_mut(member_id) {
+            member.partitions.clear();
+            member.generation = self.generation;
+        }
+        self.assign();
+    }
+
+    /// once all members has rejoined, assign partitions of each topic round robin among its members
+    fn assign(&mut self) {
+        if !self.rejoining.is_empty() {
+            return;
+        }
+
+        for member in self.members.values_mut() {
+            member.partitions.clear();
+            member.generation = self.generation;
+        }
+
+        for (topic, partition_count) in &self.topics {
+            let mut members: Vec<&mut GroupMember> = self
+                .members
+                .values_mut()
+                .filter(|member| &member.topic == topic)
+                .collect();
+            if members.is_empty() {
+                continue;
+            }
+
+            let member_count = members.len();
+            for partition in 0..*partition_count {
+                members[partition as usize % member_count]
+                    .partitions
+                    .push(partition);
+            }
+        }
+
+        debug!(
+            generation = self.generation,
+            members = self.members.len(),
+            "assigned partitions"
+        );
+    }
+
+    fn is_rebalancing(&self) -> bool {
+        !self.rejoining.is_empty()
+    }
+
+    fn member(&mut self, member_id: &str, generation: i32) -> Result<&mut GroupMember, ErrorCode> {
+        let current_generation = self.generation;
+        let member = self
+            .members
+            .get_mut(member_id)
+            .ok_or(ErrorCode::UnknownMemberId)?;
+        if generation != current_generation {
+            return Err(ErrorCode::RebalanceInProgress);
+        }
+        Ok(member)
+    }
+}
+
+/// partitions assigned to member
+#[derive(Debug, PartialEq)]
+pub struct GroupAssignment {
+    pub member_id: String,
+    pub generation: i32,
+    /// none while other members has not rejoined yet
+    pub partitions: Option<Vec<PartitionId>>,
+}
+
+#[derive(Debug)]
+struct GroupStates {
+    groups: HashMap<String, GroupState>,
+    next_member_id: u64,
+}
+
+impl Default for GroupStates {
+    fn default() -> Self {
+        // member ids are not reused after SC restarts
+        let next_member_id = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_micros() as u64)
+            .unwrap_or(0);
+        Self {
+            groups: HashMap::new(),
+            next_member_id,
+        }
+    }
+}
+
+impl GroupStates {
+    /// add member to group, known member acknowledges current generation by rejoining.
+    /// partitions are returned once all members has rejoined
+    fn join(
+        &mut self,
+        group: &str,
+        member_id: &str,
+        topic: &str,
+        partition_count: i32,
+        session_timeout: Duration,
+    ) -> GroupAssignment {
+        let state = self.groups.entry(group.to_owned()).or_default();
+        state.topics.insert(topic.to_owned(), partition_count);
+
+        let member_id = match state.members.get_mut(member_id) {
+            Some(member) if member.topic == topic => {
+                member.last_heartbeat = Instant::now();
+                member.session_timeout = session_timeout;
+                state.rejoined(member_id);
+                member_id.to_owned()
+            }
+            _ => {
+                state.members.remove(member_id);
+                self.next_member_id += 1;
+                let member_id = format!("{}-{:x}", group, self.next_member_id);
+                state.members.insert(
+                    member_id.clone(),
+                    GroupMember {
+                        topic: topic.to_owned(),
+                        session_timeout,
+                        last_heartbeat: Instant::now(),
+                        generation: state.generation,
+                        partitions: vec![],
+                    },
+                );
+                debug!(group, %member_id, "member joined");
+                state.rebalance(Some(&member_id));
+                member_id
+            }
+        };
+
+        let partitions = if state.is_rebalancing() {
+            None
+        } else {
+            Some(state.members[&member_id].partitions.clone())
+        };
+        GroupAssignment {
+            partitions,
+            generation: state.generation,
+            member_id,
+        }
+    }
+
+    fn heartbeat(
+        &mut self,
+        group: &str,
+        member_id: &str,
+        generation: i32,
+    ) -> Result<(), ErrorCode> {
+        let state = self
+            .groups
+            .get_mut(group)
+            .ok_or(ErrorCode::UnknownMemberId)?;
+        match state.members.get_mut(member_id) {
+            Some(member) => member.last_heartbeat = Instant::now(),
+            None => return Err(ErrorCode::UnknownMemberId),
+        }
+        state.member(member_id, generation).map(|_| ())
+    }
+
+    fn leave(&mut self, group: &str, member_id: &str) -> Result<(), ErrorCode> {
+        let state = self
+            .groups
+            .get_mut(group)
+            .ok_or(ErrorCode::UnknownMemberId)?;
+        if state.members.remove(member_id).is_none() {
+            return Err(ErrorCode::UnknownMemberId);
+        }
+        debug!(group, member_id, "member left");
+        state.rejoining.remove(member_id);
+        state.rebalance(None);
+        Ok(())
+    }
+
+    /// member can only commit partitions it owns, partitions of previous generation
+    /// are owned until member rejoins
+    fn check_commit(
+        &mut self,
+        group: &str,
+        member_id: &str,
+        generation: i32,
+        topic: &str,
+        partitions: impl Iterator<Item = PartitionId>,
+    ) -> Result<(), ErrorCode> {
+        let state = self
+            .groups
+            .get_mut(group)
+            .ok_or(ErrorCode::UnknownMemberId)?;
+        let member = state
+            .members
+            .get(member_id)
+            .ok_or(ErrorCode::UnknownMemberId)?;
+        if member.generation != generation {
+            return Err(ErrorCode::IllegalGeneration);
+        }
+        for partition in partitions {
+            if member.topic != topic || !member.partitions.contains(&partition) {
+                return Err(ErrorCode::IllegalGeneration);
+            }
+        }
+        Ok(())
+    }
+
+    /// remove members which has not sent heartbeat within session timeout
+    fn expire_members(&mut self, now: Instant) {
+        for (group, state) in self.groups.iter_mut() {
+            let before = state.members.len();
+            state.members.retain(|_, member| {
+                now.duration_since(member.last_heartbeat) <= member.session_timeout
+            });
+            if state.members.len() != before {
+                debug!(%group, expired = before - state.members.len(), "expired group members");
+                state.rebalance(None);
+            }
+        }
+        self.groups.retain(|_, state| !state.members.is_empty());
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct GroupCoordinator {
+    states: Mutex<GroupStates>,
+    /// committed offsets of each group, lock is held until metadata store is updated
+    /// so concurrent commits of group are not lost
+    offsets: Mutex<HashMap<String, Arc<Mutex<Option<ConsumerGroupSpec>>>>>,
+}
+
+impl GroupCoordinator {
+    pub async fn join(
+        &self,
+        group: &str,
+        member_id: &str,
+        topic: &str,
+        partition_count: i32,
+        session_timeout: Duration,
+    ) -> GroupAssignment {
+        self.states
+            .lock()
+            .await
+            .join(group, member_id, topic, partition_count, session_timeout)
+    }
+
+    pub async fn heartbeat(
+        &self,
+        group: &str,
+        member_id: &str,
+        generation: i32,
+    ) -> Result<(), ErrorCode> {
+        self.states
+            .lock()
+            .await
+            .heartbeat(group, member_id, generation)
+    }
+
+    pub async fn leave(&self, group: &str, member_id: &str) -> Result<(), ErrorCode> {
+        self.states.lock().await.leave(group, member_id)
+    }
+
+    pub async fn expire_members(&self) {
+        self.states.lock().await.expire_members(Instant::now())
+    }
+
+    async fn group_offsets(&self, group: &str) -> Arc<Mutex<Option<ConsumerGroupSpec>>> {
+        self.offsets
+            .lock()
+            .await
+            .entry(group.to_owned())
+            .or_default()
+            .clone()
+    }
+
+    /// store offsets committed by member, return once metadata store has been updated
+    pub async fn commit_offsets(
+        &self,
+        groups: &StoreContext<ConsumerGroupSpec>,
+        group: &str,
+        member_id: &str,
+        generation: i32,
+        topic: &str,
+        offsets: Vec<(PartitionId, Offset)>,
+    ) -> Result<(), ErrorCode> {
+        self.states.lock().await.check_commit(
+            group,
+            member_id,
+            generation,
+            topic,
+            offsets.iter().map(|(partition, _)| *partition),
+        )?;
+
+        let group_offsets = self.group_offsets(group).await;
+        let mut committed = group_offsets.lock().await;
+        let current = match committed.as_ref() {
+            Some(spec) => spec.clone(),
+            None => groups
+                .store()
+                .value(group)
+                .await
+                .map(|obj| obj.spec.clone())
+                .unwrap_or_default(),
+        };
+        let mut spec = current.clone();
+        for (partition, offset) in offsets {
+            spec.commit(topic, partition, offset);
+        }
+
+        // store is not changed by same offsets, so there would be no update to wait for
+        if spec != current {
+            groups
+                .create_spec(group.to_owned(), spec.clone())
+                .await
+                .map_err(|err| store_error_code(&err))?;
+        }
+        *committed = Some(spec);
+        Ok(())
+    }
+
+    /// offsets committed by group for partitions of topic
+    pub async fn fetch_offsets(
+        &self,
+        groups: &StoreContext<ConsumerGroupSpec>,
+        group: &str,
+        topic: &str,
+        partitions: Vec<PartitionId>,
+    ) -> Vec<(PartitionId, Offset)> {
+        let cached = self.group_offsets(group).await.lock().await.clone();
+        let spec = match cached {
+            Some(spec) => spec,
+            None => groups
+                .store()
+                .value(group)
+                .await
+                .map(|obj| obj.spec.clone())
+                .unwrap_or_default(),
+        };
+
+        partitions
+            .into_iter()
+            .filter_map(|partition| {
+                spec.offset(topic, partition)
+                    .map(|offset| (partition, offset))
+            })
+            .collect()
+    }
+}
+
+fn store_error_code(err: &IoError) -> ErrorCode {
+    match err.kind() {
+        ErrorKind::TimedOut => ErrorCode::RequestTimedOut,
+        _ => ErrorCode::StorageError,
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::time::{Duration, Instant};
+
+    use dataplane::ErrorCode;
+
+    use super::GroupStates;
+
+    const TIMEOUT: Duration = Duration::from_secs(10);
+
+    #[test]
+    fn test_group_rebalance() {
+        let mut states = GroupStates::default();
+
+        let first = states.join("group", "", "test", 3, TIMEOUT);
+        assert_eq!(first.generation, 1);
+        assert_eq!(first.partitions, Some(vec![0, 1, 2]));
+
+        // partitions are not assigned until first member has rejoined
+        let second = states.join("group", "", "test", 3, TIMEOUT);
+        assert_eq!(second.generation, 2);
+        assert_eq!(second.partitions, None);
+        assert_ne!(first.member_id, second.member_id);
+
+        // first member finds out about rebalance through heartbeat
+        assert_eq!(
+            states.heartbeat("group", &first.member_id, first.generation),
+            Err(ErrorCode::RebalanceInProgress)
+        );
+        // and still owns its partitions until it rejoins
+        assert!(states
+            .check_commit("group", &first.member_id, 1, "test", vec![0].into_iter())
+            .is_ok());
+        assert_eq!(
+            states
+                .join("group", &second.member_id, "test", 3, TIMEOUT)
+                .partitions,
+            None
+        );
+
+        let rejoined = states.join("group", &first.member_id, "test", 3, TIMEOUT);
+        assert_eq!(rejoined.member_id, first.member_id);
+        assert_eq!(rejoined.generation, 2);
+        assert_eq!(
+            states.check_commit("group", &first.member_id, 1, "test", vec![0].into_iter()),
+            Err(ErrorCode::IllegalGeneration)
+        );
+
+        let second = states.join("group", &second.member_id, "test", 3, TIMEOUT);
+        let second_partitions = second.partitions.expect("assigned");
+        let mut partitions = rejoined.partitions.expect("assigned");
+        partitions.extend(second_partitions.iter());
+        partitions.sort_unstable();
+        assert_eq!(partitions, vec![0, 1, 2]);
+        assert!(states
+            .heartbeat("group", &second.member_id, second.generation)
+            .is_ok());
+
+        // member can't commit partition assigned to other member
+        let other = second_partitions[0];
+        assert_eq!(
+            states.check_commit(
+                "group",
+                &first.member_id,
+                2,
+                "test",
+                vec![other].into_iter()
+            ),
+            Err(ErrorCode::IllegalGeneration)
+        );
+
+        states.leave("group", &second.member_id).expect("leave");
+        let rejoined = states.join("group", &first.member_id, "test", 3, TIMEOUT);
+        assert_eq!(rejoined.generation, 3);
+        assert_eq!(rejoined.partitions, Some(vec![0, 1, 2]));
+    }
+
+    #[test]
+    fn test_expire_members() {
+        let mut states = GroupStates::default();
+        let first = states.join("group", "", "test", 2, TIMEOUT);
+        states.join("group", "", "test", 2, Duration::from_millis(0));
+
+        states.expire_members(Instant::now() + Duration::from_secs(1));
+
+        let rejoined = states.join("group", &first.member_id, "test", 2, TIMEOUT);
+        assert_eq!(rejoined.generation, 3);
+        assert_eq!(rejoined.partitions, Some(vec![0, 1]));
+
+        states.expire_members(Instant::now() + Duration::from_secs(60));
+        assert!(states.groups.is_empty());
+    }
+}
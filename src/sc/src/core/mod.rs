@@ -1,3 +1,7 @@
 mod context;
+mod transaction;
+mod group;
 pub mod common;
 pub use self::context::*;
+pub use self::transaction::TransactionCoordinator;
+pub use self::group::GroupCoordinator;
@@ -0,0 +1,404 @@
+//!
+//! # Transaction Coordinator
+//!
+//! Keeps track of transactions of transactional producers.
+//! When transaction is committed or aborted, markers are stored in status of partitions
+//! in the transaction and sent by internal api to leader of each partition.
+//! Marker is removed from status only after leader confirms it has been written,
+//! so it is sent again after leader change or SC restart.
+//!
+//! Transaction state is kept in memory, transactions in progress are lost when SC restarts.
+//!
+
+use std::collections::{BTreeSet, HashMap};
+use std::time::{Duration, Instant};
+
+use tracing::{debug, warn};
+use async_lock::Mutex;
+
+use dataplane::{ErrorCode, ReplicaKey};
+use fluvio_types::SpuId;
+use fluvio_controlplane::TxnMarker;
+
+use crate::stores::StoreContext;
+use crate::stores::actions::WSAction;
+use crate::stores::partition::{PartitionSpec, PendingTxnMarker};
+
+#[derive(Debug)]
+struct TransactionMetadata {
+    producer_id: i64,
+    producer_epoch: i16,
+    timeout: Duration,
+    /// partitions in ongoing transaction, empty if there is no transaction
+    partitions: BTreeSet<ReplicaKey>,
+    started: Instant,
+}
+
+impl TransactionMetadata {
+    fn is_ongoing(&self) -> bool {
+        !self.partitions.is_empty()
+    }
+
+    fn check_producer(&self, producer_id: i64, producer_epoch: i16) -> Result<(), ErrorCode> {
+        if self.producer_id != producer_id {
+            Err(ErrorCode::InvalidProducerIdMapping)
+        } else if self.producer_epoch != producer_epoch {
+            Err(ErrorCode::InvalidProducerEpoch)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// complete ongoing transaction, returning markers for each partition
+    fn complete(&mut self, commit: bool) -> Vec<TxnMarker> {
+        let producer_id = self.producer_id;
+        let producer_epoch = self.producer_epoch;
+        std::mem::take(&mut self.partitions)
+            .into_iter()
+            .map(|replica| TxnMarker {
+                replica,
+                producer_id,
+                producer_epoch,
+                commit,
+            })
+            .collect()
+    }
+}
+
+/// state of transactions by transactional id
+#[derive(Debug, Default)]
+struct TransactionStates {
+    transactions: HashMap<String, TransactionMetadata>,
+}
+
+impl TransactionStates {
+    /// register transactional producer, ongoing transaction of previous producer instance is aborted
+    fn init_producer(
+        &mut self,
+        transactional_id: String,
+        timeout: Duration,
+        next_producer_id: impl FnOnce() -> i64,
+    ) -> (i64, i16, Vec<TxnMarker>) {
+        match self.transactions.get_mut(&transactional_id) {
+            Some(txn) => {
+                let markers = txn.complete(false);
+                if txn.producer_epoch == i16::MAX {
+                    txn.producer_id = next_producer_id();
+                    txn.producer_epoch = 0;
+                } else {
+                    txn.producer_epoch += 1;
+                }
+                txn.timeout = timeout;
+                (txn.producer_id, txn.producer_epoch, markers)
+            }
+            None => {
+                let producer_id = next_producer_id();
+                self.transactions.insert(
+                    transactional_id,
+                    TransactionMetadata {
+                        producer_id,
+                        producer_epoch: 0,
+                        timeout,
+                        partitions: BTreeSet::new(),
+                        started: Instant::now(),
+                    },
+                );
+                (producer_id, 0, vec![])
+            }
+        }
+    }
+
+    fn add_partitions(
+        &mut self,
+        transactional_id: &str,
+        producer_id: i64,
+        producer_epoch: i16,
+        partitions: Vec<ReplicaKey>,
+    ) -> Result<(), ErrorCode> {
+        let txn = self
+            .transactions
+            .get_mut(transactional_id)
+            .ok_or(ErrorCode::InvalidProducerIdMapping)?;
+        txn.check_producer(producer_id, producer_epoch)?;
+
+        if !txn.is_ongoing() {
+            txn.started = Instant::now();
+        }
+        txn.partitions.extend(partitions);
+        Ok(())
+    }
+
+    fn end_transaction(
+        &mut self,
+        transactional_id: &str,
+        producer_id: i64,
+        producer_epoch: i16,
+        commit: bool,
+    ) -> Result<Vec<TxnMarker>, ErrorCode> {
+        let txn = self
+            .transactions
+            .get_mut(transactional_id)
+            .ok_or(ErrorCode::InvalidProducerIdMapping)?;
+        txn.check_producer(producer_id, producer_epoch)?;
+        Ok(txn.complete(commit))
+    }
+
+    /// abort transactions which has not completed within timeout
+    /// epoch is bumped so producer can't continue with expired transaction
+    fn expire_transactions(&mut self, now: Instant) -> Vec<TxnMarker> {
+        let mut markers = vec![];
+        for (transactional_id, txn) in self.transactions.iter_mut() {
+            if txn.is_ongoing() && now.duration_since(txn.started) > txn.timeout {
+                debug!(%transactional_id, "aborting expired transaction");
+                markers.append(&mut txn.complete(false));
+                txn.producer_epoch = txn.producer_epoch.saturating_add(1);
+            }
+        }
+        markers
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct TransactionCoordinator {
+    states: Mutex<TransactionStates>,
+}
+
+impl TransactionCoordinator {
+    pub async fn init_producer(
+        &self,
+        transactional_id: String,
+        timeout: Duration,
+        next_producer_id: impl FnOnce() -> i64,
+    ) -> (i64, i16, Vec<TxnMarker>) {
+        self.states
+            .lock()
+            .await
+            .init_producer(transactional_id, timeout, next_producer_id)
+    }
+
+    pub async fn add_partitions(
+        &self,
+        transactional_id: &str,
+        producer_id: i64,
+        producer_epoch: i16,
+        partitions: Vec<ReplicaKey>,
+    ) -> Result<(), ErrorCode> {
+        self.states.lock().await.add_partitions(
+            transactional_id,
+            producer_id,
+            producer_epoch,
+            partitions,
+        )
+    }
+
+    pub async fn end_transaction(
+        &self,
+        transactional_id: &str,
+        producer_id: i64,
+        producer_epoch: i16,
+        commit: bool,
+    ) -> Result<Vec<TxnMarker>, ErrorCode> {
+        self.states.lock().await.end_transaction(
+            transactional_id,
+            producer_id,
+            producer_epoch,
+            commit,
+        )
+    }
+
+    pub async fn expire_transactions(&self) -> Vec<TxnMarker> {
+        self.states.lock().await.expire_transactions(Instant::now())
+    }
+
+    /// store markers in status of each partition until its leader has written them
+    pub async fn queue_markers(
+        &self,
+        partitions: &StoreContext<PartitionSpec>,
+        markers: Vec<TxnMarker>,
+    ) {
+        for (replica, markers) in group_by_replica(markers) {
+            if let Some(partition) = partitions.store().value(&replica).await {
+                let mut status = partition.status.clone();
+                for marker in markers {
+                    if !status.pending_txn_markers.contains(&marker) {
+                        status.pending_txn_markers.push(marker);
+                    }
+                }
+                partitions
+                    .send_action(WSAction::UpdateStatus((replica, status)))
+                    .await;
+            } else {
+                warn!(%replica, "partition not found, dropping txn markers");
+            }
+        }
+    }
+
+    /// markers which are not yet written for partitions led by spu
+    pub async fn pending_markers(
+        &self,
+        partitions: &StoreContext<PartitionSpec>,
+        spu: SpuId,
+    ) -> Vec<TxnMarker> {
+        partitions
+            .store()
+            .read()
+            .await
+            .values()
+            .map(|partition| partition.inner())
+            .filter(|partition| partition.spec.leader == spu)
+            .flat_map(|partition| {
+                let replica = partition.key_owned();
+                partition
+                    .status
+                    .pending_txn_markers
+                    .iter()
+                    .map(move |marker| TxnMarker {
+                        replica: replica.clone(),
+                        producer_id: marker.producer_id,
+                        producer_epoch: marker.producer_epoch,
+                        commit: marker.commit,
+                    })
+            })
+            .collect()
+    }
+
+    /// producer has markers of previous transaction which are not yet written to partitions.
+    /// new transaction must wait for them, so marker sent again doesn't complete it
+    pub async fn has_pending_markers(
+        &self,
+        partitions: &StoreContext<PartitionSpec>,
+        producer_id: i64,
+        replicas: &[ReplicaKey],
+    ) -> bool {
+        for replica in replicas {
+            if let Some(partition) = partitions.store().value(replica).await {
+                if partition
+                    .status
+                    .pending_txn_markers
+                    .iter()
+                    .any(|marker| marker.producer_id == producer_id)
+                {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// remove markers which leader has written
+    pub async fn remove_markers(
+        &self,
+        partitions: &StoreContext<PartitionSpec>,
+        markers: Vec<TxnMarker>,
+    ) {
+        for (replica, markers) in group_by_replica(markers) {
+            if let Some(partition) = partitions.store().value(&replica).await {
+                let mut status = partition.status.clone();
+                status
+                    .pending_txn_markers
+                    .retain(|marker| !markers.contains(marker));
+                partitions
+                    .send_action(WSAction::UpdateStatus((replica, status)))
+                    .await;
+            }
+        }
+    }
+}
+
+fn group_by_replica(markers: Vec<TxnMarker>) -> HashMap<ReplicaKey, Vec<PendingTxnMarker>> {
+    let mut grouped: HashMap<ReplicaKey, Vec<PendingTxnMarker>> = HashMap::new();
+    for marker in markers {
+        grouped
+            .entry(marker.replica)
+            .or_default()
+            .push(PendingTxnMarker {
+                producer_id: marker.producer_id,
+                producer_epoch: marker.producer_epoch,
+                commit: marker.commit,
+            });
+    }
+    grouped
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::time::{Duration, Instant};
+
+    use dataplane::{ErrorCode, ReplicaKey};
+
+    use super::TransactionStates;
+
+    fn replica(partition: i32) -> ReplicaKey {
+        ReplicaKey {
+            topic: "test".to_owned(),
+            partition,
+        }
+    }
+
+    #[test]
+    fn test_transaction_states() {
+        let mut states = TransactionStates::default();
+        let timeout = Duration::from_secs(60);
+
+        let (id, epoch, markers) = states.init_producer("txn".to_owned(), timeout, || 100);
+        assert_eq!((id, epoch), (100, 0));
+        assert!(markers.is_empty());
+
+        assert_eq!(
+            states.add_partitions("txn", 100, 1, vec![replica(0)]),
+            Err(ErrorCode::InvalidProducerEpoch)
+        );
+        assert_eq!(
+            states.add_partitions("other", 100, 0, vec![replica(0)]),
+            Err(ErrorCode::InvalidProducerIdMapping)
+        );
+
+        states
+            .add_partitions("txn", 100, 0, vec![replica(0), replica(1)])
+            .expect("add");
+        states
+            .add_partitions("txn", 100, 0, vec![replica(1)])
+            .expect("add");
+
+        let markers = states.end_transaction("txn", 100, 0, true).expect("end");
+        assert_eq!(markers.len(), 2);
+        assert!(markers.iter().all(|marker| marker.commit));
+        assert!(states
+            .end_transaction("txn", 100, 0, true)
+            .expect("end")
+            .is_empty());
+
+        // new producer instance aborts ongoing transaction and fences off old instance
+        states
+            .add_partitions("txn", 100, 0, vec![replica(2)])
+            .expect("add");
+        let (id, epoch, markers) = states.init_producer("txn".to_owned(), timeout, || 200);
+        assert_eq!((id, epoch), (100, 1));
+        assert_eq!(markers.len(), 1);
+        assert!(!markers[0].commit);
+        assert_eq!(
+            states.end_transaction("txn", 100, 0, true),
+            Err(ErrorCode::InvalidProducerEpoch)
+        );
+    }
+
+    #[test]
+    fn test_expire_transactions() {
+        let mut states = TransactionStates::default();
+        states.init_producer("txn".to_owned(), Duration::from_millis(10), || 100);
+        states
+            .add_partitions("txn", 100, 0, vec![replica(0)])
+            .expect("add");
+
+        assert!(states.expire_transactions(Instant::now()).is_empty());
+
+        let markers = states.expire_transactions(Instant::now() + Duration::from_secs(1));
+        assert_eq!(markers.len(), 1);
+        assert!(!markers[0].commit);
+        assert_eq!(
+            states.add_partitions("txn", 100, 0, vec![replica(0)]),
+            Err(ErrorCode::InvalidProducerEpoch)
+        );
+    }
+}
@@ -0,0 +1,115 @@
+//!
+//! # Leader Election
+//!
+//! SC replicas sharing file metadata store compete for lease in the store.
+//! Leader runs controllers and internal server, followers refresh metadata from the store
+//! and serve list and watch requests until they win the lease.
+//!
+use std::process;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tracing::{debug, error, info, warn};
+use tracing::instrument;
+use once_cell::sync::Lazy;
+
+use fluvio_future::task::spawn;
+use fluvio_future::timer::sleep;
+
+use crate::core::SharedContext;
+use crate::dispatcher::dispatcher::FileMetadataStore;
+use crate::init::start_leader_services;
+
+static SC_LEASE_TTL_SEC: Lazy<u64> = Lazy::new(|| {
+    use std::env;
+
+    let var_value = env::var("FLV_SC_LEASE_TTL").unwrap_or_default();
+    let ttl: u64 = var_value.parse().unwrap_or(15);
+    ttl
+});
+
+#[derive(Debug)]
+pub struct LeaderElection {
+    ctx: SharedContext,
+    store: Arc<FileMetadataStore>,
+    instance_id: String,
+}
+
+impl LeaderElection {
+    pub fn start(ctx: SharedContext, store: Arc<FileMetadataStore>, instance_id: String) {
+        let election = Self {
+            ctx,
+            store,
+            instance_id,
+        };
+
+        spawn(election.dispatch_loop());
+    }
+
+    #[instrument(skip(self), name = "LeaderElection", fields(instance = &*self.instance_id))]
+    async fn dispatch_loop(self) {
+        let ttl = Duration::from_secs(*SC_LEASE_TTL_SEC);
+        // renew well before lease expires
+        let interval = ttl / 3;
+        // stop acting as leader before lease expires, another instance can take over right after
+        let margin = interval;
+        let mut last_renewal = Instant::now();
+        self.store.require_lease(&self.instance_id, margin);
+
+        info!(ttl = ttl.as_secs(), "starting leader election");
+        loop {
+            match self.store.try_acquire_lease(&self.instance_id, ttl).await {
+                Ok(true) => {
+                    if self.ctx.is_leader() && last_renewal.elapsed() >= ttl {
+                        // lease lapsed before renewal, another instance may have been leader meanwhile
+                        error!("lease expired before renewal, exiting");
+                        process::exit(1);
+                    }
+                    last_renewal = Instant::now();
+                    if !self.ctx.is_leader() {
+                        self.become_leader().await;
+                    }
+                }
+                Ok(false) => {
+                    if self.ctx.is_leader() {
+                        // another instance may already be changing metadata
+                        error!("leadership lost, exiting");
+                        process::exit(1);
+                    }
+                    self.refresh().await;
+                }
+                Err(err) => {
+                    if self.ctx.is_leader() {
+                        warn!("can't renew lease: {}", err);
+                        if last_renewal.elapsed() + margin >= ttl {
+                            self.ctx.set_leader(false);
+                            error!("lease is about to expire, exiting");
+                            process::exit(1);
+                        }
+                    } else {
+                        warn!("can't acquire lease: {}", err);
+                        self.refresh().await;
+                    }
+                }
+            }
+
+            sleep(interval).await;
+        }
+    }
+
+    /// catch up with changes made by previous leader before taking over
+    async fn become_leader(&self) {
+        info!("elected as leader");
+        self.refresh().await;
+        self.ctx.set_leader(true);
+        start_leader_services(&self.ctx);
+    }
+
+    async fn refresh(&self) {
+        match self.store.reload().await {
+            Ok(true) => debug!("metadata refreshed from store"),
+            Ok(false) => {}
+            Err(err) => error!("can't reload metadata store: {}", err),
+        }
+    }
+}
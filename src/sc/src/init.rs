@@ -5,6 +5,8 @@
 //! and receivers.
 //!
 
+use std::sync::Arc;
+
 use tracing::info;
 use k8_metadata_client::SharedClient;
 use k8_metadata_client::MetadataClient;
@@ -14,10 +16,14 @@ use crate::core::SharedContext;
 use crate::controllers::spus::SpuController;
 use crate::controllers::topics::TopicController;
 use crate::controllers::partitions::PartitionController;
+use crate::controllers::transactions::TransactionController;
+use crate::controllers::groups::GroupController;
 use crate::config::ScConfig;
 use crate::services::start_internal_server;
 use crate::dispatcher::dispatcher::K8ClusterStateDispatcher;
+use crate::dispatcher::dispatcher::{FileClusterStateDispatcher, FileMetadataStore};
 use crate::services::auth::basic::BasicRbacPolicy;
+use crate::election::LeaderElection;
 
 /// start the main loop
 pub async fn start_main_loop<C>(
@@ -31,6 +37,8 @@ where
     use crate::stores::topic::TopicSpec;
     use crate::stores::partition::PartitionSpec;
     use crate::stores::spg::SpuGroupSpec;
+    use crate::stores::group::ConsumerGroupSpec;
+    use crate::stores::smartstream::SmartStreamSpec;
     info!("SC Platform Version: {}", &*crate::VERSION);
 
     let (sc_config, auth_policy) = sc_config_policy;
@@ -57,46 +65,112 @@ where
     );
 
     K8ClusterStateDispatcher::<SpuGroupSpec, C>::start(
+        namespace.clone(),
+        metadata_client.clone(),
+        ctx.spgs().clone(),
+    );
+
+    K8ClusterStateDispatcher::<ConsumerGroupSpec, C>::start(
+        namespace.clone(),
+        metadata_client.clone(),
+        ctx.groups().clone(),
+    );
+
+    K8ClusterStateDispatcher::<SmartStreamSpec, C>::start(
         namespace,
         metadata_client,
-        ctx.spgs().clone(),
+        ctx.smartstreams().clone(),
     );
 
+    start_services(ctx, auth_policy)
+}
+
+/// start the main loop with metadata persisted in local file store instead of Kubernetes
+pub async fn start_main_loop_with_file_store(
+    sc_config_policy: (ScConfig, Option<BasicRbacPolicy>),
+    metadata_store: Arc<FileMetadataStore>,
+) -> SharedContext {
+    use crate::stores::spu::SpuSpec;
+    use crate::stores::topic::TopicSpec;
+    use crate::stores::partition::PartitionSpec;
+    use crate::stores::spg::SpuGroupSpec;
+    use crate::stores::group::ConsumerGroupSpec;
+    use crate::stores::smartstream::SmartStreamSpec;
+    info!("SC Platform Version: {}", &*crate::VERSION);
+
+    let (sc_config, auth_policy) = sc_config_policy;
+
+    let ctx = Context::shared_metadata(sc_config);
+
+    FileClusterStateDispatcher::<SpuSpec>::start(metadata_store.clone(), ctx.spus().clone());
+    FileClusterStateDispatcher::<TopicSpec>::start(metadata_store.clone(), ctx.topics().clone());
+    FileClusterStateDispatcher::<PartitionSpec>::start(
+        metadata_store.clone(),
+        ctx.partitions().clone(),
+    );
+    FileClusterStateDispatcher::<SpuGroupSpec>::start(metadata_store.clone(), ctx.spgs().clone());
+    FileClusterStateDispatcher::<ConsumerGroupSpec>::start(
+        metadata_store.clone(),
+        ctx.groups().clone(),
+    );
+    FileClusterStateDispatcher::<SmartStreamSpec>::start(
+        metadata_store.clone(),
+        ctx.smartstreams().clone(),
+    );
+
+    if let Some(instance_id) = ctx.config().instance_id.clone() {
+        // start as follower, controllers are started after winning election
+        ctx.set_leader(false);
+        pub_server::start(ctx.clone(), auth_policy);
+        LeaderElection::start(ctx.clone(), metadata_store, instance_id);
+        ctx
+    } else {
+        start_services(ctx, auth_policy)
+    }
+}
+
+/// start controllers and servers, metadata dispatchers must be started already
+fn start_services(ctx: SharedContext, auth_policy: Option<BasicRbacPolicy>) -> SharedContext {
+    start_leader_services(&ctx);
+    pub_server::start(ctx.clone(), auth_policy);
+    ctx
+}
+
+/// start controllers and internal server which only run in leader
+pub(crate) fn start_leader_services(ctx: &SharedContext) {
     SpuController::start(ctx.clone());
     TopicController::start(ctx.clone());
     PartitionController::start(ctx.clone());
+    TransactionController::start(ctx.clone());
+    GroupController::start(ctx.clone());
 
     start_internal_server(ctx.clone());
+}
 
-    pub_server::start(ctx.clone(), auth_policy);
-
-    mod pub_server {
-
-        use std::sync::Arc;
-        use tracing::info;
-
-        use crate::services::start_public_server;
-        use crate::core::SharedContext;
-
-        use crate::services::auth::{AuthGlobalContext, RootAuthorization};
-        use crate::services::auth::basic::{BasicAuthorization, BasicRbacPolicy};
-
-        pub fn start(ctx: SharedContext, auth_policy_option: Option<BasicRbacPolicy>) {
-            if let Some(policy) = auth_policy_option {
-                info!("using basic authorization");
-                start_public_server(AuthGlobalContext::new(
-                    ctx,
-                    Arc::new(BasicAuthorization::new(policy)),
-                ));
-            } else {
-                info!("using root authorization");
-                start_public_server(AuthGlobalContext::new(
-                    ctx,
-                    Arc::new(RootAuthorization::new()),
-                ));
-            }
+mod pub_server {
+
+    use std::sync::Arc;
+    use tracing::info;
+
+    use crate::services::start_public_server;
+    use crate::core::SharedContext;
+
+    use crate::services::auth::{AuthGlobalContext, RootAuthorization};
+    use crate::services::auth::basic::{BasicAuthorization, BasicRbacPolicy};
+
+    pub fn start(ctx: SharedContext, auth_policy_option: Option<BasicRbacPolicy>) {
+        if let Some(policy) = auth_policy_option {
+            info!("using basic authorization");
+            start_public_server(AuthGlobalContext::new(
+                ctx,
+                Arc::new(BasicAuthorization::new(policy)),
+            ));
+        } else {
+            info!("using root authorization");
+            start_public_server(AuthGlobalContext::new(
+                ctx,
+                Arc::new(RootAuthorization::new()),
+            ));
         }
     }
-
-    ctx
 }
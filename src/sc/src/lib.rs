@@ -1,5 +1,6 @@
 #[cfg(feature = "k8")]
 pub mod k8;
+pub mod local;
 pub mod cli;
 pub mod core;
 pub mod config;
@@ -8,8 +9,11 @@ mod init;
 mod error;
 mod services;
 mod controllers;
+mod proxy;
+mod election;
 
 pub use init::start_main_loop;
+pub use init::start_main_loop_with_file_store;
 use once_cell::sync::Lazy;
 
 static VERSION: Lazy<String> = Lazy::new(|| {
@@ -0,0 +1,46 @@
+//!
+//! # Streaming Coordinator (SC) without Kubernetes
+//!
+//! Metadata is persisted in local directory, so SC can run on bare metal.
+//! SPUs are not managed, they must be registered as custom SPUs.
+//!
+
+use crate::cli::ScOpt;
+use crate::dispatcher::dispatcher::FileMetadataStore;
+
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+pub fn main_local_loop(opt: ScOpt) {
+    use std::time::Duration;
+
+    use fluvio_future::task::run_block_on;
+    use fluvio_future::timer::sleep;
+
+    use crate::init::start_main_loop_with_file_store;
+
+    // parse configuration (program exits on error)
+    let ((sc_config, auth_policy), metadata_dir, tls_option) = opt.parse_local_or_exit();
+
+    println!("starting sc server with local metadata: {}", VERSION);
+
+    run_block_on(async move {
+        let metadata_store =
+            FileMetadataStore::open_shared(&metadata_dir, sc_config.namespace.clone())
+                .expect("problem opening metadata store");
+        start_main_loop_with_file_store((sc_config.clone(), auth_policy), metadata_store).await;
+
+        if let Some((proxy_port, tls_config)) = tls_option {
+            let tls_acceptor = tls_config
+                .try_build_tls_acceptor()
+                .expect("can't build tls acceptor");
+            crate::proxy::start_proxy(sc_config, (tls_acceptor, proxy_port)).await;
+        }
+
+        println!("Streaming Controller started successfully");
+
+        // do inifinite loop
+        loop {
+            sleep(Duration::from_secs(60)).await;
+        }
+    });
+}
@@ -107,6 +107,7 @@ mod policy {
     impl From<InstanceAction> for Action {
         fn from(action: InstanceAction) -> Self {
             match action {
+                InstanceAction::Update => Action::Update,
                 InstanceAction::Delete => Action::Delete,
             }
         }
@@ -172,6 +173,8 @@ mod policy {
             root_policy.insert(ObjectType::SpuGroup, vec![Action::All]);
             root_policy.insert(ObjectType::Topic, vec![Action::All]);
             root_policy.insert(ObjectType::Partition, vec![Action::All]);
+            root_policy.insert(ObjectType::ConsumerGroup, vec![Action::All]);
+            root_policy.insert(ObjectType::SmartStream, vec![Action::All]);
 
             let mut policy = HashMap::new();
 
@@ -20,14 +20,16 @@ use fluvio_service::{FlvService, wait_for_request};
 use fluvio_socket::{FlvSocket, FlvSocketError, FlvSink};
 use fluvio_controlplane::{
     InternalScRequest, InternalScKey, RegisterSpuResponse, UpdateLrsRequest, UpdateReplicaRequest,
-    UpdateSpuRequest, ReplicaRemovedRequest,
+    UpdateSpuRequest, ReplicaRemovedRequest, UpdateTxnMarkersRequest, UpdateSmartStreamRequest,
+    TxnMarker,
 };
-use fluvio_controlplane_metadata::message::{ReplicaMsg, Message, SpuMsg};
+use fluvio_controlplane_metadata::message::{ReplicaMsg, Message, SpuMsg, SmartStreamMsg};
 
 use crate::core::SharedContext;
 use crate::stores::{K8ChangeListener};
 use crate::stores::partition::{PartitionSpec, PartitionStatus, PartitionResolution};
 use crate::stores::spu::SpuSpec;
+use crate::stores::smartstream::SmartStreamSpec;
 use crate::controllers::spus::SpuAction;
 use crate::stores::actions::WSAction;
 
@@ -129,6 +131,10 @@ async fn dispatch_loop(
 
     let mut spu_spec_listener = context.spus().change_listener();
     let mut partition_spec_listener = context.partitions().change_listener();
+    let mut smart_stream_listener = context.smartstreams().change_listener();
+    let mut partition_status_listener = context.partitions().change_listener();
+    // markers sent on this connection, unconfirmed markers are sent again on health check
+    let mut sent_txn_markers: Vec<TxnMarker> = vec![];
 
     loop {
         use tokio::select;
@@ -144,6 +150,16 @@ async fn dispatch_loop(
 
         send_spu_spec_changes(&mut spu_spec_listener, &mut sink, spu_id).await?;
         send_replica_spec_changes(&mut partition_spec_listener, &mut sink, spu_id).await?;
+        send_smart_stream_changes(&mut smart_stream_listener, &mut sink, spu_id).await?;
+
+        send_txn_markers(
+            &context,
+            &mut partition_status_listener,
+            &mut sink,
+            spu_id,
+            &mut sent_txn_markers,
+        )
+        .await?;
 
         trace!("waiting for events");
 
@@ -161,6 +177,8 @@ async fn dispatch_loop(
                         )
                     })?;
                 time_left = Duration::from_secs(HEALTH_DURATION);
+                sent_txn_markers.clear();
+                partition_status_listener.set_last_change(0);
             },
 
             spu_request_msg = api_stream.next() =>  {
@@ -181,6 +199,10 @@ async fn dispatch_loop(
                             },
                             InternalScRequest::ReplicaRemovedRequest(msg) => {
                                 receive_replica_remove(&context,msg.request).await;
+                            },
+                            InternalScRequest::TxnMarkersWrittenRequest(msg) => {
+                                debug!(markers = msg.request.markers.len(), "txn markers written");
+                                context.transactions().remove_markers(context.partitions(), msg.request.markers).await;
                             }
                         }
                     } else {
@@ -201,6 +223,14 @@ async fn dispatch_loop(
 
             _ = partition_spec_listener.listen() => {
                 debug!("partition spec changed");
+            },
+
+            _ = smart_stream_listener.listen() => {
+                debug!("smartstream spec changed");
+            },
+
+            _ = partition_status_listener.listen() => {
+                debug!("partition status changed");
             }
 
         }
@@ -223,6 +253,8 @@ async fn receive_lrs_update(ctx: &SharedContext, requests: UpdateLrsRequest) {
                 PartitionResolution::Online,
             );
             current_status.merge(new_status);
+            // status of replicas moved out by reassignment is no longer relevant
+            current_status.retain_replicas(&partition.spec.replicas);
 
             actions.push(WSAction::UpdateStatus::<PartitionSpec>((
                 key,
@@ -322,6 +354,96 @@ async fn send_spu_spec_changes(
     Ok(())
 }
 
+/// send registered smartstream modules, every spu keeps a copy
+async fn send_smart_stream_changes(
+    listener: &mut K8ChangeListener<SmartStreamSpec>,
+    sink: &mut FlvSink,
+    spu_id: SpuId,
+) -> Result<(), FlvSocketError> {
+    if !listener.has_change() {
+        debug!("changes is empty, skipping");
+        return Ok(());
+    }
+
+    let changes = listener.sync_spec_changes().await;
+    if changes.is_empty() {
+        debug!("spec changes is empty, skipping");
+        return Ok(());
+    }
+
+    let epoch = changes.epoch;
+    let is_sync_all = changes.is_sync_all();
+    let (updates, deletes) = changes.parts();
+    let request = if is_sync_all {
+        UpdateSmartStreamRequest::with_all(epoch, updates.into_iter().map(|u| u.into()).collect())
+    } else {
+        let mut changes: Vec<SmartStreamMsg> = updates
+            .into_iter()
+            .map(|v| Message::update(v.into()))
+            .collect();
+        let mut deletes = deletes
+            .into_iter()
+            .map(|d| Message::delete(d.into()))
+            .collect();
+        changes.append(&mut deletes);
+        UpdateSmartStreamRequest::with_changes(epoch, changes)
+    };
+
+    let mut message = RequestMessage::new_request(request);
+    message.get_mut_header().set_client_id("sc");
+
+    debug!(
+        "sending smartstreams to spu: {}, all: {}, changes: {}",
+        spu_id,
+        message.request.all.len(),
+        message.request.changes.len()
+    );
+    sink.send_request(&message).await?;
+    Ok(())
+}
+
+/// send pending transaction markers for replicas led by spu.
+/// markers are checked whenever partition changes, since leader may have moved to this spu
+#[instrument(skip(ctx, listener, sink, sent))]
+async fn send_txn_markers(
+    ctx: &SharedContext,
+    listener: &mut K8ChangeListener<PartitionSpec>,
+    sink: &mut FlvSink,
+    spu_id: SpuId,
+    sent: &mut Vec<TxnMarker>,
+) -> Result<(), FlvSocketError> {
+    if !listener.has_change() {
+        return Ok(());
+    }
+    listener.load_last();
+
+    let pending = ctx
+        .transactions()
+        .pending_markers(ctx.partitions(), spu_id)
+        .await;
+    let markers: Vec<TxnMarker> = pending
+        .iter()
+        .filter(|marker| !sent.contains(marker))
+        .cloned()
+        .collect();
+    // confirmed markers are no longer pending
+    *sent = pending;
+    if markers.is_empty() {
+        return Ok(());
+    }
+
+    let mut message = RequestMessage::new_request(UpdateTxnMarkersRequest::new(markers));
+    message.get_mut_header().set_client_id("sc");
+
+    debug!(
+        "sending to spu: {}, txn markers: {}",
+        spu_id,
+        message.request.markers.len()
+    );
+    sink.send_request(&message).await?;
+    Ok(())
+}
+
 #[instrument(skip(sink))]
 async fn send_replica_spec_changes(
     listener: &mut K8ChangeListener<PartitionSpec>,
@@ -4,6 +4,13 @@ use tracing::trace;
 use dataplane::api::{RequestMessage, ResponseMessage, Request};
 use dataplane::versions::{ApiVersionKey, ApiVersionsRequest, ApiVersionsResponse, PlatformVersion};
 use fluvio_sc_schema::objects::*;
+use fluvio_sc_schema::producer::InitProducerIdRequest;
+use fluvio_sc_schema::transaction::{AddPartitionsToTxnRequest, EndTxnRequest};
+use fluvio_sc_schema::group::{
+    JoinGroupRequest, GroupHeartbeatRequest, LeaveGroupRequest, OffsetCommitRequest,
+    OffsetFetchRequest,
+};
+use fluvio_sc_schema::election::ElectLeadersRequest;
 use fluvio_sc_schema::AdminPublicApiKey;
 
 pub async fn handle_api_versions_request(
@@ -26,6 +33,11 @@ pub async fn handle_api_versions_request(
         DeleteRequest::DEFAULT_API_VERSION,
         DeleteRequest::DEFAULT_API_VERSION,
     ));
+    response.api_keys.push(make_version_key(
+        AdminPublicApiKey::Update,
+        UpdateRequest::DEFAULT_API_VERSION,
+        UpdateRequest::DEFAULT_API_VERSION,
+    ));
     response.api_keys.push(make_version_key(
         AdminPublicApiKey::List,
         ListRequest::DEFAULT_API_VERSION,
@@ -36,6 +48,53 @@ pub async fn handle_api_versions_request(
         WatchRequest::DEFAULT_API_VERSION,
         WatchRequest::DEFAULT_API_VERSION,
     ));
+    response.api_keys.push(make_version_key(
+        AdminPublicApiKey::InitProducerId,
+        0,
+        InitProducerIdRequest::DEFAULT_API_VERSION,
+    ));
+    response.api_keys.push(make_version_key(
+        AdminPublicApiKey::AddPartitionsToTxn,
+        AddPartitionsToTxnRequest::DEFAULT_API_VERSION,
+        AddPartitionsToTxnRequest::DEFAULT_API_VERSION,
+    ));
+    response.api_keys.push(make_version_key(
+        AdminPublicApiKey::EndTxn,
+        EndTxnRequest::DEFAULT_API_VERSION,
+        EndTxnRequest::DEFAULT_API_VERSION,
+    ));
+
+    // consumer group versions
+    response.api_keys.push(make_version_key(
+        AdminPublicApiKey::JoinGroup,
+        JoinGroupRequest::DEFAULT_API_VERSION,
+        JoinGroupRequest::DEFAULT_API_VERSION,
+    ));
+    response.api_keys.push(make_version_key(
+        AdminPublicApiKey::GroupHeartbeat,
+        GroupHeartbeatRequest::DEFAULT_API_VERSION,
+        GroupHeartbeatRequest::DEFAULT_API_VERSION,
+    ));
+    response.api_keys.push(make_version_key(
+        AdminPublicApiKey::LeaveGroup,
+        LeaveGroupRequest::DEFAULT_API_VERSION,
+        LeaveGroupRequest::DEFAULT_API_VERSION,
+    ));
+    response.api_keys.push(make_version_key(
+        AdminPublicApiKey::OffsetCommit,
+        OffsetCommitRequest::DEFAULT_API_VERSION,
+        OffsetCommitRequest::DEFAULT_API_VERSION,
+    ));
+    response.api_keys.push(make_version_key(
+        AdminPublicApiKey::OffsetFetch,
+        OffsetFetchRequest::DEFAULT_API_VERSION,
+        OffsetFetchRequest::DEFAULT_API_VERSION,
+    ));
+    response.api_keys.push(make_version_key(
+        AdminPublicApiKey::ElectLeaders,
+        ElectLeadersRequest::DEFAULT_API_VERSION,
+        ElectLeadersRequest::DEFAULT_API_VERSION,
+    ));
 
     trace!("flv api versions response: {:#?}", response);
 
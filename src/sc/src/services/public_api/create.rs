@@ -1,6 +1,7 @@
 use std::io::Error as IoError;
 
 use dataplane::api::{RequestMessage, ResponseMessage};
+use dataplane::ErrorCode;
 use fluvio_sc_schema::Status;
 use fluvio_sc_schema::objects::{CreateRequest, AllCreatableSpec};
 use fluvio_auth::AuthContext;
@@ -17,6 +18,13 @@ pub async fn handle_create_request<AC: AuthContext>(
     let dry_run = req.dry_run;
     let name = req.name;
 
+    if !auth_context.global_ctx.is_leader() {
+        return Ok(ResponseMessage::from_header(
+            &header,
+            not_leader_status(name),
+        ));
+    }
+
     let status = match req.spec {
         AllCreatableSpec::Topic(topic) => {
             super::topic::handle_create_topics_request(name, dry_run, topic, auth_context).await?
@@ -33,7 +41,25 @@ pub async fn handle_create_request<AC: AuthContext>(
             )
             .await
         }
+        AllCreatableSpec::SmartStream(smart_stream) => {
+            super::smartstream::handle_create_smart_stream_request(
+                name,
+                smart_stream,
+                dry_run,
+                auth_context,
+            )
+            .await?
+        }
     };
 
     Ok(ResponseMessage::from_header(&header, status))
 }
+
+/// metadata can only be changed by leader SC
+pub(crate) fn not_leader_status(name: String) -> Status {
+    Status::new(
+        name,
+        ErrorCode::ScNotLeader,
+        Some("SC is not leader, retry with other SC".to_owned()),
+    )
+}
@@ -21,6 +21,19 @@ pub async fn handle_delete_request<AC: AuthContext>(
 ) -> Result<ResponseMessage<Status>, Error> {
     let (header, req) = request.get_header_request();
 
+    if !auth_ctx.global_ctx.is_leader() {
+        let name = match &req {
+            DeleteRequest::Topic(name)
+            | DeleteRequest::SpuGroup(name)
+            | DeleteRequest::SmartStream(name) => name.clone(),
+            DeleteRequest::CustomSpu(key) => key.to_string(),
+        };
+        return Ok(ResponseMessage::from_header(
+            &header,
+            super::create::not_leader_status(name),
+        ));
+    }
+
     let status = match req {
         DeleteRequest::Topic(name) => super::topic::handle_delete_topic(name, auth_ctx).await?,
         DeleteRequest::CustomSpu(key) => {
@@ -29,6 +42,9 @@ pub async fn handle_delete_request<AC: AuthContext>(
         DeleteRequest::SpuGroup(name) => {
             super::spg::handle_delete_spu_group(name, auth_ctx).await?
         }
+        DeleteRequest::SmartStream(name) => {
+            super::smartstream::handle_delete_smart_stream(name, auth_ctx).await?
+        }
     };
 
     trace!("flv delete topics resp {:#?}", status);
@@ -0,0 +1,85 @@
+//!
+//! # Elect Leaders Request
+//!
+//! Moves leadership of partitions back to preferred replicas on demand,
+//! same as periodic election of partition controller.
+//!
+
+use std::io::{Error as IoError, ErrorKind};
+
+use tracing::{debug, trace};
+
+use dataplane::ErrorCode;
+use dataplane::api::{RequestMessage, ResponseMessage};
+use fluvio_sc_schema::Status;
+use fluvio_sc_schema::election::ElectLeadersRequest;
+use fluvio_controlplane_metadata::partition::PartitionSpec;
+use fluvio_controlplane_metadata::extended::SpecExt;
+use fluvio_auth::{AuthContext, InstanceAction};
+
+use crate::controllers::partitions::{PartitionReducer, PartitionWSAction};
+use crate::services::auth::AuthServiceContext;
+
+/// Handler for elect leaders request
+pub async fn handle_elect_leaders_request<AC: AuthContext>(
+    request: RequestMessage<ElectLeadersRequest>,
+    auth_ctx: &AuthServiceContext<AC>,
+) -> Result<ResponseMessage<Status>, IoError> {
+    let (header, req) = request.get_header_request();
+    let name = req.topics.join(",");
+    debug!("api request: elect leaders of topics '{}'", name);
+
+    let ctx = &auth_ctx.global_ctx;
+    if !ctx.is_leader() {
+        return Ok(ResponseMessage::from_header(
+            &header,
+            super::create::not_leader_status(name),
+        ));
+    }
+
+    let reducer =
+        PartitionReducer::new(ctx.partitions().store().clone(), ctx.spus().store().clone());
+    let actions: Vec<PartitionWSAction> = reducer
+        .elect_preferred_leaders()
+        .await
+        .into_iter()
+        .filter(|action| match action {
+            PartitionWSAction::UpdateSpec((key, _)) => {
+                req.topics.is_empty() || req.topics.contains(&key.topic)
+            }
+            _ => true,
+        })
+        .collect();
+
+    for action in &actions {
+        if let PartitionWSAction::UpdateSpec((key, _)) = action {
+            let authorized = auth_ctx
+                .auth
+                .allow_instance_action(
+                    PartitionSpec::OBJECT_TYPE,
+                    InstanceAction::Update,
+                    &key.to_string(),
+                )
+                .await
+                .map_err(|_| IoError::new(ErrorKind::Interrupted, "authorization io error"))?;
+            if !authorized {
+                trace!("authorization failed");
+                return Ok(ResponseMessage::from_header(
+                    &header,
+                    Status::new(
+                        name,
+                        ErrorCode::PermissionDenied,
+                        Some(String::from("permission denied")),
+                    ),
+                ));
+            }
+        }
+    }
+
+    debug!("moving leaders of partitions: {}", actions.len());
+    for action in actions.into_iter() {
+        ctx.partitions().send_action(action).await;
+    }
+
+    Ok(ResponseMessage::from_header(&header, Status::new_ok(name)))
+}
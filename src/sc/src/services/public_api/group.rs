@@ -0,0 +1,191 @@
+use std::io::Error;
+use std::time::Duration;
+
+use tracing::debug;
+
+use dataplane::api::{RequestMessage, ResponseMessage};
+use dataplane::ErrorCode;
+use fluvio_sc_schema::group::*;
+use fluvio_auth::{AuthContext};
+
+use crate::services::auth::AuthServiceContext;
+
+/// add member to group and return partitions assigned to it
+pub async fn handle_join_group_request<AC: AuthContext>(
+    request: RequestMessage<JoinGroupRequest>,
+    auth_ctx: &AuthServiceContext<AC>,
+) -> Result<ResponseMessage<JoinGroupResponse>, Error> {
+    let (header, req) = request.get_header_request();
+    if !auth_ctx.global_ctx.is_leader() {
+        return Ok(ResponseMessage::from_header(
+            &header,
+            JoinGroupResponse {
+                error_code: ErrorCode::ScNotLeader,
+                ..Default::default()
+            },
+        ));
+    }
+    let ctx = &auth_ctx.global_ctx;
+
+    debug!(group = %req.group, member_id = %req.member_id, topic = %req.topic, "join group");
+    let response = match ctx.topics().store().value(&req.topic).await {
+        Some(topic) => {
+            let session_timeout = Duration::from_millis(req.session_timeout_ms.max(0) as u64);
+            let assignment = ctx
+                .group_coordinator()
+                .join(
+                    &req.group,
+                    &req.member_id,
+                    &req.topic,
+                    topic.spec.partitions(),
+                    session_timeout,
+                )
+                .await;
+            // member retries join until other members has rejoined
+            let (error_code, partitions) = match assignment.partitions {
+                Some(partitions) => (ErrorCode::None, partitions),
+                None => (ErrorCode::RebalanceInProgress, vec![]),
+            };
+            JoinGroupResponse {
+                error_code,
+                member_id: assignment.member_id,
+                generation: assignment.generation,
+                partitions,
+            }
+        }
+        None => JoinGroupResponse {
+            error_code: ErrorCode::TopicNotFound,
+            ..Default::default()
+        },
+    };
+
+    Ok(ResponseMessage::from_header(&header, response))
+}
+
+pub async fn handle_group_heartbeat_request<AC: AuthContext>(
+    request: RequestMessage<GroupHeartbeatRequest>,
+    auth_ctx: &AuthServiceContext<AC>,
+) -> Result<ResponseMessage<GroupHeartbeatResponse>, Error> {
+    let (header, req) = request.get_header_request();
+    if !auth_ctx.global_ctx.is_leader() {
+        return Ok(ResponseMessage::from_header(
+            &header,
+            GroupHeartbeatResponse {
+                error_code: ErrorCode::ScNotLeader,
+            },
+        ));
+    }
+
+    let error_code = match auth_ctx
+        .global_ctx
+        .group_coordinator()
+        .heartbeat(&req.group, &req.member_id, req.generation)
+        .await
+    {
+        Ok(()) => ErrorCode::None,
+        Err(error_code) => error_code,
+    };
+
+    Ok(ResponseMessage::from_header(
+        &header,
+        GroupHeartbeatResponse { error_code },
+    ))
+}
+
+pub async fn handle_leave_group_request<AC: AuthContext>(
+    request: RequestMessage<LeaveGroupRequest>,
+    auth_ctx: &AuthServiceContext<AC>,
+) -> Result<ResponseMessage<LeaveGroupResponse>, Error> {
+    let (header, req) = request.get_header_request();
+    if !auth_ctx.global_ctx.is_leader() {
+        return Ok(ResponseMessage::from_header(
+            &header,
+            LeaveGroupResponse {
+                error_code: ErrorCode::ScNotLeader,
+            },
+        ));
+    }
+
+    debug!(group = %req.group, member_id = %req.member_id, "leave group");
+    let error_code = match auth_ctx
+        .global_ctx
+        .group_coordinator()
+        .leave(&req.group, &req.member_id)
+        .await
+    {
+        Ok(()) => ErrorCode::None,
+        Err(error_code) => error_code,
+    };
+
+    Ok(ResponseMessage::from_header(
+        &header,
+        LeaveGroupResponse { error_code },
+    ))
+}
+
+pub async fn handle_offset_commit_request<AC: AuthContext>(
+    request: RequestMessage<OffsetCommitRequest>,
+    auth_ctx: &AuthServiceContext<AC>,
+) -> Result<ResponseMessage<OffsetCommitResponse>, Error> {
+    let (header, req) = request.get_header_request();
+    if !auth_ctx.global_ctx.is_leader() {
+        return Ok(ResponseMessage::from_header(
+            &header,
+            OffsetCommitResponse {
+                error_code: ErrorCode::ScNotLeader,
+            },
+        ));
+    }
+    let ctx = &auth_ctx.global_ctx;
+
+    debug!(group = %req.group, topic = %req.topic, offsets = ?req.offsets, "commit offsets");
+    let offsets = req
+        .offsets
+        .into_iter()
+        .map(|partition_offset| (partition_offset.partition, partition_offset.offset))
+        .collect();
+    let error_code = match ctx
+        .group_coordinator()
+        .commit_offsets(
+            ctx.groups(),
+            &req.group,
+            &req.member_id,
+            req.generation,
+            &req.topic,
+            offsets,
+        )
+        .await
+    {
+        Ok(()) => ErrorCode::None,
+        Err(error_code) => error_code,
+    };
+
+    Ok(ResponseMessage::from_header(
+        &header,
+        OffsetCommitResponse { error_code },
+    ))
+}
+
+pub async fn handle_offset_fetch_request<AC: AuthContext>(
+    request: RequestMessage<OffsetFetchRequest>,
+    auth_ctx: &AuthServiceContext<AC>,
+) -> Result<ResponseMessage<OffsetFetchResponse>, Error> {
+    let (header, req) = request.get_header_request();
+    let ctx = &auth_ctx.global_ctx;
+
+    let offsets = ctx
+        .group_coordinator()
+        .fetch_offsets(ctx.groups(), &req.group, &req.topic, req.partitions)
+        .await
+        .into_iter()
+        .map(|(partition, offset)| PartitionOffset { partition, offset })
+        .collect();
+
+    Ok(ResponseMessage::from_header(
+        &header,
+        OffsetFetchResponse {
+            error_code: ErrorCode::None,
+            offsets,
+        },
+    ))
+}
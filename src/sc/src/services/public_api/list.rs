@@ -30,6 +30,9 @@ pub async fn handle_list_request<AC: AuthContext>(
         ListRequest::Partition(filter) => {
             super::partition::handle_fetch_request(filter, &auth_ctx).await?
         }
+        ListRequest::SmartStream(filter) => {
+            super::smartstream::handle_fetch_smart_streams_request(filter, &auth_ctx).await?
+        }
     };
 
     Ok(ResponseMessage::from_header(&header, response))
@@ -6,8 +6,14 @@ mod partition;
 mod api_version;
 mod create;
 mod delete;
+mod update;
 mod list;
 mod watch;
+mod producer;
+mod transaction;
+mod group;
+mod election;
+mod smartstream;
 
 pub use server::start_public_server;
 
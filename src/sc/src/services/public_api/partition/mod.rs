@@ -1,3 +1,7 @@
+mod update;
+
+pub use update::*;
+
 use std::io::{Error, ErrorKind};
 
 use tracing::{trace, debug};
@@ -0,0 +1,149 @@
+//!
+//! # Update Partition Request
+//!
+//! Moves replicas of partition to other SPUs. Target replicas are added to partition spec
+//! while replicas moved out are kept until target replicas are in sync with leader.
+//! Partition controller then drops moved out replicas.
+//!
+
+use std::collections::HashSet;
+use std::convert::TryFrom;
+use std::io::{Error as IoError, ErrorKind};
+
+use tracing::{debug, trace};
+
+use dataplane::ErrorCode;
+
+use fluvio_sc_schema::Status;
+use fluvio_sc_schema::partition::{PartitionSpec, PartitionUpdate, ReplicaKey};
+use fluvio_controlplane_metadata::spu::store::SpuLocalStorePolicy;
+use fluvio_auth::{AuthContext, InstanceAction};
+use fluvio_controlplane_metadata::extended::SpecExt;
+
+use crate::services::auth::AuthServiceContext;
+
+/// Handler for update partition request
+pub async fn handle_update_partition_request<AC: AuthContext>(
+    name: String,
+    dry_run: bool,
+    update: PartitionUpdate,
+    auth_ctx: &AuthServiceContext<AC>,
+) -> Result<Status, IoError> {
+    debug!("api request: update partition '{}'", name);
+
+    if let Ok(authorized) = auth_ctx
+        .auth
+        .allow_instance_action(PartitionSpec::OBJECT_TYPE, InstanceAction::Update, &name)
+        .await
+    {
+        if !authorized {
+            trace!("authorization failed");
+            return Ok(Status::new(
+                name.clone(),
+                ErrorCode::PermissionDenied,
+                Some(String::from("permission denied")),
+            ));
+        }
+    } else {
+        return Err(IoError::new(
+            ErrorKind::Interrupted,
+            "authorization io error",
+        ));
+    }
+
+    let key = match ReplicaKey::try_from(name.clone()) {
+        Ok(key) => key,
+        Err(err) => {
+            return Ok(Status::new(
+                name,
+                ErrorCode::PartitionNotFound,
+                Some(err.to_string()),
+            ))
+        }
+    };
+
+    let mut spec = match auth_ctx.global_ctx.partitions().store().spec(&key).await {
+        Some(spec) => spec,
+        None => {
+            return Ok(Status::new(
+                name,
+                ErrorCode::PartitionNotFound,
+                Some("not found".to_owned()),
+            ))
+        }
+    };
+
+    if spec.is_reassigning() {
+        return Ok(Status::new(
+            name,
+            ErrorCode::PartitionReassignmentInProgress,
+            Some(format!(
+                "replicas {:?} are still being moved out",
+                spec.removing_replicas
+            )),
+        ));
+    }
+
+    let spu_ids: HashSet<i32> = auth_ctx
+        .global_ctx
+        .spus()
+        .store()
+        .spu_ids()
+        .await
+        .into_iter()
+        .collect();
+    if let Err(reason) = validate_replicas(&update.replicas, &spu_ids) {
+        debug!("invalid replicas: {}", reason);
+        return Ok(Status::new(
+            name,
+            ErrorCode::PartitionInvalidReplicas,
+            Some(reason),
+        ));
+    }
+
+    if update.replicas == spec.replicas {
+        debug!("replicas are not changed");
+        return Ok(Status::new_ok(name));
+    }
+
+    spec.reassign(update.replicas);
+
+    let status = if dry_run {
+        Status::new_ok(name)
+    } else if let Err(err) = auth_ctx
+        .global_ctx
+        .partitions()
+        .create_spec(key, spec)
+        .await
+    {
+        Status::new(
+            name,
+            ErrorCode::PartitionInvalidReplicas,
+            Some(err.to_string()),
+        )
+    } else {
+        Status::new_ok(name)
+    };
+
+    trace!("update partition response {:#?}", status);
+
+    Ok(status)
+}
+
+/// replicas must be unique and assigned to existing spus
+fn validate_replicas(replicas: &[i32], spu_ids: &HashSet<i32>) -> Result<(), String> {
+    if replicas.is_empty() {
+        return Err("at least one replica is required".to_owned());
+    }
+
+    let mut seen = HashSet::new();
+    for replica in replicas {
+        if !seen.insert(replica) {
+            return Err(format!("duplicate replica: {}", replica));
+        }
+        if !spu_ids.contains(replica) {
+            return Err(format!("invalid spu id: {}", replica));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use std::io::Error;
+use std::time::Duration;
+
+use tracing::debug;
+
+use dataplane::api::{RequestMessage, ResponseMessage};
+use dataplane::ErrorCode;
+use fluvio_sc_schema::producer::{InitProducerIdRequest, InitProducerIdResponse};
+use fluvio_auth::{AuthContext};
+
+use crate::services::auth::AuthServiceContext;
+
+/// allocate new producer id for idempotent producer
+/// transactional producer gets same producer id with bumped epoch
+pub async fn handle_init_producer_id_request<AC: AuthContext>(
+    request: RequestMessage<InitProducerIdRequest>,
+    auth_ctx: &AuthServiceContext<AC>,
+) -> Result<ResponseMessage<InitProducerIdResponse>, Error> {
+    let ctx = &auth_ctx.global_ctx;
+    if !ctx.is_leader() {
+        return Ok(request.new_response(InitProducerIdResponse {
+            error_code: ErrorCode::ScNotLeader,
+            ..Default::default()
+        }));
+    }
+
+    let response = if let Some(transactional_id) = request.request.transactional_id.clone() {
+        let timeout = Duration::from_millis(request.request.transaction_timeout_ms.max(0) as u64);
+        let (producer_id, producer_epoch, markers) = ctx
+            .transactions()
+            .init_producer(transactional_id, timeout, || ctx.next_producer_id())
+            .await;
+        debug!(
+            producer_id,
+            producer_epoch, "initialized transactional producer"
+        );
+        ctx.transactions()
+            .queue_markers(ctx.partitions(), markers)
+            .await;
+
+        InitProducerIdResponse {
+            error_code: ErrorCode::None,
+            producer_id,
+            producer_epoch,
+        }
+    } else {
+        let producer_id = ctx.next_producer_id();
+        debug!(producer_id, "allocated producer id");
+
+        InitProducerIdResponse {
+            error_code: ErrorCode::None,
+            producer_id,
+            producer_epoch: 0,
+        }
+    };
+
+    Ok(request.new_response(response))
+}
@@ -97,6 +97,12 @@ where
                 shared_sink,
                 "delete  handler"
             ),
+            AdminPublicRequest::UpdateRequest(request) => call_service!(
+                request,
+                super::update::handle_update_request(request, &service_context),
+                shared_sink,
+                "update handler"
+            ),
 
             AdminPublicRequest::ListRequest(request) => call_service!(
                 request,
@@ -104,6 +110,61 @@ where
                 shared_sink,
                 "list handler"
             ),
+            AdminPublicRequest::InitProducerIdRequest(request) => call_service!(
+                request,
+                super::producer::handle_init_producer_id_request(request, &service_context),
+                shared_sink,
+                "init producer id handler"
+            ),
+            AdminPublicRequest::AddPartitionsToTxnRequest(request) => call_service!(
+                request,
+                super::transaction::handle_add_partitions_to_txn_request(request, &service_context),
+                shared_sink,
+                "add partitions to txn handler"
+            ),
+            AdminPublicRequest::EndTxnRequest(request) => call_service!(
+                request,
+                super::transaction::handle_end_txn_request(request, &service_context),
+                shared_sink,
+                "end txn handler"
+            ),
+            AdminPublicRequest::JoinGroupRequest(request) => call_service!(
+                request,
+                super::group::handle_join_group_request(request, &service_context),
+                shared_sink,
+                "join group handler"
+            ),
+            AdminPublicRequest::GroupHeartbeatRequest(request) => call_service!(
+                request,
+                super::group::handle_group_heartbeat_request(request, &service_context),
+                shared_sink,
+                "group heartbeat handler"
+            ),
+            AdminPublicRequest::LeaveGroupRequest(request) => call_service!(
+                request,
+                super::group::handle_leave_group_request(request, &service_context),
+                shared_sink,
+                "leave group handler"
+            ),
+            AdminPublicRequest::OffsetCommitRequest(request) => call_service!(
+                request,
+                super::group::handle_offset_commit_request(request, &service_context),
+                shared_sink,
+                "offset commit handler"
+            ),
+            AdminPublicRequest::OffsetFetchRequest(request) => call_service!(
+                request,
+                super::group::handle_offset_fetch_request(request, &service_context),
+                shared_sink,
+                "offset fetch handler"
+            ),
+            AdminPublicRequest::ElectLeadersRequest(request) => call_service!(
+                request,
+                super::election::handle_elect_leaders_request(request, &service_context),
+                shared_sink,
+                "elect leaders handler"
+            ),
+
             AdminPublicRequest::WatchRequest(request) =>
 
                 super::watch::handle_watch_request(
@@ -0,0 +1,92 @@
+//!
+//! # Create SmartStream Request
+//!
+//! Registers named SmartStream module. Existing module is only replaced by newer version.
+//!
+
+use std::io::{Error, ErrorKind};
+
+use tracing::{debug, trace};
+
+use dataplane::ErrorCode;
+use fluvio_sc_schema::Status;
+use fluvio_controlplane_metadata::smartstream::SmartStreamSpec;
+use fluvio_controlplane_metadata::extended::SpecExt;
+use fluvio_auth::{AuthContext, TypeAction};
+
+use crate::core::Context;
+use crate::services::auth::AuthServiceContext;
+
+/// Handler for create smartstream request
+pub async fn handle_create_smart_stream_request<AC: AuthContext>(
+    name: String,
+    spec: SmartStreamSpec,
+    dry_run: bool,
+    auth_ctx: &AuthServiceContext<AC>,
+) -> Result<Status, Error> {
+    debug!(
+        "creating smartstream: {}, version: {}, size: {}",
+        name,
+        spec.version,
+        spec.wasm.len()
+    );
+
+    if let Ok(authorized) = auth_ctx
+        .auth
+        .allow_type_action(SmartStreamSpec::OBJECT_TYPE, TypeAction::Create)
+        .await
+    {
+        if !authorized {
+            trace!("authorization failed");
+            return Ok(Status::new(
+                name.clone(),
+                ErrorCode::PermissionDenied,
+                Some(String::from("permission denied")),
+            ));
+        }
+    } else {
+        return Err(Error::new(ErrorKind::Interrupted, "authorization io error"));
+    }
+
+    let status = process_smart_stream_request(&auth_ctx.global_ctx, name, spec, dry_run).await;
+    trace!("create smartstream response {:#?}", status);
+
+    Ok(status)
+}
+
+/// Validate version against registered module and send spec to KV store
+async fn process_smart_stream_request(
+    ctx: &Context,
+    name: String,
+    spec: SmartStreamSpec,
+    dry_run: bool,
+) -> Status {
+    if spec.wasm.is_empty() {
+        return Status::new(
+            name,
+            ErrorCode::SmartStreamError,
+            Some("wasm module is empty".to_owned()),
+        );
+    }
+
+    if let Some(existing) = ctx.smartstreams().store().value(&name).await {
+        if existing.spec.version >= spec.version {
+            let error = format!(
+                "smartstream {} version {} already exists",
+                name, existing.spec.version
+            );
+            return Status::new(name, ErrorCode::SmartStreamAlreadyExists, Some(error));
+        }
+    }
+
+    if dry_run {
+        return Status::new_ok(name);
+    }
+
+    if let Err(err) = ctx.smartstreams().create_spec(name.clone(), spec).await {
+        let error = Some(err.to_string());
+        Status::new(name, ErrorCode::SmartStreamError, error)
+    } else {
+        Status::new_ok(name)
+    }
+}
@@ -0,0 +1,97 @@
+use std::io::{Error, ErrorKind};
+
+use tracing::debug;
+use tracing::trace;
+
+use fluvio_sc_schema::Status;
+use fluvio_auth::{AuthContext, InstanceAction};
+use fluvio_controlplane_metadata::smartstream::SmartStreamSpec;
+use fluvio_controlplane_metadata::extended::SpecExt;
+
+use crate::services::auth::AuthServiceContext;
+
+/// Handler for delete smartstream request
+pub async fn handle_delete_smart_stream<AC: AuthContext>(
+    name: String,
+    auth_ctx: &AuthServiceContext<AC>,
+) -> Result<Status, Error> {
+    use dataplane::ErrorCode;
+
+    debug!("delete smartstream: {}", name);
+
+    if let Ok(authorized) = auth_ctx
+        .auth
+        .allow_instance_action(SmartStreamSpec::OBJECT_TYPE, InstanceAction::Delete, &name)
+        .await
+    {
+        if !authorized {
+            trace!("authorization failed");
+            return Ok(Status::new(
+                name.clone(),
+                ErrorCode::PermissionDenied,
+                Some(String::from("permission denied")),
+            ));
+        }
+    } else {
+        return Err(Error::new(ErrorKind::Interrupted, "authorization io error"));
+    }
+
+    // topics which apply smartstream on produce must be deleted first
+    let used_by = auth_ctx
+        .global_ctx
+        .topics()
+        .store()
+        .read()
+        .await
+        .values()
+        .find(|topic| {
+            topic
+                .spec
+                .smart_stream()
+                .map(|smart_stream| smart_stream.name == name)
+                .unwrap_or(false)
+        })
+        .map(|topic| topic.key().to_owned());
+    if let Some(topic) = used_by {
+        debug!(%topic, "smartstream is used by topic");
+        return Ok(Status::new(
+            name,
+            ErrorCode::SmartStreamError,
+            Some(format!("smartstream is used by topic '{}'", topic)),
+        ));
+    }
+
+    let status = if auth_ctx
+        .global_ctx
+        .smartstreams()
+        .store()
+        .value(&name)
+        .await
+        .is_some()
+    {
+        if let Err(err) = auth_ctx
+            .global_ctx
+            .smartstreams()
+            .delete(name.clone())
+            .await
+        {
+            Status::new(
+                name.clone(),
+                ErrorCode::SmartStreamError,
+                Some(err.to_string()),
+            )
+        } else {
+            Status::new_ok(name)
+        }
+    } else {
+        Status::new(
+            name,
+            ErrorCode::SmartStreamNotFound,
+            Some("not found".to_owned()),
+        )
+    };
+
+    trace!("flv delete smartstream resp {:#?}", status);
+
+    Ok(status)
+}
@@ -0,0 +1,54 @@
+use std::io::{Error, ErrorKind};
+
+use tracing::debug;
+use tracing::trace;
+
+use fluvio_sc_schema::objects::{ListResponse, NameFilter, Metadata};
+use fluvio_sc_schema::smartstream::SmartStreamSpec;
+use fluvio_auth::{AuthContext, TypeAction};
+use fluvio_controlplane_metadata::store::KeyFilter;
+use fluvio_controlplane_metadata::extended::SpecExt;
+
+use crate::services::auth::AuthServiceContext;
+
+pub async fn handle_fetch_smart_streams_request<AC: AuthContext>(
+    filters: Vec<NameFilter>,
+    auth_ctx: &AuthServiceContext<AC>,
+) -> Result<ListResponse, Error> {
+    debug!("fetching smartstreams");
+
+    if let Ok(authorized) = auth_ctx
+        .auth
+        .allow_type_action(SmartStreamSpec::OBJECT_TYPE, TypeAction::Read)
+        .await
+    {
+        if !authorized {
+            trace!("authorization failed");
+            // If permission denied, return empty list;
+            return Ok(ListResponse::SmartStream(vec![]));
+        }
+    } else {
+        return Err(Error::new(ErrorKind::Interrupted, "authorization io error"));
+    }
+
+    let smart_streams: Vec<Metadata<SmartStreamSpec>> = auth_ctx
+        .global_ctx
+        .smartstreams()
+        .store()
+        .read()
+        .await
+        .values()
+        .filter_map(|value| {
+            if filters.filter(value.key()) {
+                Some(value.inner().clone().into())
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    debug!("flv fetch smartstreams resp: {} items", smart_streams.len());
+    trace!("flv fetch smartstreams resp {:#?}", smart_streams);
+
+    Ok(ListResponse::SmartStream(smart_streams))
+}
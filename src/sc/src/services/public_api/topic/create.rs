@@ -86,6 +86,33 @@ async fn validate_topic_request(name: &str, topic_spec: &TopicSpec, metadata: &C
         );
     }
 
+    if let Some(storage) = topic_spec.storage() {
+        if let Err(err) = storage.validate() {
+            debug!("invalid storage configuration: {}", err);
+            return Status::new(
+                name.to_string(),
+                ErrorCode::TopicInvalidConfiguration,
+                Some(err.to_string()),
+            );
+        }
+    }
+
+    if let Some(smart_stream) = topic_spec.smart_stream() {
+        if !metadata
+            .smartstreams()
+            .store()
+            .contains_key(&smart_stream.name)
+            .await
+        {
+            debug!(smart_stream = %smart_stream.name, "smartstream not found");
+            return Status::new(
+                name.to_string(),
+                ErrorCode::SmartStreamNotFound,
+                Some(format!("smartstream '{}' not found", smart_stream.name)),
+            );
+        }
+    }
+
     match topic_spec {
         TopicSpec::Computed(param) => {
             let next_state = validate_computed_topic_parameters(param);
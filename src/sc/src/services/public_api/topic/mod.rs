@@ -1,7 +1,9 @@
 mod create;
 mod delete;
 mod fetch;
+mod update;
 
 pub use create::*;
 pub use delete::*;
 pub use fetch::*;
+pub use update::*;
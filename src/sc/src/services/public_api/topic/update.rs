@@ -0,0 +1,107 @@
+//!
+//! # Update Topic Request
+//!
+//! Update topic request handler. Only partitions of computed topics can be changed.
+//! New partition count is written to topic spec, topic controller extends replica map
+//! and creates new partitions while existing partitions keep their replicas.
+//!
+
+use std::io::{Error as IoError, ErrorKind};
+
+use tracing::{debug, trace};
+
+use dataplane::ErrorCode;
+
+use fluvio_sc_schema::Status;
+use fluvio_sc_schema::topic::TopicUpdate;
+use fluvio_controlplane_metadata::topic::TopicSpec;
+use fluvio_auth::{AuthContext, InstanceAction};
+use fluvio_controlplane_metadata::extended::SpecExt;
+
+use crate::services::auth::AuthServiceContext;
+
+/// Handler for update topic request
+pub async fn handle_update_topic_request<AC: AuthContext>(
+    name: String,
+    dry_run: bool,
+    update: TopicUpdate,
+    auth_ctx: &AuthServiceContext<AC>,
+) -> Result<Status, IoError> {
+    debug!("api request: update topic '{}'", name);
+
+    if let Ok(authorized) = auth_ctx
+        .auth
+        .allow_instance_action(TopicSpec::OBJECT_TYPE, InstanceAction::Update, &name)
+        .await
+    {
+        if !authorized {
+            trace!("authorization failed");
+            return Ok(Status::new(
+                name.clone(),
+                ErrorCode::PermissionDenied,
+                Some(String::from("permission denied")),
+            ));
+        }
+    } else {
+        return Err(IoError::new(
+            ErrorKind::Interrupted,
+            "authorization io error",
+        ));
+    }
+
+    let mut spec = match auth_ctx.global_ctx.topics().store().spec(&name).await {
+        Some(spec) => spec,
+        None => {
+            return Ok(Status::new(
+                name,
+                ErrorCode::TopicNotFound,
+                Some("not found".to_owned()),
+            ))
+        }
+    };
+
+    if let Err(reason) = apply_update(&mut spec, &update) {
+        debug!("invalid topic update: {}", reason);
+        return Ok(Status::new(
+            name,
+            ErrorCode::TopicInvalidConfiguration,
+            Some(reason),
+        ));
+    }
+
+    let status = if dry_run {
+        Status::new_ok(name)
+    } else if let Err(err) = auth_ctx
+        .global_ctx
+        .topics()
+        .create_spec(name.clone(), spec)
+        .await
+    {
+        Status::new(name, ErrorCode::TopicError, Some(err.to_string()))
+    } else {
+        Status::new_ok(name)
+    };
+
+    trace!("update topic response {:#?}", status);
+
+    Ok(status)
+}
+
+/// apply update to topic spec, partitions can only be added to computed topic
+fn apply_update(spec: &mut TopicSpec, update: &TopicUpdate) -> Result<(), String> {
+    match spec {
+        TopicSpec::Computed(param) => {
+            if update.partitions <= param.partitions {
+                return Err(format!(
+                    "partitions can only be increased, current: {}",
+                    param.partitions
+                ));
+            }
+            param.partitions = update.partitions;
+            Ok(())
+        }
+        TopicSpec::Assigned(_) => {
+            Err("partitions of topic with assigned replicas can't be changed".to_owned())
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use std::io::Error;
+
+use tracing::debug;
+
+use dataplane::api::{RequestMessage, ResponseMessage};
+use dataplane::ErrorCode;
+use fluvio_sc_schema::transaction::{
+    AddPartitionsToTxnRequest, AddPartitionsToTxnResponse, EndTxnRequest, EndTxnResponse,
+};
+use fluvio_auth::{AuthContext};
+
+use crate::services::auth::AuthServiceContext;
+
+/// add partitions to ongoing transaction
+pub async fn handle_add_partitions_to_txn_request<AC: AuthContext>(
+    request: RequestMessage<AddPartitionsToTxnRequest>,
+    auth_ctx: &AuthServiceContext<AC>,
+) -> Result<ResponseMessage<AddPartitionsToTxnResponse>, Error> {
+    let (header, req) = request.get_header_request();
+    if !auth_ctx.global_ctx.is_leader() {
+        return Ok(ResponseMessage::from_header(
+            &header,
+            AddPartitionsToTxnResponse {
+                error_code: ErrorCode::ScNotLeader,
+            },
+        ));
+    }
+
+    debug!(transactional_id = %req.transactional_id, partitions = ?req.partitions, "add partitions to txn");
+    let ctx = &auth_ctx.global_ctx;
+    let error_code = if ctx
+        .transactions()
+        .has_pending_markers(ctx.partitions(), req.producer_id, &req.partitions)
+        .await
+    {
+        ErrorCode::ConcurrentTransactions
+    } else {
+        match ctx
+            .transactions()
+            .add_partitions(
+                &req.transactional_id,
+                req.producer_id,
+                req.producer_epoch,
+                req.partitions,
+            )
+            .await
+        {
+            Ok(()) => ErrorCode::None,
+            Err(error_code) => error_code,
+        }
+    };
+
+    Ok(ResponseMessage::from_header(
+        &header,
+        AddPartitionsToTxnResponse { error_code },
+    ))
+}
+
+/// commit or abort transaction by writing markers to partitions in transaction
+pub async fn handle_end_txn_request<AC: AuthContext>(
+    request: RequestMessage<EndTxnRequest>,
+    auth_ctx: &AuthServiceContext<AC>,
+) -> Result<ResponseMessage<EndTxnResponse>, Error> {
+    let (header, req) = request.get_header_request();
+    if !auth_ctx.global_ctx.is_leader() {
+        return Ok(ResponseMessage::from_header(
+            &header,
+            EndTxnResponse {
+                error_code: ErrorCode::ScNotLeader,
+            },
+        ));
+    }
+    let ctx = &auth_ctx.global_ctx;
+
+    debug!(transactional_id = %req.transactional_id, commit = req.commit, "end txn");
+    let error_code = match ctx
+        .transactions()
+        .end_transaction(
+            &req.transactional_id,
+            req.producer_id,
+            req.producer_epoch,
+            req.commit,
+        )
+        .await
+    {
+        Ok(markers) => {
+            ctx.transactions()
+                .queue_markers(ctx.partitions(), markers)
+                .await;
+            ErrorCode::None
+        }
+        Err(error_code) => error_code,
+    };
+
+    Ok(ResponseMessage::from_header(
+        &header,
+        EndTxnResponse { error_code },
+    ))
+}
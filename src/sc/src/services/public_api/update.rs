@@ -0,0 +1,43 @@
+//!
+//! # Update Request
+//!
+//! Update request handler. Dispatches changes of existing objects to their handlers.
+//!
+use std::io::Error as IoError;
+
+use dataplane::api::{RequestMessage, ResponseMessage};
+use fluvio_sc_schema::Status;
+use fluvio_sc_schema::objects::{UpdateRequest, AllUpdatableSpec};
+use fluvio_auth::AuthContext;
+
+use crate::services::auth::AuthServiceContext;
+
+/// Handler for update request
+pub async fn handle_update_request<AC: AuthContext>(
+    request: RequestMessage<UpdateRequest>,
+    auth_ctx: &AuthServiceContext<AC>,
+) -> Result<ResponseMessage<Status>, IoError> {
+    let (header, req) = request.get_header_request();
+
+    let dry_run = req.dry_run;
+    let name = req.name;
+
+    if !auth_ctx.global_ctx.is_leader() {
+        return Ok(ResponseMessage::from_header(
+            &header,
+            super::create::not_leader_status(name),
+        ));
+    }
+
+    let status = match req.spec {
+        AllUpdatableSpec::Topic(update) => {
+            super::topic::handle_update_topic_request(name, dry_run, update, auth_ctx).await?
+        }
+        AllUpdatableSpec::Partition(update) => {
+            super::partition::handle_update_partition_request(name, dry_run, update, auth_ctx)
+                .await?
+        }
+    };
+
+    Ok(ResponseMessage::from_header(&header, status))
+}
@@ -22,6 +22,7 @@ use fluvio_controlplane_metadata::topic::TopicSpec;
 use crate::services::auth::AuthServiceContext;
 use crate::stores::{StoreContext, K8ChangeListener};
 use fluvio_controlplane_metadata::spg::SpuGroupSpec;
+use fluvio_controlplane_metadata::smartstream::SmartStreamSpec;
 
 /// handle watch request by spawning watch controller for each store
 pub fn handle_watch_request<T, AC>(
@@ -60,6 +61,12 @@ pub fn handle_watch_request<T, AC>(
             auth_ctx.global_ctx.partitions().clone(),
             header,
         ),
+        WatchRequest::SmartStream(_) => WatchController::<T, SmartStreamSpec>::update(
+            sink,
+            end_event,
+            auth_ctx.global_ctx.smartstreams().clone(),
+            header,
+        ),
     }
 }
 
@@ -0,0 +1,6 @@
+pub use fluvio_controlplane_metadata::group::*;
+pub use fluvio_controlplane_metadata::group::store::*;
+pub use fluvio_controlplane_metadata::store::k8::K8MetaItem;
+
+pub type ConsumerGroupAdminMd = ConsumerGroupMetadata<K8MetaItem>;
+pub type ConsumerGroupAdminStore = ConsumerGroupLocalStore<K8MetaItem>;
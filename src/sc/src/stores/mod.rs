@@ -2,6 +2,8 @@ pub mod spu;
 pub mod topic;
 pub mod partition;
 pub mod spg;
+pub mod group;
+pub mod smartstream;
 
 pub use crate::dispatcher::store::*;
 
@@ -0,0 +1,6 @@
+pub use fluvio_controlplane_metadata::smartstream::*;
+pub use fluvio_controlplane_metadata::smartstream::store::*;
+pub use fluvio_controlplane_metadata::store::k8::K8MetaItem;
+
+pub type SmartStreamAdminMd = SmartStreamMetadata<K8MetaItem>;
+pub type SmartStreamAdminStore = SmartStreamLocalStore<K8MetaItem>;
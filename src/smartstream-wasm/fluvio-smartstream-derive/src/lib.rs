@@ -0,0 +1,147 @@
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Error, Ident, ItemFn};
+
+/// Kind of SmartStream function, name of function exported to SPU
+enum SmartStreamKind {
+    Filter,
+    Map,
+    Aggregate,
+}
+
+impl SmartStreamKind {
+    fn parse(kind: &Ident) -> Result<Self, Error> {
+        match kind.to_string().as_str() {
+            "filter" => Ok(Self::Filter),
+            "map" => Ok(Self::Map),
+            "aggregate" => Ok(Self::Aggregate),
+            _ => Err(Error::new(
+                kind.span(),
+                "expected one of `filter`, `map` or `aggregate`",
+            )),
+        }
+    }
+
+    fn export_name(&self) -> &'static str {
+        match self {
+            Self::Filter => "filter",
+            Self::Map => "map",
+            Self::Aggregate => "aggregate",
+        }
+    }
+
+    fn expected_signature(&self) -> &'static str {
+        match self {
+            Self::Filter => "fn(record: &Record) -> bool",
+            Self::Map => "fn(record: &Record) -> (Option<Vec<u8>>, Vec<u8>)",
+            Self::Aggregate => "fn(accumulator: &[u8], record: &Record) -> Vec<u8>",
+        }
+    }
+
+    fn arguments(&self) -> usize {
+        match self {
+            Self::Filter | Self::Map => 1,
+            Self::Aggregate => 2,
+        }
+    }
+}
+
+/// Generates function exported to SPU from SmartStream function.
+///
+/// Generated function decodes records passed by SPU, calls annotated function
+/// and passes back records to SPU. Annotated function can return plain output
+/// or `Result`, error stops processing of records. Filter also exports `filter_slice`,
+/// which passes back positions of kept records within batches read from file.
+///
+/// ```ignore
+/// use fluvio_smartstream_wasm::{smartstream, Record};
+///
+/// #[smartstream(filter)]
+/// fn contains_a(record: &Record) -> bool {
+///     record.value().as_ref().contains(&b'a')
+/// }
+///
+/// #[smartstream(map)]
+/// fn uppercase(record: &Record) -> (Option<Vec<u8>>, Vec<u8>) {
+///     let key = record.key().map(|key| key.as_ref().to_vec());
+///     (key, record.value().as_ref().to_ascii_uppercase())
+/// }
+///
+/// #[smartstream(aggregate)]
+/// fn concat(accumulator: &[u8], record: &Record) -> Vec<u8> {
+///     let mut next = accumulator.to_vec();
+///     next.extend_from_slice(record.value().as_ref());
+///     next
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn smartstream(args: TokenStream, input: TokenStream) -> TokenStream {
+    let kind = parse_macro_input!(args as Ident);
+    let func = parse_macro_input!(input as ItemFn);
+
+    match generate_smartstream(&kind, &func) {
+        Ok(export) => quote! {
+            // only called from exported function, which is generated for wasm target
+            #[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+            #func
+            #export
+        }
+        .into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn generate_smartstream(kind: &Ident, func: &ItemFn) -> Result<TokenStream2, Error> {
+    let kind = SmartStreamKind::parse(kind)?;
+
+    let sig = &func.sig;
+    if sig.inputs.len() != kind.arguments() || sig.asyncness.is_some() || sig.unsafety.is_some() {
+        return Err(Error::new_spanned(
+            sig,
+            format!(
+                "smartstream {} must have signature `{}`",
+                kind.export_name(),
+                kind.expected_signature()
+            ),
+        ));
+    }
+
+    let user_fn = &sig.ident;
+    let export_name = Ident::new(kind.export_name(), Span::call_site());
+    let module = format_ident!("__fluvio_smartstream_{}", export_name);
+    let helper = match kind {
+        SmartStreamKind::Filter => quote!(::fluvio_smartstream_wasm::filter::filter_records),
+        SmartStreamKind::Map => quote!(::fluvio_smartstream_wasm::map::map_records),
+        SmartStreamKind::Aggregate => {
+            quote!(::fluvio_smartstream_wasm::aggregate::aggregate_records)
+        }
+    };
+
+    // filter also reads batches as they are stored, so SPU passes on kept records without decoding
+    let slice_export = match kind {
+        SmartStreamKind::Filter => quote! {
+            #[no_mangle]
+            #[allow(clippy::missing_safety_doc)]
+            pub unsafe fn filter_slice(ptr: *mut u8, len: usize) -> i32 {
+                ::fluvio_smartstream_wasm::filter::filter_slice(ptr, len, super::#user_fn)
+            }
+        },
+        SmartStreamKind::Map | SmartStreamKind::Aggregate => quote!(),
+    };
+
+    Ok(quote! {
+        #[cfg(target_arch = "wasm32")]
+        mod #module {
+            #[no_mangle]
+            #[allow(clippy::missing_safety_doc)]
+            pub unsafe fn #export_name(ptr: *mut u8, len: usize) -> i32 {
+                #helper(ptr, len, super::#user_fn)
+            }
+
+            #slice_export
+        }
+    })
+}
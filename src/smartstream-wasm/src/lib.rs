@@ -1,4 +1,36 @@
 pub use fluvio_dataplane_protocol as dataplane;
+pub use fluvio_smartstream_derive::smartstream;
+
+/// Record passed to SmartStream functions
+pub type Record = dataplane::record::DefaultRecord;
+
+/// Output of key and value produced by map
+pub type MapOutput = (Option<Vec<u8>>, Vec<u8>);
+
+/// Output of SmartStream function, either plain value or `Result` with error which stops processing
+pub trait SmartStreamOutput<T> {
+    fn into_output(self) -> Result<T, String>;
+}
+
+macro_rules! impl_output {
+    ($output:ty) => {
+        impl SmartStreamOutput<$output> for $output {
+            fn into_output(self) -> Result<$output, String> {
+                Ok(self)
+            }
+        }
+
+        impl<E: std::fmt::Display> SmartStreamOutput<$output> for Result<$output, E> {
+            fn into_output(self) -> Result<$output, String> {
+                self.map_err(|err| err.to_string())
+            }
+        }
+    };
+}
+
+impl_output!(bool);
+impl_output!(Vec<u8>);
+impl_output!(MapOutput);
 
 pub mod memory {
     /// Allocate memory into the module's linear memory
@@ -27,3 +59,331 @@ pub mod memory {
         std::mem::drop(data);
     }
 }
+
+/// Records are passed between SPU and module encoded as `Vec<DefaultRecord>`.
+/// Module exports function which receives pointer and length of encoded records,
+/// and passes back kept records through `copy_records` import provided by SPU.
+/// Failure of a record is reported through `set_error` import as encoded `SmartStreamRuntimeError`.
+///
+/// Filter also exports `filter_slice`, which receives batches as they are stored in file
+/// and passes back positions of kept records, so SPU doesn't decode and encode records.
+mod abi {
+    use std::io::Cursor;
+
+    use crate::dataplane::core::{Decoder, Encoder};
+    use crate::dataplane::record::DefaultRecord;
+    use crate::dataplane::smartstream::{SmartStreamRuntimeError, SmartStreamRecordPosition};
+
+    extern "C" {
+        fn copy_records(ptr: i32, len: i32);
+        fn set_error(ptr: i32, len: i32);
+    }
+
+    /// decode records allocated by SPU in module memory
+    pub unsafe fn read_records(ptr: *mut u8, len: usize) -> Option<Vec<DefaultRecord>> {
+        let input_data = Vec::from_raw_parts(ptr, len, len);
+        let mut records: Vec<DefaultRecord> = vec![];
+        records
+            .decode(&mut Cursor::new(input_data), 0)
+            .ok()
+            .map(|_| records)
+    }
+
+    /// decode accumulator followed by records, passed by SPU to aggregate
+    pub unsafe fn read_aggregate_input(
+        ptr: *mut u8,
+        len: usize,
+    ) -> Option<(Vec<u8>, Vec<DefaultRecord>)> {
+        let input_data = Vec::from_raw_parts(ptr, len, len);
+        let mut cursor = Cursor::new(input_data);
+        let mut accumulator: Vec<u8> = vec![];
+        let mut records: Vec<DefaultRecord> = vec![];
+        accumulator.decode(&mut cursor, 0).ok()?;
+        records.decode(&mut cursor, 0).ok()?;
+        Some((accumulator, records))
+    }
+
+    /// pass records back to SPU, returns number of records or -1 if records can't be encoded
+    pub fn write_records(records: Vec<DefaultRecord>) -> i32 {
+        let count = records.len() as i32;
+        let mut out = vec![];
+        if records.encode(&mut out, 0).is_err() {
+            return -1;
+        }
+
+        let out_len = out.len();
+        let ptr = out.as_mut_ptr();
+        std::mem::forget(out);
+
+        unsafe { copy_records(ptr as i32, out_len as i32) };
+        count
+    }
+
+    /// pass back records processed before failed record, followed by error of failed record
+    pub fn write_error(
+        records: Vec<DefaultRecord>,
+        failed: &DefaultRecord,
+        message: String,
+    ) -> i32 {
+        write_records(records);
+        report_error(SmartStreamRuntimeError {
+            offset: failed.get_offset_delta(),
+            message,
+        })
+    }
+
+    /// pass back positions of kept records, returns number of records or -1 if they can't be encoded
+    pub fn write_positions(positions: Vec<SmartStreamRecordPosition>) -> i32 {
+        let count = positions.len() as i32;
+        let mut out = vec![];
+        if positions.encode(&mut out, 0).is_err() {
+            return -1;
+        }
+
+        let out_len = out.len();
+        let ptr = out.as_mut_ptr();
+        std::mem::forget(out);
+
+        unsafe { copy_records(ptr as i32, out_len as i32) };
+        count
+    }
+
+    /// pass back positions of records kept before failed record, followed by error with its offset
+    pub fn write_positions_error(
+        positions: Vec<SmartStreamRecordPosition>,
+        offset: i64,
+        message: String,
+    ) -> i32 {
+        write_positions(positions);
+        report_error(SmartStreamRuntimeError { offset, message })
+    }
+
+    fn report_error(error: SmartStreamRuntimeError) -> i32 {
+        let mut out = vec![];
+        if error.encode(&mut out, 0).is_ok() {
+            let out_len = out.len();
+            let ptr = out.as_mut_ptr();
+            std::mem::forget(out);
+            unsafe { set_error(ptr as i32, out_len as i32) };
+        }
+        -1
+    }
+}
+
+pub mod filter {
+    use std::io::Cursor;
+
+    use crate::dataplane::batch::{BATCH_HEADER_SIZE, DefaultBatch};
+    use crate::dataplane::compression::Compression;
+    use crate::dataplane::core::{Decoder, DecoderVarInt};
+    use crate::dataplane::record::DefaultRecord;
+    use crate::dataplane::smartstream::SmartStreamRecordPosition;
+    use crate::SmartStreamOutput;
+
+    /// Keep records for which `predicate` returns true.
+    /// If `predicate` returns `Err`, records kept so far are passed back with error of the record.
+    ///
+    /// Called from `filter` function exported by module:
+    ///
+    /// ```ignore
+    /// #[no_mangle]
+    /// pub unsafe fn filter(ptr: *mut u8, len: usize) -> i32 {
+    ///     filter_records(ptr, len, |record| record.value().as_ref().contains(&b'a'))
+    /// }
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// `ptr` and `len` must be arguments passed by SPU to exported function.
+    pub unsafe fn filter_records<F, O>(ptr: *mut u8, len: usize, mut predicate: F) -> i32
+    where
+        F: FnMut(&DefaultRecord) -> O,
+        O: SmartStreamOutput<bool>,
+    {
+        let records = match super::abi::read_records(ptr, len) {
+            Some(records) => records,
+            None => return -1,
+        };
+
+        let mut kept: Vec<DefaultRecord> = vec![];
+        for record in records {
+            match predicate(&record).into_output() {
+                Ok(true) => kept.push(record),
+                Ok(false) => {}
+                Err(err) => return super::abi::write_error(kept, &record, err),
+            }
+        }
+        super::abi::write_records(kept)
+    }
+
+    /// Keep records for which `predicate` returns true, from batches as they are stored in file.
+    /// Positions of kept records are passed back, so SPU sends them to consumer without
+    /// decoding them. Control and compressed batches are skipped, SPU doesn't pass them here.
+    ///
+    /// Called from `filter_slice` function exported by module, which is generated by `#[smartstream(filter)]`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` and `len` must be arguments passed by SPU to exported function.
+    pub unsafe fn filter_slice<F, O>(ptr: *mut u8, len: usize, mut predicate: F) -> i32
+    where
+        F: FnMut(&DefaultRecord) -> O,
+        O: SmartStreamOutput<bool>,
+    {
+        let input = Vec::from_raw_parts(ptr, len, len);
+        let mut cursor = Cursor::new(&input[..]);
+        let mut kept: Vec<SmartStreamRecordPosition> = vec![];
+
+        while (cursor.position() as usize) < len {
+            let mut batch = DefaultBatch::default();
+            if batch.decode_from_file_buf(&mut cursor, 0).is_err() {
+                return -1;
+            }
+            let batch_end = cursor.position() as usize
+                + (batch.batch_len as usize).saturating_sub(BATCH_HEADER_SIZE);
+            if batch_end > len {
+                return -1;
+            }
+
+            let compressed = !matches!(batch.get_compression(), Ok(Compression::None));
+            if batch.get_header().is_control() || compressed {
+                cursor.set_position(batch_end as u64);
+                continue;
+            }
+
+            let mut count: i32 = 0;
+            if count.decode(&mut cursor, 0).is_err() {
+                return -1;
+            }
+            for _ in 0..count {
+                let start = cursor.position();
+                let mut record_len: i64 = 0;
+                if record_len.decode_varint(&mut cursor).is_err() {
+                    return -1;
+                }
+                let end = cursor.position() + record_len as u64;
+                cursor.set_position(start);
+
+                let mut record = DefaultRecord::default();
+                if record.decode(&mut cursor, 0).is_err() || end as usize > batch_end {
+                    return -1;
+                }
+                cursor.set_position(end);
+
+                match predicate(&record).into_output() {
+                    Ok(true) => kept.push(SmartStreamRecordPosition {
+                        position: start as i32,
+                        len: (end - start) as i32,
+                    }),
+                    Ok(false) => {}
+                    Err(err) => {
+                        let offset = batch.base_offset + record.get_offset_delta();
+                        return super::abi::write_positions_error(kept, offset, err);
+                    }
+                }
+            }
+            cursor.set_position(batch_end as u64);
+        }
+        super::abi::write_positions(kept)
+    }
+}
+
+pub mod map {
+    use crate::dataplane::record::{DefaultRecord, DefaultAsyncBuffer};
+    use crate::{MapOutput, SmartStreamOutput};
+
+    /// Replace key and value of each record with output of `map`.
+    /// Offset, timestamp and headers of records are kept.
+    /// If `map` returns `Err`, records mapped so far are passed back with error of the record.
+    ///
+    /// Called from `map` function exported by module:
+    ///
+    /// ```ignore
+    /// #[no_mangle]
+    /// pub unsafe fn map(ptr: *mut u8, len: usize) -> i32 {
+    ///     map_records(ptr, len, |record| {
+    ///         let key = record.key().map(|key| key.as_ref().to_vec());
+    ///         (key, record.value().as_ref().to_ascii_uppercase())
+    ///     })
+    /// }
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// `ptr` and `len` must be arguments passed by SPU to exported function.
+    pub unsafe fn map_records<F, O>(ptr: *mut u8, len: usize, mut map: F) -> i32
+    where
+        F: FnMut(&DefaultRecord) -> O,
+        O: SmartStreamOutput<MapOutput>,
+    {
+        let mut records = match super::abi::read_records(ptr, len) {
+            Some(records) => records,
+            None => return -1,
+        };
+
+        for index in 0..records.len() {
+            let (key, value) = match map(&records[index]).into_output() {
+                Ok(output) => output,
+                Err(err) => {
+                    let failed = records.remove(index);
+                    records.truncate(index);
+                    return super::abi::write_error(records, &failed, err);
+                }
+            };
+            let record = &mut records[index];
+            record.key = key.map(DefaultAsyncBuffer::from);
+            record.value = DefaultAsyncBuffer::from(value);
+        }
+        super::abi::write_records(records)
+    }
+}
+
+pub mod aggregate {
+    use crate::dataplane::record::{DefaultRecord, DefaultAsyncBuffer};
+    use crate::SmartStreamOutput;
+
+    /// Combine accumulator with each record, value of each record is replaced by running aggregate.
+    /// SPU starts next batch from aggregate of last record.
+    /// If `aggregate` returns `Err`, records aggregated so far are passed back with error of the record.
+    ///
+    /// Called from `aggregate` function exported by module:
+    ///
+    /// ```ignore
+    /// #[no_mangle]
+    /// pub unsafe fn aggregate(ptr: *mut u8, len: usize) -> i32 {
+    ///     aggregate_records(ptr, len, |accumulator, record| {
+    ///         let mut sum = accumulator.to_vec();
+    ///         sum.extend_from_slice(record.value().as_ref());
+    ///         sum
+    ///     })
+    /// }
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// `ptr` and `len` must be arguments passed by SPU to exported function.
+    pub unsafe fn aggregate_records<F, O>(ptr: *mut u8, len: usize, mut aggregate: F) -> i32
+    where
+        F: FnMut(&[u8], &DefaultRecord) -> O,
+        O: SmartStreamOutput<Vec<u8>>,
+    {
+        let (mut accumulator, mut records) = match super::abi::read_aggregate_input(ptr, len) {
+            Some(input) => input,
+            None => return -1,
+        };
+
+        for index in 0..records.len() {
+            accumulator = match aggregate(&accumulator, &records[index]).into_output() {
+                Ok(accumulator) => accumulator,
+                Err(err) => {
+                    let failed = records.remove(index);
+                    records.truncate(index);
+                    return super::abi::write_error(records, &failed, err);
+                }
+            };
+            let record = &mut records[index];
+            record.value = DefaultAsyncBuffer::from(accumulator.clone());
+        }
+        super::abi::write_records(records)
+    }
+}
@@ -112,6 +112,26 @@ where
         correlation_id(self.correlation_id_counter.clone()).await
     }
 
+    /// send request without waiting for response
+    /// this is only used for requests which server doesn't respond to
+    pub async fn send_request_only<R>(
+        &self,
+        mut req_msg: RequestMessage<R>,
+    ) -> Result<(), FlvSocketError>
+    where
+        R: Request,
+    {
+        let correlation_id = self.next_correlation_id().await;
+        req_msg.header.set_correlation_id(correlation_id);
+
+        debug!(
+            "serial multiplexing: sending request only: {} id: {}",
+            R::API_KEY,
+            correlation_id
+        );
+        self.sink.send_request(&req_msg).await
+    }
+
     /// create socket to perform request and response
     pub async fn send_and_receive<R>(
         &self,
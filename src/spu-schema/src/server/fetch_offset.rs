@@ -26,7 +26,7 @@ pub struct FetchOffsetsRequest {
 
 impl Request for FetchOffsetsRequest {
     const API_KEY: u16 = SpuServerApiKey::FetchOffsets as u16;
-    const DEFAULT_API_VERSION: i16 = 0;
+    const DEFAULT_API_VERSION: i16 = 1;
     type Response = FetchOffsetsResponse;
 }
 
@@ -38,6 +38,20 @@ impl FetchOffsetsRequest {
                 name: topic,
                 partitions: vec![FetchOffsetPartition {
                     partition_index: partition,
+                    ..Default::default()
+                }],
+            }],
+        }
+    }
+
+    /// create request which also looks up offset of timestamp, in milliseconds
+    pub fn with_timestamp(topic: String, partition: i32, timestamp: i64) -> Self {
+        Self {
+            topics: vec![FetchOffsetTopic {
+                name: topic,
+                partitions: vec![FetchOffsetPartition {
+                    partition_index: partition,
+                    timestamp: Some(timestamp),
                 }],
             }],
         }
@@ -57,6 +71,10 @@ pub struct FetchOffsetTopic {
 pub struct FetchOffsetPartition {
     /// The partition index.
     pub partition_index: i32,
+
+    /// Timestamp in milliseconds to look up offset for
+    #[fluvio(min_version = 1)]
+    pub timestamp: Option<i64>,
 }
 
 // -----------------------------------
@@ -107,6 +125,10 @@ pub struct FetchOffsetPartitionResponse {
 
     /// Last readable offset
     pub last_stable_offset: i64,
+
+    /// Offset of first record at or after requested timestamp
+    #[fluvio(min_version = 1)]
+    pub timestamp_offset: Option<i64>,
 }
 
 impl fmt::Display for FetchOffsetPartitionResponse {
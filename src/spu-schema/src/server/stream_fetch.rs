@@ -20,17 +20,47 @@ use dataplane::store::StoreValue;
 use dataplane::record::FileRecordSet;
 use dataplane::store::FileWrite;
 use dataplane::fetch::FetchablePartitionResponse;
-use dataplane::record::RecordSet;
+use dataplane::record::{RecordSet, RawRecordSet};
 use dataplane::Isolation;
+use dataplane::smartstream::SmartStreamRuntimeError;
 
 pub type DefaultStreamFetchResponse = StreamFetchResponse<RecordSet>;
 pub type FileStreamFetchRequest = StreamFetchRequest<FileRecordSet>;
 pub type DefaultStreamFetchRequest = StreamFetchRequest<RecordSet>;
+/// records processed by SmartStream are sent back encoded same as `RecordSet`
+pub type RawStreamFetchRequest = StreamFetchRequest<RawRecordSet>;
 
 use super::SpuServerApiKey;
 
 // version for WASM_MODULE
 pub const WASM_MODULE_API: i16 = 11;
+// version for SmartStream kind
+pub const SMART_STREAM_KIND_API: i16 = 12;
+// version for SmartStream aggregate
+pub const SMART_STREAM_AGGREGATE_API: i16 = 13;
+// version for SmartStream runtime error
+pub const SMART_STREAM_ERROR_API: i16 = 14;
+// version for SmartStream registered in SC
+pub const SMART_STREAM_NAME_API: i16 = 15;
+
+/// How SmartStream module is applied to records
+#[derive(Debug, Clone, Copy, PartialEq, Encode, Decode)]
+#[fluvio(encode_discriminant)]
+#[repr(u8)]
+pub enum SmartStreamKind {
+    /// module exports `filter`, which drops records
+    Filter = 0,
+    /// module exports `map`, which rewrites key and value of each record
+    Map = 1,
+    /// module exports `aggregate`, which combines accumulator with each record
+    Aggregate = 2,
+}
+
+impl Default for SmartStreamKind {
+    fn default() -> Self {
+        SmartStreamKind::Filter
+    }
+}
 
 /// Fetch records continuously
 /// Output will be send back as stream
@@ -46,6 +76,14 @@ where
     pub isolation: Isolation,
     #[fluvio(min_version = 11)]
     pub wasm_module: Vec<u8>,
+    #[fluvio(min_version = 12)]
+    pub smart_stream_kind: SmartStreamKind,
+    /// initial accumulator of aggregate
+    #[fluvio(min_version = 13)]
+    pub aggregate_accumulator: Vec<u8>,
+    /// name of SmartStream registered in SC, used instead of `wasm_module`
+    #[fluvio(min_version = 15)]
+    pub smart_stream_name: String,
     pub data: PhantomData<R>,
 }
 
@@ -54,7 +92,7 @@ where
     R: Debug + Decoder + Encoder,
 {
     const API_KEY: u16 = SpuServerApiKey::StreamFetch as u16;
-    const DEFAULT_API_VERSION: i16 = WASM_MODULE_API;
+    const DEFAULT_API_VERSION: i16 = SMART_STREAM_NAME_API;
     type Response = StreamFetchResponse<R>;
 }
 
@@ -66,6 +104,9 @@ where
     pub topic: String,
     pub stream_id: u32,
     pub partition: FetchablePartitionResponse<R>,
+    /// error of failed record, set with `SmartStreamRuntimeError` error code
+    #[fluvio(min_version = 14)]
+    pub smart_stream_error: Option<SmartStreamRuntimeError>,
 }
 
 impl FileWrite for StreamFetchResponse<FileRecordSet> {
@@ -80,6 +121,9 @@ impl FileWrite for StreamFetchResponse<FileRecordSet> {
         self.topic.encode(src, version)?;
         self.stream_id.encode(src, version)?;
         self.partition.file_encode(src, data, version)?;
+        if version >= SMART_STREAM_ERROR_API {
+            self.smart_stream_error.encode(src, version)?;
+        }
         Ok(())
     }
 }
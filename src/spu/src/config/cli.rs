@@ -34,7 +34,7 @@ pub struct SpuOpt {
     /// Spu server for internal cluster communication
     pub bind_private: Option<String>,
 
-    /// Address of the SC Server
+    /// Address of the SC Server, addresses of SC replicas are separated by comma
     #[structopt(long, value_name = "host:port", env = "FLV_SC_PRIVATE_HOST")]
     pub sc_addr: Option<String>,
 
@@ -50,6 +50,22 @@ pub struct SpuOpt {
     #[structopt(long, value_name = "integer", env = "FLV_LOG_INDEX_MAX_INTERVAL_BYTES")]
     pub index_max_interval_bytes: Option<u32>,
 
+    /// how long records are retained before they are removed, by default there is no time limit
+    #[structopt(long, value_name = "seconds", env = "FLV_LOG_RETENTION_SECONDS")]
+    pub retention_seconds: Option<u32>,
+
+    /// max bytes stored per partition, oldest records are removed when exceeded
+    #[structopt(long, value_name = "integer", env = "FLV_LOG_MAX_PARTITION_SIZE")]
+    pub max_partition_size: Option<u64>,
+
+    /// fuel available to each invocation of SmartStream module
+    #[structopt(long, value_name = "integer", env = "FLV_SMART_STREAM_FUEL")]
+    pub smart_stream_fuel: Option<u64>,
+
+    /// max linear memory of SmartStream module instance
+    #[structopt(long, value_name = "integer", env = "FLV_SMART_STREAM_MAX_MEMORY")]
+    pub smart_stream_max_memory: Option<u64>,
+
     /// max bytes to transfer between leader and follower
     #[structopt(
         long,
@@ -116,6 +132,26 @@ impl SpuOpt {
             config.log.index_max_interval_bytes = index_max_interval_bytes;
         }
 
+        if let Some(retention_seconds) = self.retention_seconds {
+            info!("overriding retention seconds: {}", retention_seconds);
+            config.log.retention_seconds = Some(retention_seconds);
+        }
+
+        if let Some(max_partition_size) = self.max_partition_size {
+            info!("overriding max partition size: {}", max_partition_size);
+            config.log.max_partition_size = Some(max_partition_size);
+        }
+
+        if let Some(fuel) = self.smart_stream_fuel {
+            info!("overriding smart stream fuel: {}", fuel);
+            config.smart_stream.fuel = fuel;
+        }
+
+        if let Some(max_memory) = self.smart_stream_max_memory {
+            info!("overriding smart stream max memory: {}", max_memory);
+            config.smart_stream.max_memory_bytes = max_memory;
+        }
+
         if let Some(public_addr) = self.bind_public {
             info!("overriding public addr: {}", public_addr);
             config.public_endpoint = public_addr;
@@ -21,6 +21,8 @@ use fluvio_types::defaults::SPU_LOG_INDEX_MAX_BYTES;
 use fluvio_types::defaults::SPU_LOG_INDEX_MAX_INTERVAL_BYTES;
 use fluvio_types::defaults::SPU_LOG_SEGMENT_MAX_BYTES;
 use fluvio_types::defaults::SPU_RETRY_SC_TIMEOUT_MS;
+use fluvio_types::defaults::SPU_SMART_STREAM_FUEL;
+use fluvio_types::defaults::SPU_SMART_STREAM_MAX_MEMORY_BYTES;
 
 // environment variables
 
@@ -28,8 +30,11 @@ use fluvio_types::defaults::SPU_MIN_IN_SYNC_REPLICAS;
 use fluvio_types::defaults::FLV_LOG_BASE_DIR;
 use fluvio_types::defaults::FLV_LOG_SIZE;
 use fluvio_types::SpuId;
+use fluvio_controlplane_metadata::partition::Replica;
+use fluvio_controlplane_metadata::topic::CleanupPolicy as TopicCleanupPolicy;
 use fluvio_storage::config::{
-    ConfigOption, DEFAULT_FLUSH_WRITE_COUNT, DEFAULT_FLUSH_IDLE_MSEC, DEFAULT_MAX_BATCH_SIZE,
+    CleanupPolicy, ConfigOption, DEFAULT_FLUSH_WRITE_COUNT, DEFAULT_FLUSH_IDLE_MSEC,
+    DEFAULT_MAX_BATCH_SIZE,
 };
 
 #[derive(Debug, PartialEq, Clone)]
@@ -55,6 +60,8 @@ pub struct Log {
     pub flush_write_count: u32,
     pub flush_idle_msec: u32,
     pub max_batch_size: u32,
+    pub retention_seconds: Option<u32>,
+    pub max_partition_size: Option<u64>,
 }
 
 impl Default for Log {
@@ -70,6 +77,26 @@ impl Default for Log {
             flush_write_count: DEFAULT_FLUSH_WRITE_COUNT,
             flush_idle_msec: DEFAULT_FLUSH_IDLE_MSEC,
             max_batch_size: DEFAULT_MAX_BATCH_SIZE,
+            retention_seconds: None,
+            max_partition_size: None,
+        }
+    }
+}
+
+/// limits applied to SmartStream modules sent by consumers
+#[derive(Debug, PartialEq, Clone)]
+pub struct SmartStreamConfig {
+    /// fuel available to each invocation of module, roughly number of wasm instructions
+    pub fuel: u64,
+    /// max size of linear memory of module instance
+    pub max_memory_bytes: u64,
+}
+
+impl Default for SmartStreamConfig {
+    fn default() -> Self {
+        Self {
+            fuel: SPU_SMART_STREAM_FUEL,
+            max_memory_bytes: SPU_SMART_STREAM_MAX_MEMORY_BYTES,
         }
     }
 }
@@ -92,6 +119,7 @@ pub struct SpuConfig {
     // parameters
     pub replication: ReplicationConfig,
     pub log: Log,
+    pub smart_stream: SmartStreamConfig,
 
     pub peer_max_bytes: u32,
 }
@@ -107,6 +135,7 @@ impl Default for SpuConfig {
             replication: ReplicationConfig::default(),
             sc_retry_ms: SPU_RETRY_SC_TIMEOUT_MS,
             log: Log::default(),
+            smart_stream: SmartStreamConfig::default(),
             peer_max_bytes: fluvio_storage::FileReplica::PREFER_MAX_LEN,
         }
     }
@@ -122,8 +151,14 @@ impl SpuConfig {
         &self.rack
     }
 
-    pub fn sc_endpoint(&self) -> &str {
-        &self.sc_endpoint
+    /// SC endpoints, replicas of SC are separated by comma
+    pub fn sc_endpoints(&self) -> Vec<String> {
+        self.sc_endpoint
+            .split(',')
+            .map(|endpoint| endpoint.trim())
+            .filter(|endpoint| !endpoint.is_empty())
+            .map(|endpoint| endpoint.to_owned())
+            .collect()
     }
 
     pub fn public_socket_addr(&self) -> &str {
@@ -143,6 +178,35 @@ impl SpuConfig {
     pub fn storage(&self) -> &Log {
         &self.log
     }
+
+    /// storage configuration for replica, topic level settings override SPU defaults
+    pub fn replica_storage_config(&self, replica: &Replica) -> ConfigOption {
+        let mut option: ConfigOption = self.into();
+        if let Some(storage) = &replica.storage {
+            if storage.retention_seconds.is_some() {
+                option.retention_seconds = storage.retention_seconds;
+            }
+            if storage.max_partition_size.is_some() {
+                option.max_partition_size = storage.max_partition_size;
+            }
+            if let Some(segment_size) = storage.segment_size {
+                option.segment_max_bytes = segment_size;
+            }
+            if let Some(flush_write_count) = storage.flush_write_count {
+                option.flush_write_count = flush_write_count;
+            }
+            if let Some(flush_idle_msec) = storage.flush_idle_msec {
+                option.flush_idle_msec = flush_idle_msec;
+            }
+        }
+        if let Some(cleanup_policy) = replica.cleanup_policy {
+            option.cleanup_policy = match cleanup_policy {
+                TopicCleanupPolicy::Delete => CleanupPolicy::Delete,
+                TopicCleanupPolicy::Compact => CleanupPolicy::Compact,
+            };
+        }
+        option
+    }
 }
 
 impl From<&SpuConfig> for ConfigOption {
@@ -157,6 +221,8 @@ impl From<&SpuConfig> for ConfigOption {
             log.flush_idle_msec,
             log.max_batch_size,
         )
+        .retention_seconds(log.retention_seconds)
+        .max_partition_size(log.max_partition_size)
     }
 }
 
@@ -22,6 +22,9 @@ use fluvio_controlplane::InternalSpuRequest;
 use fluvio_controlplane::RegisterSpuRequest;
 use fluvio_controlplane::{UpdateSpuRequest, UpdateLrsRequest};
 use fluvio_controlplane::UpdateReplicaRequest;
+use fluvio_controlplane::UpdateTxnMarkersRequest;
+use fluvio_controlplane::TxnMarkersWrittenRequest;
+use fluvio_controlplane::UpdateSmartStreamRequest;
 use fluvio_controlplane_metadata::partition::Replica;
 use dataplane::api::RequestMessage;
 use fluvio_socket::{FlvSocket, FlvSocketError, FlvSink};
@@ -185,6 +188,15 @@ impl ScDispatcher<FileReplica> {
                                 break;
                             }
                         },
+                        Some(Ok(InternalSpuRequest::UpdateTxnMarkersRequest(request))) => {
+                            if let Err(err) = self.handle_update_txn_markers_request(request,&mut sink).await {
+                                error!("error handling update txn markers request: {}", err);
+                                break;
+                            }
+                        },
+                        Some(Ok(InternalSpuRequest::UpdateSmartStreamRequest(request))) => {
+                            self.handle_update_smart_stream_request(request);
+                        },
                         Some(_) => {
                             debug!("no more sc msg content, end");
                             break;
@@ -267,28 +279,36 @@ impl ScDispatcher<FileReplica> {
     /// or if we received termination message
     async fn create_socket_to_sc(&mut self) -> Option<FlvSocket> {
         let spu_id = self.ctx.local_spu_id();
-        let sc_endpoint = self.ctx.config().sc_endpoint().to_string();
+        // multiple SC endpoints are separated by comma, only leader SC accepts connection
+        let sc_endpoints = self.ctx.config().sc_endpoints();
 
-        debug!("trying to connect to sc endpoint: {}", sc_endpoint);
+        debug!("trying to connect to sc endpoints: {:?}", sc_endpoints);
+        if sc_endpoints.is_empty() {
+            error!("no sc endpoint configured for spu: {}", spu_id);
+            return None;
+        }
 
         let wait_interval = self.ctx.config().sc_retry_ms;
+        let mut attempt = 0;
         loop {
+            let sc_endpoint = &sc_endpoints[attempt % sc_endpoints.len()];
+            attempt += 1;
             trace!(
                 "trying to create socket to sc: {:#?} for spu: {}",
                 sc_endpoint,
                 spu_id
             );
-            let connect_future = FlvSocket::connect(&sc_endpoint);
+            let connect_future = FlvSocket::connect(sc_endpoint);
 
             select! {
                 socket_res = connect_future => {
                     match socket_res {
                         Ok(socket) => {
-                            debug!("connected to sc for spu: {}",spu_id);
+                            debug!("connected to sc: {} for spu: {}",sc_endpoint,spu_id);
                             self.counter.reconnect += 1;
                             return Some(socket)
                         }
-                        Err(err) => warn!("error connecting to sc: {}",err)
+                        Err(err) => warn!("error connecting to sc: {}, {}",sc_endpoint,err)
                     }
 
                     trace!("sleeping {} ms to connect to sc: {}",wait_interval,spu_id);
@@ -365,6 +385,88 @@ impl ScDispatcher<FileReplica> {
         Ok(())
     }
 
+    ///
+    /// SmartStream modules registered in SC
+    ///
+    #[instrument(skip(self, req_msg), name = "update_smart_stream_request")]
+    fn handle_update_smart_stream_request(
+        &mut self,
+        req_msg: RequestMessage<UpdateSmartStreamRequest>,
+    ) {
+        let (_, request) = req_msg.get_header_request();
+
+        let actions = if !request.all.is_empty() {
+            debug!(
+                epoch = request.epoch,
+                item_count = request.all.len(),
+                "received smartstream sync all"
+            );
+            self.ctx.smart_stream_localstore().sync_all(request.all)
+        } else {
+            debug!(
+                epoch = request.epoch,
+                item_count = request.changes.len(),
+                "received smartstream changes"
+            );
+            self.ctx
+                .smart_stream_localstore()
+                .apply_changes(request.changes)
+        };
+
+        // compiled module is stale once smartstream is replaced or removed
+        for action in actions.into_iter() {
+            match action {
+                SpecChange::Add(_) => {}
+                SpecChange::Mod(_, old) | SpecChange::Delete(old) => {
+                    self.ctx.smart_stream_engine().evict_module(&old.name);
+                }
+            }
+        }
+    }
+
+    ///
+    /// Transaction markers sent by SC transaction coordinator.
+    /// Written markers are confirmed back to SC, others are sent again by SC
+    ///
+    #[instrument(skip(self, req_msg, sc_sink), name = "update_txn_markers_request")]
+    async fn handle_update_txn_markers_request(
+        &mut self,
+        req_msg: RequestMessage<UpdateTxnMarkersRequest>,
+        sc_sink: &mut FlvSink,
+    ) -> Result<(), FlvSocketError> {
+        let (_, request) = req_msg.get_header_request();
+
+        debug!(markers = request.markers.len(), "txn markers request");
+
+        let mut written = vec![];
+        for marker in request.markers {
+            if let Some(leader_state) = self.ctx.leaders_state().get(&marker.replica) {
+                match leader_state
+                    .write_txn_marker(marker.producer_id, marker.producer_epoch, marker.commit)
+                    .await
+                {
+                    Ok(true) => written.push(marker),
+                    Ok(false) => {
+                        debug!(replica = %marker.replica, "no ongoing transaction for txn marker");
+                        written.push(marker);
+                    }
+                    Err(err) => {
+                        error!(replica = %marker.replica, "error writing txn marker: {}", err);
+                    }
+                }
+            } else {
+                warn!(replica = %marker.replica, "no leader found for txn marker");
+            }
+        }
+
+        if written.is_empty() {
+            return Ok(());
+        }
+
+        let message = RequestMessage::new_request(TxnMarkersWrittenRequest::new(written));
+        sc_sink.send_request(&message).await
+    }
+
     #[instrument(skip(self, actions, sc_sink))]
     async fn apply_replica_actions(
         &self,
@@ -403,23 +505,18 @@ impl ScDispatcher<FileReplica> {
                         {
                             error!("error creating leader replica: {}", err);
                         }
+                    } else if !new_replica.has_spu(&local_id) {
+                        trace!("not replica of this spu: {}", new_replica);
                     } else if new_replica.is_being_deleted {
                         self.remove_follower_replica(new_replica).await;
                     } else {
-                        if let Err(err) = self
-                            .ctx
-                            .followers_state_owned()
-                            .add_replica(self.ctx.clone(), new_replica)
-                            .await
-                        {
-                            error!("adding replica failed: {}", err);
-                        }
+                        self.add_follower_replica(new_replica).await;
                     }
                 }
                 SpecChange::Delete(deleted_replica) => {
                     if deleted_replica.leader == local_id {
                         self.remove_leader_replica(deleted_replica, sc_sink).await?;
-                    } else {
+                    } else if deleted_replica.has_spu(&local_id) {
                         self.remove_follower_replica(deleted_replica).await;
                     }
                 }
@@ -433,9 +530,19 @@ impl ScDispatcher<FileReplica> {
                     if new_replica.is_being_deleted {
                         if new_replica.leader == local_id {
                             self.remove_leader_replica(new_replica, sc_sink).await?;
-                        } else {
+                        } else if new_replica.has_spu(&local_id) {
                             self.remove_follower_replica(new_replica).await;
                         }
+                    } else if !new_replica.has_spu(&local_id) {
+                        // replica has been moved out of this spu
+                        if old_replica.leader == local_id {
+                            self.remove_moved_leader_replica(old_replica).await;
+                        } else if old_replica.has_spu(&local_id) {
+                            self.remove_follower_replica(old_replica).await;
+                        }
+                    } else if !old_replica.has_spu(&local_id) {
+                        // replica has been moved into this spu
+                        self.add_follower_replica(new_replica).await;
                     } else {
                         // check for leader change
                         if new_replica.leader != old_replica.leader {
@@ -551,6 +658,30 @@ impl ScDispatcher<FileReplica> {
         sc_sink.send_request(&message).await
     }
 
+    /// remove leader replica which has been moved to other spus.
+    /// partition still exists, so there is no confirmation to sc
+    #[instrument(skip(self, replica), fields(replica = %replica.id))]
+    async fn remove_moved_leader_replica(&self, replica: Replica) {
+        debug!("removing moved leader replica");
+        if let Some(previous_state) = self.ctx.leaders_state().remove(&replica.id) {
+            if let Err(err) = previous_state
+                .send_message_to_controller(LeaderReplicaControllerCommand::RemoveReplicaFromSc)
+                .await
+            {
+                error!(
+                    "error sending external command to replica controller for replica: {}, {:#?}",
+                    replica, err
+                );
+            }
+
+            if let Err(err) = previous_state.remove().await {
+                error!("error: {} removing replica: {}", err, replica);
+            }
+        } else {
+            warn!("no existing replica found {}", replica);
+        }
+    }
+
     /// Promote follower replica as leader,
     /// This is done in 3 steps
     /// // 1: Remove follower replica from followers state
@@ -577,7 +708,8 @@ impl ScDispatcher<FileReplica> {
                 new_replica.clone(),
                 self.ctx.config().into(),
                 sender,
-            );
+            )
+            .await;
 
             self.ctx
                 .leaders_state()
@@ -611,6 +743,18 @@ impl ScDispatcher<FileReplica> {
         }
     }
 
+    async fn add_follower_replica(&self, replica: Replica) {
+        debug!("adding follower replica: {}", replica);
+        if let Err(err) = self
+            .ctx
+            .followers_state_owned()
+            .add_replica(self.ctx.clone(), replica)
+            .await
+        {
+            error!("adding replica failed: {}", err);
+        }
+    }
+
     /// update follower replida
     async fn update_follower_replica(&self, replica: Replica) {
         debug!("trying to adding follower replica: {}", &replica.leader);
@@ -15,8 +15,10 @@ use crate::replication::leader::{
     SharedReplicaLeadersState, ReplicaLeadersState, SpuUpdates, SharedSpuUpdates,
 };
 use crate::services::public::StreamPublishers;
+use crate::smart_stream::filter::SmartStreamEngine;
 
 use super::spus::SharedSpuLocalStore;
+use super::smartstream::{SharedSmartStreamLocalStore, SmartStreamLocalStore};
 use super::SharedReplicaLocalStore;
 use super::spus::SpuLocalStore;
 use super::replica::ReplicaStore;
@@ -31,6 +33,8 @@ pub struct GlobalContext<S> {
     followers_state: SharedFollowersState<S>,
     stream_publishers: StreamPublishers,
     spu_followers: SharedSpuUpdates,
+    smart_stream_localstore: SharedSmartStreamLocalStore,
+    smart_stream_engine: SmartStreamEngine,
 }
 
 // -----------------------------------
@@ -46,6 +50,8 @@ where
     }
 
     pub fn new(spu_config: SpuConfig) -> Self {
+        let smart_stream_engine = SmartStreamEngine::new(&spu_config.smart_stream)
+            .expect("failed to create smartstream engine");
         GlobalContext {
             spu_localstore: SpuLocalStore::new_shared(),
             replica_localstore: ReplicaStore::new_shared(),
@@ -54,6 +60,8 @@ where
             followers_state: FollowersState::new_shared(),
             stream_publishers: StreamPublishers::new(),
             spu_followers: SpuUpdates::shared(),
+            smart_stream_localstore: SmartStreamLocalStore::new_shared(),
+            smart_stream_engine,
         }
     }
 
@@ -74,6 +82,16 @@ where
         &self.replica_localstore
     }
 
+    /// smartstream modules registered in SC
+    pub fn smart_stream_localstore(&self) -> &SmartStreamLocalStore {
+        &self.smart_stream_localstore
+    }
+
+    /// engine shared by all streams, caches compiled modules
+    pub fn smart_stream_engine(&self) -> &SmartStreamEngine {
+        &self.smart_stream_engine
+    }
+
     pub fn leaders_state(&self) -> &ReplicaLeadersState<S> {
         &self.leaders_state
     }
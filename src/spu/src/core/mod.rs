@@ -3,6 +3,7 @@ mod store;
 
 pub mod spus;
 pub mod replica;
+pub mod smartstream;
 
 pub use self::global_context::GlobalContext;
 pub use self::store::Spec;
@@ -0,0 +1,24 @@
+//
+//  SmartStream modules registered in SC, every SPU keeps copy of all modules
+//
+
+use fluvio_controlplane_metadata::smartstream::SmartStream;
+
+use crate::core::Spec;
+use crate::core::LocalStore;
+
+impl Spec for SmartStream {
+    const LABEL: &'static str = "SmartStream";
+
+    type Key = String;
+
+    fn key(&self) -> &Self::Key {
+        &self.name
+    }
+
+    fn key_owned(&self) -> Self::Key {
+        self.name.clone()
+    }
+}
+
+pub type SmartStreamLocalStore = LocalStore<SmartStream>;
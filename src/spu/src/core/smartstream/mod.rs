@@ -0,0 +1,7 @@
+mod metadata;
+
+pub use self::metadata::SmartStreamLocalStore;
+
+use std::sync::Arc;
+
+pub type SharedSmartStreamLocalStore = Arc<SmartStreamLocalStore>;
@@ -5,6 +5,7 @@ use tracing::instrument;
 
 use tokio::select;
 use futures_util::StreamExt;
+use async_io::Timer;
 
 use fluvio_future::task::spawn;
 use fluvio_future::timer::sleep;
@@ -29,6 +30,12 @@ use super::peer_api::FollowerPeerRequest;
 /// time to resync follower offsets to leader
 const LEADER_RECONCILIATION_INTERVAL_SEC: u64 = 60; // 1 min
 
+/// time to check for segments out of retention
+const RETENTION_CHECK_INTERVAL_SEC: u64 = 60; // 1 min
+
+/// time to compact segments of topics with compact cleanup policy
+const COMPACTION_CHECK_INTERVAL_SEC: u64 = 300; // 5 min
+
 /// Controller for managing follower replicas
 /// There is a controller for follower groups (group by leader SPU)
 pub struct ReplicaFollowerController<S> {
@@ -106,6 +113,12 @@ impl ReplicaFollowerController<FileReplica> {
 
         let mut counter: i32 = 0;
 
+        // timers are created once, so records from leader don't postpone them
+        let mut retention_timer =
+            Timer::interval(Duration::from_secs(RETENTION_CHECK_INTERVAL_SEC));
+        let mut compaction_timer =
+            Timer::interval(Duration::from_secs(COMPACTION_CHECK_INTERVAL_SEC));
+
         loop {
             debug!(counter, "waiting request from leader");
 
@@ -115,6 +128,16 @@ impl ReplicaFollowerController<FileReplica> {
                     self.sync_all_offsets_to_leader(&mut sink,&replicas).await?;
                 },
 
+                _ = retention_timer.next() => {
+                    debug!("timer fired - checking retention");
+                    replicas.remove_expired_segments().await;
+                },
+
+                _ = compaction_timer.next() => {
+                    debug!("timer fired - compacting segments");
+                    replicas.compact_segments().await;
+                },
+
                 _ = event_listener.listen() => {
                     // if sync counter changes, then we need to re-compute replicas and send offsets again
                     replicas = ReplicasBySpu::filter_from(&self.states,self.leader).await;
@@ -294,6 +317,24 @@ impl ReplicasBySpu {
         Self(replicas)
     }
 
+    /// enforce retention on all replicas
+    async fn remove_expired_segments(&self) {
+        for (replica_key, replica) in self.0.iter() {
+            if let Err(err) = replica.remove_expired_segments().await {
+                error!(%replica_key, "error removing expired segments: {}", err);
+            }
+        }
+    }
+
+    /// compact replicas of compacted topics
+    async fn compact_segments(&self) {
+        for (replica_key, replica) in self.0.iter() {
+            if let Err(err) = replica.compact_segments().await {
+                error!(%replica_key, "error compacting segments: {}", err);
+            }
+        }
+    }
+
     // generate offset requests
     fn replica_offsets(&self) -> UpdateOffsetRequest {
         let replicas = self
@@ -88,9 +88,12 @@ impl FollowersState<FileReplica> {
                 replica
             );
 
-            let replica_state =
-                FollowerReplicaState::create(leader, replica.id.clone(), ctx.config().into())
-                    .await?;
+            let replica_state = FollowerReplicaState::create(
+                leader,
+                replica.id.clone(),
+                ctx.config().replica_storage_config(&replica),
+            )
+            .await?;
             writer.insert(replica.id, replica_state.clone());
 
             let mut leaders = self.leaders.write().await;
@@ -8,6 +8,7 @@ use fluvio_types::SpuId;
 pub enum LeaderReplicaControllerCommand {
     UpdateReplicaFromSc(Replica),
     RemoveReplicaFromSc,
+    FollowerInSync(SpuId),
 }
 
 #[derive(Debug)]
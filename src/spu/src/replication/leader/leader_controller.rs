@@ -1,5 +1,7 @@
+use std::time::Duration;
+
 use dataplane::Isolation;
-use tracing::{debug};
+use tracing::{debug, error};
 use tracing::instrument;
 use async_channel::Receiver;
 use futures_util::stream::StreamExt;
@@ -13,6 +15,12 @@ use crate::control_plane::SharedSinkMessageChannel;
 use super::LeaderReplicaControllerCommand;
 use super::replica_state::{SharedLeaderState};
 
+/// time to check for segments out of retention
+const RETENTION_CHECK_INTERVAL_SEC: u64 = 60; // 1 min
+
+/// time to compact segments of topics with compact cleanup policy
+const COMPACTION_CHECK_INTERVAL_SEC: u64 = 300; // 5 min
+
 /// Controller for managing leader replica.
 /// Each leader replica controller is spawned and managed by master controller to ensure max parallism.
 pub struct ReplicaLeaderController<S> {
@@ -51,9 +59,16 @@ impl ReplicaLeaderController<FileReplica> {
     )]
     async fn dispatch_loop(mut self) {
         use tokio::select;
+        use async_io::Timer;
 
         self.send_status_to_sc().await;
 
+        // timers are created once, so offset changes and commands don't postpone them
+        let mut retention_timer =
+            Timer::interval(Duration::from_secs(RETENTION_CHECK_INTERVAL_SEC));
+        let mut compaction_timer =
+            Timer::interval(Duration::from_secs(COMPACTION_CHECK_INTERVAL_SEC));
+
         let mut hw_listener = self.state.offset_listener(&Isolation::ReadCommitted);
         let mut leo_listener = self.state.offset_listener(&Isolation::ReadUncommitted);
         loop {
@@ -61,6 +76,19 @@ impl ReplicaLeaderController<FileReplica> {
 
             select! {
 
+                _ = retention_timer.next() => {
+                    debug!("timer fired - checking retention");
+                    if let Err(err) = self.state.remove_expired_segments().await {
+                        error!("error removing expired segments: {}", err);
+                    }
+                },
+
+                _ = compaction_timer.next() => {
+                    debug!("timer fired - compacting segments");
+                    if let Err(err) = self.state.compact_segments().await {
+                        error!("error compacting segments: {}", err);
+                    }
+                },
 
                 offset = hw_listener.listen() => {
                     debug!(hw_update = offset);
@@ -76,8 +104,14 @@ impl ReplicaLeaderController<FileReplica> {
                     if let Some(command) = controller_req {
                         match command {
 
-                            LeaderReplicaControllerCommand::UpdateReplicaFromSc(_) => {
+                            LeaderReplicaControllerCommand::UpdateReplicaFromSc(replica) => {
                                 debug!("update replica from sc");
+                                self.state.update_followers(&replica).await;
+                                self.send_status_to_sc().await;
+                            },
+                            LeaderReplicaControllerCommand::FollowerInSync(follower_id) => {
+                                debug!(follower_id, "follower in sync");
+                                self.send_status_to_sc().await;
                             },
                             LeaderReplicaControllerCommand::RemoveReplicaFromSc => {
                                 debug!("RemoveReplica command, exiting");
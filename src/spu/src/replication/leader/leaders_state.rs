@@ -80,8 +80,9 @@ impl ReplicaLeadersState<FileReplica> {
         sink_channel: SharedSinkMessageChannel,
     ) -> Result<LeaderReplicaState<FileReplica>, StorageError> {
         let replica_id = replica.id.clone();
+        let storage_config = ctx.config().replica_storage_config(&replica);
 
-        match LeaderReplicaState::create(replica, ctx.config()).await {
+        match LeaderReplicaState::create(replica, ctx.config(), storage_config).await {
             Ok((leader_replica, receiver)) => {
                 debug!("file replica created and spawing leader controller");
                 self.spawn_leader_controller(
@@ -7,10 +7,13 @@ mod peer_api;
 mod update_offsets;
 mod actions;
 mod spu;
+mod producers;
 
 pub use self::leader_controller::ReplicaLeaderController;
 pub use self::leaders_state::{ReplicaLeadersState, SharedReplicaLeadersState};
-pub use self::replica_state::{SharedFileLeaderState, SharedLeaderState, LeaderReplicaState};
+pub use self::replica_state::{
+    SharedFileLeaderState, SharedLeaderState, LeaderReplicaState, ProducerWrite,
+};
 pub use self::connection::FollowerHandler;
 pub use self::api_key::LeaderPeerApiEnum;
 pub use self::peer_api::LeaderPeerRequest;
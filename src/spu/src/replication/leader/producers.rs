@@ -0,0 +1,238 @@
+//!
+//! # Idempotent producer state
+//!
+//! Leader tracks last batch written by each idempotent producer, so batch retried by producer
+//! is not written twice.
+//! State is restored from batch headers in the log when leader replica is loaded.
+//!
+
+use std::collections::HashMap;
+
+use dataplane::batch::BatchHeader;
+use dataplane::record::RecordSet;
+use dataplane::{ErrorCode, Offset};
+
+/// last batch written by producer
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ProducerEntry {
+    epoch: i16,
+    first_sequence: i32,
+    last_sequence: i32,
+    base_offset: Offset,
+}
+
+impl ProducerEntry {
+    fn new(header: &BatchHeader, base_offset: Offset) -> Self {
+        Self {
+            epoch: header.producer_epoch,
+            first_sequence: header.first_sequence,
+            last_sequence: header.last_sequence(),
+            base_offset,
+        }
+    }
+}
+
+/// outcome of checking batch sequence against producer state
+#[derive(Debug, PartialEq)]
+pub enum SequenceCheck {
+    /// batches can be written
+    Accept,
+    /// batch has already been written at base offset
+    Duplicate(Offset),
+    /// batches must be rejected
+    Reject(ErrorCode),
+}
+
+/// sequence state of idempotent producers for a replica
+#[derive(Debug, Default)]
+pub struct ProducerStateMap {
+    producers: HashMap<i64, ProducerEntry>,
+}
+
+impl ProducerStateMap {
+    /// restore state from last batch of each producer written to the log
+    pub fn restore(last_batches: Vec<(BatchHeader, Offset)>) -> Self {
+        Self {
+            producers: last_batches
+                .iter()
+                .map(|(header, base_offset)| {
+                    (header.producer_id, ProducerEntry::new(header, *base_offset))
+                })
+                .collect(),
+        }
+    }
+
+    /// check batches in record set against last sequence of each producer
+    pub fn check(&self, records: &RecordSet) -> SequenceCheck {
+        // batches earlier in the set are taken into account for later batches
+        let mut pending: HashMap<i64, ProducerEntry> = HashMap::new();
+
+        for batch in &records.batches {
+            let header = batch.get_header();
+            // transaction markers are written by leader and don't carry producer sequence
+            if !header.is_idempotent() || header.is_control() {
+                continue;
+            }
+
+            let last = pending
+                .get(&header.producer_id)
+                .or_else(|| self.producers.get(&header.producer_id));
+            match check_sequence(last, header) {
+                SequenceCheck::Accept => {
+                    pending.insert(header.producer_id, ProducerEntry::new(header, -1));
+                }
+                SequenceCheck::Duplicate(base_offset) if records.batches.len() == 1 => {
+                    return SequenceCheck::Duplicate(base_offset)
+                }
+                SequenceCheck::Duplicate(_) => {
+                    return SequenceCheck::Reject(ErrorCode::DuplicateSequenceNumber)
+                }
+                reject => return reject,
+            }
+        }
+
+        SequenceCheck::Accept
+    }
+
+    /// record batches which has been written
+    pub fn update(&mut self, records: &RecordSet) {
+        for batch in &records.batches {
+            let header = batch.get_header();
+            if header.is_idempotent() && !header.is_control() {
+                self.producers.insert(
+                    header.producer_id,
+                    ProducerEntry::new(header, batch.get_base_offset()),
+                );
+            }
+        }
+    }
+}
+
+fn check_sequence(last: Option<&ProducerEntry>, header: &BatchHeader) -> SequenceCheck {
+    let last = match last {
+        // state is not known for new producer, or its batches has been removed from the log
+        None => return SequenceCheck::Accept,
+        Some(last) => last,
+    };
+
+    if header.producer_epoch < last.epoch {
+        return SequenceCheck::Reject(ErrorCode::InvalidProducerEpoch);
+    }
+
+    if header.producer_epoch > last.epoch {
+        return SequenceCheck::Accept;
+    }
+
+    if header.first_sequence == last.first_sequence && header.last_sequence() == last.last_sequence
+    {
+        return SequenceCheck::Duplicate(last.base_offset);
+    }
+
+    if header.first_sequence == last.last_sequence.wrapping_add(1) {
+        SequenceCheck::Accept
+    } else if header.first_sequence <= last.last_sequence {
+        SequenceCheck::Reject(ErrorCode::DuplicateSequenceNumber)
+    } else {
+        SequenceCheck::Reject(ErrorCode::OutOfOrderSequenceNumber)
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use dataplane::batch::DefaultBatch;
+    use dataplane::record::DefaultRecord;
+
+    use super::*;
+
+    fn producer_records(producer_id: i64, epoch: i16, sequence: i32, count: usize) -> RecordSet {
+        let records = (0..count).map(|_| DefaultRecord::new("value")).collect();
+        let mut batch = DefaultBatch::new(records);
+        batch
+            .get_mut_header()
+            .set_producer(producer_id, epoch, sequence);
+        RecordSet::default().add(batch)
+    }
+
+    fn write(state: &mut ProducerStateMap, records: &mut RecordSet, base_offset: Offset) {
+        for batch in records.batches.iter_mut() {
+            batch.set_base_offset(base_offset);
+        }
+        state.update(records);
+    }
+
+    #[test]
+    fn test_producer_sequence() {
+        let mut state = ProducerStateMap::default();
+
+        let mut first = producer_records(1, 0, 0, 3);
+        assert_eq!(state.check(&first), SequenceCheck::Accept);
+        write(&mut state, &mut first, 10);
+
+        // retry of same batch returns offset of first write
+        assert_eq!(
+            state.check(&producer_records(1, 0, 0, 3)),
+            SequenceCheck::Duplicate(10)
+        );
+
+        // next batch
+        let mut second = producer_records(1, 0, 3, 2);
+        assert_eq!(state.check(&second), SequenceCheck::Accept);
+        write(&mut state, &mut second, 13);
+
+        // older batch
+        assert_eq!(
+            state.check(&producer_records(1, 0, 0, 3)),
+            SequenceCheck::Reject(ErrorCode::DuplicateSequenceNumber)
+        );
+
+        // gap in sequence
+        assert_eq!(
+            state.check(&producer_records(1, 0, 10, 1)),
+            SequenceCheck::Reject(ErrorCode::OutOfOrderSequenceNumber)
+        );
+
+        // stale epoch
+        assert_eq!(
+            state.check(&producer_records(1, -1, 5, 1)),
+            SequenceCheck::Reject(ErrorCode::InvalidProducerEpoch)
+        );
+
+        // renewed epoch starts sequences over
+        assert_eq!(
+            state.check(&producer_records(1, 1, 0, 1)),
+            SequenceCheck::Accept
+        );
+
+        // other producers and non idempotent batches are not affected
+        assert_eq!(
+            state.check(&producer_records(2, 0, 7, 1)),
+            SequenceCheck::Accept
+        );
+        assert_eq!(
+            state.check(&producer_records(-1, -1, -1, 1)),
+            SequenceCheck::Accept
+        );
+    }
+
+    #[test]
+    fn test_restore_producer_state() {
+        let mut written = producer_records(1, 0, 0, 3);
+        written.batches[0].set_base_offset(10);
+        let header = written.batches[0].get_header().clone();
+        let state = ProducerStateMap::restore(vec![(header, 10)]);
+
+        assert_eq!(
+            state.check(&producer_records(1, 0, 0, 3)),
+            SequenceCheck::Duplicate(10)
+        );
+        assert_eq!(
+            state.check(&producer_records(1, 0, 3, 1)),
+            SequenceCheck::Accept
+        );
+        assert_eq!(
+            state.check(&producer_records(1, 0, 5, 1)),
+            SequenceCheck::Reject(ErrorCode::OutOfOrderSequenceNumber)
+        );
+    }
+}
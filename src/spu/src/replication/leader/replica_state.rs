@@ -14,7 +14,9 @@ use async_channel::{Sender, Receiver, SendError};
 
 use fluvio_socket::{FlvSink, FlvSocketError};
 use dataplane::{record::RecordSet};
-use dataplane::{Offset, Isolation};
+use dataplane::batch::DefaultBatch;
+use dataplane::transaction::ControlRecordType;
+use dataplane::{ErrorCode, Offset, Isolation};
 use dataplane::api::RequestMessage;
 use fluvio_controlplane_metadata::partition::{Replica};
 use fluvio_controlplane::LrsRequest;
@@ -30,11 +32,24 @@ use crate::replication::follower::sync::{
 };
 use super::super::follower::FollowerReplicaState;
 use crate::storage::SharableReplicaStorage;
+use crate::smart_stream::produce::SharedTopicSmartStream;
 
 pub type SharedLeaderState<S> = LeaderReplicaState<S>;
 pub type SharedFileLeaderState = LeaderReplicaState<FileReplica>;
 
 use super::LeaderReplicaControllerCommand;
+use super::producers::{ProducerStateMap, SequenceCheck};
+
+/// result of writing records from producer
+#[derive(Debug, PartialEq)]
+pub enum ProducerWrite {
+    /// records has been written, contains log end offset after write
+    Written(Offset),
+    /// batch has already been written by idempotent producer at base offset
+    Duplicate(Offset),
+    /// records are rejected because of invalid producer sequence
+    Rejected(ErrorCode),
+}
 
 #[derive(Debug)]
 pub struct LeaderReplicaState<S> {
@@ -43,6 +58,8 @@ pub struct LeaderReplicaState<S> {
     config: ReplicationConfig,
     followers: Arc<RwLock<BTreeMap<SpuId, OffsetInfo>>>,
     sender: Sender<LeaderReplicaControllerCommand>,
+    producers: Arc<RwLock<ProducerStateMap>>,
+    smart_stream: SharedTopicSmartStream,
 }
 
 impl<S> Clone for LeaderReplicaState<S> {
@@ -53,6 +70,8 @@ impl<S> Clone for LeaderReplicaState<S> {
             config: self.config.clone(),
             followers: self.followers.clone(),
             sender: self.sender.clone(),
+            producers: self.producers.clone(),
+            smart_stream: self.smart_stream.clone(),
         }
     }
 }
@@ -93,8 +112,9 @@ impl<S> LeaderReplicaState<S>
 where
     S: ReplicaStorage,
 {
-    /// create ne state from existing storage
-    pub fn new(
+    /// create new state from existing storage
+    /// producer sequences are restored from the log
+    pub async fn new(
         replica: Replica,
         config: ReplicationConfig,
         inner: SharableReplicaStorage<S>,
@@ -102,12 +122,15 @@ where
     ) -> Self {
         let follower_ids = HashSet::from_iter(replica.replicas);
         let followers = ids_to_map(replica.leader, follower_ids);
+        let producers = ProducerStateMap::restore(inner.read().await.last_producer_batches());
         Self {
             leader: replica.leader,
             storage: inner,
             config,
             followers: Arc::new(RwLock::new(followers)),
             sender,
+            producers: Arc::new(RwLock::new(producers)),
+            smart_stream: Arc::default(),
         }
     }
 
@@ -115,6 +138,7 @@ where
     pub async fn create<'a, C>(
         replica: Replica,
         config: &'a C,
+        storage_config: S::Config,
     ) -> Result<
         (
             LeaderReplicaState<S>,
@@ -124,26 +148,30 @@ where
     >
     where
         ReplicationConfig: From<&'a C>,
-        S::Config: From<&'a C>,
     {
         use async_channel::bounded;
 
         let (sender, receiver) = bounded(10);
 
-        let inner = SharableReplicaStorage::create(replica.id.clone(), config.into()).await?;
+        let inner = SharableReplicaStorage::create(replica.id.clone(), storage_config).await?;
 
-        let leader_replica = Self::new(replica, config.into(), inner, sender);
+        let leader_replica = Self::new(replica, config.into(), inner, sender).await;
         Ok((leader_replica, receiver))
     }
 
-    pub fn promoted_from(
+    pub async fn promoted_from(
         follower: FollowerReplicaState<S>,
         replica: Replica,
         config: ReplicationConfig,
         sender: Sender<LeaderReplicaControllerCommand>,
     ) -> Self {
         let replica_storage = follower.inner_owned();
-        Self::new(replica, config, replica_storage, sender)
+        Self::new(replica, config, replica_storage, sender).await
+    }
+
+    /// SmartStream of topic applied to produced records
+    pub fn smart_stream(&self) -> &SharedTopicSmartStream {
+        &self.smart_stream
     }
 
     /// send message to leader controller
@@ -174,32 +202,63 @@ where
         }
 
         // get follower info
-        let mut followers = self.followers.write().await;
-        if let Some(current_follow_info) = followers.get_mut(&follower_id) {
-            if current_follow_info.update(&follower_pos) {
-                // if our leo and hw is same there is no need to recompute hw
-                if !leader_pos.is_committed() {
-                    if let Some(hw) =
-                        compute_hw(&leader_pos, self.config.min_in_sync_replicas, &followers)
-                    {
-                        debug!(hw, "updating hw");
-                        if let Err(err) = self.update_hw(hw).await {
-                            error!("error updating hw: {}", err);
+        let (changed, caught_up) = {
+            let mut followers = self.followers.write().await;
+            if let Some(current_follow_info) = followers.get_mut(&follower_id) {
+                let was_in_sync = is_in_sync(current_follow_info, &leader_pos);
+                if current_follow_info.update(&follower_pos) {
+                    let caught_up = !was_in_sync && is_in_sync(current_follow_info, &leader_pos);
+                    // if our leo and hw is same there is no need to recompute hw
+                    if !leader_pos.is_committed() {
+                        if let Some(hw) =
+                            compute_hw(&leader_pos, self.config.min_in_sync_replicas, &followers)
+                        {
+                            debug!(hw, "updating hw");
+                            if let Err(err) = self.update_hw(hw).await {
+                                error!("error updating hw: {}", err);
+                            }
+                        } else {
+                            debug!("no change");
                         }
                     } else {
-                        debug!("no change");
+                        debug!("leader is committed");
                     }
+                    (true, caught_up)
                 } else {
-                    debug!("leader is committed");
+                    (false, false)
                 }
-                true
             } else {
-                false
+                error!(follower_id, "invalid follower");
+                (false, false)
             }
-        } else {
-            error!(follower_id, "invalid follower");
-            false
+        };
+
+        // sc waits for followers to be in sync before moving replicas
+        if caught_up {
+            debug!(follower_id, "follower is in sync");
+            if let Err(err) = self
+                .send_message_to_controller(LeaderReplicaControllerCommand::FollowerInSync(
+                    follower_id,
+                ))
+                .await
+            {
+                error!("error notifying controller: {}", err);
+            }
+        }
+
+        changed
+    }
+
+    /// sync followers with replicas assigned by sc.
+    /// followers added by reassignment start with unknown offsets until they report back,
+    /// followers moved out are no longer tracked
+    pub async fn update_followers(&self, replica: &Replica) {
+        let mut followers = self.followers.write().await;
+        followers.retain(|id, _| replica.replicas.contains(id));
+        for id in replica.replicas.iter().filter(|id| **id != self.leader) {
+            followers.entry(*id).or_insert_with(OffsetInfo::default);
         }
+        debug!(followers = ?followers.keys(), "updated followers");
     }
 
     /// compute follower that needs to be updated
@@ -257,12 +316,68 @@ where
         sc_sink.send(lrs).await
     }
 
-    pub async fn write_record_set(&self, records: &mut RecordSet) -> Result<(), StorageError> {
+    /// write records, return log end offset after write
+    pub async fn write_record_set(&self, records: &mut RecordSet) -> Result<Offset, StorageError> {
         self.storage
             .write_record_set(records, self.config.min_in_sync_replicas == 1)
             .await
     }
 
+    /// write records from producer
+    /// batch retried by idempotent producer is not written again
+    pub async fn write_producer_record_set(
+        &self,
+        records: &mut RecordSet,
+    ) -> Result<ProducerWrite, StorageError> {
+        let mut producers = self.producers.write().await;
+        match producers.check(records) {
+            SequenceCheck::Accept => {
+                let leo = self.write_record_set(records).await?;
+                producers.update(records);
+                Ok(ProducerWrite::Written(leo))
+            }
+            SequenceCheck::Duplicate(base_offset) => {
+                debug!(base_offset, "skipping duplicate batch");
+                Ok(ProducerWrite::Duplicate(base_offset))
+            }
+            SequenceCheck::Reject(error_code) => {
+                warn!(?error_code, "rejecting producer batch");
+                Ok(ProducerWrite::Rejected(error_code))
+            }
+        }
+    }
+
+    /// write marker which completes transaction of producer.
+    /// marker is skipped if producer has no ongoing transaction,
+    /// so marker sent again by SC is not written twice. return false if marker is skipped
+    pub async fn write_txn_marker(
+        &self,
+        producer_id: i64,
+        producer_epoch: i16,
+        commit: bool,
+    ) -> Result<bool, StorageError> {
+        // hold producer state so marker is not interleaved with producer batches
+        let _producers = self.producers.write().await;
+        if !self
+            .storage
+            .read()
+            .await
+            .has_ongoing_transaction(producer_id)
+        {
+            return Ok(false);
+        }
+
+        let control = if commit {
+            ControlRecordType::Commit
+        } else {
+            ControlRecordType::Abort
+        };
+        let mut records =
+            RecordSet::default().add(DefaultBatch::control(producer_id, producer_epoch, control));
+        self.write_record_set(&mut records).await?;
+        Ok(true)
+    }
+
     #[allow(dead_code)]
     pub async fn live_replicas(&self) -> Vec<SpuId> {
         self.followers.read().await.keys().cloned().collect()
@@ -330,6 +445,11 @@ where
     }
 }
 
+/// follower has replicated all records committed by leader
+fn is_in_sync(follower: &OffsetInfo, leader: &OffsetInfo) -> bool {
+    follower.is_valid() && follower.leo >= leader.hw
+}
+
 /// compute leader's updated hw based on follower offset
 /// this is done after follower's leo updated
 /// min_replica must be at least 1 and must be less than followers.len(0)
@@ -677,12 +797,6 @@ mod test_leader {
         pos: OffsetInfo,
     }
 
-    impl From<&SpuConfig> for MockConfig {
-        fn from(_log: &SpuConfig) -> MockConfig {
-            MockConfig::default()
-        }
-    }
-
     #[async_trait]
     impl ReplicaStorage for MockStorage {
         async fn create(
@@ -742,6 +856,26 @@ mod test_leader {
             todo!()
         }
 
+        async fn remove_expired_segments(&mut self) -> Result<bool, fluvio_storage::StorageError> {
+            Ok(false)
+        }
+
+        async fn compact_segments(&mut self) -> Result<bool, fluvio_storage::StorageError> {
+            Ok(false)
+        }
+
+        fn find_offset_by_timestamp(&self, _timestamp: i64) -> Offset {
+            self.pos.leo
+        }
+
+        fn last_producer_batches(&self) -> Vec<(dataplane::batch::BatchHeader, Offset)> {
+            vec![]
+        }
+
+        fn has_ongoing_transaction(&self, _producer_id: i64) -> bool {
+            false
+        }
+
         async fn remove(&self) -> Result<(), fluvio_storage::StorageError> {
             todo!()
         }
@@ -758,6 +892,7 @@ mod test_leader {
         let (state, _): (LeaderReplicaState<MockStorage>, _) = LeaderReplicaState::create(
             Replica::new(replica, 5000, vec![5001, 5002]),
             &leader_config,
+            MockConfig::default(),
         )
         .await
         .expect("state");
@@ -831,6 +966,7 @@ mod test_leader {
         let (state, _): (LeaderReplicaState<MockStorage>, _) = LeaderReplicaState::create(
             Replica::new(replica, 5000, vec![5001, 5002]),
             &leader_config,
+            MockConfig::default(),
         )
         .await
         .expect("state");
@@ -873,4 +1009,52 @@ mod test_leader {
         assert_eq!(state.hw(), 6);
         Ok(())
     }
+
+    #[test_async]
+    async fn test_update_followers_from_reassignment() -> Result<(), ()> {
+        let mut leader_config = SpuConfig::default();
+        leader_config.replication.min_in_sync_replicas = 1;
+        leader_config.id = 5000;
+
+        let replica: ReplicaKey = ("test", 1).into();
+        let (state, receiver): (LeaderReplicaState<MockStorage>, _) = LeaderReplicaState::create(
+            Replica::new(replica.clone(), 5000, vec![5000, 5001]),
+            &leader_config,
+            MockConfig::default(),
+        )
+        .await
+        .expect("state");
+
+        state
+            .write_record_set(&mut create_recordset(10))
+            .await
+            .expect("write");
+        assert_eq!(state.hw(), 10);
+
+        // 5001 is moved out to 5002
+        state
+            .update_followers(&Replica::new(replica, 5000, vec![5000, 5002]))
+            .await;
+        assert_eq!(state.live_replicas().await, vec![5002]);
+
+        // 5002 is still catching up
+        assert!(
+            state
+                .update_states_from_followers(5002, OffsetInfo { leo: 4, hw: 0 })
+                .await
+        );
+        assert!(receiver.try_recv().is_err());
+
+        // 5002 has replicated all committed records
+        assert!(
+            state
+                .update_states_from_followers(5002, OffsetInfo { leo: 10, hw: 10 })
+                .await
+        );
+        assert!(matches!(
+            receiver.try_recv(),
+            Ok(LeaderReplicaControllerCommand::FollowerInSync(5002))
+        ));
+        Ok(())
+    }
 }
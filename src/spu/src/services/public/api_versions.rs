@@ -30,7 +30,7 @@ pub async fn handle_kf_lookup_version_request(
     ));
     response.api_keys.push(make_version_key(
         SpuServerApiKey::FetchOffsets,
-        FetchOffsetsRequest::DEFAULT_API_VERSION,
+        0,
         FetchOffsetsRequest::DEFAULT_API_VERSION,
     ));
     response.api_keys.push(make_version_key(
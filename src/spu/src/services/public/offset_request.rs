@@ -42,6 +42,10 @@ pub async fn handle_offset_request(
                 partition_response.error_code = ErrorCode::None;
                 partition_response.start_offset = start_offset;
                 partition_response.last_stable_offset = hw;
+                if let Some(timestamp) = partition_req.timestamp {
+                    partition_response.timestamp_offset =
+                        Some(replica.find_offset_by_timestamp(timestamp).await);
+                }
             } else {
                 trace!("offset fetch request is not found: {}", rep_id);
                 partition_response.error_code = ErrorCode::PartitionNotLeader;
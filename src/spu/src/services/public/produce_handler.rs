@@ -1,19 +1,86 @@
 use std::io::Error;
+use std::mem;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use fluvio_storage::StorageError;
-use tracing::{debug, trace, error};
+use tracing::{debug, trace, error, warn};
 use tracing::instrument;
+use tokio::select;
 
-use dataplane::ErrorCode;
+use fluvio_future::task::spawn_blocking;
+use fluvio_future::timer::sleep;
+use dataplane::{ErrorCode, Isolation, Offset};
 use dataplane::produce::{
     DefaultProduceRequest, ProduceResponse, TopicProduceResponse, PartitionProduceResponse,
 };
-use dataplane::api::RequestMessage;
+use dataplane::api::{RequestHeader, RequestMessage};
 use dataplane::api::ResponseMessage;
+use dataplane::record::RecordSet;
 use fluvio_controlplane_metadata::partition::ReplicaKey;
+use fluvio_controlplane_metadata::topic::TopicSmartStreamKind;
+use fluvio_spu_schema::server::stream_fetch::SmartStreamKind;
 
 use crate::core::DefaultSharedGlobalContext;
+use crate::replication::leader::{SharedFileLeaderState, ProducerWrite};
+use crate::smart_stream::filter::SmartStreamLimitError;
+use crate::smart_stream::produce::TopicSmartStreamFunction;
 
+/// no acknowledgement, producer doesn't wait for response
+const ACKS_NONE: i16 = 0;
+/// acknowledged when leader has written records
+const ACKS_LEADER: i16 = 1;
+/// acknowledged when records has been committed by in-sync replicas
+const ACKS_ALL: i16 = -1;
+
+/// response of produce request, partitions written with acks all wait for records to be committed
+pub struct PendingProduceResponse {
+    header: RequestHeader,
+    response: ProduceResponse,
+    timeout: Duration,
+    pending: Vec<PendingCommit>,
+}
+
+/// partition waiting until its high watermark reaches offset
+struct PendingCommit {
+    topic_index: usize,
+    partition_index: usize,
+    leader_state: SharedFileLeaderState,
+    offset: Offset,
+}
+
+impl PendingProduceResponse {
+    /// true if any partition waits for commit
+    pub fn has_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// wait for partitions to be committed, partitions not committed within timeout are timed out
+    pub async fn wait_for_commit(mut self) -> ResponseMessage<ProduceResponse> {
+        let deadline = Instant::now() + self.timeout;
+        for pending in self.pending.drain(..) {
+            let timeout = deadline.saturating_duration_since(Instant::now());
+            if !wait_for_commit(&pending.leader_state, pending.offset, timeout).await {
+                debug!(
+                    leo = pending.offset,
+                    hw = pending.leader_state.hw(),
+                    "timed out waiting for commit"
+                );
+                self.response.responses[pending.topic_index].partitions[pending.partition_index]
+                    .error_code = ErrorCode::RequestTimedOut;
+            }
+        }
+        self.into_response()
+    }
+
+    /// response without waiting for pending partitions
+    pub fn into_response(self) -> ResponseMessage<ProduceResponse> {
+        RequestMessage::<DefaultProduceRequest>::response_with_header(&self.header, self.response)
+    }
+}
+
+/// handle produce request, response is not returned if producer doesn't require acknowledgement.
+/// records are written before returning, so writes of a connection are applied in order,
+/// while waiting for commit is left to caller
 #[instrument(
     skip(request,ctx),
     fields(
@@ -24,15 +91,18 @@ use crate::core::DefaultSharedGlobalContext;
 pub async fn handle_produce_request(
     request: RequestMessage<DefaultProduceRequest>,
     ctx: DefaultSharedGlobalContext,
-) -> Result<ResponseMessage<ProduceResponse>, Error> {
+) -> Result<Option<PendingProduceResponse>, Error> {
     let (header, produce_request) = request.get_header_request();
     trace!("handling produce request: {:#?}", produce_request);
 
     let mut response = ProduceResponse::default();
 
-    //let ack = produce_request.acks;
+    let acks = produce_request.acks;
+    let timeout = Duration::from_millis(produce_request.timeout_ms.max(0) as u64);
+    let invalid_acks = !matches!(acks, ACKS_NONE | ACKS_LEADER | ACKS_ALL);
+    let mut pending = vec![];
 
-    for topic_request in produce_request.topics.into_iter() {
+    for (topic_index, topic_request) in produce_request.topics.into_iter().enumerate() {
         let topic = &topic_request.name;
         trace!("handling produce request for topic{}", topic);
 
@@ -41,7 +111,9 @@ pub async fn handle_produce_request(
             ..Default::default()
         };
 
-        for mut partition_request in topic_request.partitions.into_iter() {
+        for (partition_index, mut partition_request) in
+            topic_request.partitions.into_iter().enumerate()
+        {
             let rep_id = ReplicaKey::new(topic.clone(), partition_request.partition_index);
 
             trace!("handling produce request for replia: {}", rep_id);
@@ -51,22 +123,59 @@ pub async fn handle_produce_request(
                 ..Default::default()
             };
 
-            if let Some(leader_state) = ctx.leaders_state().get(&rep_id) {
-                match leader_state
-                    .write_record_set(&mut partition_request.records)
-                    .await
+            if invalid_acks {
+                warn!(acks, "invalid acks");
+                partition_response.error_code = ErrorCode::InvalidRequiredAcks;
+            } else if let Some(leader_state) = ctx.leaders_state().get(&rep_id) {
+                if let Err(error_code) =
+                    apply_smart_stream(&ctx, &leader_state, &rep_id, &mut partition_request.records)
+                        .await
                 {
-                    Ok(_) => {
-                        partition_response.error_code = ErrorCode::None;
-                    }
-                    Err(err) => {
-                        error!("error: {:#?} writing to replica: {}", err, rep_id);
-                        match err {
-                            StorageError::BatchTooBig(_) => {
-                                partition_response.error_code = ErrorCode::MessageTooLarge
+                    partition_response.error_code = error_code;
+                } else {
+                    match leader_state
+                        .write_producer_record_set(&mut partition_request.records)
+                        .await
+                    {
+                        Ok(ProducerWrite::Written(leo)) => {
+                            partition_response.base_offset =
+                                partition_request.records.base_offset();
+                            partition_response.log_append_time_ms = current_time_ms();
+                            partition_response.error_code = ErrorCode::None;
+                            if acks == ACKS_ALL {
+                                pending.push(PendingCommit {
+                                    topic_index,
+                                    partition_index,
+                                    leader_state: leader_state.clone(),
+                                    offset: leo,
+                                });
+                            }
+                        }
+                        Ok(ProducerWrite::Duplicate(base_offset)) => {
+                            partition_response.base_offset = base_offset;
+                            partition_response.log_append_time_ms = -1;
+                            partition_response.error_code = ErrorCode::None;
+                            if acks == ACKS_ALL {
+                                pending.push(PendingCommit {
+                                    topic_index,
+                                    partition_index,
+                                    leader_state: leader_state.clone(),
+                                    offset: leader_state.leo(),
+                                });
                             }
-                            _ => {
-                                partition_response.error_code = ErrorCode::StorageError;
+                        }
+                        Ok(ProducerWrite::Rejected(error_code)) => {
+                            partition_response.error_code = error_code;
+                        }
+                        Err(err) => {
+                            error!("error: {:#?} writing to replica: {}", err, rep_id);
+                            match err {
+                                StorageError::BatchTooBig(_) => {
+                                    partition_response.error_code = ErrorCode::MessageTooLarge
+                                }
+                                _ => {
+                                    partition_response.error_code = ErrorCode::StorageError;
+                                }
                             }
                         }
                     }
@@ -84,5 +193,241 @@ pub async fn handle_produce_request(
 
     trace!("produce request completed");
 
-    Ok(RequestMessage::<DefaultProduceRequest>::response_with_header(&header, response))
+    if acks == ACKS_NONE {
+        debug!("no acknowledgement required, skipping response");
+        return Ok(None);
+    }
+
+    Ok(Some(PendingProduceResponse {
+        header,
+        response,
+        timeout,
+        pending,
+    }))
+}
+
+/// apply SmartStream of topic to records before they are written.
+/// records are rejected when module fails on any of them
+async fn apply_smart_stream(
+    ctx: &DefaultSharedGlobalContext,
+    leader_state: &SharedFileLeaderState,
+    rep_id: &ReplicaKey,
+    records: &mut RecordSet,
+) -> Result<(), ErrorCode> {
+    let topic_smart_stream = match ctx
+        .replica_localstore()
+        .spec(rep_id)
+        .and_then(|replica| replica.smart_stream)
+    {
+        Some(smart_stream) => smart_stream,
+        None => return Ok(()),
+    };
+
+    let smart_stream = match ctx.smart_stream_localstore().spec(&topic_smart_stream.name) {
+        Some(smart_stream) => smart_stream,
+        None => {
+            warn!(%rep_id, name = %topic_smart_stream.name, "smartstream of topic not found");
+            return Err(ErrorCode::SmartStreamNotFound);
+        }
+    };
+
+    let kind = match topic_smart_stream.kind {
+        TopicSmartStreamKind::Filter => SmartStreamKind::Filter,
+        TopicSmartStreamKind::Map => SmartStreamKind::Map,
+    };
+
+    // held until records are processed, so cached function is used by one request at a time
+    let mut cached = leader_state.smart_stream().lock().await;
+    let mut function = match cached.take() {
+        Some(function) if function.is_current(&smart_stream, kind) => function,
+        _ => TopicSmartStreamFunction::create(ctx.smart_stream_engine(), &smart_stream, kind)
+            .map_err(|err| {
+                error!(%rep_id, %err, "failed to create smartstream of topic");
+                ErrorCode::SmartStreamError
+            })?,
+    };
+
+    let mut input = mem::take(records);
+    let (function, output, result) = spawn_blocking(move || {
+        let result = function.process(&mut input);
+        (function, input, result)
+    })
+    .await;
+    *records = output;
+
+    match result {
+        Ok(None) => {
+            *cached = Some(function);
+            Ok(())
+        }
+        Ok(Some(runtime_error)) => {
+            debug!(%rep_id, offset = runtime_error.offset, message = %runtime_error.message, "record rejected by smartstream");
+            *cached = Some(function);
+            Err(ErrorCode::SmartStreamRecordRejected)
+        }
+        // instance may be left in inconsistent state, it is created again for next request
+        Err(err) => {
+            warn!(%rep_id, %err, "smartstream of topic failed");
+            Err(match err.downcast_ref::<SmartStreamLimitError>() {
+                Some(limit_err) => limit_err.error_code(),
+                None => ErrorCode::SmartStreamRuntimeError,
+            })
+        }
+    }
+}
+
+/// current time in milliseconds since epoch, used as log append time
+fn current_time_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as i64)
+        .unwrap_or(-1)
+}
+
+/// wait until high watermark reaches offset or timeout expires
+/// return true if offset has been committed
+async fn wait_for_commit(
+    leader_state: &SharedFileLeaderState,
+    offset: Offset,
+    timeout: Duration,
+) -> bool {
+    if leader_state.hw() >= offset {
+        return true;
+    }
+
+    let mut listener = leader_state.offset_listener(&Isolation::ReadCommitted);
+    let timer = sleep(timeout);
+    tokio::pin!(timer);
+
+    loop {
+        if leader_state.hw() >= offset {
+            return true;
+        }
+
+        select! {
+            _ = &mut timer => {
+                return leader_state.hw() >= offset;
+            },
+            hw = listener.listen() => {
+                trace!(hw, offset, "hw changed");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(target_os = "linux")]
+mod test {
+
+    use std::env::temp_dir;
+
+    use flv_util::fixture::ensure_clean_dir;
+    use fluvio_future::test_async;
+    use dataplane::batch::DefaultBatch;
+    use dataplane::record::DefaultRecord;
+    use dataplane::produce::{DefaultPartitionRequest, DefaultTopicRequest};
+    use fluvio_controlplane_metadata::partition::Replica;
+    use fluvio_controlplane_metadata::smartstream::{SmartStream, SmartStreamSpec};
+    use fluvio_controlplane_metadata::topic::TopicSmartStream;
+
+    use crate::core::GlobalContext;
+    use crate::config::SpuConfig;
+    use crate::replication::leader::LeaderReplicaState;
+    use crate::services::public::stream_fetch::test::load_wasm_module;
+    use super::*;
+
+    fn produce_request(topic: &str, values: &[&str]) -> RequestMessage<DefaultProduceRequest> {
+        let records = values
+            .iter()
+            .map(|value| DefaultRecord::new(value.to_string()))
+            .collect();
+        let partition = DefaultPartitionRequest {
+            partition_index: 0,
+            records: RecordSet::default().add(DefaultBatch::new(records)),
+        };
+        let topic = DefaultTopicRequest {
+            name: topic.to_owned(),
+            partitions: vec![partition],
+            ..Default::default()
+        };
+        RequestMessage::new_request(DefaultProduceRequest {
+            acks: ACKS_LEADER,
+            topics: vec![topic],
+            ..Default::default()
+        })
+    }
+
+    fn error_code(response: &ProduceResponse) -> ErrorCode {
+        response.responses[0].partitions[0].error_code
+    }
+
+    /// records are filtered by smartstream of topic, batch with failed record is rejected
+    #[test_async]
+    async fn test_produce_smart_stream() -> Result<(), ()> {
+        let test_path = temp_dir().join("produce_smart_stream");
+        ensure_clean_dir(&test_path);
+
+        let mut spu_config = SpuConfig::default();
+        spu_config.log.base_dir = test_path;
+        let ctx = GlobalContext::new_shared_context(spu_config);
+
+        // keeps records containing `a`, fails on records starting with `z`
+        ctx.smart_stream_localstore().insert(SmartStream::new(
+            "validate",
+            SmartStreamSpec::new(1, load_wasm_module("fluvio_error_test")),
+        ));
+
+        let topic = "produce-smart-stream";
+        let test = Replica::new((topic.to_owned(), 0), 5001, vec![]).with_smart_stream(Some(
+            TopicSmartStream::new("validate", TopicSmartStreamKind::Filter),
+        ));
+        let test_id = test.id.clone();
+        ctx.replica_localstore().insert(test.clone());
+        let (replica, _) = LeaderReplicaState::create(test, ctx.config(), ctx.config().into())
+            .await
+            .expect("replica");
+        ctx.leaders_state().insert(test_id, replica.clone());
+
+        let response = handle_produce_request(
+            produce_request(topic, &["apple", "berry", "banana"]),
+            ctx.clone(),
+        )
+        .await
+        .expect("produce")
+        .expect("response")
+        .into_response();
+        assert_eq!(error_code(&response.response), ErrorCode::None);
+        // dropped record still takes up offset
+        assert_eq!(replica.leo(), 3);
+        // function is kept for next request
+        assert!(replica.smart_stream().lock().await.is_some());
+
+        let response = handle_produce_request(
+            produce_request(topic, &["avocado", "zucchini"]),
+            ctx.clone(),
+        )
+        .await
+        .expect("produce")
+        .expect("response")
+        .into_response();
+        assert_eq!(
+            error_code(&response.response),
+            ErrorCode::SmartStreamRecordRejected
+        );
+        assert_eq!(replica.leo(), 3);
+
+        // smartstream must be registered
+        ctx.smart_stream_localstore().delete(&"validate".to_owned());
+        let response = handle_produce_request(produce_request(topic, &["apple"]), ctx.clone())
+            .await
+            .expect("produce")
+            .expect("response")
+            .into_response();
+        assert_eq!(
+            error_code(&response.response),
+            ErrorCode::SmartStreamNotFound
+        );
+
+        Ok(())
+    }
 }
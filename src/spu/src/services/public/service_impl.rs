@@ -1,6 +1,7 @@
 use std::{sync::Arc};
 
 use tracing::debug;
+use tracing::error;
 use tracing::trace;
 use async_trait::async_trait;
 use futures_util::io::AsyncRead;
@@ -11,6 +12,7 @@ use tokio::select;
 use fluvio_types::event::SimpleEvent;
 use fluvio_socket::{InnerFlvSocket, InnerFlvSink};
 use fluvio_future::zero_copy::ZeroCopyWrite;
+use fluvio_future::task::spawn;
 use fluvio_socket::FlvSocketError;
 use fluvio_service::{call_service, FlvService};
 use fluvio_spu_schema::server::{SpuServerApiKey, SpuServerRequest};
@@ -79,12 +81,23 @@ where
                                 ),
 
 
-                                SpuServerRequest::ProduceRequest(request) => call_service!(
-                                    request,
-                                    handle_produce_request(request,context.clone()),
-                                    s_sink,
-                                    "roduce request handler"
-                                ),
+                                SpuServerRequest::ProduceRequest(request) => {
+                                    let version = request.header.api_version();
+                                    if let Some(response) = handle_produce_request(request,context.clone()).await? {
+                                        if response.has_pending() {
+                                            // don't block other requests of connection while waiting for commit
+                                            let mut sink = s_sink.clone();
+                                            spawn(async move {
+                                                let response = response.wait_for_commit().await;
+                                                if let Err(err) = sink.send_response(&response, version).await {
+                                                    error!("error sending produce response: {}", err);
+                                                }
+                                            });
+                                        } else {
+                                            s_sink.send_response(&response.into_response(), version).await?;
+                                        }
+                                    }
+                                },
                                 SpuServerRequest::FileFetchRequest(request) => handle_fetch_request(request,context.clone(),s_sink.clone()).await?,
 
                                 SpuServerRequest::FetchOffsetsRequest(request) => call_service!(
@@ -1,5 +1,6 @@
 use std::sync::Arc;
 use std::time::{Instant};
+use std::collections::BTreeMap;
 use std::io::ErrorKind;
 use std::io::Error as IoError;
 
@@ -16,18 +17,20 @@ use dataplane::{
     ErrorCode,
     api::{RequestMessage, RequestHeader},
     fetch::FetchablePartitionResponse,
-    record::RecordSet,
+    record::{RecordSet, RawRecordSet},
+    smartstream::SmartStreamRuntimeError,
 };
 use dataplane::{Offset, Isolation, ReplicaKey};
 use dataplane::fetch::FilePartitionResponse;
 use fluvio_spu_schema::server::stream_fetch::{
-    FileStreamFetchRequest, DefaultStreamFetchRequest, StreamFetchResponse,
+    FileStreamFetchRequest, DefaultStreamFetchRequest, RawStreamFetchRequest, StreamFetchResponse,
+    SmartStreamKind,
 };
 use fluvio_types::event::offsets::OffsetChangeListener;
 
 use crate::core::DefaultSharedGlobalContext;
 use crate::replication::leader::SharedFileLeaderState;
-use crate::smart_stream::filter::{SmartStreamModule, SmartStreamEngine};
+use crate::smart_stream::filter::{SmartStreamModule, SmartStreamLimitError};
 use publishers::INIT_OFFSET;
 
 /// Fetch records as stream
@@ -43,8 +46,13 @@ pub struct StreamFetchHandler<S> {
     consumer_offset_listener: OffsetChangeListener,
     leader_state: SharedFileLeaderState,
     stream_id: u32,
-    sm_engine: SmartStreamEngine,
     sm_bytes: Vec<u8>,
+    /// name of smartstream registered in SC, takes precedence over `sm_bytes`
+    sm_name: String,
+    sm_kind: SmartStreamKind,
+    /// running aggregate before each offset records were sent from,
+    /// so records sent again to consumer are not aggregated twice
+    sm_accumulators: BTreeMap<Offset, Vec<u8>>,
 }
 
 impl<S> StreamFetchHandler<S>
@@ -68,6 +76,10 @@ where
         let replica = ReplicaKey::new(msg.topic, msg.partition);
         let max_bytes = msg.max_bytes as u32;
         let sm_bytes = msg.wasm_module;
+        let sm_name = msg.smart_stream_name;
+        let sm_kind = msg.smart_stream_kind;
+        let mut sm_accumulators = BTreeMap::new();
+        sm_accumulators.insert(current_offset, msg.aggregate_accumulator);
 
         if let Some(leader_state) = ctx.leaders_state().get(&replica) {
             let (stream_id, offset_publisher) =
@@ -81,11 +93,13 @@ where
                 current_offset,
                 max_bytes,
                 sm_bytes = sm_bytes.len(),
+                %sm_name,
+                ?sm_kind,
                 "start stream fetch"
             );
 
             // if we are filtered we should scan all batches instead of just limit to max bytes
-            let max_fetch_bytes = if sm_bytes.is_empty() {
+            let max_fetch_bytes = if sm_bytes.is_empty() && sm_name.is_empty() {
                 max_bytes
             } else {
                 u32::MAX
@@ -102,8 +116,10 @@ where
                 consumer_offset_listener: offset_listener,
                 stream_id,
                 leader_state: leader_state.clone(),
-                sm_engine: SmartStreamEngine::new(),
                 sm_bytes,
+                sm_name,
+                sm_kind,
+                sm_accumulators,
                 max_fetch_bytes,
             };
 
@@ -119,6 +135,7 @@ where
                     error_code: ErrorCode::NotLeaderForPartition,
                     ..Default::default()
                 },
+                smart_stream_error: None,
             };
 
             let response_msg =
@@ -153,24 +170,50 @@ where
     async fn inner_process(&mut self, starting_offset: Offset) -> Result<(), FlvSocketError> {
         // initialize smart stream module here instead of beginning because WASM module is not thread safe
         // and can't be send across Send
-        let module = if !self.sm_bytes.is_empty() {
+        let module = if !self.sm_name.is_empty() {
+            match self.ctx.smart_stream_localstore().spec(&self.sm_name) {
+                Some(smart_stream) => Some(
+                    self.ctx
+                        .smart_stream_engine()
+                        .create_module_from_registry(&smart_stream)
+                        .map_err(module_loading_error)?,
+                ),
+                None => {
+                    debug!(name = %self.sm_name, "smartstream is not registered");
+                    self.send_back_error_response(ErrorCode::SmartStreamNotFound, None)
+                        .await?;
+                    self.ctx
+                        .stream_publishers()
+                        .remove_publisher(self.stream_id)
+                        .await;
+                    return Ok(());
+                }
+            }
+        } else if !self.sm_bytes.is_empty() {
             Some(
-                self.sm_engine
+                self.ctx
+                    .smart_stream_engine()
                     .create_module_from_binary(&self.sm_bytes)
-                    .map_err(|err| -> FlvSocketError {
-                        FlvSocketError::IoError(IoError::new(
-                            ErrorKind::Other,
-                            format!("module loading error {}", err),
-                        ))
-                    })?,
+                    .map_err(module_loading_error)?,
             )
         } else {
             None
         };
 
-        let (mut last_read_offset, consumer_wait) = self
+        let (mut last_read_offset, consumer_wait) = match self
             .send_back_records(starting_offset, module.as_ref())
-            .await?;
+            .await?
+        {
+            Some(result) => result,
+            None => {
+                debug!("smart stream failed, stream is stopped");
+                self.ctx
+                    .stream_publishers()
+                    .remove_publisher(self.stream_id)
+                    .await;
+                return Ok(());
+            }
+        };
 
         let mut leader_offset_receiver = self.leader_state.offset_listener(&self.isolation);
 
@@ -200,6 +243,8 @@ where
 
                     if changed_consumer_offset != INIT_OFFSET  {
 
+                        self.prune_accumulators(changed_consumer_offset);
+
                         // consume hasn't read all offsets, need to send back gaps
                         if changed_consumer_offset < last_read_offset {
                             debug!(
@@ -207,7 +252,13 @@ where
                                 last_read_offset,
                                 "need send back"
                             );
-                            let (offset,wait) = self.send_back_records(changed_consumer_offset,module.as_ref()).await?;
+                            let (offset,wait) = match self.send_back_records(changed_consumer_offset,module.as_ref()).await? {
+                                Some(result) => result,
+                                None => {
+                                    debug!("smart stream failed, terminating");
+                                    break;
+                                }
+                            };
                             last_read_offset = offset;
                             if wait {
                                 consumer_offset = None;
@@ -241,7 +292,13 @@ where
                             debug!(leader_offset_update,
                                 consumer_offset = last_consumer_offset,
                                 "reading offset event");
-                            let (offset,wait) = self.send_back_records(last_consumer_offset,module.as_ref()).await?;
+                            let (offset,wait) = match self.send_back_records(last_consumer_offset,module.as_ref()).await? {
+                                Some(result) => result,
+                                None => {
+                                    debug!("smart stream failed, terminating");
+                                    break;
+                                }
+                            };
                             last_read_offset = offset;
                             if wait {
                                 consumer_offset = None;
@@ -281,7 +338,7 @@ where
     }
 
     /// send back records back to consumer
-    /// return (next offset, consumer wait)
+    /// return (next offset, consumer wait), none if smart stream failed and stream is stopped
     //  consumer wait flag tells that there are records send back to consumer
     #[instrument(
         skip(self,module_option),
@@ -291,8 +348,9 @@ where
         &mut self,
         offset: Offset,
         module_option: Option<&SmartStreamModule>,
-    ) -> Result<(Offset, bool), FlvSocketError> {
+    ) -> Result<Option<(Offset, bool)>, FlvSocketError> {
         let now = Instant::now();
+        let fetch_offset = offset;
         let mut file_partition_response = FilePartitionResponse {
             partition_index: self.replica.partition,
             ..Default::default()
@@ -318,37 +376,80 @@ where
 
         let mut next_offset = offset.isolation(&self.isolation);
 
+        if file_partition_response.error_code.is_error() {
+            // let consumer know, for example, that records has been removed by retention
+            debug!(error_code = ?file_partition_response.error_code, "sending back error");
+
+            let response = StreamFetchResponse {
+                topic: self.replica.topic.clone(),
+                stream_id: self.stream_id,
+                partition: file_partition_response,
+                smart_stream_error: None,
+            };
+
+            let response_msg = RequestMessage::<FileStreamFetchRequest>::response_with_header(
+                &self.header,
+                response,
+            );
+
+            let mut inner_sink = self.sink.lock().await;
+            inner_sink
+                .encode_file_slices(&response_msg, self.header.api_version())
+                .await?;
+
+            return Ok(Some((next_offset, true)));
+        }
+
         if file_partition_response.records.len() > 0 {
             if let Some(module) = module_option {
-                type DefaultPartitionResponse = FetchablePartitionResponse<RecordSet>;
+                type RawPartitionResponse = FetchablePartitionResponse<RawRecordSet>;
+
+                debug!(kind = ?self.sm_kind, "creating smart stream function");
+                let (records, runtime_error, accumulator) = {
+                    let mut function = match module.create_function(self.sm_kind) {
+                        Ok(function) => function,
+                        Err(err) => {
+                            self.send_back_smart_stream_error(
+                                err,
+                                "creating smart stream function",
+                            )
+                            .await?;
+                            return Ok(None);
+                        }
+                    };
 
-                debug!("creating smart filter");
-                let filter_batch = {
-                    let filter = module.create_filter().map_err(|err| {
-                        IoError::new(ErrorKind::Other, format!("creating filter {}", err))
-                    })?;
+                    function.set_accumulator(self.accumulator_at(fetch_offset));
 
                     let records = &file_partition_response.records;
 
-                    filter
-                        .filter(records.raw_slice(), self.max_bytes as usize)
-                        .map_err(|err| {
-                            IoError::new(ErrorKind::Other, format!("filter err {}", err))
-                        })?
+                    let (records, runtime_error) =
+                        match function.process(records.raw_slice(), self.max_bytes as usize) {
+                            Ok(result) => result,
+                            Err(err) => {
+                                self.send_back_smart_stream_error(err, "smart stream err")
+                                    .await?;
+                                return Ok(None);
+                            }
+                        };
+
+                    (records, runtime_error, function.accumulator().cloned())
                 };
 
-                let consumer_wait = if !filter_batch.records().is_empty() {
-                    trace!("filter batch: {:#?}", filter_batch);
+                let consumer_wait = if let Some(last_offset) = records.last_offset() {
+                    next_offset = last_offset + 1;
 
-                    next_offset = filter_batch.get_last_offset() + 1;
+                    // records from next offset are aggregated on top of records sent back now
+                    if let Some(accumulator) = accumulator {
+                        self.sm_accumulators.insert(next_offset, accumulator);
+                    }
 
                     debug!(
                         next_offset,
-                        records = filter_batch.records().len(),
+                        batches = records.batches.len(),
+                        records = records.total_records(),
                         "sending back to consumer"
                     );
-                    let records = RecordSet::default().add(filter_batch);
-                    let filter_partition_response = DefaultPartitionResponse {
+                    let filter_partition_response = RawPartitionResponse {
                         partition_index: self.replica.partition,
                         error_code: file_partition_response.error_code,
                         high_watermark: file_partition_response.high_watermark,
@@ -363,10 +464,11 @@ where
                         topic: self.replica.topic.clone(),
                         stream_id: self.stream_id,
                         partition: filter_partition_response,
+                        smart_stream_error: None,
                     };
 
                     let filter_response_msg =
-                        RequestMessage::<DefaultStreamFetchRequest>::response_with_header(
+                        RequestMessage::<RawStreamFetchRequest>::response_with_header(
                             &self.header,
                             filter_response,
                         );
@@ -383,7 +485,18 @@ where
                     false
                 };
 
-                Ok((next_offset, consumer_wait))
+                // records before failed record have been sent, stream stops at failed record
+                // and consumer is told why, so it can skip it or abort
+                if let Some(runtime_error) = runtime_error {
+                    self.send_back_error_response(
+                        ErrorCode::SmartStreamRuntimeError,
+                        Some(runtime_error),
+                    )
+                    .await?;
+                    return Ok(None);
+                }
+
+                Ok(Some((next_offset, consumer_wait)))
             } else {
                 debug!("no filter, sending back entire");
 
@@ -391,6 +504,7 @@ where
                     topic: self.replica.topic.clone(),
                     stream_id: self.stream_id,
                     partition: file_partition_response,
+                    smart_stream_error: None,
                 };
 
                 let response_msg = RequestMessage::<FileStreamFetchRequest>::response_with_header(
@@ -409,14 +523,94 @@ where
 
                 debug!(read_time_ms = %now.elapsed().as_millis(),"finish sending back records");
 
-                Ok((offset.isolation(&self.isolation), true))
+                Ok(Some((offset.isolation(&self.isolation), true)))
             }
         } else {
             debug!("empty records, skipping");
 
-            Ok((offset.isolation(&self.isolation), false))
+            Ok(Some((offset.isolation(&self.isolation), false)))
         }
     }
+
+    /// aggregate of records before offset, starting point of aggregate when records are sent from offset
+    fn accumulator_at(&self, offset: Offset) -> Vec<u8> {
+        self.sm_accumulators
+            .range(..=offset)
+            .next_back()
+            .or_else(|| self.sm_accumulators.iter().next())
+            .map(|(_, accumulator)| accumulator.clone())
+            .unwrap_or_default()
+    }
+
+    /// consumer has read records before offset, only aggregate stream started with is kept before it
+    fn prune_accumulators(&mut self, consumer_offset: Offset) {
+        if let Some(start) = self
+            .sm_accumulators
+            .range(..=consumer_offset)
+            .next_back()
+            .map(|(offset, _)| *offset)
+        {
+            let mut kept = self.sm_accumulators.split_off(&start);
+            if let Some((offset, accumulator)) = self.sm_accumulators.iter().next() {
+                kept.insert(*offset, accumulator.clone());
+            }
+            self.sm_accumulators = kept;
+        }
+    }
+
+    /// smart stream exceeding limits of SPU is reported to consumer with error code,
+    /// other errors terminate stream
+    async fn send_back_smart_stream_error(
+        &mut self,
+        err: anyhow::Error,
+        context: &str,
+    ) -> Result<(), FlvSocketError> {
+        let limit_err = match err.downcast_ref::<SmartStreamLimitError>() {
+            Some(limit_err) => *limit_err,
+            None => {
+                return Err(IoError::new(ErrorKind::Other, format!("{} {}", context, err)).into())
+            }
+        };
+        debug!(%limit_err, "smart stream exceeded limit, sending back error");
+        self.send_back_error_response(limit_err.error_code(), None)
+            .await
+    }
+
+    /// send back smart stream error without records
+    async fn send_back_error_response(
+        &mut self,
+        error_code: ErrorCode,
+        smart_stream_error: Option<SmartStreamRuntimeError>,
+    ) -> Result<(), FlvSocketError> {
+        let response = StreamFetchResponse {
+            topic: self.replica.topic.clone(),
+            stream_id: self.stream_id,
+            partition: FetchablePartitionResponse::<RecordSet> {
+                partition_index: self.replica.partition,
+                error_code,
+                ..Default::default()
+            },
+            smart_stream_error,
+        };
+
+        let response_msg = RequestMessage::<DefaultStreamFetchRequest>::response_with_header(
+            &self.header,
+            response,
+        );
+
+        let mut inner_sink = self.sink.lock().await;
+        inner_sink
+            .send_response(&response_msg, self.header.api_version())
+            .await?;
+        Ok(())
+    }
+}
+
+fn module_loading_error(err: anyhow::Error) -> FlvSocketError {
+    FlvSocketError::IoError(IoError::new(
+        ErrorKind::Other,
+        format!("module loading error {}", err),
+    ))
 }
 
 pub mod publishers {
@@ -486,7 +680,7 @@ pub mod publishers {
 
 #[cfg(test)]
 #[cfg(target_os = "linux")]
-mod test {
+pub(super) mod test {
 
     use std::{
         path::{Path, PathBuf},
@@ -538,7 +732,7 @@ mod test {
             let topic = format!("test{}", version);
             let test = Replica::new((topic.clone(), 0), 5001, vec![]);
             let test_id = test.id.clone();
-            let (replica, _) = LeaderReplicaState::create(test, ctx.config())
+            let (replica, _) = LeaderReplicaState::create(test, ctx.config(), ctx.config().into())
                 .await
                 .expect("replica");
             ctx.leaders_state().insert(test_id, replica.clone());
@@ -665,7 +859,7 @@ mod test {
         std::fs::read(filter_path).expect("Unable to read file")
     }
 
-    fn load_wasm_module(module_name: &str) -> Vec<u8> {
+    pub(crate) fn load_wasm_module(module_name: &str) -> Vec<u8> {
         let spu_dir = std::env::var("CARGO_MANIFEST_DIR").expect("target");
         let mut wasm_path = PathBuf::from(spu_dir)
             .parent()
@@ -706,7 +900,7 @@ mod test {
 
         let test = Replica::new((topic.to_owned(), 0), 5001, vec![]);
         let test_id = test.id.clone();
-        let (replica, _) = LeaderReplicaState::create(test, ctx.config())
+        let (replica, _) = LeaderReplicaState::create(test, ctx.config(), ctx.config().into())
             .await
             .expect("replica");
         ctx.leaders_state().insert(test_id, replica.clone());
@@ -794,15 +988,20 @@ mod test {
             assert_eq!(partition.high_watermark, 10);
             assert_eq!(partition.next_offset_for_fetch(), Some(10)); // shoule be same as HW
 
-            // we got whole batch rather than individual batches
-            assert_eq!(partition.records.batches.len(), 1);
+            // each batch with filtered records is sent back with its own base offset
+            assert_eq!(partition.records.batches.len(), 2);
             let batch = &partition.records.batches[0];
             assert_eq!(batch.base_offset, 4); // first base offset where we had filtered records
-            assert_eq!(batch.records().len(), 2);
+            assert_eq!(batch.records().len(), 1);
             assert_eq!(
                 batch.records()[0].value().as_ref(),
                 "a".repeat(100).as_bytes()
             );
+            assert_eq!(batch.records()[0].get_offset_delta(), 1);
+            let batch = &partition.records.batches[1];
+            assert_eq!(batch.base_offset, 7);
+            assert_eq!(batch.records().len(), 1);
+            assert_eq!(batch.get_last_offset(), 9);
         }
 
         drop(response);
@@ -861,7 +1060,7 @@ mod test {
 
         let test = Replica::new((topic.to_owned(), 0), 5001, vec![]);
         let test_id = test.id.clone();
-        let (replica, _) = LeaderReplicaState::create(test, ctx.config())
+        let (replica, _) = LeaderReplicaState::create(test, ctx.config(), ctx.config().into())
             .await
             .expect("replica");
         ctx.leaders_state().insert(test_id, replica.clone());
@@ -913,14 +1112,17 @@ mod test {
             assert_eq!(partition.high_watermark, 30);
             assert_eq!(partition.next_offset_for_fetch(), Some(20)); // shoule be same as HW
 
-            assert_eq!(partition.records.batches.len(), 1);
+            assert_eq!(partition.records.batches.len(), 2);
             let batch = &partition.records.batches[0];
             assert_eq!(batch.base_offset, 0);
-            assert_eq!(batch.records().len(), 2);
+            assert_eq!(batch.records().len(), 1);
             assert_eq!(
                 batch.records()[0].value().as_ref(),
                 "a".repeat(100).as_bytes()
             );
+            let batch = &partition.records.batches[1];
+            assert_eq!(batch.base_offset, 10);
+            assert_eq!(batch.records().len(), 1);
         }
 
         drop(response);
@@ -964,4 +1166,464 @@ mod test {
 
         Ok(())
     }
+
+    #[test_async]
+    async fn test_stream_map_fetch() -> Result<(), ()> {
+        let test_path = temp_dir().join("map_stream_fetch");
+        ensure_clean_dir(&test_path);
+
+        let addr = "127.0.0.1:12003";
+        let mut spu_config = SpuConfig::default();
+        spu_config.log.base_dir = test_path;
+        let ctx = GlobalContext::new_shared_context(spu_config);
+
+        let server_end_event = create_public_server(addr.to_owned(), ctx.clone()).run();
+
+        // wait for stream controller async to start
+        sleep(Duration::from_millis(100)).await;
+
+        let client_socket =
+            DefaultMultiplexerSocket::new(FlvSocket::connect(addr).await.expect("connect"));
+
+        let topic = "testmap";
+
+        let test = Replica::new((topic.to_owned(), 0), 5001, vec![]);
+        let test_id = test.id.clone();
+        let (replica, _) = LeaderReplicaState::create(test, ctx.config(), ctx.config().into())
+            .await
+            .expect("replica");
+        ctx.leaders_state().insert(test_id, replica.clone());
+
+        replica
+            .write_record_set(&mut create_filter_records(3))
+            .await
+            .expect("write");
+
+        let stream_request = DefaultStreamFetchRequest {
+            topic: topic.to_owned(),
+            partition: 0,
+            fetch_offset: 0,
+            isolation: Isolation::ReadUncommitted,
+            max_bytes: 10000,
+            wasm_module: load_wasm_module("fluvio_map_test"),
+            smart_stream_kind: SmartStreamKind::Map,
+            ..Default::default()
+        };
+
+        let mut stream = client_socket
+            .create_stream(RequestMessage::new_request(stream_request), 12)
+            .await
+            .expect("create stream");
+
+        let response = stream.next().await.expect("first").expect("response");
+        {
+            let partition = &response.partition;
+            assert_eq!(partition.error_code, ErrorCode::None);
+            assert_eq!(partition.high_watermark, 3);
+            assert_eq!(partition.next_offset_for_fetch(), Some(3));
+
+            // every record is mapped, offsets are kept
+            assert_eq!(partition.records.batches.len(), 1);
+            let batch = &partition.records.batches[0];
+            assert_eq!(batch.base_offset, 0);
+            assert_eq!(batch.get_last_offset(), 2);
+            assert_eq!(batch.records().len(), 3);
+            assert_eq!(
+                batch.records()[0].value().as_ref(),
+                "B".repeat(100).as_bytes()
+            );
+            assert_eq!(
+                batch.records()[1].value().as_ref(),
+                "A".repeat(100).as_bytes()
+            );
+            assert_eq!(
+                batch.records()[2].value().as_ref(),
+                "Z".repeat(100).as_bytes()
+            );
+        }
+
+        server_end_event.notify();
+        debug!("terminated controller");
+
+        Ok(())
+    }
+
+    /// test aggregate, accumulator is carried to next batch
+    #[test_async]
+    async fn test_stream_aggregate_fetch() -> Result<(), ()> {
+        let test_path = temp_dir().join("aggregate_stream_fetch");
+        ensure_clean_dir(&test_path);
+
+        let addr = "127.0.0.1:12004";
+        let mut spu_config = SpuConfig::default();
+        spu_config.log.base_dir = test_path;
+        let ctx = GlobalContext::new_shared_context(spu_config);
+
+        let server_end_event = create_public_server(addr.to_owned(), ctx.clone()).run();
+
+        // wait for stream controller async to start
+        sleep(Duration::from_millis(100)).await;
+
+        let client_socket =
+            DefaultMultiplexerSocket::new(FlvSocket::connect(addr).await.expect("connect"));
+
+        let topic = "testaggregate";
+
+        let test = Replica::new((topic.to_owned(), 0), 5001, vec![]);
+        let test_id = test.id.clone();
+        let (replica, _) = LeaderReplicaState::create(test, ctx.config(), ctx.config().into())
+            .await
+            .expect("replica");
+        ctx.leaders_state().insert(test_id, replica.clone());
+
+        replica
+            .write_record_set(&mut create_filter_records(3))
+            .await
+            .expect("write");
+
+        let stream_request = DefaultStreamFetchRequest {
+            topic: topic.to_owned(),
+            partition: 0,
+            fetch_offset: 0,
+            isolation: Isolation::ReadUncommitted,
+            max_bytes: 10000,
+            wasm_module: load_wasm_module("fluvio_aggregate_test"),
+            smart_stream_kind: SmartStreamKind::Aggregate,
+            aggregate_accumulator: b"x".to_vec(),
+            ..Default::default()
+        };
+
+        let mut stream = client_socket
+            .create_stream(RequestMessage::new_request(stream_request), 13)
+            .await
+            .expect("create stream");
+
+        let response = stream.next().await.expect("first").expect("response");
+        let stream_id = response.stream_id;
+        {
+            let partition = &response.partition;
+            assert_eq!(partition.error_code, ErrorCode::None);
+            assert_eq!(partition.high_watermark, 3);
+            assert_eq!(partition.next_offset_for_fetch(), Some(3));
+
+            // value of each record is running aggregate
+            assert_eq!(partition.records.batches.len(), 1);
+            let batch = &partition.records.batches[0];
+            assert_eq!(batch.base_offset, 0);
+            assert_eq!(batch.records().len(), 3);
+            assert_eq!(batch.records()[0].value().as_ref(), b"xb");
+            assert_eq!(batch.records()[1].value().as_ref(), b"xba");
+            assert_eq!(batch.records()[2].value().as_ref(), b"xbaz");
+        }
+
+        drop(response);
+
+        client_socket
+            .send_and_receive(RequestMessage::new_request(UpdateOffsetsRequest {
+                offsets: vec![OffsetUpdate {
+                    offset: 3,
+                    session_id: stream_id,
+                }],
+            }))
+            .await
+            .expect("send offset");
+
+        replica
+            .write_record_set(&mut create_filter_records(3))
+            .await
+            .expect("write");
+
+        let response = stream.next().await.expect("second").expect("response");
+        {
+            let partition = &response.partition;
+            assert_eq!(partition.error_code, ErrorCode::None);
+            assert_eq!(partition.high_watermark, 6);
+
+            // aggregate continues from last record of previous batch
+            assert_eq!(partition.records.batches.len(), 1);
+            let batch = &partition.records.batches[0];
+            assert_eq!(batch.base_offset, 3);
+            assert_eq!(batch.records().len(), 3);
+            assert_eq!(batch.records()[0].value().as_ref(), b"xbazb");
+            assert_eq!(batch.records()[1].value().as_ref(), b"xbazba");
+            assert_eq!(batch.records()[2].value().as_ref(), b"xbazbaz");
+        }
+
+        drop(response);
+
+        // consumer starts over, records are sent again with same aggregate
+        client_socket
+            .send_and_receive(RequestMessage::new_request(UpdateOffsetsRequest {
+                offsets: vec![OffsetUpdate {
+                    offset: 0,
+                    session_id: stream_id,
+                }],
+            }))
+            .await
+            .expect("send offset");
+
+        let response = stream.next().await.expect("resend").expect("response");
+        {
+            let partition = &response.partition;
+            assert_eq!(partition.error_code, ErrorCode::None);
+            assert_eq!(partition.next_offset_for_fetch(), Some(6));
+            assert_eq!(partition.records.batches.len(), 1);
+            let batch = &partition.records.batches[0];
+            assert_eq!(batch.base_offset, 0);
+            assert_eq!(batch.records().len(), 6);
+            assert_eq!(batch.records()[0].value().as_ref(), b"xb");
+            assert_eq!(batch.records()[5].value().as_ref(), b"xbazbaz");
+        }
+
+        server_end_event.notify();
+        debug!("terminated controller");
+
+        Ok(())
+    }
+
+    /// module running out of fuel is reported with error code
+    #[test_async]
+    async fn test_stream_fetch_fuel_exhausted() -> Result<(), ()> {
+        let test_path = temp_dir().join("fuel_stream_fetch");
+        ensure_clean_dir(&test_path);
+
+        let addr = "127.0.0.1:12005";
+        let mut spu_config = SpuConfig::default();
+        spu_config.log.base_dir = test_path;
+        spu_config.smart_stream.fuel = 1000;
+        let ctx = GlobalContext::new_shared_context(spu_config);
+
+        let server_end_event = create_public_server(addr.to_owned(), ctx.clone()).run();
+
+        // wait for stream controller async to start
+        sleep(Duration::from_millis(100)).await;
+
+        let client_socket =
+            DefaultMultiplexerSocket::new(FlvSocket::connect(addr).await.expect("connect"));
+
+        let topic = "testfuel";
+
+        let test = Replica::new((topic.to_owned(), 0), 5001, vec![]);
+        let test_id = test.id.clone();
+        let (replica, _) = LeaderReplicaState::create(test, ctx.config(), ctx.config().into())
+            .await
+            .expect("replica");
+        ctx.leaders_state().insert(test_id, replica.clone());
+
+        replica
+            .write_record_set(&mut create_filter_records(100))
+            .await
+            .expect("write");
+
+        let stream_request = DefaultStreamFetchRequest {
+            topic: topic.to_owned(),
+            partition: 0,
+            fetch_offset: 0,
+            isolation: Isolation::ReadUncommitted,
+            max_bytes: 10000,
+            wasm_module: load_wasm_module("fluvio_filter_test"),
+            ..Default::default()
+        };
+
+        let mut stream = client_socket
+            .create_stream(RequestMessage::new_request(stream_request), 11)
+            .await
+            .expect("create stream");
+
+        let response = stream.next().await.expect("first").expect("response");
+        assert_eq!(
+            response.partition.error_code,
+            ErrorCode::SmartStreamFuelExhausted
+        );
+        assert!(response.partition.records.batches.is_empty());
+
+        server_end_event.notify();
+        debug!("terminated controller");
+
+        Ok(())
+    }
+
+    /// records before failed record are sent back, followed by error of the record
+    #[test_async]
+    async fn test_stream_fetch_runtime_error() -> Result<(), ()> {
+        let test_path = temp_dir().join("runtime_error_stream_fetch");
+        ensure_clean_dir(&test_path);
+
+        let addr = "127.0.0.1:12006";
+        let mut spu_config = SpuConfig::default();
+        spu_config.log.base_dir = test_path;
+        let ctx = GlobalContext::new_shared_context(spu_config);
+
+        let server_end_event = create_public_server(addr.to_owned(), ctx.clone()).run();
+
+        // wait for stream controller async to start
+        sleep(Duration::from_millis(100)).await;
+
+        let client_socket =
+            DefaultMultiplexerSocket::new(FlvSocket::connect(addr).await.expect("connect"));
+
+        let topic = "testerror";
+
+        let test = Replica::new((topic.to_owned(), 0), 5001, vec![]);
+        let test_id = test.id.clone();
+        let (replica, _) = LeaderReplicaState::create(test, ctx.config(), ctx.config().into())
+            .await
+            .expect("replica");
+        ctx.leaders_state().insert(test_id, replica.clone());
+
+        replica
+            .write_record_set(&mut create_filter_records(3))
+            .await
+            .expect("write");
+
+        let stream_request = DefaultStreamFetchRequest {
+            topic: topic.to_owned(),
+            partition: 0,
+            fetch_offset: 0,
+            isolation: Isolation::ReadUncommitted,
+            max_bytes: 10000,
+            wasm_module: load_wasm_module("fluvio_error_test"),
+            ..Default::default()
+        };
+
+        let mut stream = client_socket
+            .create_stream(RequestMessage::new_request(stream_request), 14)
+            .await
+            .expect("create stream");
+
+        let response = stream.next().await.expect("first").expect("response");
+        let stream_id = response.stream_id;
+        {
+            let partition = &response.partition;
+            assert_eq!(partition.error_code, ErrorCode::None);
+            assert!(response.smart_stream_error.is_none());
+
+            // record before failed one is kept
+            assert_eq!(partition.records.batches.len(), 1);
+            let batch = &partition.records.batches[0];
+            assert_eq!(batch.base_offset, 0);
+            assert_eq!(batch.get_last_offset(), 1);
+            assert_eq!(batch.records().len(), 1);
+            assert_eq!(partition.next_offset_for_fetch(), Some(2));
+        }
+
+        let response = stream.next().await.expect("second").expect("response");
+        assert_eq!(
+            response.partition.error_code,
+            ErrorCode::SmartStreamRuntimeError
+        );
+        let error = response.smart_stream_error.expect("smart stream error");
+        assert_eq!(error.offset, 2);
+        assert_eq!(error.message, "record starts with z");
+
+        // stream stops at failed record, records after it are not sent
+        sleep(Duration::from_millis(100)).await;
+        assert!(ctx
+            .stream_publishers()
+            .get_publisher(stream_id)
+            .await
+            .is_none());
+
+        server_end_event.notify();
+        debug!("terminated controller");
+
+        Ok(())
+    }
+
+    /// smartstream registered in SC is referenced by name
+    #[test_async]
+    async fn test_stream_fetch_registered_smart_stream() -> Result<(), ()> {
+        use fluvio_controlplane_metadata::smartstream::{SmartStream, SmartStreamSpec};
+
+        let test_path = temp_dir().join("registered_smart_stream_fetch");
+        ensure_clean_dir(&test_path);
+
+        let addr = "127.0.0.1:12007";
+        let mut spu_config = SpuConfig::default();
+        spu_config.log.base_dir = test_path;
+        let ctx = GlobalContext::new_shared_context(spu_config);
+
+        ctx.smart_stream_localstore().insert(SmartStream::new(
+            "uppercase",
+            SmartStreamSpec::new(1, load_wasm_module("fluvio_map_test")),
+        ));
+
+        let server_end_event = create_public_server(addr.to_owned(), ctx.clone()).run();
+
+        // wait for stream controller async to start
+        sleep(Duration::from_millis(100)).await;
+
+        let client_socket =
+            DefaultMultiplexerSocket::new(FlvSocket::connect(addr).await.expect("connect"));
+
+        let topic = "testregistered";
+
+        let test = Replica::new((topic.to_owned(), 0), 5001, vec![]);
+        let test_id = test.id.clone();
+        let (replica, _) = LeaderReplicaState::create(test, ctx.config(), ctx.config().into())
+            .await
+            .expect("replica");
+        ctx.leaders_state().insert(test_id, replica.clone());
+
+        replica
+            .write_record_set(&mut create_filter_records(3))
+            .await
+            .expect("write");
+
+        let stream_request = DefaultStreamFetchRequest {
+            topic: topic.to_owned(),
+            partition: 0,
+            fetch_offset: 0,
+            isolation: Isolation::ReadUncommitted,
+            max_bytes: 10000,
+            smart_stream_name: "uppercase".to_owned(),
+            smart_stream_kind: SmartStreamKind::Map,
+            ..Default::default()
+        };
+
+        let mut stream = client_socket
+            .create_stream(RequestMessage::new_request(stream_request), 15)
+            .await
+            .expect("create stream");
+
+        let response = stream.next().await.expect("first").expect("response");
+        {
+            let partition = &response.partition;
+            assert_eq!(partition.error_code, ErrorCode::None);
+            assert_eq!(partition.records.batches.len(), 1);
+            let batch = &partition.records.batches[0];
+            assert_eq!(batch.records().len(), 3);
+            assert_eq!(
+                batch.records()[0].value().as_ref(),
+                "B".repeat(100).as_bytes()
+            );
+        }
+
+        // smartstream which is not registered is rejected
+        let stream_request = DefaultStreamFetchRequest {
+            topic: topic.to_owned(),
+            partition: 0,
+            fetch_offset: 0,
+            isolation: Isolation::ReadUncommitted,
+            max_bytes: 10000,
+            smart_stream_name: "missing".to_owned(),
+            ..Default::default()
+        };
+
+        let mut stream = client_socket
+            .create_stream(RequestMessage::new_request(stream_request), 15)
+            .await
+            .expect("create stream");
+
+        let response = stream.next().await.expect("first").expect("response");
+        assert_eq!(
+            response.partition.error_code,
+            ErrorCode::SmartStreamNotFound
+        );
+
+        server_end_event.notify();
+        debug!("terminated controller");
+
+        Ok(())
+    }
 }
@@ -1,43 +1,193 @@
 use std::{io::Error as IoError, sync::Arc, time::Instant};
 use std::io::{ErrorKind, Cursor};
+use std::borrow::Cow;
+use std::ops::Range;
 use std::path::Path;
 use std::sync::{RwLock, Mutex};
-use std::os::unix::io::RawFd;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::fmt;
 
 use anyhow::{Result, Error, anyhow};
 
 // use bytes::{Bytes, BytesMut};
 use tracing::{debug, warn};
 use nix::sys::uio::pread;
-use wasmtime::{Caller, Engine, Extern, Func, Instance, Module, Store, Trap, TypedFunc, Memory};
+use wasmtime::{
+    Caller, Config, Engine, Extern, Func, Instance, Linker, Module, Store, Trap, TypedFunc, Memory,
+    ResourceLimiter,
+};
 
 use fluvio_future::file_slice::AsyncFileSlice;
 use dataplane::core::{Decoder, Encoder};
-use dataplane::Offset;
+use dataplane::{ErrorCode, Offset};
 use dataplane::{
-    batch::{BATCH_FILE_HEADER_SIZE, BATCH_HEADER_SIZE, Batch, DefaultBatch},
-    record::{DefaultRecord},
+    batch::{BATCH_FILE_HEADER_SIZE, BATCH_HEADER_SIZE, Batch, DefaultBatch, RawBatch},
+    compression::Compression,
+    record::{DefaultRecord, RawRecordSet},
+    smartstream::{SmartStreamRuntimeError, SmartStreamRecordPosition},
 };
+use fluvio_spu_schema::server::stream_fetch::SmartStreamKind;
+use fluvio_controlplane_metadata::smartstream::SmartStream;
+
+use crate::config::SmartStreamConfig;
 // use fluvio_future::task::spawn_blocking;
 
 // use fluvio_storage::config::DEFAULT_MAX_BATCH_SIZE;
 
 const FILTER_FN_NAME: &str = "filter";
+const FILTER_SLICE_FN_NAME: &str = "filter_slice";
+const MAP_FN_NAME: &str = "map";
+const AGGREGATE_FN_NAME: &str = "aggregate";
+const WASM_PAGE_SIZE: u64 = 65536;
+const HOST_MODULE: &str = "env";
+const COPY_RECORDS_FN_NAME: &str = "copy_records";
+const SET_ERROR_FN_NAME: &str = "set_error";
+/// maximum bytes of file slice passed to module at once, unless single batch is bigger
+const MAX_CHUNK_BYTES: usize = 1024 * 1024;
+
+/// SmartStream module exceeded limit configured in SPU
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SmartStreamLimitError {
+    FuelExhausted,
+    MemoryLimitExceeded,
+}
+
+impl SmartStreamLimitError {
+    pub fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::FuelExhausted => ErrorCode::SmartStreamFuelExhausted,
+            Self::MemoryLimitExceeded => ErrorCode::SmartStreamMemoryLimitExceeded,
+        }
+    }
+}
+
+impl fmt::Display for SmartStreamLimitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::FuelExhausted => write!(f, "smart stream fuel exhausted"),
+            Self::MemoryLimitExceeded => write!(f, "smart stream memory limit exceeded"),
+        }
+    }
+}
+
+impl std::error::Error for SmartStreamLimitError {}
 
-pub struct SmartStreamEngine(Engine);
+/// fuel and memory available to module
+#[derive(Debug, Clone)]
+struct SmartStreamLimits {
+    fuel: u64,
+    memory_exceeded: Arc<AtomicBool>,
+}
+
+impl SmartStreamLimits {
+    /// run guest code, fuel used is refilled afterwards so every invocation starts with same fuel.
+    /// failure caused by exceeding limits is reported as `SmartStreamLimitError`
+    fn metered<T>(&self, store: &Store, run: impl FnOnce() -> Result<T>) -> Result<T> {
+        self.memory_exceeded.store(false, Ordering::SeqCst);
+        let before = store.fuel_consumed().unwrap_or_default();
+        let result = run();
+        let used = store.fuel_consumed().unwrap_or_default() - before;
+        store.add_fuel(used)?;
+
+        result.map_err(|err| {
+            if used >= self.fuel {
+                SmartStreamLimitError::FuelExhausted.into()
+            } else if self.memory_exceeded.load(Ordering::SeqCst) {
+                SmartStreamLimitError::MemoryLimitExceeded.into()
+            } else {
+                err
+            }
+        })
+    }
+}
+
+/// refuses to grow linear memory beyond max pages
+struct MemoryLimiter {
+    max_pages: u32,
+    exceeded: Arc<AtomicBool>,
+}
+
+impl ResourceLimiter for MemoryLimiter {
+    fn memory_growing(&self, _current: u32, desired: u32, _maximum: Option<u32>) -> bool {
+        if desired > self.max_pages {
+            debug!(desired, max_pages = self.max_pages, "refused memory growth");
+            self.exceeded.store(true, Ordering::SeqCst);
+            false
+        } else {
+            true
+        }
+    }
+
+    fn table_growing(&self, _current: u32, _desired: u32, _maximum: Option<u32>) -> bool {
+        true
+    }
+}
+
+pub struct SmartStreamEngine {
+    engine: Engine,
+    config: SmartStreamConfig,
+    /// compiled modules of registered smartstreams with their version
+    modules: Mutex<HashMap<String, (u32, Module)>>,
+}
+
+impl fmt::Debug for SmartStreamEngine {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "SmartStreamEngine")
+    }
+}
 
 impl SmartStreamEngine {
-    pub fn new() -> Self {
-        Self(Engine::default())
+    pub fn new(config: &SmartStreamConfig) -> Result<Self> {
+        let mut wasm_config = Config::new();
+        wasm_config.consume_fuel(true);
+        Ok(Self {
+            engine: Engine::new(&wasm_config)?,
+            config: config.clone(),
+            modules: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// create module for registered smartstream.
+    /// module is compiled only once for each version
+    pub fn create_module_from_registry(
+        &self,
+        smart_stream: &SmartStream,
+    ) -> Result<SmartStreamModule> {
+        let module = {
+            let mut modules = self.modules.lock().unwrap();
+            match modules.get(&smart_stream.name) {
+                Some((version, module)) if *version == smart_stream.version => module.clone(),
+                _ => {
+                    debug!(
+                        name = %smart_stream.name,
+                        version = smart_stream.version,
+                        "compiling smartstream"
+                    );
+                    let module = Module::from_binary(&self.engine, &smart_stream.wasm)?;
+                    modules.insert(
+                        smart_stream.name.clone(),
+                        (smart_stream.version, module.clone()),
+                    );
+                    module
+                }
+            }
+        };
+        SmartStreamModule::from_module(&self.engine, &self.config, module)
+    }
+
+    /// drop compiled module, called when smartstream is changed or deleted
+    pub fn evict_module(&self, name: &str) {
+        self.modules.lock().unwrap().remove(name);
     }
 
     #[allow(unused)]
     pub fn create_module_from_path(&self, path: impl AsRef<Path>) -> Result<SmartStreamModule> {
-        SmartStreamModule::from_path(&self.0, path)
+        SmartStreamModule::from_path(&self.engine, &self.config, path)
     }
 
     pub fn create_module_from_binary(&self, binary: &[u8]) -> Result<SmartStreamModule> {
-        SmartStreamModule::from_binary(&self.0, binary)
+        SmartStreamModule::from_binary(&self.engine, &self.config, binary)
     }
 }
 
@@ -45,21 +195,31 @@ pub struct SmartStreamModule(RwLock<SmartStreamModuleInner>);
 
 impl SmartStreamModule {
     #[allow(unused)]
-    fn from_path(engine: &Engine, path: impl AsRef<Path>) -> Result<Self> {
+    fn from_path(
+        engine: &Engine,
+        config: &SmartStreamConfig,
+        path: impl AsRef<Path>,
+    ) -> Result<Self> {
         Ok(Self(RwLock::new(SmartStreamModuleInner::create_from_path(
-            engine, path,
+            engine, config, path,
         )?)))
     }
 
-    fn from_binary(engine: &Engine, binary: &[u8]) -> Result<Self> {
+    fn from_binary(engine: &Engine, config: &SmartStreamConfig, binary: &[u8]) -> Result<Self> {
         Ok(Self(RwLock::new(
-            SmartStreamModuleInner::create_from_binary(engine, binary)?,
+            SmartStreamModuleInner::create_from_binary(engine, config, binary)?,
         )))
     }
 
-    pub fn create_filter(&self) -> Result<SmartFilter> {
+    fn from_module(engine: &Engine, config: &SmartStreamConfig, module: Module) -> Result<Self> {
+        Ok(Self(RwLock::new(
+            SmartStreamModuleInner::create_from_module(engine, config, module)?,
+        )))
+    }
+
+    pub fn create_function(&self, kind: SmartStreamKind) -> Result<SmartStreamFunction> {
         let write_inner = self.0.write().unwrap();
-        write_inner.create_filter()
+        write_inner.create_function(kind)
     }
 }
 
@@ -69,52 +229,139 @@ unsafe impl Sync for SmartStreamModuleInner {}
 pub struct SmartStreamModuleInner {
     module: Module,
     store: Store,
+    limits: SmartStreamLimits,
 }
 
 impl SmartStreamModuleInner {
     #[allow(unused)]
-    pub fn create_from_path(engine: &Engine, path: impl AsRef<Path>) -> Result<Self> {
-        let store = Store::new(&engine);
+    pub fn create_from_path(
+        engine: &Engine,
+        config: &SmartStreamConfig,
+        path: impl AsRef<Path>,
+    ) -> Result<Self> {
+        let (store, limits) = Self::create_store(engine, config)?;
         let module = Module::from_file(store.engine(), path)?;
-        Ok(Self { module, store })
+        Ok(Self {
+            module,
+            store,
+            limits,
+        })
     }
 
-    pub fn create_from_binary(engine: &Engine, binary: &[u8]) -> Result<Self> {
-        let store = Store::new(&engine);
+    pub fn create_from_binary(
+        engine: &Engine,
+        config: &SmartStreamConfig,
+        binary: &[u8],
+    ) -> Result<Self> {
+        let (store, limits) = Self::create_store(engine, config)?;
         let module = Module::from_binary(store.engine(), binary)?;
-        Ok(Self { module, store })
+        Ok(Self {
+            module,
+            store,
+            limits,
+        })
     }
 
-    pub fn create_filter(&self) -> Result<SmartFilter> {
-        let callback = Arc::new(RecordsCallBack::new());
-        let callback2 = callback.clone();
-        let copy_records = Func::wrap(
-            &self.store,
-            move |caller: Caller<'_>, ptr: i32, len: i32| {
-                debug!(len, "callback from wasm filter");
-                let memory = match caller.get_export("memory") {
-                    Some(Extern::Memory(mem)) => mem,
-                    _ => return Err(Trap::new("failed to find host memory")),
-                };
+    /// use module already compiled with same engine
+    pub fn create_from_module(
+        engine: &Engine,
+        config: &SmartStreamConfig,
+        module: Module,
+    ) -> Result<Self> {
+        let (store, limits) = Self::create_store(engine, config)?;
+        Ok(Self {
+            module,
+            store,
+            limits,
+        })
+    }
 
-                let records = RecordsMemory { ptr, len, memory };
+    /// store with memory limit and fuel for single invocation
+    fn create_store(
+        engine: &Engine,
+        config: &SmartStreamConfig,
+    ) -> Result<(Store, SmartStreamLimits)> {
+        let memory_exceeded = Arc::new(AtomicBool::new(false));
+        let limiter = MemoryLimiter {
+            max_pages: (config.max_memory_bytes / WASM_PAGE_SIZE).min(u32::MAX as u64) as u32,
+            exceeded: memory_exceeded.clone(),
+        };
+        let store = Store::new_with_limits(engine, limiter);
+        store.add_fuel(config.fuel)?;
+        Ok((
+            store,
+            SmartStreamLimits {
+                fuel: config.fuel,
+                memory_exceeded,
+            },
+        ))
+    }
 
-                callback.set(records);
+    /// instantiate module and look up function exported for kind
+    pub fn create_function(&self, kind: SmartStreamKind) -> Result<SmartStreamFunction> {
+        let records_cb = Arc::new(RecordsCallBack::new());
+        let error_cb = Arc::new(RecordsCallBack::new());
+
+        let mut linker = Linker::new(&self.store);
+        linker.define(
+            HOST_MODULE,
+            COPY_RECORDS_FN_NAME,
+            memory_callback(&self.store, records_cb.clone()),
+        )?;
+        linker.define(
+            HOST_MODULE,
+            SET_ERROR_FN_NAME,
+            memory_callback(&self.store, error_cb.clone()),
+        )?;
+
+        let instance = self
+            .limits
+            .metered(&self.store, || linker.instantiate(&self.module))?;
+
+        let fn_name = match kind {
+            SmartStreamKind::Filter => FILTER_FN_NAME,
+            SmartStreamKind::Map => MAP_FN_NAME,
+            SmartStreamKind::Aggregate => AGGREGATE_FN_NAME,
+        };
+        let process_fn = instance.get_typed_func::<(i32, i32), i32>(fn_name)?;
+        // modules built before filter of file slice was introduced only export `filter`
+        let slice_fn = match kind {
+            SmartStreamKind::Filter => instance
+                .get_typed_func::<(i32, i32), i32>(FILTER_SLICE_FN_NAME)
+                .ok(),
+            SmartStreamKind::Map | SmartStreamKind::Aggregate => None,
+        };
 
-                Ok(())
-            },
+        let mut function = SmartStreamFunction::new(
+            process_fn,
+            slice_fn,
+            SmartStreamInstance::new(instance),
+            records_cb,
+            error_cb,
+            self.limits.clone(),
         );
+        if kind == SmartStreamKind::Aggregate {
+            function.accumulator = Some(vec![]);
+        }
+        Ok(function)
+    }
+}
 
-        let instance = Instance::new(&self.store, &self.module, &[copy_records.into()])?;
+/// host function through which module passes back region of its memory
+fn memory_callback(store: &Store, callback: Arc<RecordsCallBack>) -> Func {
+    Func::wrap(store, move |caller: Caller<'_>, ptr: i32, len: i32| {
+        debug!(len, "callback from wasm module");
+        let memory = match caller.get_export("memory") {
+            Some(Extern::Memory(mem)) => mem,
+            _ => return Err(Trap::new("failed to find host memory")),
+        };
 
-        let filter_fn = instance.get_typed_func::<(i32, i32), i32>(FILTER_FN_NAME)?;
+        let records = RecordsMemory { ptr, len, memory };
 
-        Ok(SmartFilter::new(
-            filter_fn,
-            SmartStreamInstance::new(instance),
-            callback2,
-        ))
-    }
+        callback.set(records);
+
+        Ok(())
+    })
 }
 
 pub struct SmartStreamInstance(Instance);
@@ -183,45 +430,288 @@ impl RecordsCallBack {
 /// Instance are not thread safe, we need to take care to ensure access to instance are thread safe
 
 /// Instance must be hold in thread safe lock to ensure only one thread can access at time
-pub struct SmartFilter {
-    filter_fn: TypedFunc<(i32, i32), i32>,
+///
+/// Filter and map share same ABI: guest receives encoded records of a batch and passes back
+/// records to be kept, either filtered or rewritten. Offset delta of each record is preserved.
+///
+/// Aggregate guest receives encoded accumulator followed by records, and passes back each record
+/// with value replaced by running aggregate. Value of last record becomes accumulator for next batch.
+///
+/// When record fails, guest passes back records processed before it and reports
+/// `SmartStreamRuntimeError` through `set_error`. Processing stops at failed record.
+///
+/// Filter of file slice receives batches as they are read from file and passes back
+/// positions of kept records, which are sent to consumer without being decoded.
+pub struct SmartStreamFunction {
+    process_fn: TypedFunc<(i32, i32), i32>,
+    slice_fn: Option<TypedFunc<(i32, i32), i32>>,
     instance: SmartStreamInstance,
     records_cb: Arc<RecordsCallBack>,
+    error_cb: Arc<RecordsCallBack>,
+    limits: SmartStreamLimits,
+    accumulator: Option<Vec<u8>>,
 }
 
-impl SmartFilter {
-    pub fn new(
-        filter_fn: TypedFunc<(i32, i32), i32>,
+impl SmartStreamFunction {
+    fn new(
+        process_fn: TypedFunc<(i32, i32), i32>,
+        slice_fn: Option<TypedFunc<(i32, i32), i32>>,
         instance: SmartStreamInstance,
         records_cb: Arc<RecordsCallBack>,
+        error_cb: Arc<RecordsCallBack>,
+        limits: SmartStreamLimits,
     ) -> Self {
         Self {
-            filter_fn,
+            process_fn,
+            slice_fn,
             instance,
             records_cb,
+            error_cb,
+            limits,
+            accumulator: None,
+        }
+    }
+
+    /// accumulator of aggregate, none for other kinds
+    pub fn accumulator(&self) -> Option<&Vec<u8>> {
+        self.accumulator.as_ref()
+    }
+
+    /// set accumulator aggregate starts from, ignored for other kinds
+    pub fn set_accumulator(&mut self, accumulator: Vec<u8>) {
+        if let Some(current) = self.accumulator.as_mut() {
+            *current = accumulator;
+        }
+    }
+
+    /// process records of batch sent by producer, records are replaced by output of module.
+    /// offset delta of records are preserved, so dropped records leave gap in offsets
+    pub fn process_batch(
+        &mut self,
+        batch: &mut DefaultBatch,
+    ) -> Result<Option<SmartStreamRuntimeError>, Error> {
+        if batch.get_header().is_control() {
+            return Ok(None);
         }
+
+        let mut input = vec![];
+        batch.records().encode(&mut input, 0)?;
+        let (records, runtime_error) = self.invoke(&input)?;
+        *batch.mut_records() = records;
+
+        let base_offset = batch.get_base_offset();
+        Ok(runtime_error.map(|mut error| {
+            error.offset += base_offset;
+            error
+        }))
+    }
+
+    /// run module on encoded records of single batch.
+    /// offset of runtime error is relative to batch
+    fn invoke(
+        &self,
+        records: &[u8],
+    ) -> Result<(Vec<DefaultRecord>, Option<SmartStreamRuntimeError>), Error> {
+        let aggregate_input;
+        let input: &[u8] = match &self.accumulator {
+            Some(accumulator) => {
+                let mut buf = vec![];
+                accumulator.encode(&mut buf, 0)?;
+                buf.extend_from_slice(records);
+                aggregate_input = buf;
+                &aggregate_input
+            }
+            None => records,
+        };
+
+        let (bytes, runtime_error) = self.call(&self.process_fn, input)?;
+        // this is inefficient for now
+        let mut records: Vec<DefaultRecord> = vec![];
+        records.decode(&mut Cursor::new(bytes), 0)?;
+
+        Ok((records, runtime_error))
     }
 
-    /// filter batches with maximum bytes to be send back consumer
-    pub fn filter(&self, slice: AsyncFileSlice, max_bytes: usize) -> Result<DefaultBatch, Error> {
-        use std::os::unix::io::AsRawFd;
+    /// run function of module on input.
+    /// returns bytes passed back through `copy_records` and error reported through `set_error`
+    fn call(
+        &self,
+        function: &TypedFunc<(i32, i32), i32>,
+        input: &[u8],
+    ) -> Result<(Vec<u8>, Option<SmartStreamRuntimeError>), Error> {
+        let now = Instant::now();
+
+        self.records_cb.clear();
+        self.error_cb.clear();
+
+        let instance = &self.instance;
+        let error_cb = &self.error_cb;
+        let filter_record_count = self.limits.metered(instance.0.store(), || {
+            let array_ptr = instance.copy_memory_to(input)?;
+            let count = function.call((array_ptr as i32, input.len() as i32))?;
+            if count == -1 && error_cb.get().is_none() {
+                return Err(anyhow!("smart stream failed"));
+            }
+            Ok(count)
+        })?;
+
+        let runtime_error = match self.error_cb.get() {
+            Some(error_memory) => {
+                let mut error = SmartStreamRuntimeError::default();
+                error.decode(&mut Cursor::new(error_memory.copy_memory_from()), 0)?;
+                debug!(offset = error.offset, message = %error.message, "smart stream error");
+                Some(error)
+            }
+            None => None,
+        };
 
-        let fd = slice.as_raw_fd();
+        debug!(filter_record_count,filter_execution_time = %now.elapsed().as_millis());
 
-        let mut batch_iterator =
-            FileBatchIterator::new(fd, slice.position() as i64, slice.len() as i64);
+        let bytes = self
+            .records_cb
+            .get()
+            .map(|m| m.copy_memory_from())
+            .unwrap_or_default();
+        debug!(out_filter_bytes = bytes.len());
+
+        Ok((bytes, runtime_error))
+    }
+
+    /// process batches with maximum bytes to be send back consumer.
+    /// slice is read in chunks of whole batches, up to max bytes, until max bytes of kept records is reached.
+    /// if record fails, records before it are returned with error of the record
+    pub fn process(
+        &mut self,
+        slice: AsyncFileSlice,
+        max_bytes: usize,
+    ) -> Result<(RawRecordSet, Option<SmartStreamRuntimeError>), Error> {
+        let chunk_size = max_bytes.min(MAX_CHUNK_BYTES).max(BATCH_FILE_HEADER_SIZE);
+        let mut records = RawRecordSet::default();
+        let mut total_bytes = 0;
+        let mut position = 0;
+
+        while position < slice.len() as usize {
+            let buf = read_chunk(&slice, position, chunk_size)?;
+            if buf.is_empty() {
+                warn!(
+                    position,
+                    slice_len = slice.len(),
+                    "no whole batch left in file slice"
+                );
+                break;
+            }
+            position += buf.len();
+
+            let batches = FileBatch::from_slice(&buf)?;
+            let compressed = batches.iter().any(|batch| batch.is_compressed());
+
+            // filter of file slice can't read compressed records
+            let mut output = match self.slice_fn.clone() {
+                Some(slice_fn) if !compressed => {
+                    self.process_slice(&slice_fn, &buf, &batches, max_bytes, &mut total_bytes)?
+                }
+                _ => self.process_batches(&batches, max_bytes, &mut total_bytes)?,
+            };
+
+            records.batches.append(&mut output.records.batches);
+            if output.runtime_error.is_some() || output.max_bytes_reached {
+                return Ok((records, output.runtime_error));
+            }
+        }
+
+        debug!(
+            total_records = records.total_records(),
+            "no more batches filter end"
+        );
+        Ok((records, None))
+    }
+
+    /// pass whole chunk to module, kept records are copied from chunk as they are stored.
+    /// each batch with kept records is sent back with its base offset, so offsets of records are not changed
+    fn process_slice(
+        &self,
+        slice_fn: &TypedFunc<(i32, i32), i32>,
+        buf: &[u8],
+        batches: &[FileBatch],
+        max_bytes: usize,
+        total_bytes: &mut usize,
+    ) -> Result<ChunkOutput, Error> {
+        // error is reported with absolute offset
+        let (bytes, runtime_error) = self.call(slice_fn, buf)?;
+        let mut positions: Vec<SmartStreamRecordPosition> = vec![];
+        positions.decode(&mut Cursor::new(bytes), 0)?;
+
+        let mut positions = positions.into_iter().peekable();
+        let mut records = RawRecordSet::default();
+
+        for file_batch in batches {
+            // transaction markers are not passed to filter
+            if file_batch.batch.get_header().is_control() {
+                continue;
+            }
+
+            let mut raw_batch = RawBatch::default();
+            raw_batch.base_offset = file_batch.base_offset();
+            raw_batch.header = file_batch.batch.get_header().clone();
+
+            while let Some(position) = positions.peek() {
+                let start = position.position as usize;
+                let end = start + position.len as usize;
+                if start >= file_batch.range.end {
+                    break;
+                }
+                if start < file_batch.range.start || end > file_batch.range.end {
+                    return Err(anyhow!("invalid record position: {}", start));
+                }
+                raw_batch.mut_records().push(&buf[start..end]);
+                positions.next();
+            }
+
+            if raw_batch.records().is_empty() {
+                continue;
+            }
+
+            let record_bytes = raw_batch.records().write_size(0);
+            // if filter bytes exceed max bytes then we skip this batch
+            if *total_bytes + record_bytes > max_bytes {
+                debug!(
+                    total_bytes = *total_bytes + record_bytes,
+                    max_bytes, "total filter bytes reached"
+                );
+                return Ok(ChunkOutput::max_bytes_reached(records));
+            }
+            *total_bytes += record_bytes;
+
+            if let Some(error) = &runtime_error {
+                // batch ends before failed record
+                if error.offset <= raw_batch.get_last_offset() {
+                    raw_batch.set_offset_delta((error.offset - 1 - raw_batch.base_offset) as i32);
+                }
+            }
+
+            records.batches.push(raw_batch);
+        }
+
+        Ok(ChunkOutput::new(records, runtime_error))
+    }
+
+    /// run module on records of each batch, output is merged into single batch
+    fn process_batches(
+        &mut self,
+        batches: &[FileBatch],
+        max_bytes: usize,
+        total_bytes: &mut usize,
+    ) -> Result<ChunkOutput, Error> {
+        let mut batch_iterator = batches.iter();
 
         let mut filter_batch = DefaultBatch::default();
         filter_batch.base_offset = -1; // indicate this is unitialized
         filter_batch.set_offset_delta(-1); // make add_to_offset_delta correctly
 
-        let mut total_bytes = 0;
-
         loop {
-            if let Some(batch_result) = batch_iterator.next() {
+            if let Some(file_batch) = batch_iterator.next() {
                 // we filter-map entire batches.  entire batches are process as group
                 // if we can't fit current batch into max bytes then it is dicarded
-                let file_batch = batch_result?;
 
                 debug!(
                     current_batch_offset = file_batch.batch.base_offset,
@@ -232,31 +722,20 @@ impl SmartFilter {
                     "starting filter processing"
                 );
 
-                let now = Instant::now();
-
-                self.records_cb.clear();
-
-                let array_ptr = self.instance.copy_memory_to(&file_batch.records)?;
-
-                let filter_record_count = self
-                    .filter_fn
-                    .call((array_ptr as i32, file_batch.records.len() as i32))?;
-
-                debug!(filter_record_count,filter_execution_time = %now.elapsed().as_millis());
-
-                if filter_record_count == -1 {
-                    return Err(anyhow!("filter failed"));
+                // transaction markers are not passed to filter
+                if file_batch.batch.get_header().is_control() {
+                    if filter_batch.base_offset != -1 {
+                        filter_batch.add_to_offset_delta(file_batch.offset_delta() + 1);
+                    }
+                    continue;
                 }
 
-                let bytes = self
-                    .records_cb
-                    .get()
-                    .map(|m| m.copy_memory_from())
-                    .unwrap_or_default();
-                debug!(out_filter_bytes = bytes.len());
-                // this is inefficient for now
-                let mut records: Vec<DefaultRecord> = vec![];
-                records.decode(&mut Cursor::new(bytes), 0)?;
+                let (mut records, runtime_error) = self.invoke(&file_batch.records)?;
+                // module reports offset delta of record within batch
+                let runtime_error = runtime_error.map(|mut error| {
+                    error.offset += file_batch.base_offset();
+                    error
+                });
 
                 // there are filtered records!!
                 if records.is_empty() {
@@ -278,23 +757,45 @@ impl SmartFilter {
                     let record_bytes = records.write_size(0);
 
                     // if filter bytes exceed max bytes then we skip this batch
-                    if total_bytes + record_bytes > max_bytes {
+                    if *total_bytes + record_bytes > max_bytes {
                         debug!(
-                            total_bytes = total_bytes + record_bytes,
+                            total_bytes = *total_bytes + record_bytes,
                             max_bytes, "total filter bytes reached"
                         );
-                        return Ok(filter_batch);
+                        return Ok(ChunkOutput::max_bytes_reached(into_record_set(
+                            filter_batch,
+                        )?));
                     }
 
-                    total_bytes += record_bytes;
+                    *total_bytes += record_bytes;
+
+                    // batch is sent back, so aggregate continues from its last value
+                    if let (Some(accumulator), Some(last)) =
+                        (self.accumulator.as_mut(), records.last())
+                    {
+                        *accumulator = last.value().as_ref().to_vec();
+                    }
 
                     debug!(
                         filter_records = records.len(),
-                        total_bytes, "finished filtering"
+                        total_bytes = *total_bytes,
+                        "finished filtering"
                     );
                     filter_batch.mut_records().append(&mut records);
                 }
 
+                if let Some(error) = runtime_error {
+                    // batch ends before failed record
+                    if filter_batch.base_offset != -1 {
+                        filter_batch
+                            .set_offset_delta((error.offset - 1 - filter_batch.base_offset) as i32);
+                    }
+                    return Ok(ChunkOutput::new(
+                        into_record_set(filter_batch)?,
+                        Some(error),
+                    ));
+                }
+
                 // only increment filter offset delta if filter_batch has been initialized
                 if filter_batch.base_offset != -1 {
                     debug!(
@@ -306,9 +807,9 @@ impl SmartFilter {
             } else {
                 debug!(
                     total_records = filter_batch.records().len(),
-                    "no more batches filter end"
+                    "no more batches in chunk"
                 );
-                return Ok(filter_batch);
+                return Ok(ChunkOutput::new(into_record_set(filter_batch)?, None));
             }
         }
     }
@@ -329,111 +830,192 @@ async fn read<'a>(fd: RawFd, bytes: &'a mut [u8], position: i64) -> Result<usize
 }
 */
 
-// only encode information necessary to decode batches efficiently
-struct FileBatch {
-    batch: DefaultBatch,
-    records: Vec<u8>,
+/// records kept from chunk of file slice
+struct ChunkOutput {
+    records: RawRecordSet,
+    runtime_error: Option<SmartStreamRuntimeError>,
+    /// records exceeding max bytes are not sent, so rest of slice is not processed
+    max_bytes_reached: bool,
 }
 
-impl FileBatch {
-    fn base_offset(&self) -> Offset {
-        self.batch.base_offset
+impl ChunkOutput {
+    fn new(records: RawRecordSet, runtime_error: Option<SmartStreamRuntimeError>) -> Self {
+        Self {
+            records,
+            runtime_error,
+            max_bytes_reached: false,
+        }
     }
 
-    fn offset_delta(&self) -> i32 {
-        self.batch.header.last_offset_delta
+    fn max_bytes_reached(records: RawRecordSet) -> Self {
+        Self {
+            records,
+            runtime_error: None,
+            max_bytes_reached: true,
+        }
     }
 }
 
-/// Iterator that returns batch from file
-struct FileBatchIterator {
-    fd: RawFd,
-    offset: i64,
-    end: i64,
-}
+/// output of module merged into single batch, records are encoded to be sent back
+fn into_record_set(batch: DefaultBatch) -> Result<RawRecordSet, IoError> {
+    if batch.records().is_empty() {
+        return Ok(RawRecordSet::default());
+    }
 
-impl FileBatchIterator {
-    fn new(fd: RawFd, offset: i64, len: i64) -> Self {
-        Self {
-            fd,
-            offset,
-            end: offset + len,
-        }
+    let mut raw_batch = RawBatch::default();
+    raw_batch.base_offset = batch.base_offset;
+    raw_batch.header = batch.get_header().clone();
+    for record in batch.records() {
+        raw_batch.mut_records().push_record(record)?;
     }
+    Ok(RawRecordSet::default().add(raw_batch))
 }
 
-impl Iterator for FileBatchIterator {
-    type Item = Result<FileBatch, IoError>;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.offset >= self.end {
-            return None;
+/// read chunk of file slice from position, chunk ends with last whole batch within chunk size.
+/// if first batch is bigger than chunk size, chunk is that batch
+fn read_chunk(
+    slice: &AsyncFileSlice,
+    position: usize,
+    chunk_size: usize,
+) -> Result<Vec<u8>, IoError> {
+    let remaining = slice.len() as usize - position;
+    let mut buf = read_slice(slice, position, chunk_size.min(remaining))?;
+
+    let mut end = 0;
+    while end + BATCH_FILE_HEADER_SIZE <= buf.len() {
+        let batch_end = end + FileBatch::size(&buf[end..])?;
+        if batch_end > buf.len() {
+            if end == 0 && batch_end <= remaining {
+                buf = read_slice(slice, position, batch_end)?;
+                end = buf.len();
+            }
+            break;
         }
+        end = batch_end;
+    }
 
-        let mut header = vec![0u8; BATCH_FILE_HEADER_SIZE];
-        let bytes_read = match pread(self.fd, &mut header, self.offset)
-            .map_err(|err| IoError::new(ErrorKind::Other, format!("pread error {}", err)))
-        {
-            Ok(bytes) => bytes,
-            Err(err) => return Some(Err(err)),
-        };
+    buf.truncate(end);
+    Ok(buf)
+}
 
-        if bytes_read < header.len() {
-            warn!(bytes_read, header_len = header.len());
-            return Some(Err(IoError::new(
-                ErrorKind::UnexpectedEof,
-                format!(
-                    "not eough for batch header {} out of {}",
-                    bytes_read,
-                    header.len()
-                ),
-            )));
-        }
+/// read bytes of file slice from position at once, rather than reading each batch
+fn read_slice(slice: &AsyncFileSlice, position: usize, len: usize) -> Result<Vec<u8>, IoError> {
+    use std::os::unix::io::AsRawFd;
 
-        let mut batch = Batch::default();
-        if let Err(err) = batch.decode_from_file_buf(&mut Cursor::new(header), 0) {
-            return Some(Err(IoError::new(
-                ErrorKind::Other,
-                format!("decodinge batch header error {}", err),
-            )));
+    let mut buf = vec![0u8; len];
+    let mut bytes_read = 0;
+    while bytes_read < buf.len() {
+        let read = pread(
+            slice.as_raw_fd(),
+            &mut buf[bytes_read..],
+            slice.position() as i64 + (position + bytes_read) as i64,
+        )
+        .map_err(|err| IoError::new(ErrorKind::Other, format!("pread error {}", err)))?;
+        if read == 0 {
+            warn!(bytes_read, slice_len = buf.len(), "file slice truncated");
+            buf.truncate(bytes_read);
+            break;
         }
+        bytes_read += read;
+    }
+    Ok(buf)
+}
 
-        let remainder = batch.batch_len as usize - BATCH_HEADER_SIZE as usize;
+/// batch read from file slice
+struct FileBatch<'a> {
+    batch: DefaultBatch,
+    /// position of encoded records within slice
+    range: Range<usize>,
+    /// records are uncompressed if batch is compressed
+    records: Cow<'a, [u8]>,
+}
 
-        debug!(
-            file_offset = self.offset,
-            base_offset = batch.base_offset,
-            "fbatch header"
-        );
+impl<'a> FileBatch<'a> {
+    /// decode headers of batches in slice
+    fn from_slice(buf: &'a [u8]) -> Result<Vec<Self>, IoError> {
+        let mut batches = vec![];
+        let mut position = 0;
+
+        while position < buf.len() {
+            let header_end = position + BATCH_FILE_HEADER_SIZE;
+            if header_end > buf.len() {
+                return Err(IoError::new(
+                    ErrorKind::UnexpectedEof,
+                    format!(
+                        "not eough for batch header {} out of {}",
+                        buf.len() - position,
+                        BATCH_FILE_HEADER_SIZE
+                    ),
+                ));
+            }
 
-        let mut records = vec![0u8; remainder];
+            let mut batch = Batch::default();
+            batch
+                .decode_from_file_buf(&mut Cursor::new(&buf[position..header_end]), 0)
+                .map_err(|err| {
+                    IoError::new(
+                        ErrorKind::Other,
+                        format!("decodinge batch header error {}", err),
+                    )
+                })?;
+
+            let records_end =
+                header_end + (batch.batch_len as usize).saturating_sub(BATCH_HEADER_SIZE);
+            if records_end > buf.len() {
+                return Err(IoError::new(
+                    ErrorKind::UnexpectedEof,
+                    format!(
+                        "not enough for batch records {} out of {}",
+                        buf.len() - header_end,
+                        records_end - header_end
+                    ),
+                ));
+            }
 
-        self.offset += BATCH_FILE_HEADER_SIZE as i64;
+            debug!(position, base_offset = batch.base_offset, "fbatch header");
+
+            let range = header_end..records_end;
+            // filter works on raw records, so compressed records must be uncompressed first
+            let records = match batch.get_compression()? {
+                Compression::None => Cow::Borrowed(&buf[range.clone()]),
+                compression => Cow::Owned(compression.uncompress(&buf[range.clone()])?),
+            };
+
+            batches.push(FileBatch {
+                batch,
+                range,
+                records,
+            });
+            position = records_end;
+        }
 
-        let bytes_read = match pread(self.fd, &mut records, self.offset)
-            .map_err(|err| IoError::new(ErrorKind::Other, format!("pread error {}", err)))
-        {
-            Ok(bytes) => bytes,
-            Err(err) => return Some(Err(err)),
-        };
+        Ok(batches)
+    }
 
-        if bytes_read < records.len() {
-            warn!(bytes_read, record_len = records.len());
-            return Some(Err(IoError::new(
-                ErrorKind::UnexpectedEof,
-                format!(
-                    "not enough for batch records {} out of {}",
-                    bytes_read,
-                    records.len()
-                ),
-            )));
-        }
+    /// size of batch in file, decoded from its header
+    fn size(buf: &[u8]) -> Result<usize, IoError> {
+        let mut batch = DefaultBatch::default();
+        batch
+            .decode_from_file_buf(&mut Cursor::new(&buf[..BATCH_FILE_HEADER_SIZE]), 0)
+            .map_err(|err| {
+                IoError::new(
+                    ErrorKind::Other,
+                    format!("decoding batch header error {}", err),
+                )
+            })?;
+        Ok(BATCH_FILE_HEADER_SIZE + (batch.batch_len as usize).saturating_sub(BATCH_HEADER_SIZE))
+    }
 
-        self.offset += bytes_read as i64;
+    fn base_offset(&self) -> Offset {
+        self.batch.base_offset
+    }
 
-        debug!(file_offset = self.offset, "fbatch end");
+    fn offset_delta(&self) -> i32 {
+        self.batch.header.last_offset_delta
+    }
 
-        Some(Ok(FileBatch { batch, records }))
+    fn is_compressed(&self) -> bool {
+        !matches!(self.batch.get_compression(), Ok(Compression::None))
     }
 }
 
@@ -463,4 +1045,78 @@ mod test {
         println!("bytes read: {}", bytes_read);
         assert!(bytes_read > 2);
     }
+
+    #[test]
+    fn test_file_batch_from_slice() {
+        let mut first = DefaultBatch::new(vec![
+            DefaultRecord::new("apple"),
+            DefaultRecord::new("banana"),
+        ]);
+        first.base_offset = 0;
+        let mut second = DefaultBatch::new(vec![DefaultRecord::new("cherry")]);
+        second.base_offset = 2;
+        second.compress(Compression::Gzip).expect("compress");
+
+        let mut buf = vec![];
+        first.encode(&mut buf, 0).expect("encode");
+        let first_end = buf.len();
+        second.encode(&mut buf, 0).expect("encode");
+
+        let batches = FileBatch::from_slice(&buf).expect("batches");
+        assert_eq!(batches.len(), 2);
+
+        assert_eq!(batches[0].base_offset(), 0);
+        assert_eq!(batches[0].offset_delta(), 1);
+        assert!(!batches[0].is_compressed());
+        assert_eq!(batches[0].range.start, BATCH_FILE_HEADER_SIZE);
+        assert_eq!(batches[0].range.end, first_end);
+
+        assert_eq!(batches[1].base_offset(), 2);
+        assert!(batches[1].is_compressed());
+        let mut records: Vec<DefaultRecord> = vec![];
+        records
+            .decode(&mut Cursor::new(batches[1].records.as_ref()), 0)
+            .expect("decode");
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].value().as_ref(), b"cherry");
+
+        // truncated slice
+        assert!(FileBatch::from_slice(&buf[..buf.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_read_chunk() {
+        let mut first = DefaultBatch::new(vec![
+            DefaultRecord::new("apple"),
+            DefaultRecord::new("banana"),
+        ]);
+        first.base_offset = 0;
+        let mut second = DefaultBatch::new(vec![DefaultRecord::new("cherry")]);
+        second.base_offset = 2;
+
+        let mut buf = vec![];
+        first.encode(&mut buf, 0).expect("encode");
+        let first_end = buf.len();
+        second.encode(&mut buf, 0).expect("encode");
+
+        let path = temp_dir().join("filter_read_chunk");
+        let mut file = File::create(&path).expect("create");
+        file.write_all(&buf).expect("write");
+        file.sync_all().expect("flush");
+        drop(file);
+
+        let read_only = File::open(path).expect("open");
+        let slice = AsyncFileSlice::new(read_only.as_raw_fd(), 0, buf.len() as u64);
+
+        // chunk ends with last whole batch
+        let chunk = read_chunk(&slice, 0, first_end + 1).expect("chunk");
+        assert_eq!(chunk, &buf[..first_end]);
+
+        // batch bigger than chunk is read whole
+        let chunk = read_chunk(&slice, 0, BATCH_FILE_HEADER_SIZE).expect("chunk");
+        assert_eq!(chunk, &buf[..first_end]);
+
+        let chunk = read_chunk(&slice, first_end, buf.len()).expect("chunk");
+        assert_eq!(chunk, &buf[first_end..]);
+    }
 }
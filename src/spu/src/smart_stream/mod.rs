@@ -1,2 +1,3 @@
 pub mod filter;
 mod memory;
+pub mod produce;
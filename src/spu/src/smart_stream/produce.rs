@@ -0,0 +1,80 @@
+//!
+//! # SmartStream of topic
+//!
+//! Leader applies SmartStream of topic to records before they are written.
+//! Function is kept by leader replica and instantiated again only when SmartStream of topic
+//! or its version is changed. Records are processed on blocking thread, so module doesn't
+//! hold up other requests served by executor.
+//!
+use std::fmt;
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_mutex::Mutex;
+
+use dataplane::record::RecordSet;
+use dataplane::smartstream::SmartStreamRuntimeError;
+use fluvio_controlplane_metadata::smartstream::SmartStream;
+use fluvio_spu_schema::server::stream_fetch::SmartStreamKind;
+
+use super::filter::{SmartStreamEngine, SmartStreamFunction, SmartStreamModule};
+
+/// SmartStream function cached by leader replica, empty until first record is produced
+pub type SharedTopicSmartStream = Arc<Mutex<Option<TopicSmartStreamFunction>>>;
+
+/// instantiated SmartStream of topic
+pub struct TopicSmartStreamFunction {
+    name: String,
+    version: u32,
+    kind: SmartStreamKind,
+    function: SmartStreamFunction,
+    // store of module is shared with function, both are moved between threads together
+    #[allow(dead_code)]
+    module: SmartStreamModule,
+}
+
+impl fmt::Debug for TopicSmartStreamFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "TopicSmartStreamFunction({}:{})",
+            self.name, self.version
+        )
+    }
+}
+
+// store is only referenced by module and function, and cached function is used by one thread at a time
+unsafe impl Send for TopicSmartStreamFunction {}
+
+impl TopicSmartStreamFunction {
+    pub fn create(
+        engine: &SmartStreamEngine,
+        smart_stream: &SmartStream,
+        kind: SmartStreamKind,
+    ) -> Result<Self> {
+        let module = engine.create_module_from_registry(smart_stream)?;
+        let function = module.create_function(kind)?;
+        Ok(Self {
+            name: smart_stream.name.clone(),
+            version: smart_stream.version,
+            kind,
+            function,
+            module,
+        })
+    }
+
+    /// true if function has been instantiated from same version of SmartStream
+    pub fn is_current(&self, smart_stream: &SmartStream, kind: SmartStreamKind) -> bool {
+        self.name == smart_stream.name && self.version == smart_stream.version && self.kind == kind
+    }
+
+    /// process batches of record set, processing stops at first record rejected by module
+    pub fn process(&mut self, records: &mut RecordSet) -> Result<Option<SmartStreamRuntimeError>> {
+        for batch in records.batches.iter_mut() {
+            if let Some(runtime_error) = self.function.process_batch(batch)? {
+                return Ok(Some(runtime_error));
+            }
+        }
+        Ok(None)
+    }
+}
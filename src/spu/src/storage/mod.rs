@@ -96,6 +96,12 @@ where
         (reader.get_log_start_offset(), reader.get_hw())
     }
 
+    /// get offset of first batch at or after timestamp
+    pub async fn find_offset_by_timestamp(&self, timestamp: i64) -> Offset {
+        let reader = self.read().await;
+        reader.find_offset_by_timestamp(timestamp)
+    }
+
     /// read records into partition response
     /// return leo and hw
     pub async fn read_records<P>(
@@ -125,11 +131,12 @@ where
         }
     }
 
+    /// write records to storage, return log end offset after write
     pub async fn write_record_set(
         &self,
         records: &mut RecordSet,
         hw_update: bool,
-    ) -> Result<(), StorageError> {
+    ) -> Result<Offset, StorageError> {
         debug!(
             replica = %self.id,
             leo = self.leo(),
@@ -153,7 +160,31 @@ where
             self.hw.update(hw);
         }
 
-        Ok(())
+        Ok(leo)
+    }
+
+    /// remove records that are out of retention
+    pub async fn remove_expired_segments(&self) -> Result<bool, StorageError> {
+        let mut writer = self.write().await;
+        let removed = writer.remove_expired_segments().await?;
+        if removed {
+            debug!(
+                replica = %self.id,
+                log_start_offset = writer.get_log_start_offset(),
+                "removed expired segments"
+            );
+        }
+        Ok(removed)
+    }
+
+    /// compact records of replica with compact cleanup policy
+    pub async fn compact_segments(&self) -> Result<bool, StorageError> {
+        let mut writer = self.write().await;
+        let compacted = writer.compact_segments().await?;
+        if compacted {
+            debug!(replica = %self.id, "compacted segments");
+        }
+        Ok(compacted)
     }
 
     /// perform permanent remove
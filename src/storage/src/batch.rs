@@ -149,7 +149,7 @@ where
         }
 
         let mut cursor = Cursor::new(bytes);
-        self.inner.mut_records().decode(&mut cursor, 0)?;
+        self.inner.decode_records(&mut cursor, 0)?;
 
         Ok(())
     }
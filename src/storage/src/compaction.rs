@@ -0,0 +1,430 @@
+//!
+//! # Log compaction
+//!
+//! Read only segments of compacted replica are rewritten, keeping only latest record of each key.
+//! Kept records are copied with their original offsets, so offset index and followers
+//! see same offsets as before. Offsets of removed records become gaps in the log.
+//!
+//! Compacted files are moved next to original files as swap files, message log last,
+//! then swap files replace original files, message log last again. Swap interrupted by crash
+//! is completed when segments are loaded if message log swap file exists, otherwise rolled back.
+//!
+use std::cmp::max;
+use std::collections::{BTreeSet, HashMap};
+use std::io::{Error as IoError, ErrorKind};
+use std::path::{Path, PathBuf};
+
+use tracing::{debug, trace};
+
+use fluvio_future::fs::{create_dir_all, remove_dir_all, remove_file, rename};
+use dataplane::Offset;
+
+use crate::config::ConfigOption;
+use crate::index::EXTENSION as INDEX_EXTENSION;
+use crate::mut_records::MESSAGE_LOG_EXTENSION;
+use crate::time_index::EXTENSION as TIME_INDEX_EXTENSION;
+use crate::segment::{MutableSegment, ReadSegment};
+use crate::transaction::TransactionIndex;
+use crate::util::generate_file_name;
+use crate::StorageError;
+
+/// directory under replica where compacted segments are written before they replace originals
+const COMPACTION_DIR: &str = "compaction";
+
+/// suffix of compacted segment file which is about to replace original file
+const SWAP_SUFFIX: &str = "swap";
+
+/// files of segment in order they are swapped, message log marks swap as complete
+const SEGMENT_EXTENSIONS: [&str; 3] =
+    [INDEX_EXTENSION, TIME_INDEX_EXTENSION, MESSAGE_LOG_EXTENSION];
+
+/// rewrite segments with latest record of each key
+pub(crate) struct Compaction {
+    option: ConfigOption,
+    latest_offsets: HashMap<Vec<u8>, Offset>,
+}
+
+impl Compaction {
+    /// start new compaction, any files left over from previous one are removed
+    pub async fn new(option: &ConfigOption) -> Result<Self, StorageError> {
+        let compaction_dir = option.base_dir.join(COMPACTION_DIR);
+        if compaction_dir.exists() {
+            remove_dir_all(&compaction_dir).await?;
+        }
+        create_dir_all(&compaction_dir).await?;
+
+        Ok(Self {
+            option: option.clone(),
+            latest_offsets: HashMap::new(),
+        })
+    }
+
+    /// record offsets of keys in segment, segments must be scanned in order of offsets.
+    /// records of aborted transactions are skipped, so they don't replace committed records
+    pub async fn scan(
+        &mut self,
+        segment: &ReadSegment,
+        transactions: &TransactionIndex,
+    ) -> Result<(), StorageError> {
+        let mut batch_stream = segment.open_default_batch_stream().await?;
+        while let Some(batch_pos) = batch_stream.next().await {
+            let batch = batch_pos.get_batch();
+            let header = batch.get_header();
+            if header.is_control() {
+                continue;
+            }
+            if header.is_transactional()
+                && transactions.is_aborted(header.producer_id, batch.get_base_offset())
+            {
+                trace!(
+                    base_offset = batch.get_base_offset(),
+                    "skipping aborted batch"
+                );
+                continue;
+            }
+            for record in batch.records() {
+                if let Some(key) = record.key() {
+                    self.latest_offsets.insert(
+                        key.as_ref().to_vec(),
+                        batch.get_base_offset() + record.get_offset_delta(),
+                    );
+                }
+            }
+        }
+
+        if let Some(err) = batch_stream.invalid() {
+            return Err(err.into());
+        }
+        Ok(())
+    }
+
+    /// copy records which are latest for their keys into new segment.
+    /// tombstones, records with empty value, are removed only if `remove_tombstones` is set.
+    /// return new segment if any record has been removed, otherwise segment is left as it is
+    pub async fn compact(
+        &self,
+        segment: &ReadSegment,
+        remove_tombstones: bool,
+    ) -> Result<Option<ReadSegment>, StorageError> {
+        let base_offset = segment.get_base_offset();
+        let compaction_option = ConfigOption {
+            base_dir: self.option.base_dir.join(COMPACTION_DIR),
+            // compacted segment is never larger than original
+            segment_max_bytes: max(self.option.segment_max_bytes, segment.get_msg_size() as u32),
+            // segment is flushed once it is written
+            flush_write_count: 0,
+            flush_idle_msec: 0,
+            ..self.option.clone()
+        };
+        let mut compacted = MutableSegment::create(base_offset, &compaction_option).await?;
+
+        let mut removed = 0;
+        let mut batch_stream = segment.open_default_batch_stream().await?;
+        let mut next_batch = batch_stream.next().await;
+        while let Some(batch_pos) = next_batch {
+            next_batch = batch_stream.next().await;
+            let mut batch = batch_pos.get_batch().clone();
+            if !batch.get_header().is_control() {
+                let records = batch.records().len();
+                let latest_offsets = &self.latest_offsets;
+                let base_offset = batch.get_base_offset();
+                batch.mut_records().retain(|record| match record.key() {
+                    // records without key can't be compacted
+                    None => true,
+                    Some(key) => {
+                        let offset = base_offset + record.get_offset_delta();
+                        latest_offsets.get(key.as_ref()) == Some(&offset)
+                            && !(remove_tombstones && record.value().as_ref().is_empty())
+                    }
+                });
+                removed += records - batch.records().len();
+
+                // last batch is always kept, so segment still ends at same offset
+                if batch.records().is_empty() && next_batch.is_some() {
+                    trace!(base_offset, "all records of batch removed");
+                    continue;
+                }
+            }
+            if !compacted.write_batch(&mut batch).await? {
+                return Err(IoError::new(
+                    ErrorKind::Other,
+                    format!("compacted segment: {} is full", base_offset),
+                )
+                .into());
+            }
+        }
+
+        if let Some(err) = batch_stream.invalid() {
+            return Err(err.into());
+        }
+
+        compacted.roll_over().await?;
+        compacted.flush().await?;
+        drop(compacted);
+
+        if removed == 0 {
+            for extension in &SEGMENT_EXTENSIONS {
+                remove_file(generate_file_name(
+                    &compaction_option.base_dir,
+                    base_offset,
+                    extension,
+                ))
+                .await?;
+            }
+            debug!(base_offset, "no records removed, keeping segment");
+            return Ok(None);
+        }
+
+        for extension in &SEGMENT_EXTENSIONS {
+            rename(
+                generate_file_name(&compaction_option.base_dir, base_offset, extension),
+                swap_file_name(&self.option.base_dir, base_offset, extension),
+            )
+            .await?;
+        }
+        // files are replaced by rename, so readers of old segment still read from old files
+        swap_segment(&self.option.base_dir, base_offset).await?;
+
+        debug!(base_offset, removed, "compacted segment");
+        Ok(Some(
+            ReadSegment::open_for_read(base_offset, &self.option).await?,
+        ))
+    }
+}
+
+/// complete or roll back swaps of compacted segments interrupted by crash
+pub(crate) async fn recover_swaps(dir: &Path) -> Result<(), StorageError> {
+    let mut base_offsets = BTreeSet::new();
+    for entry in dir.read_dir()? {
+        let path = entry?.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some(SWAP_SUFFIX) {
+            continue;
+        }
+        let base_offset = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.split('.').next())
+            .and_then(|offset| offset.parse::<Offset>().ok());
+        if let Some(base_offset) = base_offset {
+            base_offsets.insert(base_offset);
+        }
+    }
+
+    for base_offset in base_offsets {
+        if swap_file_name(dir, base_offset, MESSAGE_LOG_EXTENSION).exists() {
+            debug!(base_offset, "completing swap of compacted segment");
+            swap_segment(dir, base_offset).await?;
+        } else {
+            debug!(base_offset, "rolling back swap of compacted segment");
+            for extension in &SEGMENT_EXTENSIONS {
+                let swap_file = swap_file_name(dir, base_offset, extension);
+                if swap_file.exists() {
+                    remove_file(swap_file).await?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// replace files of segment with swap files which are present, message log last
+async fn swap_segment(dir: &Path, base_offset: Offset) -> Result<(), IoError> {
+    for extension in &SEGMENT_EXTENSIONS {
+        let swap_file = swap_file_name(dir, base_offset, extension);
+        if swap_file.exists() {
+            rename(swap_file, generate_file_name(dir, base_offset, extension)).await?;
+        }
+    }
+    Ok(())
+}
+
+fn swap_file_name(dir: &Path, base_offset: Offset, extension: &str) -> PathBuf {
+    generate_file_name(dir, base_offset, &format!("{}.{}", extension, SWAP_SUFFIX))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::env::temp_dir;
+    use std::fs;
+
+    use fluvio_future::test_async;
+    use flv_util::fixture::ensure_new_dir;
+    use dataplane::batch::{BatchHeader, DefaultBatch};
+    use dataplane::record::DefaultRecord;
+    use dataplane::transaction::ControlRecordType;
+
+    use crate::config::ConfigOption;
+    use crate::segment::MutableSegment;
+    use crate::transaction::TransactionIndex;
+    use crate::util::generate_file_name;
+    use crate::StorageError;
+
+    use super::{Compaction, SEGMENT_EXTENSIONS, recover_swaps, swap_file_name};
+    use super::{INDEX_EXTENSION, MESSAGE_LOG_EXTENSION, TIME_INDEX_EXTENSION};
+
+    fn key_values(records: &[(&str, &str)]) -> DefaultBatch {
+        DefaultBatch::new(
+            records
+                .iter()
+                .map(|(key, value)| DefaultRecord::new_key_value(*key, *value))
+                .collect(),
+        )
+    }
+
+    #[test_async]
+    async fn test_compact_segment() -> Result<(), StorageError> {
+        let test_dir = temp_dir().join("compaction-segment");
+        ensure_new_dir(&test_dir)?;
+
+        let option = ConfigOption {
+            base_dir: test_dir,
+            segment_max_bytes: 1000,
+            index_max_bytes: 1000,
+            index_max_interval_bytes: 0,
+            ..Default::default()
+        };
+
+        let mut segment = MutableSegment::create(10, &option).await?;
+        segment
+            .write_batch(&mut key_values(&[("a", "1"), ("b", "1")]))
+            .await?; // 10, 11
+        segment
+            .write_batch(&mut key_values(&[("a", "2"), ("c", "1")]))
+            .await?; // 12, 13
+        segment
+            .write_batch(&mut key_values(&[("b", ""), ("a", "3")]))
+            .await?; // 14, 15
+        let segment = segment.convert_to_segment().await?;
+        assert_eq!(segment.get_end_offset(), 16);
+
+        let mut compaction = Compaction::new(&option).await?;
+        compaction
+            .scan(&segment, &TransactionIndex::default())
+            .await?;
+
+        let compacted = compaction
+            .compact(&segment, false)
+            .await?
+            .expect("compacted");
+        assert_eq!(compacted.get_base_offset(), 10);
+        assert_eq!(compacted.get_end_offset(), 16);
+        assert!(compacted.get_msg_size() < segment.get_msg_size());
+
+        let mut batch_stream = compacted.open_default_batch_stream().await?;
+        let batch = batch_stream.next().await.expect("batch");
+        assert_eq!(batch.get_base_offset(), 12);
+        let records = batch.get_batch().records();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].get_offset_delta(), 1);
+        assert_eq!(records[0].value().as_ref(), b"1");
+
+        let batch = batch_stream.next().await.expect("batch");
+        assert_eq!(batch.get_base_offset(), 14);
+        assert_eq!(batch.get_last_offset(), 15);
+        assert_eq!(batch.get_batch().records().len(), 2);
+        assert!(batch_stream.next().await.is_none());
+
+        // offset removed by compaction is read from next batch
+        let position = compacted.find_offset_position(11).await?.expect("position");
+        assert_eq!(position.get_base_offset(), 12);
+
+        // nothing more to remove unless tombstones are removed
+        assert!(compaction.compact(&compacted, false).await?.is_none());
+        let compacted = compaction
+            .compact(&compacted, true)
+            .await?
+            .expect("tombstone removed");
+        assert_eq!(compacted.get_end_offset(), 16);
+
+        Ok(())
+    }
+
+    #[test_async]
+    async fn test_compact_aborted_transaction() -> Result<(), StorageError> {
+        let test_dir = temp_dir().join("compaction-aborted");
+        ensure_new_dir(&test_dir)?;
+
+        let option = ConfigOption {
+            base_dir: test_dir,
+            segment_max_bytes: 1000,
+            index_max_bytes: 1000,
+            index_max_interval_bytes: 0,
+            ..Default::default()
+        };
+
+        let mut segment = MutableSegment::create(10, &option).await?;
+        segment
+            .write_batch(&mut key_values(&[("a", "1"), ("b", "1")]))
+            .await?; // 10, 11
+        let mut aborted = key_values(&[("a", "2")]);
+        aborted.get_mut_header().set_producer(1, 0, 0);
+        aborted.get_mut_header().set_transactional(true);
+        segment.write_batch(&mut aborted).await?; // 12
+        segment.write_batch(&mut key_values(&[("b", "2")])).await?; // 13
+        let segment = segment.convert_to_segment().await?;
+
+        let mut transactions = TransactionIndex::default();
+        transactions.add_batch(aborted.get_header(), 12, None);
+        let mut marker = BatchHeader::default();
+        marker.set_producer(1, 0, 0);
+        marker.set_transactional(true);
+        marker.set_control(true);
+        transactions.add_batch(&marker, 14, Some(ControlRecordType::Abort));
+
+        let mut compaction = Compaction::new(&option).await?;
+        compaction.scan(&segment, &transactions).await?;
+        let compacted = compaction
+            .compact(&segment, false)
+            .await?
+            .expect("compacted");
+
+        // committed value of key is kept, aborted one is removed
+        let mut batch_stream = compacted.open_default_batch_stream().await?;
+        let batch = batch_stream.next().await.expect("batch");
+        assert_eq!(batch.get_base_offset(), 10);
+        let records = batch.get_batch().records();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].key().expect("key").as_ref(), b"a");
+        assert_eq!(records[0].value().as_ref(), b"1");
+
+        let batch = batch_stream.next().await.expect("batch");
+        assert_eq!(batch.get_base_offset(), 13);
+        assert!(batch_stream.next().await.is_none());
+
+        Ok(())
+    }
+
+    #[test_async]
+    async fn test_recover_swaps() -> Result<(), StorageError> {
+        let test_dir = temp_dir().join("compaction-swap");
+        ensure_new_dir(&test_dir)?;
+
+        for extension in &SEGMENT_EXTENSIONS {
+            fs::write(generate_file_name(&test_dir, 10, extension), "old")?;
+            fs::write(generate_file_name(&test_dir, 20, extension), "old")?;
+        }
+        // crash after message log of segment 10 has been moved next to original
+        fs::write(swap_file_name(&test_dir, 10, TIME_INDEX_EXTENSION), "new")?;
+        fs::write(swap_file_name(&test_dir, 10, MESSAGE_LOG_EXTENSION), "new")?;
+        // crash before all files of segment 20 have been moved
+        fs::write(swap_file_name(&test_dir, 20, INDEX_EXTENSION), "new")?;
+
+        recover_swaps(&test_dir).await?;
+
+        let contents = |offset, extension| {
+            fs::read_to_string(generate_file_name(&test_dir, offset, extension)).expect("read")
+        };
+        // index of segment 10 has been swapped before crash
+        assert_eq!(contents(10, INDEX_EXTENSION), "old");
+        assert_eq!(contents(10, TIME_INDEX_EXTENSION), "new");
+        assert_eq!(contents(10, MESSAGE_LOG_EXTENSION), "new");
+        for extension in &SEGMENT_EXTENSIONS {
+            assert_eq!(contents(20, extension), "old");
+            assert!(!swap_file_name(&test_dir, 10, extension).exists());
+            assert!(!swap_file_name(&test_dir, 20, extension).exists());
+        }
+
+        Ok(())
+    }
+}
@@ -34,6 +34,28 @@ pub struct ConfigOption {
     pub max_batch_size: Size,
     #[serde(default = "default_update_hw")]
     pub update_hw: bool, // if true, enable hw update
+    #[serde(default)]
+    pub retention_seconds: Option<Size>, // if none, segments are kept regardless of age
+    #[serde(default)]
+    pub max_partition_size: Option<u64>, // if none, there is no limit
+    #[serde(default)]
+    pub cleanup_policy: CleanupPolicy,
+}
+
+/// How records are removed from read only segments
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CleanupPolicy {
+    /// segments are removed when out of retention
+    Delete,
+    /// segments are rewritten with only latest record of each key
+    Compact,
+}
+
+impl Default for CleanupPolicy {
+    fn default() -> Self {
+        CleanupPolicy::Delete
+    }
 }
 
 impl fmt::Display for ConfigOption {
@@ -95,6 +117,9 @@ impl ConfigOption {
             flush_idle_msec,
             max_batch_size,
             update_hw: true,
+            retention_seconds: None,
+            max_partition_size: None,
+            cleanup_policy: CleanupPolicy::default(),
         }
     }
 
@@ -113,6 +138,24 @@ impl ConfigOption {
         self
     }
 
+    /// segments older than this are removed, if none there is no time limit
+    pub fn retention_seconds(mut self, seconds: Option<Size>) -> Self {
+        self.retention_seconds = seconds;
+        self
+    }
+
+    /// max bytes per replica, oldest segments are removed when exceeded
+    pub fn max_partition_size(mut self, bytes: Option<u64>) -> Self {
+        self.max_partition_size = bytes;
+        self
+    }
+
+    /// compacted replica keeps latest record of each key rather than removing old segments
+    pub fn cleanup_policy(mut self, policy: CleanupPolicy) -> Self {
+        self.cleanup_policy = policy;
+        self
+    }
+
     /// disable hw update
     pub fn disable_update_hw(mut self) -> Self {
         self.update_hw = false;
@@ -131,6 +174,9 @@ impl Default for ConfigOption {
             flush_idle_msec: default_flush_idle_msec(),
             max_batch_size: default_max_batch_size(),
             update_hw: true,
+            retention_seconds: None,
+            max_partition_size: None,
+            cleanup_policy: CleanupPolicy::default(),
         }
     }
 }
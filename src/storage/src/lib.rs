@@ -1,6 +1,7 @@
 pub mod batch;
 pub mod batch_header;
 mod checkpoint;
+mod compaction;
 mod error;
 mod records;
 mod index;
@@ -11,6 +12,10 @@ mod replica;
 pub mod segment;
 mod util;
 mod validator;
+mod transaction;
+mod producer;
+mod time_index;
+mod snapshot;
 pub mod config;
 
 #[cfg(feature = "fixture")]
@@ -27,7 +32,8 @@ mod inner {
     use async_trait::async_trait;
 
     use dataplane::{ErrorCode, Isolation, Offset, ReplicaKey};
-    use dataplane::fetch::FilePartitionResponse;
+    use dataplane::batch::BatchHeader;
+    use dataplane::fetch::{AbortedTransaction, FilePartitionResponse};
     use dataplane::record::RecordSet;
     use fluvio_future::file_slice::AsyncFileSlice;
 
@@ -114,6 +120,8 @@ mod inner {
         fn set_slice(&mut self, slice: AsyncFileSlice);
 
         fn set_error_code(&mut self, error: ErrorCode);
+
+        fn set_aborted_transactions(&mut self, _aborted: Vec<AbortedTransaction>) {}
     }
 
     impl SlicePartitionResponse for FilePartitionResponse {
@@ -137,6 +145,10 @@ mod inner {
         fn set_error_code(&mut self, error: ErrorCode) {
             self.error_code = error;
         }
+
+        fn set_aborted_transactions(&mut self, aborted: Vec<AbortedTransaction>) {
+            self.aborted = Some(aborted);
+        }
     }
 
     /// some storage configuration
@@ -179,6 +191,24 @@ mod inner {
 
         async fn update_high_watermark(&mut self, offset: Offset) -> Result<bool, StorageError>;
 
+        /// remove records that are no longer retained according to retention policy
+        /// return true if log start offset has been changed
+        async fn remove_expired_segments(&mut self) -> Result<bool, StorageError>;
+
+        /// rewrite read only segments with only latest record of each key if replica is compacted
+        /// return true if any segment has been compacted
+        async fn compact_segments(&mut self) -> Result<bool, StorageError>;
+
+        /// offset of first batch with timestamp, in milliseconds, at or after given timestamp
+        /// log end offset if there is no such batch
+        fn find_offset_by_timestamp(&self, timestamp: i64) -> Offset;
+
+        /// header and base offset of last batch written by each idempotent producer
+        fn last_producer_batches(&self) -> Vec<(BatchHeader, Offset)>;
+
+        /// producer has transactional records which are not yet committed or aborted
+        fn has_ongoing_transaction(&self, producer_id: i64) -> bool;
+
         /// permanently remove
         async fn remove(&self) -> Result<(), StorageError>;
     }
@@ -0,0 +1,102 @@
+//!
+//! # Producer index
+//!
+//! Tracks last batch written by each idempotent producer.
+//! Leader restores producer sequences from it, so retried batch is not written twice
+//! after SPU restart or leader change.
+//!
+
+use std::collections::HashMap;
+
+use dataplane::Offset;
+use dataplane::batch::BatchHeader;
+use dataplane::derive::{Decode, Encode};
+
+#[derive(Debug, Default)]
+pub struct ProducerIndex {
+    /// header and base offset of last batch of each producer
+    last_batches: HashMap<i64, (BatchHeader, Offset)>,
+}
+
+impl ProducerIndex {
+    /// update index with batch written to the log
+    pub fn add_batch(&mut self, header: &BatchHeader, base_offset: Offset) {
+        // transaction markers are written by leader and don't carry producer sequence
+        if header.is_idempotent() && !header.is_control() {
+            self.last_batches
+                .insert(header.producer_id, (header.clone(), base_offset));
+        }
+    }
+
+    /// header and base offset of last batch written by each producer
+    pub fn last_batches(&self) -> Vec<(BatchHeader, Offset)> {
+        self.last_batches.values().cloned().collect()
+    }
+
+    pub(crate) fn snapshot(&self) -> ProducerSnapshot {
+        ProducerSnapshot {
+            batches: self
+                .last_batches
+                .values()
+                .map(|(header, base_offset)| ProducerBatch {
+                    header: header.clone(),
+                    base_offset: *base_offset,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<ProducerSnapshot> for ProducerIndex {
+    fn from(snapshot: ProducerSnapshot) -> Self {
+        let mut index = Self::default();
+        for batch in snapshot.batches {
+            index.add_batch(&batch.header, batch.base_offset);
+        }
+        index
+    }
+}
+
+/// producer index as saved in snapshot
+#[derive(Debug, Default, Encode, Decode)]
+pub(crate) struct ProducerSnapshot {
+    batches: Vec<ProducerBatch>,
+}
+
+#[derive(Debug, Default, Encode, Decode)]
+struct ProducerBatch {
+    header: BatchHeader,
+    base_offset: Offset,
+}
+
+#[cfg(test)]
+mod tests {
+
+    use dataplane::batch::BatchHeader;
+
+    use super::ProducerIndex;
+
+    fn header(producer_id: i64, sequence: i32, control: bool) -> BatchHeader {
+        let mut header = BatchHeader::default();
+        header.set_producer(producer_id, 0, sequence);
+        header.set_control(control);
+        header
+    }
+
+    #[test]
+    fn test_producer_index() {
+        let mut index = ProducerIndex::default();
+
+        index.add_batch(&BatchHeader::default(), 0);
+        assert!(index.last_batches().is_empty());
+
+        index.add_batch(&header(1, 0, false), 1);
+        index.add_batch(&header(1, 1, false), 2);
+        index.add_batch(&header(1, -1, true), 3);
+
+        let batches = index.last_batches();
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].0.first_sequence, 1);
+        assert_eq!(batches[0].1, 2);
+    }
+}
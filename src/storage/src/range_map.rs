@@ -11,6 +11,7 @@ use tracing::error;
 
 use dataplane::Offset;
 
+use crate::compaction::recover_swaps;
 use crate::segment::ReadSegment;
 use crate::StorageError;
 use crate::config::ConfigOption;
@@ -36,6 +37,8 @@ impl SegmentList {
     pub async fn from_dir(
         option: &ConfigOption,
     ) -> Result<(SegmentList, Option<Offset>), StorageError> {
+        // segment files must be consistent before they are opened
+        recover_swaps(&option.base_dir).await?;
         let dirs = option.base_dir.read_dir()?;
         debug!("reading segments at: {:#?}", dirs);
         let files: Vec<_> = dirs.filter_map(|entry| entry.ok()).collect();
@@ -105,7 +108,38 @@ impl SegmentList {
         self.segments.insert(segment.get_base_offset(), segment);
     }
 
+    /// remove segment with base offset, this doesn't delete underlying files
+    pub fn remove_segment(&mut self, base_offset: Offset) -> Option<ReadSegment> {
+        let segment = self.segments.remove(&base_offset);
+        if segment.is_some() {
+            debug!("removed segment base: {}", base_offset);
+            self.min_base_offset = self.segments.keys().next().copied().unwrap_or(-1);
+            self.max_base_offset = self.segments.keys().next_back().copied().unwrap_or(0);
+        }
+        segment
+    }
+
+    /// oldest segment and base offset of the segment after it, if any
+    pub fn oldest_segment(&self) -> Option<(&ReadSegment, Option<Offset>)> {
+        let mut iter = self.segments.values();
+        iter.next()
+            .map(|segment| (segment, iter.next().map(|next| next.get_base_offset())))
+    }
+
+    /// total bytes of message logs for all segments
+    pub fn total_size(&self) -> u64 {
+        self.segments
+            .values()
+            .map(|segment| segment.get_msg_size())
+            .sum()
+    }
+
     #[allow(dead_code)]
+    /// segments ordered by base offset
+    pub fn segments(&self) -> impl Iterator<Item = &ReadSegment> {
+        self.segments.values()
+    }
+
     pub fn get_segment(&self, offset: Offset) -> Option<&ReadSegment> {
         self.segments.get(&offset)
     }
@@ -210,6 +244,41 @@ mod tests {
         Ok(())
     }
 
+    const TEST_REMOVE_DIR: &str = "segmentlist-remove";
+
+    #[test_async]
+    async fn test_segment_remove() -> Result<(), StorageError> {
+        let rep_dir = temp_dir().join(TEST_REMOVE_DIR);
+        ensure_new_dir(&rep_dir)?;
+        let mut list = SegmentList::new();
+
+        let option = default_option(rep_dir);
+
+        list.add_segment(create_segment(&option, 0, 500).await?);
+        list.add_segment(create_segment(&option, 500, 2000).await?);
+        list.add_segment(create_segment(&option, 2000, 1000).await?);
+
+        let (oldest, next_base) = list.oldest_segment().expect("oldest");
+        assert_eq!(oldest.get_base_offset(), 0);
+        assert_eq!(next_base, Some(500));
+
+        let total_size = list.total_size();
+        let removed = list.remove_segment(0).expect("removed");
+        assert_eq!(list.total_size(), total_size - removed.get_msg_size());
+        removed.remove().await?;
+
+        assert_eq!(list.len(), 2);
+        assert_eq!(list.min_offset(), 500);
+        assert_eq!(list.max_offset(), 2000);
+        assert!(list.remove_segment(0).is_none());
+
+        // segments are gone from disk, only remaining segments are loaded
+        let (segments, _) = SegmentList::from_dir(&option).await?;
+        assert_eq!(segments.min_offset(), 500);
+
+        Ok(())
+    }
+
     const TEST_EMPTY_DIR: &str = "segmentlist-read-empty";
 
     #[test_async]
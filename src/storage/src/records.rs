@@ -2,10 +2,12 @@ use std::io::Error as IoError;
 use std::io::ErrorKind;
 use std::path::PathBuf;
 use std::path::Path;
+use std::time::{Duration, SystemTime};
 
 use tracing::debug;
 
 use fluvio_future::fs::File;
+use fluvio_future::fs::remove_file;
 use fluvio_future::fs::util as file_util;
 use fluvio_future::file_slice::AsyncFileSlice;
 use fluvio_future::fs::AsyncFileExtension;
@@ -37,6 +39,7 @@ pub struct FileRecordsSlice {
     file: File,
     path: PathBuf,
     len: u64,
+    last_modified_time: SystemTime,
 }
 
 impl FileRecordsSlice {
@@ -50,12 +53,14 @@ impl FileRecordsSlice {
         let file = file_util::open(&log_path).await?;
         let metadata = file.metadata().await?;
         let len = metadata.len();
+        let last_modified_time = metadata.modified()?;
 
         Ok(FileRecordsSlice {
             base_offset,
             file,
             path: log_path,
             len,
+            last_modified_time,
         })
     }
 
@@ -63,7 +68,24 @@ impl FileRecordsSlice {
         self.base_offset
     }
 
-    #[allow(dead_code)]
+    pub fn get_len(&self) -> u64 {
+        self.len
+    }
+
+    /// check if log has not been modified for longer than expired duration
+    pub fn is_expired(&self, expired_duration: &Duration) -> bool {
+        match self.last_modified_time.elapsed() {
+            Ok(elapsed) => elapsed > *expired_duration,
+            Err(_) => false, // modified time is in the future
+        }
+    }
+
+    /// remove log file
+    pub async fn remove(self) -> Result<(), StorageError> {
+        debug!("removing log: {}", self.path.display());
+        remove_file(&self.path).await.map_err(|err| err.into())
+    }
+
     pub async fn validate(&mut self) -> Result<Offset, LogValidationError> {
         validate(&self.path).await
     }
@@ -1,4 +1,5 @@
 use std::mem;
+use std::time::Duration;
 
 use fluvio_protocol::Encoder;
 use tracing::{debug, trace, error, warn};
@@ -6,13 +7,17 @@ use async_trait::async_trait;
 
 use fluvio_future::fs::{create_dir_all, remove_dir_all};
 use dataplane::{ErrorCode, Isolation, Offset, ReplicaKey, Size};
-use dataplane::batch::DefaultBatch;
+use dataplane::batch::{BatchHeader, DefaultBatch};
 use dataplane::record::RecordSet;
 
 use crate::{OffsetInfo, checkpoint::CheckPoint};
+use crate::compaction::Compaction;
 use crate::range_map::SegmentList;
 use crate::segment::MutableSegment;
-use crate::config::ConfigOption;
+use crate::config::{CleanupPolicy, ConfigOption};
+use crate::transaction::TransactionIndex;
+use crate::producer::ProducerIndex;
+use crate::snapshot::BatchIndexSnapshot;
 use crate::{SegmentSlice};
 use crate::{StorageError, SlicePartitionResponse, ReplicaStorage};
 
@@ -29,6 +34,10 @@ pub struct FileReplica {
     active_segment: MutableSegment,
     prev_segments: SegmentList,
     commit_checkpoint: CheckPoint<Offset>,
+    transactions: TransactionIndex,
+    producers: ProducerIndex,
+    /// end offset of segments compacted so far
+    compacted_offset: Offset,
 }
 
 impl Unpin for FileReplica {}
@@ -74,8 +83,17 @@ impl ReplicaStorage for FileReplica {
     {
         match isolation {
             Isolation::ReadCommitted => {
-                self.read_records(offset, Some(self.get_hw()), max_len, partition_response)
-                    .await
+                // records of ongoing transactions are not readable, consumer only advances up to last stable offset
+                let lso = self.get_last_stable_offset();
+                let offsets = self
+                    .read_records(offset, Some(lso), max_len, partition_response)
+                    .await;
+                partition_response
+                    .set_aborted_transactions(self.transactions.aborted_transactions(offset, lso));
+                OffsetInfo {
+                    leo: offsets.leo,
+                    hw: lso,
+                }
             }
             Isolation::ReadUncommitted => {
                 self.read_records(offset, None, max_len, partition_response)
@@ -102,6 +120,13 @@ impl ReplicaStorage for FileReplica {
 
         for mut batch in &mut records.batches {
             self.write_batch(&mut batch).await?;
+            self.transactions.add_batch(
+                batch.get_header(),
+                batch.get_base_offset(),
+                batch.control_type(),
+            );
+            self.producers
+                .add_batch(batch.get_header(), batch.get_base_offset());
         }
 
         if update_highwatermark {
@@ -131,6 +156,152 @@ impl ReplicaStorage for FileReplica {
         }
     }
 
+    /// remove read only segments that are out of retention.
+    /// segment is removed when it's older than retention period, if any, or
+    /// when total size of replica exceed max partition size, oldest segment first.
+    /// active segment and segments not fully committed are never removed.
+    async fn remove_expired_segments(&mut self) -> Result<bool, StorageError> {
+        // compacted replica keeps latest records regardless of age
+        if self.option.cleanup_policy == CleanupPolicy::Compact {
+            return Ok(false);
+        }
+
+        let retention = self
+            .option
+            .retention_seconds
+            .map(|seconds| Duration::from_secs(seconds as u64));
+        let hw = self.get_hw();
+        let mut total_size = self.prev_segments.total_size() + self.active_segment.get_msg_size();
+        let mut removed = false;
+
+        while let Some((segment, next_base_offset)) = self.prev_segments.oldest_segment() {
+            let base_offset = segment.get_base_offset();
+            let end_offset =
+                next_base_offset.unwrap_or_else(|| self.active_segment.get_base_offset());
+            if end_offset > hw {
+                trace!(
+                    base_offset,
+                    end_offset,
+                    hw,
+                    "segment is not committed, keeping"
+                );
+                break;
+            }
+
+            let size = segment.get_msg_size();
+            let expired = retention
+                .map(|retention| segment.is_expired(&retention))
+                .unwrap_or(false);
+            let exceed_size = match self.option.max_partition_size {
+                Some(max_size) => total_size > max_size,
+                None => false,
+            };
+            if !expired && !exceed_size {
+                break;
+            }
+
+            debug!(base_offset, expired, exceed_size, "removing segment");
+            if let Some(old_segment) = self.prev_segments.remove_segment(base_offset) {
+                old_segment.remove().await?;
+            }
+            total_size -= size;
+            removed = true;
+        }
+
+        if removed {
+            let log_start_offset = self.get_log_start_offset();
+            debug!(log_start_offset, "updated log start offset");
+            self.transactions.remove_before(log_start_offset);
+        }
+        Ok(removed)
+    }
+
+    /// rewrite read only segments, keeping only latest record of each key.
+    /// only segments below last stable offset are compacted, and only when new segments
+    /// have been rolled over since last compaction.
+    /// tombstone is removed once its segment has not been modified for retention period,
+    /// without retention period tombstones are kept.
+    async fn compact_segments(&mut self) -> Result<bool, StorageError> {
+        if self.option.cleanup_policy != CleanupPolicy::Compact {
+            return Ok(false);
+        }
+
+        let lso = self.get_last_stable_offset();
+        let mut base_offsets = vec![];
+        let mut end_offset = self.compacted_offset;
+        let mut segments = self.prev_segments.segments().peekable();
+        while let Some(segment) = segments.next() {
+            let segment_end = segments
+                .peek()
+                .map(|next| next.get_base_offset())
+                .unwrap_or_else(|| self.active_segment.get_base_offset());
+            if segment_end > lso {
+                break;
+            }
+            base_offsets.push(segment.get_base_offset());
+            end_offset = segment_end;
+        }
+
+        if end_offset <= self.compacted_offset {
+            trace!(end_offset, "no new segments to compact");
+            return Ok(false);
+        }
+
+        let mut compaction = Compaction::new(&self.option).await?;
+        for base_offset in &base_offsets {
+            if let Some(segment) = self.prev_segments.get_segment(*base_offset) {
+                compaction.scan(segment, &self.transactions).await?;
+            }
+        }
+
+        let retention = self
+            .option
+            .retention_seconds
+            .map(|seconds| Duration::from_secs(seconds as u64));
+        let mut compacted = false;
+        for base_offset in base_offsets {
+            let new_segment = match self.prev_segments.get_segment(base_offset) {
+                Some(segment) => {
+                    compaction
+                        .compact(
+                            segment,
+                            retention
+                                .map(|retention| segment.is_expired(&retention))
+                                .unwrap_or(false),
+                        )
+                        .await?
+                }
+                None => None,
+            };
+            if let Some(new_segment) = new_segment {
+                self.prev_segments.remove_segment(base_offset);
+                self.prev_segments.add_segment(new_segment);
+                compacted = true;
+            }
+        }
+
+        debug!(end_offset, compacted, "compacted segments");
+        self.compacted_offset = end_offset;
+        Ok(compacted)
+    }
+
+    /// search segments from oldest, segments without timestamps are skipped
+    fn find_offset_by_timestamp(&self, timestamp: i64) -> Offset {
+        self.prev_segments
+            .segments()
+            .find_map(|segment| segment.find_timestamp(timestamp))
+            .or_else(|| self.active_segment.find_timestamp(timestamp))
+            .unwrap_or_else(|| self.get_leo())
+    }
+
+    fn last_producer_batches(&self) -> Vec<(BatchHeader, Offset)> {
+        self.producers.last_batches()
+    }
+
+    fn has_ongoing_transaction(&self, producer_id: i64) -> bool {
+        self.transactions.is_ongoing(producer_id)
+    }
+
     async fn remove(&self) -> Result<(), StorageError> {
         remove_dir_all(&self.option.base_dir)
             .await
@@ -193,14 +364,55 @@ impl FileReplica {
         let commit_checkpoint: CheckPoint<Offset> =
             CheckPoint::create(&rep_option, "replication.chk", last_base_offset).await?;
 
-        Ok(Self {
+        let mut replica = Self {
             option: rep_option,
             last_base_offset,
             partition,
             active_segment,
             prev_segments: segments,
             commit_checkpoint,
-        })
+            transactions: TransactionIndex::default(),
+            producers: ProducerIndex::default(),
+            compacted_offset: -1,
+        };
+        replica.load_batch_index().await?;
+        Ok(replica)
+    }
+
+    /// rebuild transaction and producer index from batches in the log
+    /// restore index from latest snapshot, then read batches of segments written after it
+    async fn load_batch_index(&mut self) -> Result<(), StorageError> {
+        let (snapshot_offset, (mut transactions, mut producers)) =
+            match BatchIndexSnapshot::load_latest(
+                &self.option.base_dir,
+                self.active_segment.get_base_offset(),
+            )
+            .await?
+            {
+                Some((offset, snapshot)) => (offset, snapshot.into_index()),
+                None => (-1, (TransactionIndex::default(), ProducerIndex::default())),
+            };
+        for segment in self.prev_segments.segments() {
+            if segment.get_base_offset() < snapshot_offset {
+                continue;
+            }
+            segment
+                .load_batch_index(&mut transactions, &mut producers)
+                .await?;
+        }
+        self.active_segment
+            .load_batch_index(&mut transactions, &mut producers)
+            .await?;
+        transactions.remove_before(self.get_log_start_offset());
+        self.transactions = transactions;
+        self.producers = producers;
+        Ok(())
+    }
+
+    /// offset before which there are no ongoing transactions
+    /// this is never greater than high watermark
+    pub fn get_last_stable_offset(&self) -> Offset {
+        self.transactions.last_stable_offset(self.get_hw())
     }
 
     /// clear the any holding directory for replica
@@ -274,8 +486,18 @@ impl FileReplica {
         );
 
         response.set_hw(hw);
-        response.set_last_stable_offset(hw);
-        response.set_log_start_offset(self.get_log_start_offset());
+        response.set_last_stable_offset(self.get_last_stable_offset());
+        let log_start_offset = self.get_log_start_offset();
+        response.set_log_start_offset(log_start_offset);
+
+        if start_offset < log_start_offset {
+            debug!(
+                start_offset,
+                log_start_offset, "offset is before log start, records has been removed"
+            );
+            response.set_error_code(ErrorCode::OffsetOutOfRange);
+            return OffsetInfo { leo, hw };
+        }
 
         match self.find_segment(start_offset) {
             Some(segment) => {
@@ -362,6 +584,14 @@ impl FileReplica {
             let old_mut_segment = mem::replace(&mut self.active_segment, new_segment);
             let old_segment = old_mut_segment.as_segment().await?;
             self.prev_segments.add_segment(old_segment);
+            // index doesn't include batch being written yet, so it covers offsets before new segment
+            let snapshot = BatchIndexSnapshot::new(&self.transactions, &self.producers);
+            if let Err(err) = snapshot.save(&self.option.base_dir, last_offset).await {
+                warn!(
+                    "failed to save batch index snapshot at {}: {}",
+                    last_offset, err
+                );
+            }
             self.active_segment.write_batch(item).await?;
         }
         Ok(())
@@ -387,12 +617,13 @@ mod tests {
     use dataplane::{Offset, ErrorCode};
     use dataplane::core::{Decoder, Encoder};
     use dataplane::fetch::FilePartitionResponse;
-    use dataplane::record::RecordSet;
+    use dataplane::record::{DefaultRecord, RecordSet};
     use dataplane::fixture::{BatchProducer, create_batch};
     use dataplane::fixture::read_bytes_from_file;
     use flv_util::fixture::ensure_clean_dir;
 
-    use crate::config::ConfigOption;
+    use crate::config::{CleanupPolicy, ConfigOption};
+    use crate::segment::SegmentSlice;
     use crate::StorageError;
     use crate::ReplicaStorage;
 
@@ -590,7 +821,7 @@ mod tests {
         assert_eq!(replica.get_log_start_offset(), START_OFFSET);
         let replica_dir = &option.base_dir.join("test-1");
         let dir_contents = fs::read_dir(&replica_dir)?;
-        assert_eq!(dir_contents.count(), 5, "should be 5 files");
+        assert_eq!(dir_contents.count(), 8, "should be 8 files");
 
         let seg2_file = replica_dir.join(TEST_SE2_NAME);
         let bytes = read_bytes_from_file(&seg2_file)?;
@@ -611,6 +842,231 @@ mod tests {
         Ok(())
     }
 
+    const TEST_SNAPSHOT_DIR: &str = "test_batch_index_snapshot";
+
+    #[test_async]
+    async fn test_replica_batch_index_snapshot() -> Result<(), StorageError> {
+        let option = rollover_option(TEST_SNAPSHOT_DIR);
+
+        let mut replica = FileReplica::create("test", 1, START_OFFSET, option.clone())
+            .await
+            .expect("create rep");
+        for sequence in &[0, 2] {
+            let mut batch = create_batch();
+            let header = batch.get_mut_header();
+            header.set_producer(7, 0, *sequence);
+            header.set_transactional(true);
+            replica.write_batch(&mut batch).await?;
+        }
+
+        // snapshot is taken at roll over, before second batch
+        let replica_dir = option.base_dir.join("test-1");
+        assert!(replica_dir.join("00000000000000000022.snapshot").exists());
+        drop(replica);
+
+        let replica = FileReplica::create("test", 1, START_OFFSET, option)
+            .await
+            .expect("reopen rep");
+        assert!(replica.has_ongoing_transaction(7));
+        let batches = replica.last_producer_batches();
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].0.first_sequence, 2);
+        assert_eq!(batches[0].1, 22);
+
+        Ok(())
+    }
+
+    const TEST_RETENTION_SIZE_DIR: &str = "test_retention_size";
+
+    #[test_async]
+    async fn test_replica_retention_size() -> Result<(), StorageError> {
+        let mut option = rollover_option(TEST_RETENTION_SIZE_DIR);
+        option.max_partition_size = Some(100);
+
+        let mut replica = FileReplica::create("test", 0, START_OFFSET, option.clone())
+            .await
+            .expect("create rep");
+
+        // each batch rolls over into new segment
+        for _ in 0..3 {
+            replica.write_batch(&mut create_batch()).await?;
+        }
+        assert_eq!(replica.get_log_start_offset(), START_OFFSET);
+
+        // segments are not removed until committed
+        assert!(!replica.remove_expired_segments().await?);
+        assert_eq!(replica.get_log_start_offset(), START_OFFSET);
+
+        replica.update_high_watermark_to_end().await?;
+        assert!(replica.remove_expired_segments().await?);
+        assert_eq!(replica.get_log_start_offset(), START_OFFSET + 4);
+        assert!(!option.base_dir.join("test-0").join(TEST_SEG_NAME).exists());
+
+        // reading removed offset should result in error
+        let mut partition_response = FilePartitionResponse::default();
+        replica
+            .read_partition_slice(
+                START_OFFSET,
+                FileReplica::PREFER_MAX_LEN,
+                Isolation::ReadCommitted,
+                &mut partition_response,
+            )
+            .await;
+        assert_eq!(partition_response.error_code, ErrorCode::OffsetOutOfRange);
+        assert_eq!(partition_response.log_start_offset, START_OFFSET + 4);
+
+        // log start offset is restored
+        drop(replica);
+        let replica = FileReplica::create("test", 0, START_OFFSET, option)
+            .await
+            .expect("restore rep");
+        assert_eq!(replica.get_log_start_offset(), START_OFFSET + 4);
+
+        Ok(())
+    }
+
+    const TEST_RETENTION_TIME_DIR: &str = "test_retention_time";
+
+    #[test_async]
+    async fn test_replica_retention_time() -> Result<(), StorageError> {
+        let mut option = rollover_option(TEST_RETENTION_TIME_DIR);
+        option.retention_seconds = Some(1);
+
+        let mut replica = FileReplica::create("test", 0, START_OFFSET, option)
+            .await
+            .expect("create rep");
+
+        replica.write_batch(&mut create_batch()).await?;
+        replica.write_batch(&mut create_batch()).await?;
+        replica.update_high_watermark_to_end().await?;
+
+        // segment is still within retention
+        assert!(!replica.remove_expired_segments().await?);
+        assert_eq!(replica.get_log_start_offset(), START_OFFSET);
+
+        fluvio_future::timer::after(std::time::Duration::from_secs(2)).await;
+
+        assert!(replica.remove_expired_segments().await?);
+        assert_eq!(replica.get_log_start_offset(), START_OFFSET + 2);
+        // active segment is always kept
+        assert_eq!(replica.get_leo(), START_OFFSET + 4);
+
+        Ok(())
+    }
+
+    const TEST_COMPACTION_DIR: &str = "test_compaction";
+
+    #[test_async]
+    async fn test_replica_compaction() -> Result<(), StorageError> {
+        let mut option = rollover_option(TEST_COMPACTION_DIR);
+        option.cleanup_policy = CleanupPolicy::Compact;
+
+        let mut replica = FileReplica::create("test", 0, START_OFFSET, option.clone())
+            .await
+            .expect("create rep");
+
+        // each batch rolls over into new segment
+        for (key, value) in &[("a", "1"), ("b", "1"), ("a", "2"), ("c", "1")] {
+            replica
+                .write_batch(&mut DefaultBatch::new(vec![DefaultRecord::new_key_value(
+                    *key, *value,
+                )]))
+                .await?;
+        }
+        assert_eq!(replica.get_leo(), START_OFFSET + 4);
+
+        // segments are not compacted until committed
+        assert!(!replica.compact_segments().await?);
+
+        replica.update_high_watermark_to_end().await?;
+        assert!(replica.compact_segments().await?);
+        // nothing changed since last compaction
+        assert!(!replica.compact_segments().await?);
+
+        // compacted replica is not subject to retention
+        assert!(!replica.remove_expired_segments().await?);
+        assert_eq!(replica.get_log_start_offset(), START_OFFSET);
+
+        // first value of "a" is removed, but batch is kept so offsets are not changed
+        let records = segment_records(&replica, START_OFFSET).await;
+        assert!(records.is_empty());
+        let records = segment_records(&replica, START_OFFSET + 1).await;
+        assert_eq!(records[0].value().as_ref(), b"1");
+        let records = segment_records(&replica, START_OFFSET + 2).await;
+        assert_eq!(records[0].value().as_ref(), b"2");
+
+        let mut partition_response = FilePartitionResponse::default();
+        replica
+            .read_partition_slice(
+                START_OFFSET,
+                FileReplica::PREFER_MAX_LEN,
+                Isolation::ReadCommitted,
+                &mut partition_response,
+            )
+            .await;
+        assert_eq!(partition_response.error_code, ErrorCode::None);
+
+        // compacted segments are restored
+        drop(replica);
+        let replica = FileReplica::create("test", 0, START_OFFSET, option)
+            .await
+            .expect("restore rep");
+        assert_eq!(replica.get_leo(), START_OFFSET + 4);
+        assert!(segment_records(&replica, START_OFFSET).await.is_empty());
+
+        Ok(())
+    }
+
+    /// records of first batch in read only segment containing offset
+    async fn segment_records(replica: &FileReplica, offset: Offset) -> Vec<DefaultRecord> {
+        match replica.find_segment(offset).expect("segment") {
+            SegmentSlice::Segment(segment) => {
+                let mut batch_stream = segment
+                    .open_default_batch_stream()
+                    .await
+                    .expect("batch stream");
+                let batch = batch_stream.next().await.expect("batch");
+                assert_eq!(batch.get_base_offset(), offset);
+                batch.get_batch().records().clone()
+            }
+            SegmentSlice::MutableSegment(_) => panic!("segment should be read only"),
+        }
+    }
+
+    const TEST_TIMESTAMP_DIR: &str = "test_timestamp";
+
+    #[test_async]
+    async fn test_replica_find_offset_by_timestamp() -> Result<(), StorageError> {
+        let option = rollover_option(TEST_TIMESTAMP_DIR);
+        let mut replica = FileReplica::create("test", 0, START_OFFSET, option.clone())
+            .await
+            .expect("create rep");
+
+        // each batch rolls over into new segment
+        for timestamp in &[1000, 2000, 1500, 3000] {
+            let mut batch = create_batch();
+            batch.set_first_timestamp(*timestamp);
+            replica.write_batch(&mut batch).await?;
+        }
+        assert_eq!(replica.get_leo(), START_OFFSET + 8);
+
+        assert_eq!(replica.find_offset_by_timestamp(0), START_OFFSET);
+        assert_eq!(replica.find_offset_by_timestamp(1000), START_OFFSET);
+        assert_eq!(replica.find_offset_by_timestamp(1001), START_OFFSET + 2);
+        // batch with older timestamp is before batch found
+        assert_eq!(replica.find_offset_by_timestamp(2500), START_OFFSET + 6);
+        assert_eq!(replica.find_offset_by_timestamp(4000), START_OFFSET + 8);
+
+        // time index is restored with segments
+        drop(replica);
+        let replica = FileReplica::create("test", 0, START_OFFSET, option)
+            .await
+            .expect("restore rep");
+        assert_eq!(replica.find_offset_by_timestamp(1500), START_OFFSET + 2);
+
+        Ok(())
+    }
+
     const TEST_COMMIT_DIR: &str = "test_commit";
 
     #[test_async]
@@ -1,6 +1,7 @@
 use std::fmt;
 use std::io::Error as IoError;
 use std::ops::Deref;
+use std::time::Duration;
 
 use tracing::debug;
 use tracing::trace;
@@ -9,6 +10,7 @@ use dataplane::batch::DefaultBatch;
 use dataplane::{Offset, Size};
 use fluvio_future::file_slice::AsyncFileSlice;
 use fluvio_future::fs::util as file_util;
+use fluvio_future::fs::remove_file;
 
 use crate::batch_header::{BatchHeaderStream, BatchHeaderPos};
 use crate::mut_index::MutLogIndex;
@@ -23,6 +25,10 @@ use crate::batch::DefaultFileBatchStream;
 use crate::index::OffsetPosition;
 use crate::validator::LogValidationError;
 use crate::util::OffsetError;
+use crate::util::generate_file_name;
+use crate::transaction::TransactionIndex;
+use crate::producer::ProducerIndex;
+use crate::time_index::TimeIndex;
 
 pub type MutableSegment = Segment<MutLogIndex, MutFileRecords>;
 pub type ReadSegment = Segment<LogIndex, FileRecordsSlice>;
@@ -57,6 +63,7 @@ pub struct Segment<I, L> {
     option: ConfigOption,
     msg_log: L,
     index: I,
+    time_index: TimeIndex,
     base_offset: Offset,
     end_offset: Offset,
 }
@@ -87,6 +94,11 @@ impl<I, L> Segment<I, L> {
     pub fn get_base_offset(&self) -> Offset {
         self.base_offset
     }
+
+    /// offset of first batch which has timestamp at or after given timestamp
+    pub fn find_timestamp(&self, timestamp: i64) -> Option<Offset> {
+        self.time_index.find_timestamp(timestamp)
+    }
 }
 
 impl<I, L> Segment<I, L>
@@ -115,6 +127,38 @@ where
         BatchHeaderStream::new_with_pos(file, start_pos).await
     }
 
+    /// add batches in this segment to transaction and producer index
+    pub async fn load_batch_index(
+        &self,
+        transactions: &mut TransactionIndex,
+        producers: &mut ProducerIndex,
+    ) -> Result<(), StorageError> {
+        let mut header_stream = self.open_batch_header_stream(0).await?;
+        while let Some(batch_pos) = header_stream.next().await {
+            let batch = batch_pos.get_batch();
+            let header = batch.get_header();
+            producers.add_batch(header, batch.get_base_offset());
+            if !header.is_transactional() {
+                continue;
+            }
+
+            let control = if header.is_control() {
+                // control type is stored in record, so entire batch must be read
+                let file = file_util::open(self.msg_log.get_path()).await?;
+                let mut batch_stream =
+                    DefaultFileBatchStream::new_with_pos(file, batch_pos.get_pos()).await?;
+                batch_stream
+                    .next()
+                    .await
+                    .and_then(|control_pos| control_pos.get_batch().control_type())
+            } else {
+                None
+            };
+            transactions.add_batch(header, batch.get_base_offset(), control);
+        }
+        Ok(())
+    }
+
     #[allow(dead_code)]
     pub async fn open_default_batch_stream(&self) -> Result<DefaultFileBatchStream, StorageError> {
         let file_path = self.msg_log.get_path();
@@ -213,23 +257,55 @@ impl Segment<LogIndex, FileRecordsSlice> {
         base_offset: Offset,
         option: &ConfigOption,
     ) -> Result<Self, StorageError> {
-        let msg_log = FileRecordsSlice::open(base_offset, option).await?;
+        let mut msg_log = FileRecordsSlice::open(base_offset, option).await?;
         let base_offset = msg_log.get_base_offset();
         let index = LogIndex::open_from_offset(base_offset, option).await?;
+        let mut time_index = TimeIndex::open(base_offset, option).await?;
 
-        let base_offset = msg_log.get_base_offset();
+        // offsets may have gaps if segment has been compacted, so end offset is read from batches
+        let end_offset = msg_log.validate().await?;
+        time_index.truncate(end_offset).await?;
         Ok(Segment {
             msg_log,
             index,
+            time_index,
             option: option.to_owned(),
             base_offset,
-            end_offset: base_offset,
+            end_offset,
         })
     }
 
     pub fn to_segment_slice(&self) -> SegmentSlice {
         SegmentSlice::new_segment(self)
     }
+
+    /// size of message log in bytes
+    pub fn get_msg_size(&self) -> u64 {
+        self.msg_log.get_len()
+    }
+
+    /// check if segment has not been written for longer than expired duration
+    pub fn is_expired(&self, expired_duration: &Duration) -> bool {
+        self.msg_log.is_expired(expired_duration)
+    }
+
+    /// permanently remove message log and indexes
+    pub async fn remove(self) -> Result<(), StorageError> {
+        let index_path = generate_file_name(
+            &self.option.base_dir,
+            self.base_offset,
+            crate::index::EXTENSION,
+        );
+        let time_index_path = self.time_index.get_path().to_owned();
+        drop(self.index);
+        drop(self.time_index);
+        self.msg_log.remove().await?;
+        debug!("removing index: {}", index_path.display());
+        remove_file(&index_path).await?;
+        debug!("removing time index: {}", time_index_path.display());
+        remove_file(&time_index_path).await?;
+        Ok(())
+    }
 }
 
 impl Unpin for Segment<MutLogIndex, MutFileRecords> {}
@@ -245,11 +321,13 @@ impl Segment<MutLogIndex, MutFileRecords> {
         let msg_log = MutFileRecords::create(base_offset, option).await?;
 
         let index = MutLogIndex::create(base_offset, option).await?;
+        let time_index = TimeIndex::open(base_offset, option).await?;
 
         Ok(MutableSegment {
             option: option.to_owned(),
             msg_log,
             index,
+            time_index,
             base_offset,
             end_offset: base_offset,
         })
@@ -267,12 +345,14 @@ impl Segment<MutLogIndex, MutFileRecords> {
         let msg_log = MutFileRecords::open(base_offset, option).await?;
         let base_offset = msg_log.get_base_offset();
         let index = MutLogIndex::open(base_offset, option).await?;
+        let time_index = TimeIndex::open(base_offset, option).await?;
 
         let base_offset = msg_log.get_base_offset();
         Ok(MutableSegment {
             option: option.to_owned(),
             msg_log,
             index,
+            time_index,
             base_offset,
             end_offset: base_offset,
         })
@@ -282,9 +362,15 @@ impl Segment<MutLogIndex, MutFileRecords> {
         self.msg_log.get_pos()
     }
 
+    /// size of message log in bytes
+    pub fn get_msg_size(&self) -> u64 {
+        self.get_log_pos() as u64
+    }
+
     /// validate the segment and load last offset
     pub async fn validate(&mut self) -> Result<(), StorageError> {
         self.end_offset = self.msg_log.validate().await?;
+        self.time_index.truncate(self.end_offset).await?;
         Ok(())
     }
 
@@ -300,7 +386,9 @@ impl Segment<MutLogIndex, MutFileRecords> {
 
     /// convert to immutable segment
     #[allow(clippy::wrong_self_convention)]
-    pub async fn as_segment(self) -> Result<ReadSegment, StorageError> {
+    pub async fn as_segment(mut self) -> Result<ReadSegment, StorageError> {
+        // read only segment is loaded from file
+        self.flush().await?;
         Segment::open_for_read(self.get_base_offset(), &self.option).await
     }
 
@@ -330,7 +418,8 @@ impl Segment<MutLogIndex, MutFileRecords> {
             ));
         }
 
-        let batch_offset_delta = (current_offset - base_offset) as i32;
+        // batch may start after current offset, for example when copied from compacted segment
+        let batch_offset_delta = (item.base_offset - base_offset) as i32;
         debug!(
             base_offset,
             file_offset = pos,
@@ -344,10 +433,15 @@ impl Segment<MutLogIndex, MutFileRecords> {
             self.index
                 .send((batch_offset_delta as u32, pos, batch_len))
                 .await?;
+            self.time_index
+                .add_batch(item.get_header().max_time_stamp, item.base_offset);
+            self.time_index
+                .flush_after_log(self.msg_log.flush_count())
+                .await?;
 
             let last_offset_delta = self.msg_log.get_item_last_offset_delta();
             trace!("flushing: last offset delta: {}", last_offset_delta);
-            self.end_offset = self.end_offset + last_offset_delta as Offset + 1;
+            self.end_offset = item.base_offset + last_offset_delta as Offset + 1;
             debug!(end_offset = self.end_offset, "updated leo");
             Ok(true)
         } else {
@@ -355,9 +449,10 @@ impl Segment<MutLogIndex, MutFileRecords> {
         }
     }
 
-    #[allow(unused)]
     pub async fn flush(&mut self) -> Result<(), StorageError> {
-        self.msg_log.flush().await.map_err(|err| err.into())
+        self.msg_log.flush().await?;
+        self.time_index.flush().await?;
+        Ok(())
     }
 }
 
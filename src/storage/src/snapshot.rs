@@ -0,0 +1,146 @@
+//!
+//! # Batch index snapshot
+//!
+//! Transaction and producer index are saved when segment is rolled over, named after base offset
+//! of new segment. When replica is loaded, index is restored from latest snapshot, and only
+//! batches written after it are read from the log.
+//!
+use std::io::{Cursor, Error as IoError};
+use std::path::{Path, PathBuf};
+
+use futures_lite::io::{AsyncReadExt, AsyncWriteExt};
+use tracing::{debug, warn};
+
+use fluvio_future::fs::{File, remove_file, rename};
+use dataplane::Offset;
+use dataplane::core::{Decoder, Encoder};
+use dataplane::derive::{Decode, Encode};
+
+use crate::producer::{ProducerIndex, ProducerSnapshot};
+use crate::transaction::{TransactionIndex, TransactionSnapshot};
+use crate::util::generate_file_name;
+
+pub const EXTENSION: &str = "snapshot";
+
+const TMP_EXTENSION: &str = "snapshot.tmp";
+
+#[derive(Debug, Default, Encode, Decode)]
+pub(crate) struct BatchIndexSnapshot {
+    transactions: TransactionSnapshot,
+    producers: ProducerSnapshot,
+}
+
+impl BatchIndexSnapshot {
+    pub fn new(transactions: &TransactionIndex, producers: &ProducerIndex) -> Self {
+        Self {
+            transactions: transactions.snapshot(),
+            producers: producers.snapshot(),
+        }
+    }
+
+    pub fn into_index(self) -> (TransactionIndex, ProducerIndex) {
+        (self.transactions.into(), self.producers.into())
+    }
+
+    /// write snapshot of index before offset, older snapshots are removed
+    pub async fn save(&self, dir: &Path, offset: Offset) -> Result<(), IoError> {
+        let tmp_path = generate_file_name(dir, offset, TMP_EXTENSION);
+        let mut file = File::create(&tmp_path).await?;
+        file.write_all(&self.as_bytes(0)?).await?;
+        file.sync_all().await?;
+        drop(file);
+        rename(&tmp_path, generate_file_name(dir, offset, EXTENSION)).await?;
+        debug!(offset, "saved batch index snapshot");
+
+        for (old_offset, path) in snapshot_files(dir)? {
+            if old_offset < offset {
+                remove_file(path).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// latest snapshot which is not after offset, unreadable snapshots are skipped
+    pub async fn load_latest(
+        dir: &Path,
+        max_offset: Offset,
+    ) -> Result<Option<(Offset, Self)>, IoError> {
+        let mut snapshots: Vec<(Offset, PathBuf)> = snapshot_files(dir)?
+            .into_iter()
+            .filter(|(offset, _)| *offset <= max_offset)
+            .collect();
+        snapshots.sort_by_key(|(offset, _)| *offset);
+
+        while let Some((offset, path)) = snapshots.pop() {
+            match Self::read(&path).await {
+                Ok(snapshot) => {
+                    debug!(offset, "loaded batch index snapshot");
+                    return Ok(Some((offset, snapshot)));
+                }
+                Err(err) => warn!("skipping snapshot: {:#?}, err: {}", path, err),
+            }
+        }
+        Ok(None)
+    }
+
+    async fn read(path: &Path) -> Result<Self, IoError> {
+        let mut bytes = vec![];
+        File::open(path).await?.read_to_end(&mut bytes).await?;
+        Self::decode_from(&mut Cursor::new(bytes), 0)
+    }
+}
+
+fn snapshot_files(dir: &Path) -> Result<Vec<(Offset, PathBuf)>, IoError> {
+    let mut files = vec![];
+    for entry in dir.read_dir()? {
+        let path = entry?.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some(EXTENSION) {
+            continue;
+        }
+        let offset = path
+            .file_stem()
+            .and_then(|name| name.to_str())
+            .and_then(|offset| offset.parse::<Offset>().ok());
+        if let Some(offset) = offset {
+            files.push((offset, path));
+        }
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::env::temp_dir;
+
+    use fluvio_future::test_async;
+    use flv_util::fixture::ensure_new_dir;
+
+    use crate::util::generate_file_name;
+
+    use super::{BatchIndexSnapshot, EXTENSION};
+
+    #[test_async]
+    async fn test_latest_snapshot() -> Result<(), std::io::Error> {
+        let dir = temp_dir().join("test_latest_snapshot");
+        ensure_new_dir(&dir)?;
+
+        let snapshot = BatchIndexSnapshot::default();
+        snapshot.save(&dir, 10).await?;
+        snapshot.save(&dir, 20).await?;
+        assert!(!generate_file_name(&dir, 10, EXTENSION).exists());
+
+        // snapshot after active segment is not used
+        let latest = BatchIndexSnapshot::load_latest(&dir, 15).await?;
+        assert!(latest.is_none());
+
+        // corrupted snapshot is skipped
+        std::fs::write(generate_file_name(&dir, 30, EXTENSION), b"bad")?;
+        let (offset, _) = BatchIndexSnapshot::load_latest(&dir, 30)
+            .await?
+            .expect("snapshot");
+        assert_eq!(offset, 20);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,243 @@
+//!
+//! # Time index
+//!
+//! Maps timestamps of batches in segment to their offsets, so records can be looked up by time.
+//! Entry is added only for batch whose max timestamp is greater than of any batch before it,
+//! so entries are ordered by both timestamp and offset.
+//!
+//! Entries are buffered and written when segment flushes message log, so time index follows
+//! flush policy of segment. Entries of batches which were lost from message log by crash
+//! are dropped when segment is validated.
+//!
+use std::io::Cursor;
+use std::io::Error as IoError;
+use std::io::SeekFrom;
+use std::mem::size_of;
+use std::path::Path;
+use std::path::PathBuf;
+
+use bytes::Buf;
+use bytes::BufMut;
+use futures_lite::io::AsyncReadExt;
+use futures_lite::io::AsyncSeekExt;
+use futures_lite::io::AsyncWriteExt;
+use tracing::debug;
+use tracing::trace;
+
+use fluvio_future::fs::File;
+use fluvio_future::fs::util as file_util;
+use dataplane::{Offset, Size};
+
+use crate::config::ConfigOption;
+use crate::util::generate_file_name;
+
+pub const EXTENSION: &str = "timeindex";
+
+/// max timestamp followed by relative offset of batch
+const TIME_ENTRY_SIZE: usize = size_of::<i64>() + size_of::<Size>();
+
+#[derive(Debug)]
+pub struct TimeIndex {
+    base_offset: Offset,
+    path: PathBuf,
+    file: File,
+    /// max timestamp and offset relative to base offset
+    entries: Vec<(i64, Size)>,
+    /// number of entries written to file, rest are buffered until flush
+    written: usize,
+    /// flush count of message log when entries were last written
+    log_flush_count: u32,
+}
+
+impl TimeIndex {
+    /// open time index of segment, index file is created if it doesn't exist
+    pub async fn open(base_offset: Offset, option: &ConfigOption) -> Result<Self, IoError> {
+        let path = generate_file_name(&option.base_dir, base_offset, EXTENSION);
+        debug!("opening time index at: {}", path.display());
+        let mut file = file_util::open_read_write(&path).await?;
+
+        let mut contents = Vec::new();
+        file.read_to_end(&mut contents).await?;
+        // incomplete entry left by crash is overwritten by next entry
+        let len = contents.len() - contents.len() % TIME_ENTRY_SIZE;
+        let mut buf = Cursor::new(&contents[..len]);
+        let mut entries = Vec::with_capacity(len / TIME_ENTRY_SIZE);
+        while buf.has_remaining() {
+            let timestamp = buf.get_i64();
+            let relative_offset = buf.get_u32();
+            entries.push((timestamp, relative_offset));
+        }
+        trace!(entries = entries.len(), "time index loaded");
+
+        Ok(Self {
+            base_offset,
+            path,
+            file,
+            written: entries.len(),
+            entries,
+            log_flush_count: 0,
+        })
+    }
+
+    pub fn get_path(&self) -> &Path {
+        &self.path
+    }
+
+    /// greatest timestamp of batches in segment, 0 if batches don't have timestamp
+    pub fn max_timestamp(&self) -> i64 {
+        self.entries
+            .last()
+            .map(|(timestamp, _)| *timestamp)
+            .unwrap_or(0)
+    }
+
+    /// add batch written to segment, batches must be added in order of offsets.
+    /// entry is written to file on next flush
+    pub fn add_batch(&mut self, max_timestamp: i64, offset: Offset) {
+        if max_timestamp <= self.max_timestamp() {
+            return;
+        }
+
+        let relative_offset = (offset - self.base_offset) as Size;
+        trace!(max_timestamp, offset, "time index entry added");
+        self.entries.push((max_timestamp, relative_offset));
+    }
+
+    /// write buffered entries to file
+    pub async fn flush(&mut self) -> Result<(), IoError> {
+        if self.written == self.entries.len() {
+            return Ok(());
+        }
+
+        let pending = &self.entries[self.written..];
+        let mut contents = Vec::with_capacity(pending.len() * TIME_ENTRY_SIZE);
+        for (timestamp, relative_offset) in pending {
+            contents.put_i64(*timestamp);
+            contents.put_u32(*relative_offset);
+        }
+
+        let pos = (self.written * TIME_ENTRY_SIZE) as u64;
+        self.file.seek(SeekFrom::Start(pos)).await?;
+        self.file.write_all(&contents).await?;
+        self.file.flush().await?;
+        trace!(entries = pending.len(), "time index entries written");
+
+        self.written = self.entries.len();
+        Ok(())
+    }
+
+    /// write buffered entries if message log has been flushed since entries were last written
+    pub async fn flush_after_log(&mut self, log_flush_count: u32) -> Result<(), IoError> {
+        if log_flush_count == self.log_flush_count {
+            return Ok(());
+        }
+        self.flush().await?;
+        self.log_flush_count = log_flush_count;
+        Ok(())
+    }
+
+    /// drop entries of batches at or after end offset of segment
+    pub async fn truncate(&mut self, end_offset: Offset) -> Result<(), IoError> {
+        let base_offset = self.base_offset;
+        let len = self
+            .entries
+            .iter()
+            .take_while(|(_, relative_offset)| {
+                base_offset + (*relative_offset as Offset) < end_offset
+            })
+            .count();
+        if len == self.entries.len() {
+            return Ok(());
+        }
+
+        debug!(
+            dropped = self.entries.len() - len,
+            end_offset, "dropping time index entries after end of segment"
+        );
+        self.entries.truncate(len);
+        if self.written > len {
+            self.written = len;
+            self.file.set_len((len * TIME_ENTRY_SIZE) as u64).await?;
+        }
+        Ok(())
+    }
+
+    /// offset of first batch which has timestamp at or after given timestamp
+    pub fn find_timestamp(&self, timestamp: i64) -> Option<Offset> {
+        let idx = match self
+            .entries
+            .binary_search_by(|(entry_timestamp, _)| entry_timestamp.cmp(&timestamp))
+        {
+            Ok(idx) => idx,
+            Err(idx) => idx,
+        };
+        self.entries
+            .get(idx)
+            .map(|(_, relative_offset)| self.base_offset + *relative_offset as Offset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::env::temp_dir;
+    use std::io::Error as IoError;
+
+    use fluvio_future::test_async;
+    use flv_util::fixture::ensure_new_dir;
+
+    use crate::config::ConfigOption;
+
+    use super::TimeIndex;
+
+    #[test_async]
+    async fn test_time_index() -> Result<(), IoError> {
+        let test_dir = temp_dir().join("time-index");
+        ensure_new_dir(&test_dir)?;
+
+        let option = ConfigOption {
+            base_dir: test_dir,
+            ..Default::default()
+        };
+
+        let mut index = TimeIndex::open(100, &option).await?;
+        assert_eq!(index.max_timestamp(), 0);
+        assert!(index.find_timestamp(0).is_none());
+
+        index.add_batch(0, 100); // batch without timestamp
+        index.add_batch(1000, 101);
+        index.add_batch(900, 103); // older than previous batch
+        index.add_batch(2000, 105);
+        assert_eq!(index.max_timestamp(), 2000);
+
+        assert_eq!(index.find_timestamp(500), Some(101));
+        assert_eq!(index.find_timestamp(1000), Some(101));
+        assert_eq!(index.find_timestamp(1001), Some(105));
+        assert!(index.find_timestamp(2001).is_none());
+
+        // buffered entries are not written until flush
+        assert_eq!(std::fs::metadata(index.get_path())?.len(), 0);
+        index.flush_after_log(0).await?;
+        assert_eq!(std::fs::metadata(index.get_path())?.len(), 0);
+        index.flush_after_log(1).await?;
+
+        drop(index);
+
+        let mut index = TimeIndex::open(100, &option).await?;
+        assert_eq!(index.max_timestamp(), 2000);
+        assert_eq!(index.find_timestamp(1500), Some(105));
+        index.add_batch(3000, 110);
+        assert_eq!(index.find_timestamp(2500), Some(110));
+        index.flush().await?;
+
+        // batch at 110 was lost from message log
+        index.truncate(110).await?;
+        assert!(index.find_timestamp(2500).is_none());
+        drop(index);
+
+        let index = TimeIndex::open(100, &option).await?;
+        assert_eq!(index.max_timestamp(), 2000);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,190 @@
+//!
+//! # Transaction index
+//!
+//! Tracks ongoing and aborted transactions of a replica.
+//! Ongoing transactions determine last stable offset, which is the limit of read committed fetch.
+//! Aborted transactions are returned with read committed fetch so consumer can skip them.
+//!
+
+use std::collections::HashMap;
+
+use dataplane::Offset;
+use dataplane::batch::BatchHeader;
+use dataplane::derive::{Decode, Encode};
+use dataplane::fetch::AbortedTransaction;
+use dataplane::transaction::ControlRecordType;
+
+/// offsets of aborted transaction, including abort marker
+#[derive(Debug, Default, Clone, PartialEq, Encode, Decode)]
+struct AbortedRange {
+    producer_id: i64,
+    first_offset: Offset,
+    last_offset: Offset,
+}
+
+#[derive(Debug, Default)]
+pub struct TransactionIndex {
+    /// first offset of ongoing transaction for each producer
+    ongoing: HashMap<i64, Offset>,
+    /// aborted transactions, ordered by abort marker offset
+    aborted: Vec<AbortedRange>,
+}
+
+impl TransactionIndex {
+    /// update index with batch written to the log
+    pub fn add_batch(
+        &mut self,
+        header: &BatchHeader,
+        base_offset: Offset,
+        control: Option<ControlRecordType>,
+    ) {
+        if !header.is_transactional() {
+            return;
+        }
+
+        let producer_id = header.producer_id;
+        if header.is_control() {
+            // marker for transaction without records in this partition is ignored
+            if let Some(first_offset) = self.ongoing.remove(&producer_id) {
+                if control == Some(ControlRecordType::Abort) {
+                    self.aborted.push(AbortedRange {
+                        producer_id,
+                        first_offset,
+                        last_offset: base_offset,
+                    });
+                }
+            }
+        } else {
+            self.ongoing.entry(producer_id).or_insert(base_offset);
+        }
+    }
+
+    /// producer has written records which are not yet committed or aborted
+    pub fn is_ongoing(&self, producer_id: i64) -> bool {
+        self.ongoing.contains_key(&producer_id)
+    }
+
+    /// records before this offset are not part of ongoing transaction
+    pub fn last_stable_offset(&self, hw: Offset) -> Offset {
+        self.ongoing.values().copied().fold(hw, Offset::min)
+    }
+
+    /// aborted transactions which overlap offsets from start up to end (exclusive)
+    pub fn aborted_transactions(&self, start: Offset, end: Offset) -> Vec<AbortedTransaction> {
+        self.aborted
+            .iter()
+            .filter(|range| range.last_offset >= start && range.first_offset < end)
+            .map(|range| AbortedTransaction {
+                producer_id: range.producer_id,
+                first_offset: range.first_offset,
+            })
+            .collect()
+    }
+
+    /// batch of producer at offset is part of aborted transaction
+    pub fn is_aborted(&self, producer_id: i64, offset: Offset) -> bool {
+        self.aborted.iter().any(|range| {
+            range.producer_id == producer_id
+                && range.first_offset <= offset
+                && offset <= range.last_offset
+        })
+    }
+
+    /// remove aborted transactions which has been removed from the log
+    pub fn remove_before(&mut self, log_start_offset: Offset) {
+        self.aborted
+            .retain(|range| range.last_offset >= log_start_offset);
+    }
+
+    pub(crate) fn snapshot(&self) -> TransactionSnapshot {
+        TransactionSnapshot {
+            ongoing: self
+                .ongoing
+                .iter()
+                .map(|(producer_id, first_offset)| OngoingTransaction {
+                    producer_id: *producer_id,
+                    first_offset: *first_offset,
+                })
+                .collect(),
+            aborted: self.aborted.clone(),
+        }
+    }
+}
+
+impl From<TransactionSnapshot> for TransactionIndex {
+    fn from(snapshot: TransactionSnapshot) -> Self {
+        Self {
+            ongoing: snapshot
+                .ongoing
+                .into_iter()
+                .map(|ongoing| (ongoing.producer_id, ongoing.first_offset))
+                .collect(),
+            aborted: snapshot.aborted,
+        }
+    }
+}
+
+/// transaction index as saved in snapshot
+#[derive(Debug, Default, Encode, Decode)]
+pub(crate) struct TransactionSnapshot {
+    ongoing: Vec<OngoingTransaction>,
+    aborted: Vec<AbortedRange>,
+}
+
+#[derive(Debug, Default, Encode, Decode)]
+struct OngoingTransaction {
+    producer_id: i64,
+    first_offset: Offset,
+}
+
+#[cfg(test)]
+mod tests {
+
+    use dataplane::batch::BatchHeader;
+    use dataplane::transaction::ControlRecordType;
+
+    use super::TransactionIndex;
+
+    fn header(producer_id: i64, control: bool) -> BatchHeader {
+        let mut header = BatchHeader::default();
+        header.set_producer(producer_id, 0, 0);
+        header.set_transactional(true);
+        header.set_control(control);
+        header
+    }
+
+    #[test]
+    fn test_transaction_index() {
+        let mut index = TransactionIndex::default();
+
+        index.add_batch(&BatchHeader::default(), 0, None);
+        assert_eq!(index.last_stable_offset(10), 10);
+
+        index.add_batch(&header(1, false), 2, None);
+        index.add_batch(&header(2, false), 3, None);
+        index.add_batch(&header(1, false), 4, None);
+        assert_eq!(index.last_stable_offset(10), 2);
+        assert!(index.is_ongoing(1));
+
+        index.add_batch(&header(1, true), 5, Some(ControlRecordType::Commit));
+        assert_eq!(index.last_stable_offset(10), 3);
+        assert!(!index.is_ongoing(1));
+        assert!(index.aborted_transactions(0, 10).is_empty());
+
+        index.add_batch(&header(2, true), 6, Some(ControlRecordType::Abort));
+        assert_eq!(index.last_stable_offset(10), 10);
+
+        assert!(index.is_aborted(2, 3));
+        assert!(!index.is_aborted(1, 4));
+        assert!(!index.is_aborted(2, 7));
+
+        let aborted = index.aborted_transactions(4, 10);
+        assert_eq!(aborted.len(), 1);
+        assert_eq!(aborted[0].producer_id, 2);
+        assert_eq!(aborted[0].first_offset, 3);
+        assert!(index.aborted_transactions(7, 10).is_empty());
+
+        index.remove_before(7);
+        assert!(index.aborted_transactions(0, 10).is_empty());
+    }
+}
@@ -0,0 +1,255 @@
+use std::fmt;
+use std::fmt::{Debug, Display};
+use std::io::Error as IoError;
+use std::sync::Arc;
+
+use tracing::debug;
+use tracing::error;
+use tracing::info;
+use tracing::trace;
+use tracing::instrument;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use fluvio_future::task::spawn;
+
+use crate::k8_types::{K8Obj, K8Watch, ObjectMeta, Spec as K8Spec};
+
+use crate::core::Spec;
+use crate::store::k8::{K8ExtendedSpec, K8MetaItem, K8ConvertError};
+use crate::store::actions::LSUpdate;
+use crate::store::{MetadataStoreObject, StoreContext};
+use crate::actions::WSAction;
+
+use super::FileMetadataStore;
+
+/// For each spec, process updates from metadata store persisted in local file
+pub struct FileClusterStateDispatcher<S>
+where
+    S: K8ExtendedSpec,
+    <S as Spec>::Owner: K8ExtendedSpec,
+    S::Status: PartialEq,
+    S::IndexKey: Debug,
+{
+    store: Arc<FileMetadataStore>,
+    ctx: StoreContext<S>,
+    version: u64,
+}
+
+impl<S> Debug for FileClusterStateDispatcher<S>
+where
+    S: K8ExtendedSpec,
+    <S as Spec>::Owner: K8ExtendedSpec,
+    S::Status: PartialEq,
+    S::IndexKey: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} FileStateDispatcher", S::LABEL)
+    }
+}
+
+impl<S> FileClusterStateDispatcher<S>
+where
+    S: K8ExtendedSpec + Sync + Send + 'static,
+    <S as Spec>::Owner: K8ExtendedSpec,
+    S::Status: Display + Sync + Send + 'static,
+    S::Status: Into<<<S as K8ExtendedSpec>::K8Spec as K8Spec>::Status>,
+    S::IndexKey: Display + Sync + Send + 'static,
+    S: K8ExtendedSpec + Into<<S as K8ExtendedSpec>::K8Spec>,
+    K8Obj<S::K8Spec>: DeserializeOwned,
+    S::K8Spec: Sync + Send + 'static,
+    <S as K8ExtendedSpec>::K8Spec: DeserializeOwned + Serialize + Send + Sync,
+    <<S as K8ExtendedSpec>::K8Spec as K8Spec>::Status: Serialize,
+{
+    /// start dispatcher
+    pub fn start(store: Arc<FileMetadataStore>, ctx: StoreContext<S>) {
+        let dispatcher = Self {
+            store,
+            ctx,
+            version: 0,
+        };
+
+        spawn(dispatcher.dispatch_loop());
+    }
+
+    #[instrument(skip(self))]
+    async fn dispatch_loop(mut self) {
+        use tokio::select;
+
+        info!("starting file dispatcher loop");
+
+        if let Err(err) = self.retrieve_all_items().await {
+            error!(
+                "cannot retrieve {} objects from file store: {}",
+                S::LABEL,
+                err
+            );
+        }
+
+        loop {
+            // listen before reading changes, so change made in between is not missed
+            let listener = self.store.listen();
+            if let Err(err) = self.sync_changes().await {
+                error!("cannot read {} changes from file store: {}", S::LABEL, err);
+            }
+
+            trace!("dispatcher waiting");
+            let ws_receiver = self.ctx.receiver();
+
+            select! {
+                _ = listener => {
+                    trace!("file store changed");
+                },
+
+                msg = ws_receiver.recv() => {
+                    match msg {
+                        Ok(action) => {
+                            debug!("store: received ws action: {}", action);
+                            if let Err(err) = self.process_ws_action(action).await {
+                                error!("error: {}, processing ws action {}", S::LABEL, err);
+                            }
+                        },
+                        Err(err) => {
+                            error!("WS channel error: {}", err);
+                            panic!("WS channel error: {}", err);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Retrieve all items from file store and sync them into local store
+    async fn retrieve_all_items(&mut self) -> Result<(), IoError> {
+        let (k8_objects, version) = self.store.retrieve_items::<S::K8Spec>().await?;
+        debug!(
+            Spec = S::LABEL,
+            version,
+            item_count = k8_objects.len(),
+            "Retrieving items",
+        );
+
+        let mut meta_items = vec![];
+        for k8_obj in k8_objects {
+            if let Some(item) = convert(k8_obj) {
+                meta_items.push(item);
+            }
+        }
+        self.ctx.store().sync_all(meta_items).await;
+        self.version = version;
+        Ok(())
+    }
+
+    /// apply changes made in file store since last version.
+    /// if deletions since last version are no longer in store, all items are retrieved again
+    async fn sync_changes(&mut self) -> Result<(), IoError> {
+        let (events, version) = match self.store.changes_since::<S::K8Spec>(self.version).await? {
+            Some(changes) => changes,
+            None => {
+                debug!(
+                    version = self.version,
+                    "changes purged, retrieving all items"
+                );
+                return self.retrieve_all_items().await;
+            }
+        };
+        self.version = version;
+        if events.is_empty() {
+            return Ok(());
+        }
+
+        debug!("file {}: received changes: {}", S::LABEL, events.len());
+        let mut changes = vec![];
+        for event in events {
+            match event {
+                K8Watch::ADDED(k8_obj) | K8Watch::MODIFIED(k8_obj) => {
+                    if let Some(item) = convert(k8_obj) {
+                        changes.push(LSUpdate::Mod(item));
+                    }
+                }
+                K8Watch::DELETED(k8_obj) => {
+                    if let Some(item) = convert(k8_obj) {
+                        changes.push(LSUpdate::Delete(item.key_owned()));
+                    }
+                }
+            }
+        }
+
+        self.ctx.store().apply_changes(changes).await;
+        Ok(())
+    }
+
+    async fn process_ws_action(&mut self, action: WSAction<S>) -> Result<(), IoError> {
+        match action {
+            WSAction::Apply(obj) => {
+                let (key, spec, _status, ctx) = obj.parts();
+                let mut metadata = ctx.item().inner().clone();
+                metadata.name = key.to_string();
+                if metadata.namespace.is_empty() {
+                    metadata.namespace = self.store.namespace().to_owned();
+                }
+
+                if let Some(parent_metadata) = ctx.owner() {
+                    let input_metadata = parent_metadata
+                        .make_child_input_metadata::<<<S as Spec>::Owner as K8ExtendedSpec>::K8Spec>(
+                            key.to_string(),
+                        );
+                    metadata.owner_references = input_metadata.owner_references;
+                    if let Some(finalizer) = S::FINALIZER {
+                        metadata.finalizers = vec![finalizer.to_owned()];
+                    }
+                }
+
+                let k8_spec: S::K8Spec = spec.into();
+                self.store.apply(metadata, k8_spec).await
+            }
+            WSAction::UpdateSpec((key, spec)) => {
+                let metadata = self.existing_metadata(&key).await.unwrap_or_else(|| {
+                    ObjectMeta::new(key.to_string(), self.store.namespace().to_owned())
+                });
+                let k8_spec: S::K8Spec = spec.into();
+                self.store.apply(metadata, k8_spec).await
+            }
+            WSAction::UpdateStatus((key, status)) => {
+                let k8_status: <S::K8Spec as K8Spec>::Status = status.into();
+                self.store
+                    .update_status::<S::K8Spec>(&key.to_string(), k8_status)
+                    .await
+            }
+            WSAction::Delete(key) => {
+                self.store
+                    .delete::<S::K8Spec>(&key.to_string(), S::DELETE_WAIT_DEPENDENTS)
+                    .await
+            }
+            WSAction::DeleteFinal(key) => {
+                self.store.final_delete::<S::K8Spec>(&key.to_string()).await
+            }
+        }
+    }
+
+    async fn existing_metadata(&self, key: &S::IndexKey) -> Option<ObjectMeta> {
+        let read_guard = self.ctx.store().read().await;
+        read_guard
+            .get(key)
+            .map(|obj| obj.inner().ctx().item().inner().clone())
+    }
+}
+
+/// Translates stored object into internal metadata object
+fn convert<S>(k8_obj: K8Obj<S::K8Spec>) -> Option<MetadataStoreObject<S, K8MetaItem>>
+where
+    S: K8ExtendedSpec,
+    <S as Spec>::Owner: K8ExtendedSpec,
+{
+    match S::convert_from_k8(k8_obj) {
+        Ok(item) => Some(item),
+        Err(K8ConvertError::Skip(obj)) => {
+            debug!("skipping: {} {}", S::LABEL, obj.metadata.name);
+            None
+        }
+        Err(err) => {
+            error!("converting {} {:#?}", S::LABEL, err);
+            None
+        }
+    }
+}
@@ -0,0 +1,844 @@
+//!
+//! # File Metadata Store
+//!
+//! Persists metadata objects of all kinds in a single file, so SC can run without Kubernetes.
+//! Objects are kept in the same form as Kubernetes objects, and store emulates the parts of
+//! API server used by dispatcher: resource versions, uid, finalizers and deletion of dependents.
+//!
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Error as IoError;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tracing::debug;
+use tracing::info;
+use tracing::trace;
+use async_rwlock::RwLock;
+use futures_lite::io::{AsyncReadExt, AsyncWriteExt};
+use once_cell::sync::OnceCell;
+use event_listener::{Event, EventListener};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use fluvio_future::fs::{metadata, remove_file, rename, File, OpenOptions};
+
+use crate::k8_types::{K8Obj, K8Watch, ObjectMeta, Spec as K8Spec};
+
+const METADATA_FILE: &str = "metadata.json";
+const LEASE_FILE: &str = "leader.json";
+const LEASE_LOCK_FILE: &str = "leader.lock";
+
+/// deleted objects are kept for this many versions, so watchers can see deletion.
+/// watcher which falls further behind must retrieve all objects again
+const TOMBSTONE_VERSIONS: u64 = 1000;
+
+#[derive(Debug)]
+pub struct FileMetadataStore {
+    base_dir: PathBuf,
+    path: PathBuf,
+    namespace: String,
+    state: RwLock<StoreState>,
+    event: Event,
+    /// when store is shared by SC instances, only holder of lease can change it
+    lease_requirement: OnceCell<LeaseRequirement>,
+}
+
+impl FileMetadataStore {
+    /// open store in base directory, objects persisted from previous run are loaded
+    pub fn open(base_dir: impl AsRef<Path>, namespace: impl Into<String>) -> Result<Self, IoError> {
+        let base_dir = base_dir.as_ref();
+        fs::create_dir_all(base_dir)?;
+        let path = base_dir.join(METADATA_FILE);
+
+        let state = if path.exists() {
+            let mut state = parse_state(&fs::read(&path)?)?;
+            state.purge_deleted(state.version);
+            state
+        } else {
+            StoreState::default()
+        };
+        debug!(
+            path = %path.display(),
+            version = state.version,
+            "opened file metadata store"
+        );
+
+        Ok(Self {
+            base_dir: base_dir.to_owned(),
+            path,
+            namespace: namespace.into(),
+            state: RwLock::new(state),
+            event: Event::new(),
+            lease_requirement: OnceCell::new(),
+        })
+    }
+
+    pub fn open_shared(
+        base_dir: impl AsRef<Path>,
+        namespace: impl Into<String>,
+    ) -> Result<Arc<Self>, IoError> {
+        Ok(Arc::new(Self::open(base_dir, namespace)?))
+    }
+
+    /// refuse changes unless holder has lease which doesn't expire within margin.
+    /// instance which notices late that it lost the lease can't overwrite changes of new leader
+    pub fn require_lease(&self, holder: impl Into<String>, margin: Duration) {
+        let _ = self.lease_requirement.set(LeaseRequirement {
+            holder: holder.into(),
+            margin,
+        });
+    }
+
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    /// listen for changes in the store
+    pub fn listen(&self) -> EventListener {
+        self.event.listen()
+    }
+
+    /// all objects of kind with version of store
+    pub async fn retrieve_items<S>(&self) -> Result<(Vec<K8Obj<S>>, u64), IoError>
+    where
+        S: K8Spec,
+        K8Obj<S>: DeserializeOwned,
+    {
+        let state = self.state.read().await;
+        let mut items = vec![];
+        if let Some(objects) = state.objects.get(&S::kind()) {
+            for object in objects.values().filter(|object| !object.deleted) {
+                items.push(from_value(object.value.clone())?);
+            }
+        }
+        Ok((items, state.version))
+    }
+
+    /// objects of kind changed after version.
+    /// none if deletions after version have been purged, all items must be retrieved again
+    pub async fn changes_since<S>(
+        &self,
+        version: u64,
+    ) -> Result<Option<(Vec<K8Watch<S>>, u64)>, IoError>
+    where
+        S: K8Spec,
+        K8Obj<S>: DeserializeOwned,
+    {
+        let state = self.state.read().await;
+        if version < state.purged_version {
+            return Ok(None);
+        }
+        let mut changes = vec![];
+        if let Some(objects) = state.objects.get(&S::kind()) {
+            for object in objects.values().filter(|object| object.version > version) {
+                let k8_obj = from_value(object.value.clone())?;
+                if object.deleted {
+                    changes.push(K8Watch::DELETED(k8_obj));
+                } else {
+                    changes.push(K8Watch::MODIFIED(k8_obj));
+                }
+            }
+        }
+        Ok(Some((changes, state.version)))
+    }
+
+    /// create or update spec of object, status of existing object is kept
+    pub async fn apply<S>(&self, metadata: ObjectMeta, spec: S) -> Result<(), IoError>
+    where
+        S: K8Spec,
+        S::Status: Serialize,
+    {
+        let kind = S::kind();
+        let name = metadata.name.clone();
+        let mut metadata = to_value(metadata)?;
+        let spec = to_value(spec)?;
+        let default_status = to_value(S::Status::default())?;
+
+        debug!(%kind, %name, "file store apply");
+        self.update(|state| {
+            let object = match state.live_object(&kind, &name) {
+                Some(existing) => {
+                    for field in &[
+                        "uid",
+                        "creationTimestamp",
+                        "deletionTimestamp",
+                        "deletionGracePeriodSeconds",
+                    ] {
+                        let value = &existing["metadata"][*field];
+                        if !value.is_null() {
+                            metadata[*field] = value.clone();
+                        }
+                    }
+                    json!({
+                        "apiVersion": S::api_version(),
+                        "kind": kind,
+                        "metadata": metadata,
+                        "spec": spec,
+                        "status": existing["status"].clone(),
+                    })
+                }
+                None => {
+                    metadata["uid"] =
+                        json!(format!("{}-{}", kind.to_lowercase(), state.version + 1));
+                    json!({
+                        "apiVersion": S::api_version(),
+                        "kind": kind,
+                        "metadata": metadata,
+                        "spec": spec,
+                        "status": default_status,
+                    })
+                }
+            };
+            state.put(&kind, &name, object);
+            Ok(())
+        })
+        .await
+    }
+
+    /// update status of existing object
+    pub async fn update_status<S>(&self, name: &str, status: S::Status) -> Result<(), IoError>
+    where
+        S: K8Spec,
+        S::Status: Serialize,
+    {
+        let kind = S::kind();
+        let status = to_value(status)?;
+
+        debug!(%kind, %name, "file store update status");
+        self.update(|state| {
+            let mut object = state
+                .live_object(&kind, name)
+                .cloned()
+                .ok_or_else(|| not_found(&kind, name))?;
+            object["status"] = status;
+            state.put(&kind, name, object);
+            Ok(())
+        })
+        .await
+    }
+
+    /// delete object.  object with finalizers is only marked as being deleted.
+    /// if wait for dependents, object is removed after all of its dependents are removed
+    pub async fn delete<S>(&self, name: &str, wait_dependents: bool) -> Result<(), IoError>
+    where
+        S: K8Spec,
+    {
+        let kind = S::kind();
+        debug!(%kind, %name, wait_dependents, "file store delete");
+        self.update(|state| {
+            if state.live_object(&kind, name).is_none() {
+                return Err(not_found(&kind, name));
+            }
+            state.delete(&kind, name, wait_dependents);
+            Ok(())
+        })
+        .await
+    }
+
+    /// remove finalizers of object, object being deleted is removed
+    pub async fn final_delete<S>(&self, name: &str) -> Result<(), IoError>
+    where
+        S: K8Spec,
+    {
+        let kind = S::kind();
+        debug!(%kind, %name, "file store final delete");
+        self.update(|state| {
+            if state.live_object(&kind, name).is_none() {
+                return Err(not_found(&kind, name));
+            }
+            state.final_delete(&kind, name);
+            Ok(())
+        })
+        .await
+    }
+
+    /// reload objects written by another SC sharing the store directory.
+    /// objects missing from file are reported to watchers as deleted
+    pub async fn reload(&self) -> Result<bool, IoError> {
+        let mut loaded = match read_file(&self.path).await? {
+            Some(contents) => parse_state(&contents)?,
+            None => return Ok(false),
+        };
+
+        let mut state = self.state.write().await;
+        if loaded.version <= state.version {
+            return Ok(false);
+        }
+
+        for (kind, objects) in &state.objects {
+            for (name, object) in objects.iter().filter(|(_, object)| !object.deleted) {
+                let loaded_objects = loaded.objects.entry(kind.clone()).or_default();
+                if !loaded_objects.contains_key(name) {
+                    loaded_objects.insert(
+                        name.clone(),
+                        StoredObject {
+                            version: loaded.version,
+                            deleted: true,
+                            value: object.value.clone(),
+                        },
+                    );
+                }
+            }
+        }
+        loaded.purge_old_tombstones();
+        debug!(
+            old_version = state.version,
+            new_version = loaded.version,
+            "file store reloaded"
+        );
+        *state = loaded;
+        drop(state);
+
+        self.event.notify(usize::MAX);
+        Ok(true)
+    }
+
+    /// acquire or renew leadership lease for holder, returns true if holder is the leader.
+    /// lease is kept in store directory, so only SC instances sharing directory compete for it
+    pub async fn try_acquire_lease(&self, holder: &str, ttl: Duration) -> Result<bool, IoError> {
+        let lock_path = self.base_dir.join(LEASE_LOCK_FILE);
+        if let Err(err) = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&lock_path)
+            .await
+        {
+            if err.kind() != ErrorKind::AlreadyExists {
+                return Err(err);
+            }
+            // lock left by instance which died while holding it
+            let lock_age = metadata(&lock_path)
+                .await?
+                .modified()?
+                .elapsed()
+                .unwrap_or_default();
+            if lock_age > ttl {
+                remove_file(&lock_path).await?;
+            }
+            return Ok(false);
+        }
+
+        let result = self.update_lease(holder, ttl).await;
+        remove_file(&lock_path).await?;
+        result
+    }
+
+    async fn update_lease(&self, holder: &str, ttl: Duration) -> Result<bool, IoError> {
+        let lease_path = self.base_dir.join(LEASE_FILE);
+        let now = now_ms();
+        if let Some(lease) = read_lease(&lease_path).await? {
+            if lease.holder != holder && lease.expires_at_ms > now {
+                trace!(leader = %lease.holder, "lease held by other instance");
+                return Ok(false);
+            }
+            if lease.holder != holder {
+                info!(previous = %lease.holder, %holder, "taking over expired lease");
+            }
+        }
+
+        let lease = Lease {
+            holder: holder.to_owned(),
+            expires_at_ms: now + ttl.as_millis() as u64,
+        };
+        let contents =
+            serde_json::to_vec(&lease).map_err(|err| IoError::new(ErrorKind::InvalidData, err))?;
+        write_file(&lease_path, &contents).await?;
+        Ok(true)
+    }
+
+    /// refuse change if lease is not held or is about to expire
+    async fn check_lease(&self) -> Result<(), IoError> {
+        let requirement = match self.lease_requirement.get() {
+            Some(requirement) => requirement,
+            None => return Ok(()),
+        };
+        let lease = read_lease(&self.base_dir.join(LEASE_FILE)).await?;
+        match lease {
+            Some(lease)
+                if lease.holder == requirement.holder
+                    && lease.expires_at_ms > now_ms() + requirement.margin.as_millis() as u64 =>
+            {
+                Ok(())
+            }
+            _ => Err(IoError::new(
+                ErrorKind::PermissionDenied,
+                format!("{} doesn't hold store lease", requirement.holder),
+            )),
+        }
+    }
+
+    /// apply change to copy of state, which replaces state only after it has been written,
+    /// so failed write doesn't leave change visible
+    async fn update<F>(&self, change: F) -> Result<(), IoError>
+    where
+        F: FnOnce(&mut StoreState) -> Result<(), IoError>,
+    {
+        let mut state = self.state.write().await;
+        let mut updated = state.clone();
+        change(&mut updated)?;
+        updated.purge_old_tombstones();
+        self.commit(&updated, state.version).await?;
+        *state = updated;
+        drop(state);
+
+        self.event.notify(usize::MAX);
+        Ok(())
+    }
+
+    /// write state based on version to file.
+    /// change is refused if store file has been changed by another instance since that version
+    async fn commit(&self, state: &StoreState, base_version: u64) -> Result<(), IoError> {
+        self.check_lease().await?;
+        if let Some(stored_version) = read_version(&self.path).await? {
+            if stored_version > base_version {
+                return Err(IoError::new(
+                    ErrorKind::Other,
+                    format!(
+                        "store was changed by another instance, version: {} expected: {}",
+                        stored_version, base_version
+                    ),
+                ));
+            }
+        }
+
+        let contents =
+            serde_json::to_vec(state).map_err(|err| IoError::new(ErrorKind::InvalidData, err))?;
+        write_file(&self.path, &contents).await?;
+        trace!(version = state.version, "file store committed");
+        Ok(())
+    }
+}
+
+/// contents of file, none if file doesn't exist
+async fn read_file(path: &Path) -> Result<Option<Vec<u8>>, IoError> {
+    let mut file = match File::open(path).await {
+        Ok(file) => file,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err),
+    };
+    let mut contents = vec![];
+    file.read_to_end(&mut contents).await?;
+    Ok(Some(contents))
+}
+
+/// replace file through temporary file, which is synced before rename,
+/// so crash leaves either old or new contents
+async fn write_file(path: &Path, contents: &[u8]) -> Result<(), IoError> {
+    let tmp_path = path.with_extension("tmp");
+    let mut file = File::create(&tmp_path).await?;
+    file.write_all(contents).await?;
+    file.sync_all().await?;
+    drop(file);
+    rename(&tmp_path, path).await
+}
+
+fn parse_state(contents: &[u8]) -> Result<StoreState, IoError> {
+    serde_json::from_slice(contents).map_err(|err| IoError::new(ErrorKind::InvalidData, err))
+}
+
+/// version of state in file, without decoding objects
+async fn read_version(path: &Path) -> Result<Option<u64>, IoError> {
+    #[derive(Deserialize)]
+    struct StoredVersion {
+        version: u64,
+    }
+
+    match read_file(path).await? {
+        Some(contents) => serde_json::from_slice::<StoredVersion>(&contents)
+            .map(|stored| Some(stored.version))
+            .map_err(|err| IoError::new(ErrorKind::InvalidData, err)),
+        None => Ok(None),
+    }
+}
+
+async fn read_lease(path: &Path) -> Result<Option<Lease>, IoError> {
+    match read_file(path).await? {
+        Some(contents) => serde_json::from_slice(&contents)
+            .map(Some)
+            .map_err(|err| IoError::new(ErrorKind::InvalidData, err)),
+        None => Ok(None),
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn from_value<T: DeserializeOwned>(value: Value) -> Result<T, IoError> {
+    serde_json::from_value(value).map_err(|err| IoError::new(ErrorKind::InvalidData, err))
+}
+
+fn to_value<T: Serialize>(value: T) -> Result<Value, IoError> {
+    serde_json::to_value(value).map_err(|err| IoError::new(ErrorKind::InvalidData, err))
+}
+
+fn not_found(kind: &str, name: &str) -> IoError {
+    IoError::new(
+        ErrorKind::NotFound,
+        format!("{} {} not found in store", kind, name),
+    )
+}
+
+/// leadership among SC instances, holder must renew it before it expires
+#[derive(Debug, Serialize, Deserialize)]
+struct Lease {
+    holder: String,
+    expires_at_ms: u64,
+}
+
+#[derive(Debug)]
+struct LeaseRequirement {
+    holder: String,
+    margin: Duration,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredObject {
+    version: u64,
+    /// removed object is kept so watchers can see deletion
+    #[serde(default)]
+    deleted: bool,
+    value: Value,
+}
+
+/// objects of all kinds, by kind and name
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+struct StoreState {
+    version: u64,
+    /// deleted objects up to this version has been purged
+    #[serde(default)]
+    purged_version: u64,
+    objects: BTreeMap<String, BTreeMap<String, StoredObject>>,
+}
+
+impl StoreState {
+    /// remove deleted objects up to version
+    fn purge_deleted(&mut self, version: u64) {
+        for objects in self.objects.values_mut() {
+            objects.retain(|_, object| !(object.deleted && object.version <= version));
+        }
+        self.purged_version = self.purged_version.max(version);
+    }
+
+    fn purge_old_tombstones(&mut self) {
+        if self.version > self.purged_version + TOMBSTONE_VERSIONS {
+            self.purge_deleted(self.version - TOMBSTONE_VERSIONS);
+        }
+    }
+
+    fn live_object(&self, kind: &str, name: &str) -> Option<&Value> {
+        self.objects
+            .get(kind)
+            .and_then(|objects| objects.get(name))
+            .filter(|object| !object.deleted)
+            .map(|object| &object.value)
+    }
+
+    /// store object with new resource version
+    fn put(&mut self, kind: &str, name: &str, mut value: Value) {
+        self.version += 1;
+        value["metadata"]["resourceVersion"] = json!(self.version.to_string());
+        self.objects.entry(kind.to_owned()).or_default().insert(
+            name.to_owned(),
+            StoredObject {
+                version: self.version,
+                deleted: false,
+                value,
+            },
+        );
+    }
+
+    /// live objects owned by uid
+    fn dependents(&self, uid: &str) -> Vec<(String, String)> {
+        let mut dependents = vec![];
+        for (kind, objects) in &self.objects {
+            for (name, object) in objects.iter().filter(|(_, object)| !object.deleted) {
+                if owner_uids(&object.value).iter().any(|owner| owner == uid) {
+                    dependents.push((kind.clone(), name.clone()));
+                }
+            }
+        }
+        dependents
+    }
+
+    fn find_by_uid(&self, uid: &str) -> Option<(String, String)> {
+        for (kind, objects) in &self.objects {
+            for (name, object) in objects.iter().filter(|(_, object)| !object.deleted) {
+                if object.value["metadata"]["uid"].as_str() == Some(uid) {
+                    return Some((kind.clone(), name.clone()));
+                }
+            }
+        }
+        None
+    }
+
+    fn delete(&mut self, kind: &str, name: &str, wait_dependents: bool) {
+        let value = match self.live_object(kind, name) {
+            Some(value) => value.clone(),
+            None => return,
+        };
+        let dependents = self.dependents(uid(&value));
+
+        if has_finalizers(&value) || (wait_dependents && !dependents.is_empty()) {
+            if !is_being_deleted(&value) {
+                let mut value = value;
+                value["metadata"]["deletionGracePeriodSeconds"] = json!(0);
+                self.put(kind, name, value);
+            }
+            if wait_dependents {
+                for (dependent_kind, dependent_name) in dependents {
+                    self.delete(&dependent_kind, &dependent_name, false);
+                }
+            }
+        } else {
+            self.remove(kind, name);
+        }
+    }
+
+    fn final_delete(&mut self, kind: &str, name: &str) {
+        let mut value = match self.live_object(kind, name) {
+            Some(value) => value.clone(),
+            None => return,
+        };
+        value["metadata"]["finalizers"] = json!([]);
+        if is_being_deleted(&value) {
+            self.remove(kind, name);
+        } else {
+            self.put(kind, name, value);
+        }
+    }
+
+    /// remove object, its dependents are deleted and owner waiting for it is removed
+    fn remove(&mut self, kind: &str, name: &str) {
+        let value = match self.objects.get_mut(kind).and_then(|o| o.get_mut(name)) {
+            Some(object) if !object.deleted => {
+                self.version += 1;
+                object.version = self.version;
+                object.deleted = true;
+                object.value["metadata"]["resourceVersion"] = json!(self.version.to_string());
+                object.value.clone()
+            }
+            _ => return,
+        };
+        trace!(%kind, %name, "removed from file store");
+
+        for (dependent_kind, dependent_name) in self.dependents(uid(&value)) {
+            self.delete(&dependent_kind, &dependent_name, false);
+        }
+
+        for owner_uid in owner_uids(&value) {
+            if let Some((owner_kind, owner_name)) = self.find_by_uid(&owner_uid) {
+                let ready_to_remove = self
+                    .live_object(&owner_kind, &owner_name)
+                    .map(|owner| is_being_deleted(owner) && !has_finalizers(owner))
+                    .unwrap_or(false);
+                if ready_to_remove && self.dependents(&owner_uid).is_empty() {
+                    self.remove(&owner_kind, &owner_name);
+                }
+            }
+        }
+    }
+}
+
+fn uid(value: &Value) -> &str {
+    value["metadata"]["uid"].as_str().unwrap_or_default()
+}
+
+fn owner_uids(value: &Value) -> Vec<String> {
+    value["metadata"]["ownerReferences"]
+        .as_array()
+        .map(|owners| {
+            owners
+                .iter()
+                .filter_map(|owner| owner["uid"].as_str().map(|uid| uid.to_owned()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn has_finalizers(value: &Value) -> bool {
+    value["metadata"]["finalizers"]
+        .as_array()
+        .map(|finalizers| !finalizers.is_empty())
+        .unwrap_or(false)
+}
+
+fn is_being_deleted(value: &Value) -> bool {
+    !value["metadata"]["deletionGracePeriodSeconds"].is_null()
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::env::temp_dir;
+    use std::io::{Error as IoError, ErrorKind};
+    use std::time::Duration;
+
+    use serde_json::json;
+    use serde_json::Value;
+    use fluvio_future::test_async;
+    use flv_util::fixture::ensure_new_dir;
+
+    use super::FileMetadataStore;
+    use super::StoreState;
+    use super::TOMBSTONE_VERSIONS;
+    use super::is_being_deleted;
+
+    fn object(uid: &str, owner: Option<&str>, finalizer: bool) -> Value {
+        let mut value = json!({
+            "metadata": {
+                "uid": uid,
+            }
+        });
+        if let Some(owner) = owner {
+            value["metadata"]["ownerReferences"] = json!([{ "uid": owner }]);
+        }
+        if finalizer {
+            value["metadata"]["finalizers"] = json!(["finalizer"]);
+        }
+        value
+    }
+
+    #[test]
+    fn test_delete_wait_dependents() {
+        let mut state = StoreState::default();
+        state.put("Topic", "test", object("t1", None, false));
+        state.put("Partition", "test-0", object("p0", Some("t1"), true));
+        state.put("Partition", "test-1", object("p1", Some("t1"), true));
+        state.put("Partition", "other-0", object("p2", Some("t2"), true));
+        assert_eq!(state.version, 4);
+
+        // topic waits for partitions, which wait for their finalizer
+        state.delete("Topic", "test", true);
+        let topic = state.live_object("Topic", "test").expect("topic");
+        assert!(is_being_deleted(topic));
+        assert!(is_being_deleted(
+            state.live_object("Partition", "test-0").expect("partition")
+        ));
+        assert!(!is_being_deleted(
+            state
+                .live_object("Partition", "other-0")
+                .expect("partition")
+        ));
+
+        state.final_delete("Partition", "test-0");
+        assert!(state.live_object("Partition", "test-0").is_none());
+        assert!(state.live_object("Topic", "test").is_some());
+
+        state.final_delete("Partition", "test-1");
+        assert!(state.live_object("Partition", "test-1").is_none());
+        assert!(state.live_object("Topic", "test").is_none());
+        assert!(state.live_object("Partition", "other-0").is_some());
+
+        // removed objects are kept for watchers until store is reloaded
+        let topic = &state.objects["Topic"]["test"];
+        assert!(topic.deleted);
+        assert_eq!(topic.version, state.version);
+        state.purge_deleted(state.version);
+        assert!(state.objects["Topic"].is_empty());
+    }
+
+    #[test]
+    fn test_delete_without_finalizer() {
+        let mut state = StoreState::default();
+        state.put("Spu", "spu-1", object("s1", None, false));
+        state.final_delete("Spu", "spu-1");
+        assert!(!is_being_deleted(
+            state.live_object("Spu", "spu-1").expect("spu")
+        ));
+
+        state.delete("Spu", "spu-1", false);
+        assert!(state.live_object("Spu", "spu-1").is_none());
+    }
+
+    #[test_async]
+    async fn test_lease() -> Result<(), IoError> {
+        let test_dir = temp_dir().join("file-metadata-lease");
+        ensure_new_dir(&test_dir)?;
+        let store = FileMetadataStore::open(&test_dir, "default")?;
+        let ttl = Duration::from_secs(60);
+
+        assert!(store.try_acquire_lease("sc-1", ttl).await?);
+        assert!(!store.try_acquire_lease("sc-2", ttl).await?);
+        // holder renews its lease
+        assert!(store.try_acquire_lease("sc-1", ttl).await?);
+
+        // expired lease is taken over
+        assert!(
+            store
+                .try_acquire_lease("sc-1", Duration::from_millis(0))
+                .await?
+        );
+        assert!(store.try_acquire_lease("sc-2", ttl).await?);
+        assert!(!store.try_acquire_lease("sc-1", ttl).await?);
+        Ok(())
+    }
+
+    #[test_async]
+    async fn test_commit_requires_lease() -> Result<(), IoError> {
+        let test_dir = temp_dir().join("file-metadata-commit-lease");
+        ensure_new_dir(&test_dir)?;
+        let sc1 = FileMetadataStore::open(&test_dir, "default")?;
+        let sc2 = FileMetadataStore::open(&test_dir, "default")?;
+        let ttl = Duration::from_secs(60);
+        sc1.require_lease("sc-1", Duration::from_secs(1));
+        sc2.require_lease("sc-2", Duration::from_secs(1));
+
+        let mut state = StoreState::default();
+        state.put("Spu", "spu-1", object("s1", None, false));
+
+        assert!(sc1.try_acquire_lease("sc-1", ttl).await?);
+        sc1.commit(&state, 0).await?;
+        let err = sc2.commit(&state, 0).await.expect_err("not leader");
+        assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+
+        // lease about to expire is not enough to change store
+        assert!(
+            sc1.try_acquire_lease("sc-1", Duration::from_millis(500))
+                .await?
+        );
+        assert!(sc1.commit(&state, 1).await.is_err());
+
+        // new leader can't overwrite changes it hasn't seen
+        assert!(
+            sc1.try_acquire_lease("sc-1", Duration::from_millis(0))
+                .await?
+        );
+        assert!(sc2.try_acquire_lease("sc-2", ttl).await?);
+        let err = sc2.commit(&state, 0).await.expect_err("stale");
+        assert_eq!(err.kind(), ErrorKind::Other);
+        state.put("Spu", "spu-2", object("s2", None, false));
+        sc2.commit(&state, 1).await?;
+        assert!(sc1.commit(&state, 2).await.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_purge_old_tombstones() {
+        let mut state = StoreState::default();
+        state.put("Spu", "spu-1", object("s1", None, false));
+        state.put("Spu", "spu-2", object("s2", None, false));
+        state.delete("Spu", "spu-1", false);
+
+        // recent deletion is kept for watchers
+        state.purge_old_tombstones();
+        assert!(state.objects["Spu"].contains_key("spu-1"));
+        assert_eq!(state.purged_version, 0);
+
+        for _ in 0..TOMBSTONE_VERSIONS {
+            state.put("Spu", "spu-2", object("s2", None, false));
+        }
+        state.purge_old_tombstones();
+        assert!(!state.objects["Spu"].contains_key("spu-1"));
+        assert!(state.live_object("Spu", "spu-2").is_some());
+        assert_eq!(state.purged_version, state.version - TOMBSTONE_VERSIONS);
+    }
+}
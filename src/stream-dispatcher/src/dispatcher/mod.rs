@@ -1,8 +1,12 @@
 mod k8_dispatcher;
 mod k8_ws_service;
+mod file_metadata;
+mod file_dispatcher;
 
 pub use k8_dispatcher::*;
 pub use k8_ws_service::*;
+pub use file_metadata::*;
+pub use file_dispatcher::*;
 
 /*
 mod delta{
@@ -29,6 +29,8 @@ pub const SPU_LOG_SIZE: &str = "1Gi";
 pub const SPU_LOG_INDEX_MAX_BYTES: u32 = 10485760;
 pub const SPU_LOG_INDEX_MAX_INTERVAL_BYTES: u32 = 4096;
 pub const SPU_LOG_SEGMENT_MAX_BYTES: u32 = 1073741824;
+pub const SPU_SMART_STREAM_FUEL: u64 = 100_000_000;
+pub const SPU_SMART_STREAM_MAX_MEMORY_BYTES: u64 = 67108864;
 
 // CLI config
 pub const CLI_PROFILES_DIR: &str = "profiles";